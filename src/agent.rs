@@ -191,6 +191,10 @@ pub struct AgentRoleTemplate {
     pub default_mode: CoordinationMode,
     pub default_capabilities: &'static [&'static str],
     pub description_hint: &'static str,
+    /// Tool categories this role may not use (tool policy default).
+    pub denied_categories: &'static [&'static str],
+    /// Tool categories this role must confirm (`confirm: true`).
+    pub confirm_categories: &'static [&'static str],
 }
 
 pub const ROLE_TEMPLATES: &[AgentRoleTemplate] = &[
@@ -199,30 +203,40 @@ pub const ROLE_TEMPLATES: &[AgentRoleTemplate] = &[
         default_mode: CoordinationMode::Supervised,
         default_capabilities: &["code", "test", "debug", "refactor"],
         description_hint: "Writes and maintains code",
+        denied_categories: &[],
+        confirm_categories: &["destructive", "agent_admin"],
     },
     AgentRoleTemplate {
         role: "coordinator",
         default_mode: CoordinationMode::Coordinator,
         default_capabilities: &["plan", "delegate", "review", "merge"],
         description_hint: "Coordinates other agents, delegates tasks",
+        denied_categories: &[],
+        confirm_categories: &[],
     },
     AgentRoleTemplate {
         role: "reviewer",
         default_mode: CoordinationMode::Supervised,
         default_capabilities: &["review", "audit", "test"],
         description_hint: "Reviews code and provides feedback",
+        denied_categories: &["destructive", "agent_admin"],
+        confirm_categories: &["wake"],
     },
     AgentRoleTemplate {
         role: "researcher",
         default_mode: CoordinationMode::Autonomous,
         default_capabilities: &["search", "analyze", "document"],
         description_hint: "Researches topics and gathers information",
+        denied_categories: &["destructive", "agent_admin"],
+        confirm_categories: &[],
     },
     AgentRoleTemplate {
         role: "architect",
         default_mode: CoordinationMode::Coordinator,
         default_capabilities: &["design", "plan", "review", "delegate"],
         description_hint: "Designs system architecture and coordinates implementation",
+        denied_categories: &[],
+        confirm_categories: &["destructive"],
     },
 ];
 
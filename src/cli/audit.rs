@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use ai_smartness::storage::audit::{AuditFilter, AuditLog};
+use ai_smartness::storage::database::{open_connection, ConnectionRole};
+use ai_smartness::storage::{migrations, path_utils};
+
+use super::resolve_project_hash;
+
+/// `audit` — query the audit log (newest first).
+///
+/// Scoped to the current project unless `all_projects` is set.
+pub fn run(
+    agent_id: Option<&str>,
+    tool: Option<&str>,
+    outcome: Option<&str>,
+    since_hours: Option<u64>,
+    affected_id: Option<&str>,
+    limit: usize,
+    project_hash: Option<&str>,
+    all_projects: bool,
+    json: bool,
+) -> Result<()> {
+    let project_hash = if all_projects {
+        None
+    } else {
+        Some(resolve_project_hash(project_hash)?)
+    };
+    let reg_path = path_utils::registry_db_path();
+    let conn = open_connection(&reg_path, ConnectionRole::Cli)
+        .context("Failed to open registry database")?;
+    migrations::migrate_registry_db(&conn).context("Registry migration failed")?;
+
+    let since = since_hours.map(|h| {
+        let cutoff = ai_smartness::time_utils::now() - chrono::Duration::hours(h as i64);
+        ai_smartness::time_utils::to_sqlite(&cutoff)
+    });
+    let filter = AuditFilter {
+        project_hash,
+        agent_id: agent_id.map(String::from),
+        tool: tool.map(String::from),
+        outcome: outcome.map(String::from),
+        since,
+        until: None,
+        affected_id: affected_id.map(String::from),
+        limit,
+    };
+    let entries = AuditLog::query(&conn, &filter).context("Audit query failed")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No audit entries match.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20}  {:<16}  {:<24}  {:<7}  {:>7}  {}",
+        "TIME", "AGENT", "TOOL", "OUTCOME", "MS", "AFFECTED"
+    );
+    println!("{}", "-".repeat(100));
+    for e in &entries {
+        let time: String = e.timestamp.chars().take(19).collect::<String>().replace('T', " ");
+        let affected = match e.affected_ids.len() {
+            0 => "-".to_string(),
+            1..=3 => e.affected_ids.join(","),
+            n => format!("{},... ({} ids)", e.affected_ids[..2].join(","), n),
+        };
+        println!(
+            "{:<20}  {:<16}  {:<24}  {:<7}  {:>7}  {}",
+            time, e.agent_id, e.tool, e.outcome, e.duration_ms, affected
+        );
+        if let Some(ref err) = e.error {
+            println!("{:<20}  ↳ {}", "", err);
+        }
+    }
+    println!("\n{} entries", entries.len());
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use ai_smartness::processing::daemon_ipc_client;
+use ai_smartness::storage::database::{open_connection, ConnectionRole};
+use ai_smartness::storage::dead_letters::{DeadLetterFilter, DeadLetterStore, FailureStage};
+use ai_smartness::storage::{migrations, path_utils};
+use rusqlite::Connection;
+
+use super::resolve_project_hash;
+
+/// Selection shared by `captures failed list|retry|purge`.
+pub struct FailedSelection<'a> {
+    pub ids: Vec<i64>,
+    pub agent_id: Option<&'a str>,
+    pub stage: Option<&'a str>,
+    pub older_than_days: Option<u64>,
+    pub project_hash: Option<&'a str>,
+    pub all_projects: bool,
+}
+
+impl FailedSelection<'_> {
+    fn filter(&self) -> Result<DeadLetterFilter> {
+        let stage = match self.stage {
+            Some(s) => Some(FailureStage::parse(s).with_context(|| {
+                format!("Unknown stage '{}' (capture, prompt, enrichment, pool_write, interrupted)", s)
+            })?),
+            None => None,
+        };
+        // Explicit ids are global; other selections default to the current project
+        let project_hash = if self.all_projects || !self.ids.is_empty() {
+            None
+        } else {
+            Some(resolve_project_hash(self.project_hash)?)
+        };
+        let failed_before = self.older_than_days.map(|d| {
+            let cutoff = ai_smartness::time_utils::now() - chrono::Duration::days(d as i64);
+            ai_smartness::time_utils::to_sqlite(&cutoff)
+        });
+        Ok(DeadLetterFilter {
+            ids: if self.ids.is_empty() { None } else { Some(self.ids.clone()) },
+            project_hash,
+            agent_id: self.agent_id.map(String::from),
+            stage,
+            failed_before,
+        })
+    }
+}
+
+fn open_journal() -> Result<Connection> {
+    let path = path_utils::capture_journal_path();
+    let conn = open_connection(&path, ConnectionRole::Cli)
+        .context("Failed to open capture journal")?;
+    migrations::migrate_journal_db(&conn).context("Capture journal migration failed")?;
+    Ok(conn)
+}
+
+/// `captures failed list` — dead-lettered captures, most recent first.
+pub fn list(selection: &FailedSelection, limit: usize, json: bool) -> Result<()> {
+    let conn = open_journal()?;
+    let letters = DeadLetterStore::list(&conn, &selection.filter()?, limit).context("Dead letter query failed")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&letters)?);
+        return Ok(());
+    }
+    if letters.is_empty() {
+        println!("No failed captures.");
+        return Ok(());
+    }
+
+    println!(
+        "{:>6}  {:<20}  {:<16}  {:<12}  {:<10}  {:>8}  {}",
+        "ID", "FAILED", "AGENT", "STAGE", "SOURCE", "CHARS", "ERROR"
+    );
+    println!("{}", "-".repeat(110));
+    for l in &letters {
+        let failed: String = l.failed_at.chars().take(19).collect::<String>().replace('T', " ");
+        let error: String = l.error.chars().take(48).collect();
+        println!(
+            "{:>6}  {:<20}  {:<16}  {:<12}  {:<10}  {:>8}  {}",
+            l.id, failed, l.agent_id, l.stage.as_str(), l.source_type, l.content.chars().count(), error
+        );
+    }
+    println!("\n{} failed captures", letters.len());
+    Ok(())
+}
+
+/// `captures failed show` — one dead letter with its full content.
+pub fn show(id: i64, json: bool) -> Result<()> {
+    let conn = open_journal()?;
+    let letter = DeadLetterStore::get(&conn, id)?
+        .with_context(|| format!("No failed capture with id {}", id))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&letter)?);
+        return Ok(());
+    }
+    println!("Failed capture #{}", letter.id);
+    println!("  Project:   {}", letter.project_hash);
+    println!("  Agent:     {}", letter.agent_id);
+    println!("  Source:    {}{}", letter.source_type, if letter.is_prompt { " (prompt)" } else { "" });
+    if let Some(ref fp) = letter.file_path {
+        println!("  File:      {}", fp);
+    }
+    if let Some(ref tid) = letter.enrich_thread_id {
+        println!("  Enriching: {}", tid);
+    }
+    println!("  Stage:     {}", letter.stage.as_str());
+    println!("  Attempts:  {}", letter.attempts);
+    println!("  Enqueued:  {}", letter.enqueued_at);
+    println!("  Failed:    {}", letter.failed_at);
+    println!("  Error:     {}", letter.error);
+    println!("\n{}", letter.content);
+    Ok(())
+}
+
+/// `captures failed retry` — requeue selected captures.
+///
+/// Through the daemon when it runs (processed right away), otherwise moved
+/// back into the capture journal, replayed on the next daemon start.
+pub fn retry(selection: &FailedSelection, all: bool) -> Result<()> {
+    require_selection(selection, all)?;
+    let filter = selection.filter()?;
+
+    if matches!(daemon_ipc_client::ping(), Ok(true)) {
+        // failed_before is not an IPC filter: resolve the selection to ids locally
+        let params = if filter.failed_before.is_some() {
+            let conn = open_journal()?;
+            let ids: Vec<i64> = DeadLetterStore::list(&conn, &filter, usize::MAX)?.iter().map(|l| l.id).collect();
+            if ids.is_empty() {
+                println!("No failed captures match.");
+                return Ok(());
+            }
+            serde_json::json!({ "ids": ids })
+        } else {
+            serde_json::json!({
+                "ids": filter.ids,
+                "project_hash": filter.project_hash,
+                "agent_id": filter.agent_id,
+                "stage": filter.stage.map(|s| s.as_str()),
+                "all": true,
+            })
+        };
+        let result = daemon_ipc_client::send_method("requeue_failed", params)
+            .context("Daemon requeue failed")?;
+        let n = result.get("requeued").and_then(|v| v.as_u64()).unwrap_or(0);
+        println!("Requeued {} failed captures (processing now).", n);
+    } else {
+        let conn = open_journal()?;
+        let n = DeadLetterStore::restore(&conn, &filter).context("Requeue failed")?;
+        println!("Requeued {} failed captures (daemon not running: replayed on next start).", n);
+    }
+    Ok(())
+}
+
+/// `captures failed purge` — delete selected dead letters for good.
+pub fn purge(selection: &FailedSelection, all: bool) -> Result<()> {
+    require_selection(selection, all)?;
+    let conn = open_journal()?;
+    let n = DeadLetterStore::purge(&conn, &selection.filter()?).context("Purge failed")?;
+    println!("Purged {} failed captures.", n);
+    Ok(())
+}
+
+/// Retry/purge never act on everything by accident.
+fn require_selection(selection: &FailedSelection, all: bool) -> Result<()> {
+    if !all && selection.ids.is_empty() && selection.agent_id.is_none() && selection.stage.is_none()
+        && selection.older_than_days.is_none()
+    {
+        anyhow::bail!("Nothing selected: pass capture ids, --agent/--stage/--older-than-days, or --all");
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anyhow::{bail, Context, Result};
+use ai_smartness::processing::daemon_ipc_client;
 
 /// `config show` — display the full config.
 pub fn run_show() -> Result<()> {
@@ -72,6 +73,14 @@ pub fn run_set(key: &str, value: &str) -> Result<()> {
     ai_smartness::config_sync::sync_guardian_configs(&config);
 
     println!("{} = {}", key, serde_json::to_string(&parsed)?);
+
+    // Apply to the running daemon, if any
+    if matches!(daemon_ipc_client::ping(), Ok(true)) {
+        match daemon_ipc_client::reload_config() {
+            Ok(_) => println!("Daemon config reloaded."),
+            Err(e) => eprintln!("Warning: daemon kept its current config: {}", e),
+        }
+    }
     Ok(())
 }
 
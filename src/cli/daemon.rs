@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use ai_smartness::processing::daemon_ipc_client;
+use ai_smartness::processing::ipc_auth::{self, Scope};
 use ai_smartness::storage::path_utils;
 
 /// Start the global daemon (no project/agent args — serves all).
@@ -101,6 +102,75 @@ pub fn restart() -> Result<()> {
     }
 }
 
+/// Print an IPC token on stdout (scope details on stderr), so that
+/// `AI_SMARTNESS_IPC_TOKEN=$(ai-smartness daemon token --agent dev)` works.
+pub fn token(
+    agent_id: Option<&str>,
+    project_hash: Option<&str>,
+    project_wide: bool,
+    admin: bool,
+    rotate: bool,
+) -> Result<()> {
+    if rotate {
+        let path = path_utils::ipc_secret_path();
+        if path.exists() {
+            std::fs::remove_file(&path).with_context(|| format!("Cannot remove {}", path.display()))?;
+        }
+        eprintln!("IPC secret rotated: previously issued tokens are revoked.");
+    }
+    let secret = ipc_auth::load_or_create_secret().map_err(|e| anyhow::anyhow!("{}", e))?;
+    if rotate {
+        let issued = ipc_auth::issue_registered_agent_tokens().map_err(|e| anyhow::anyhow!("{}", e))?;
+        eprintln!("Agent tokens re-issued: {}", issued);
+    }
+
+    let scope = if admin {
+        Scope::Admin
+    } else {
+        let ph = super::resolve_project_hash(project_hash)?;
+        if project_wide {
+            Scope::Project(ph)
+        } else {
+            let aid = super::resolve_agent_id(agent_id, &ph)?;
+            Scope::Agent { project_hash: ph, agent_id: aid }
+        }
+    };
+    eprintln!("Token scope: {} (export it as {})", scope, ipc_auth::TOKEN_ENV);
+    println!("{}", ipc_auth::mint(&secret, &[scope]));
+    Ok(())
+}
+
+/// Reload config.json + daemon_config.json in the running daemon (no restart).
+pub fn reload() -> Result<()> {
+    if !matches!(daemon_ipc_client::ping(), Ok(true)) {
+        anyhow::bail!("Daemon not running (config is read when it starts)");
+    }
+    let report = daemon_ipc_client::reload_config()
+        .map_err(|e| anyhow::anyhow!("Config reload rejected: {}", e))?;
+    let list = |field: &str| -> Vec<String> {
+        report
+            .get(field)
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default()
+    };
+
+    let changed = list("changed");
+    if changed.is_empty() {
+        println!("Config reloaded: no changes.");
+    } else {
+        println!("Config reloaded: {}", changed.join(", "));
+    }
+    if report.get("model_reload").and_then(|v| v.as_bool()).unwrap_or(false) {
+        println!("Local model parameters changed: model reloading in the background.");
+    }
+    let restart_required = list("restart_required");
+    if !restart_required.is_empty() {
+        println!("Restart required to apply: {}", restart_required.join(", "));
+    }
+    Ok(())
+}
+
 pub fn status() -> Result<()> {
     let data_dir = path_utils::data_dir();
 
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use ai_smartness::config::{parse_decay_strategy, DaemonConfig, GuardianConfig};
+use ai_smartness::intelligence::decayer::Decayer;
+use ai_smartness::storage::database::{open_connection, ConnectionRole};
+use ai_smartness::storage::path_utils;
+
+use super::{resolve_agent_id, resolve_project_hash};
+
+/// `decay simulate` — project weights of a sample of real threads over N days.
+///
+/// Read-only: replays the decay model at the daemon's prune interval on copies.
+pub fn simulate(
+    days: u32,
+    sample: usize,
+    strategy: Option<&str>,
+    project_hash: Option<&str>,
+    agent_id: Option<&str>,
+) -> Result<()> {
+    anyhow::ensure!(days > 0, "--days must be at least 1");
+    let hash = resolve_project_hash(project_hash)?;
+    let agent_id = resolve_agent_id(agent_id, &hash)?;
+    let db_path = path_utils::agent_db_path(&hash, &agent_id);
+    let conn = open_connection(&db_path, ConnectionRole::Cli)
+        .context("Failed to open agent database")?;
+
+    let forced = match strategy {
+        Some(s) => Some(parse_decay_strategy(s).with_context(|| {
+            format!("Unknown strategy '{}' (HalfLife, ActR, SpacedRepetition)", s)
+        })?),
+        None => None,
+    };
+
+    let config_path = path_utils::data_dir().join("config.json");
+    let guardian: GuardianConfig = std::fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let step_secs = DaemonConfig::load().prune_interval_secs;
+
+    let projections = Decayer::simulate(&conn, &guardian.decay, forced, days, sample, step_secs)
+        .context("Decay simulation failed")?;
+
+    if projections.is_empty() {
+        println!("No active threads to simulate.");
+        return Ok(());
+    }
+
+    let checkpoints: Vec<u32> = [1u32, 3, 7, 14, 30, 60, 90]
+        .into_iter()
+        .filter(|d| *d < days)
+        .chain(std::iter::once(days))
+        .collect();
+
+    print!("{:<12}  {:<30}  {:<16}  {:>6}", "ID", "TITLE", "STRATEGY", "NOW");
+    for d in &checkpoints {
+        print!("  {:>6}", format!("d{}", d));
+    }
+    println!("  {:<10}", "SUSPENDED");
+    println!("{}", "-".repeat(72 + checkpoints.len() * 8 + 12));
+
+    for p in &projections {
+        let id_short = if p.thread_id.len() > 11 { &p.thread_id[..11] } else { &p.thread_id };
+        let title: String = if p.title.chars().count() > 30 {
+            format!("{}...", p.title.chars().take(27).collect::<String>())
+        } else {
+            p.title.clone()
+        };
+        print!("{:<12}  {:<30}  {:<16}  {:>6.3}", id_short, title, p.strategy.as_str(), p.current_weight);
+        for d in &checkpoints {
+            let w = p.daily.get(*d as usize - 1).copied().unwrap_or(0.0);
+            print!("  {:>6.3}", w);
+        }
+        let suspended = p.suspended_on_day.map(|d| format!("day {}", d)).unwrap_or_else(|| "-".to_string());
+        println!("  {:<10}", suspended);
+    }
+
+    println!(
+        "\n{} threads, {} days, one decay pass every {}s, assuming no further access.",
+        projections.len(), days, step_secs
+    );
+
+    Ok(())
+}
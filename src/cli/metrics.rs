@@ -0,0 +1,17 @@
+use anyhow::{Context, Result};
+use ai_smartness::processing::daemon_ipc_client;
+
+/// `metrics` — print the daemon metrics in OpenMetrics text format.
+pub fn run() -> Result<()> {
+    if !matches!(daemon_ipc_client::ping(), Ok(true)) {
+        anyhow::bail!("Daemon not running (metrics live in the daemon process)");
+    }
+    let result = daemon_ipc_client::send_method("metrics", serde_json::json!({}))
+        .context("Daemon metrics request failed")?;
+    let text = result
+        .get("text")
+        .and_then(|v| v.as_str())
+        .context("Daemon returned no metrics")?;
+    print!("{}", text);
+    Ok(())
+}
@@ -1,16 +1,21 @@
 pub mod agent;
+pub mod audit;
 pub mod bridges;
+pub mod captures;
 pub mod config;
 pub mod controller;
 pub mod daemon;
+pub mod decay;
 pub mod hardware;
 pub mod init;
+pub mod metrics;
 pub mod project;
 pub mod rule;
 pub mod search;
 pub mod model;
 pub mod setup_onnx;
 pub mod status;
+pub mod tasks;
 pub mod threads;
 
 use anyhow::{Context, Result};
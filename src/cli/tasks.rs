@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use ai_smartness::config::DaemonConfig;
+use ai_smartness::processing::daemon_ipc_client;
+use serde_json::Value;
+
+use crate::daemon::scheduler::{self, Scope};
+
+use super::{resolve_agent_id, resolve_project_hash};
+
+/// How long `daemon tasks run` waits for the task (gossip or backup of a big
+/// memory can take minutes).
+const RUN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// `daemon tasks` — periodic task schedules with their last/next run.
+///
+/// Read from the daemon when it runs, otherwise from the persisted task state.
+pub fn list(json: bool) -> Result<()> {
+    let snapshot = if matches!(daemon_ipc_client::ping(), Ok(true)) {
+        daemon_ipc_client::send_method("tasks", serde_json::json!({}))
+            .context("Daemon tasks request failed")?
+    } else {
+        scheduler::snapshot(&DaemonConfig::load())
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        return Ok(());
+    }
+
+    let tasks = snapshot.get("tasks").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    println!(
+        "{:<28}  {:<30}  {:<16}  {:<16}  {:>9}  {}",
+        "TASK", "SCHEDULE", "LAST RUN", "NEXT RUN", "DURATION", "STATUS"
+    );
+    println!("{}", "-".repeat(120));
+    for t in &tasks {
+        let str_of = |field: &str| t.get(field).and_then(|v| v.as_str()).unwrap_or("");
+        let name = match str_of("scope") {
+            "global" => format!("{} (global)", str_of("name")),
+            _ => str_of("name").to_string(),
+        };
+        let duration = t
+            .get("last_duration_ms")
+            .and_then(|v| v.as_u64())
+            .map(|ms| format!("{}ms", ms))
+            .unwrap_or_else(|| "-".into());
+        let status = match (t.get("last_run_at").and_then(|v| v.as_str()), str_of("last_error")) {
+            (_, error) if !error.is_empty() => format!("error: {}", error.chars().take(40).collect::<String>()),
+            (None, _) => "never run".to_string(),
+            (Some(_), _) if t.get("last_deferred").and_then(|v| v.as_u64()).unwrap_or(0) > 0 => {
+                format!("ok, {} agents deferred", t["last_deferred"])
+            }
+            (Some(_), _) => "ok".to_string(),
+        };
+        println!(
+            "{:<28}  {:<30}  {:<16}  {:<16}  {:>9}  {}",
+            name,
+            str_of("schedule"),
+            short_time(t.get("last_run_at")),
+            if t.get("enabled").and_then(|v| v.as_bool()) == Some(false) { "disabled".into() } else { short_time(t.get("next_run_at")) },
+            duration,
+            status
+        );
+    }
+    Ok(())
+}
+
+/// `daemon tasks run <name>` — run one task now in the daemon, for one agent
+/// (agent tasks) or once (global tasks).
+pub fn run(name: &str, agent_id: Option<&str>, project_hash: Option<&str>) -> Result<()> {
+    let task = scheduler::find(name).with_context(|| {
+        let names: Vec<&str> = scheduler::TASKS.iter().map(|t| t.name).collect();
+        format!("Unknown task '{}' (tasks: {})", name, names.join(", "))
+    })?;
+    if !matches!(daemon_ipc_client::ping(), Ok(true)) {
+        anyhow::bail!("Daemon not running (tasks run in the daemon process)");
+    }
+
+    let params = match task.scope {
+        Scope::Agent => {
+            let ph = resolve_project_hash(project_hash)?;
+            let aid = resolve_agent_id(agent_id, &ph)?;
+            serde_json::json!({ "task": name, "project_hash": ph, "agent_id": aid })
+        }
+        Scope::Global => serde_json::json!({ "task": name }),
+    };
+    let result = daemon_ipc_client::send_method_with_timeout("run_task", params, RUN_TIMEOUT)
+        .context("Daemon run_task failed")?;
+
+    let target = match result.get("agent_id").and_then(|v| v.as_str()) {
+        Some(aid) => format!(" for {}", aid),
+        None => String::new(),
+    };
+    let ms = result.get("duration_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+    match result.get("error").and_then(|v| v.as_str()) {
+        Some(error) => anyhow::bail!("Task '{}'{} failed after {}ms: {}", name, target, ms, error),
+        None => println!("Task '{}'{} done in {}ms.", name, target, ms),
+    }
+    Ok(())
+}
+
+/// RFC 3339 timestamp → local "YYYY-MM-DD HH:MM" ("-" when absent).
+fn short_time(value: Option<&Value>) -> String {
+    value
+        .and_then(|v| v.as_str())
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".into())
+}
@@ -724,6 +724,19 @@ pub struct GossipConfig {
     /// Minimum weight for propagated bridges.
     #[serde(default = "default_propagation_min_weight")]
     pub propagation_min_weight: f64,             // default: 0.10
+    // LLM relation classification (high-weight pairs only)
+    /// Ask the LLM for the relation type of new high-weight concept pairs.
+    #[serde(default)]
+    pub llm_relation_enabled: bool,              // default: false
+    /// Minimum pair weight before the LLM is consulted.
+    #[serde(default = "default_llm_relation_min_weight")]
+    pub llm_relation_min_weight: f64,            // default: 0.50
+    /// Minimum LLM confidence to override the heuristic relation.
+    #[serde(default = "default_llm_relation_min_confidence")]
+    pub llm_relation_min_confidence: f64,        // default: 0.60
+    /// Max LLM classifications per gossip cycle (per agent).
+    #[serde(default = "default_llm_relation_max_per_cycle")]
+    pub llm_relation_max_per_cycle: usize,       // default: 5
 }
 
 fn default_min_bridges() -> usize { 5 }
@@ -734,6 +747,9 @@ fn default_merge_auto_threshold() -> f64 { 0.85 }
 fn default_propagation_max_depth() -> u32 { 2 }
 fn default_propagation_decay_factor() -> f64 { 0.5 }
 fn default_propagation_min_weight() -> f64 { 0.10 }
+fn default_llm_relation_min_weight() -> f64 { 0.50 }
+fn default_llm_relation_min_confidence() -> f64 { 0.60 }
+fn default_llm_relation_max_per_cycle() -> usize { 5 }
 
 impl Default for GossipConfig {
     fn default() -> Self {
@@ -758,6 +774,10 @@ impl Default for GossipConfig {
             propagation_max_depth: 2,
             propagation_decay_factor: 0.5,
             propagation_min_weight: 0.10,
+            llm_relation_enabled: false,
+            llm_relation_min_weight: 0.50,
+            llm_relation_min_confidence: 0.60,
+            llm_relation_max_per_cycle: 5,
         }
     }
 }
@@ -804,6 +824,77 @@ pub struct EngramConfig {
     /// Threads with fewer total bridges are filtered out (display + retrieval alignment).
     #[serde(default = "default_min_bridge_connections")]
     pub min_bridge_connections: usize,           // default: 5
+
+    /// Multi-hop spreading activation used by ai_recall (hops > 0).
+    #[serde(default)]
+    pub spreading: SpreadingActivationConfig,
+}
+
+/// Spreading activation over bridges + continuity edges (ai_recall `hops`).
+///
+/// Activation of a neighbor = activation × edge_factor × hop_decay, where
+/// edge_factor = weight × confidence × relation factor for bridges, or
+/// `continuity_factor` for continuity parent/child edges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpreadingActivationConfig {
+    /// Hop cap applied to the `hops` parameter. Default: 3
+    #[serde(default = "default_spreading_max_hops")]
+    pub max_hops: u32,
+    /// Multiplicative decay applied at every hop. Default: 0.7
+    #[serde(default = "default_spreading_hop_decay")]
+    pub hop_decay: f64,
+    /// Threads below this activation are dropped. Default: 0.05
+    #[serde(default = "default_spreading_min_activation")]
+    pub min_activation: f64,
+    /// Max indirect threads returned. Default: 10
+    #[serde(default = "default_spreading_max_results")]
+    pub max_results: usize,
+    /// Edge factor for continuity parent/child edges. 0.0 disables. Default: 0.6
+    #[serde(default = "default_spreading_continuity_factor")]
+    pub continuity_factor: f64,
+    // Per-relation factors for ThinkBridge edges
+    #[serde(default = "default_spreading_extends_factor")]
+    pub extends_factor: f64,                     // default: 1.0
+    #[serde(default = "default_spreading_depends_factor")]
+    pub depends_factor: f64,                     // default: 0.9
+    #[serde(default = "default_spreading_replaces_factor")]
+    pub replaces_factor: f64,                    // default: 0.8
+    #[serde(default = "default_spreading_child_of_factor")]
+    pub child_of_factor: f64,                    // default: 0.7
+    #[serde(default = "default_spreading_contradicts_factor")]
+    pub contradicts_factor: f64,                 // default: 0.6
+    #[serde(default = "default_spreading_sibling_factor")]
+    pub sibling_factor: f64,                     // default: 0.5
+}
+
+fn default_spreading_max_hops() -> u32 { 3 }
+fn default_spreading_hop_decay() -> f64 { 0.7 }
+fn default_spreading_min_activation() -> f64 { 0.05 }
+fn default_spreading_max_results() -> usize { 10 }
+fn default_spreading_continuity_factor() -> f64 { 0.6 }
+fn default_spreading_extends_factor() -> f64 { 1.0 }
+fn default_spreading_depends_factor() -> f64 { 0.9 }
+fn default_spreading_replaces_factor() -> f64 { 0.8 }
+fn default_spreading_child_of_factor() -> f64 { 0.7 }
+fn default_spreading_contradicts_factor() -> f64 { 0.6 }
+fn default_spreading_sibling_factor() -> f64 { 0.5 }
+
+impl Default for SpreadingActivationConfig {
+    fn default() -> Self {
+        Self {
+            max_hops: 3,
+            hop_decay: 0.7,
+            min_activation: 0.05,
+            max_results: 10,
+            continuity_factor: 0.6,
+            extends_factor: 1.0,
+            depends_factor: 0.9,
+            replaces_factor: 0.8,
+            child_of_factor: 0.7,
+            contradicts_factor: 0.6,
+            sibling_factor: 0.5,
+        }
+    }
 }
 
 /// Per-validator weight configuration.
@@ -880,6 +971,7 @@ impl Default for EngramConfig {
             max_archived_scan: 50,
             hash_index_enabled: true,
             min_bridge_connections: 5,
+            spreading: SpreadingActivationConfig::default(),
         }
     }
 }
@@ -960,6 +1052,65 @@ impl Default for ThreadMatchingConfig {
 // DECAY & LIFECYCLE CONFIG
 // ============================================================================
 
+/// Thread decay model used by the Decayer.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
+pub enum DecayStrategyKind {
+    /// Exponential half-life by importance + orphan acceleration (historic behaviour).
+    #[default]
+    HalfLife,
+    /// ACT-R base-level activation over the full access history.
+    ActR,
+    /// Spaced-repetition stability, growing with each successful reuse.
+    SpacedRepetition,
+}
+
+impl DecayStrategyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HalfLife => "HalfLife",
+            Self::ActR => "ActR",
+            Self::SpacedRepetition => "SpacedRepetition",
+        }
+    }
+}
+
+pub fn parse_decay_strategy(s: &str) -> Option<DecayStrategyKind> {
+    match s {
+        "HalfLife" | "half_life" => Some(DecayStrategyKind::HalfLife),
+        "ActR" | "act_r" | "actr" => Some(DecayStrategyKind::ActR),
+        "SpacedRepetition" | "spaced_repetition" => Some(DecayStrategyKind::SpacedRepetition),
+        _ => None,
+    }
+}
+
+/// Declarative retention rule — evaluated in order, first match wins.
+///
+/// Matchers (all set matchers must match): origin, label, max_weight.
+/// Example: `{"name": "fetch-ttl", "origin": "fetch", "archive_after_hours": 24, "delete_after_hours": 168}`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct RetentionRule {
+    /// Rule name (reported per rule in ai_status / GUI).
+    pub name: String,
+    /// Origin type to match ("fetch", "command", "file_read", ...). None = any.
+    #[serde(default)]
+    pub origin: Option<String>,
+    /// Label to match. None = any.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Match only threads with weight strictly below this. None = any.
+    #[serde(default)]
+    pub max_weight: Option<f64>,
+    /// Exempt matching threads from decay, suspension and archival.
+    #[serde(default)]
+    pub never_decay: bool,
+    /// Archive after this many hours of inactivity (overrides archive_after_hours).
+    #[serde(default)]
+    pub archive_after_hours: Option<f64>,
+    /// Delete (thread + messages + bridges) after this many hours of inactivity.
+    #[serde(default)]
+    pub delete_after_hours: Option<f64>,
+}
+
 /// Decay & lifecycle parameters — configurable via GUI.
 /// Controls thread weight decay, orphan acceleration, bridge decay, and archival.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -994,6 +1145,39 @@ pub struct DecayConfig {
     /// Hours after suspension before archival. Default: 72.0
     #[serde(default = "default_archive_after_hours")]
     pub archive_after_hours: f64,
+    /// Default thread decay strategy. Default: HalfLife
+    #[serde(default)]
+    pub strategy: DecayStrategyKind,
+    /// Per-label strategy overrides (label → strategy). Checked before origin.
+    #[serde(default)]
+    pub strategy_by_label: HashMap<String, DecayStrategyKind>,
+    /// Per-origin strategy overrides (origin type, e.g. "file_read" → strategy).
+    #[serde(default)]
+    pub strategy_by_origin: HashMap<String, DecayStrategyKind>,
+    /// ACT-R decay exponent d (time in days). Default: 0.5
+    #[serde(default = "default_actr_decay")]
+    pub actr_decay: f64,
+    /// ACT-R retrieval threshold τ. Default: -1.0
+    #[serde(default = "default_actr_threshold")]
+    pub actr_threshold: f64,
+    /// ACT-R activation noise s (logistic scale). Default: 0.4
+    #[serde(default = "default_actr_noise")]
+    pub actr_noise: f64,
+    /// Spaced repetition: stability in days before any reuse. Default: 1.0
+    #[serde(default = "default_sr_initial_stability")]
+    pub sr_initial_stability: f64,
+    /// Spaced repetition: stability multiplier per successful reuse. Default: 2.0
+    #[serde(default = "default_sr_stability_growth")]
+    pub sr_stability_growth: f64,
+    /// Spaced repetition: stability cap in days. Default: 180.0
+    #[serde(default = "default_sr_max_stability")]
+    pub sr_max_stability: f64,
+    /// Retention rules (label/origin lifecycle overrides). Default: none
+    #[serde(default)]
+    pub retention_rules: Vec<RetentionRule>,
+    /// Evaluate retention rules and report counts without archiving/deleting. Default: false
+    #[serde(default)]
+    pub retention_dry_run: bool,
 }
 
 fn default_thread_suspend_threshold() -> f64 { 0.1 }
@@ -1006,6 +1190,12 @@ fn default_bridge_half_life() -> f64 { 4.0 }
 fn default_bridge_death_threshold() -> f64 { 0.05 }
 fn default_bridge_use_boost() -> f64 { 0.1 }
 fn default_archive_after_hours() -> f64 { 72.0 }
+fn default_actr_decay() -> f64 { 0.5 }
+fn default_actr_threshold() -> f64 { -1.0 }
+fn default_actr_noise() -> f64 { 0.4 }
+fn default_sr_initial_stability() -> f64 { 1.0 }
+fn default_sr_stability_growth() -> f64 { 2.0 }
+fn default_sr_max_stability() -> f64 { 180.0 }
 
 impl Default for DecayConfig {
     fn default() -> Self {
@@ -1020,6 +1210,17 @@ impl Default for DecayConfig {
             bridge_death_threshold: 0.05,
             bridge_use_boost: 0.1,
             archive_after_hours: 72.0,
+            strategy: DecayStrategyKind::HalfLife,
+            strategy_by_label: HashMap::new(),
+            strategy_by_origin: HashMap::new(),
+            actr_decay: 0.5,
+            actr_threshold: -1.0,
+            actr_noise: 0.4,
+            sr_initial_stability: 1.0,
+            sr_stability_growth: 2.0,
+            sr_max_stability: 180.0,
+            retention_rules: Vec::new(),
+            retention_dry_run: false,
         }
     }
 }
@@ -1064,6 +1265,14 @@ pub struct GuardianConfig {
     #[serde(default)]
     pub decay: DecayConfig,
 
+    // --- Tool policy (per-role allow/deny/confirm on MCP tools) ---
+    #[serde(default)]
+    pub tool_policy: crate::registry::policy::ToolPolicyConfig,
+
+    // --- Audit log (tool calls, memory mutations) ---
+    #[serde(default)]
+    pub audit: AuditConfig,
+
     // --- Global settings ---
     pub enabled: bool,
     /// LLM backend selection: Local, Remote, or Auto.
@@ -1101,6 +1310,8 @@ impl Default for GuardianConfig {
             hooks: HooksConfig::default(),
             capture: CaptureConfig::default(),
             decay: DecayConfig::default(),
+            tool_policy: crate::registry::policy::ToolPolicyConfig::default(),
+            audit: AuditConfig::default(),
             enabled: true,
             llm_backend: LlmBackend::Local,
             local_model_size: LocalModelSize::default(),
@@ -1131,6 +1342,40 @@ impl Default for HooksConfig {
     }
 }
 
+// ============================================================================
+// AUDIT CONFIG (tool call / memory mutation log)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Record tool calls in the registry audit_log table.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Entries older than this are pruned by the daemon. 0 = keep forever.
+    #[serde(default = "default_audit_retention_days")]
+    pub retention_days: u32,
+    /// Hard cap on stored entries (oldest pruned first). 0 = unlimited.
+    #[serde(default = "default_audit_max_entries")]
+    pub max_entries: usize,
+    /// Tools never recorded (e.g. noisy read-only tools).
+    #[serde(default)]
+    pub exclude_tools: Vec<String>,
+}
+
+fn default_audit_retention_days() -> u32 { 30 }
+fn default_audit_max_entries() -> usize { 100_000 }
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: default_audit_retention_days(),
+            max_entries: default_audit_max_entries(),
+            exclude_tools: Vec::new(),
+        }
+    }
+}
+
 // ============================================================================
 // CAPTURE CONFIG (per-tool toggles)
 // ============================================================================
@@ -1176,7 +1421,34 @@ pub struct PoolConfig {
     pub max_age_secs: u64,
     /// Interval for .done cleanup (seconds).
     pub cleanup_interval_secs: u64,
-}
+    /// Sustained tool captures per agent and minute (0 = unlimited).
+    /// Prompt captures are never rate limited.
+    #[serde(default = "default_agent_rate_per_min")]
+    pub agent_rate_per_min: u32,
+    /// Tool captures an agent may send in a burst above its rate.
+    #[serde(default = "default_agent_burst")]
+    pub agent_burst: u32,
+    /// Max queued tool captures per agent, in memory and spilled (0 = unlimited).
+    #[serde(default = "default_agent_max_backlog")]
+    pub agent_max_backlog: usize,
+    /// Share of the capture workers per agent_id (default 1). An agent with
+    /// weight 3 is served three times as often as a busy weight-1 agent.
+    #[serde(default)]
+    pub agent_weights: HashMap<String, u32>,
+    /// Dead letters (failed captures) older than this are pruned by the
+    /// daemon. 0 = keep forever.
+    #[serde(default = "default_dead_letter_retention_days")]
+    pub dead_letter_retention_days: u32,
+    /// Hard cap on stored dead letters (oldest pruned first). 0 = unlimited.
+    #[serde(default = "default_dead_letter_max")]
+    pub dead_letter_max: usize,
+}
+
+fn default_agent_rate_per_min() -> u32 { 120 }
+fn default_agent_burst() -> u32 { 30 }
+fn default_agent_max_backlog() -> usize { 50 }
+fn default_dead_letter_retention_days() -> u32 { 30 }
+fn default_dead_letter_max() -> usize { 10_000 }
 
 impl Default for PoolConfig {
     fn default() -> Self {
@@ -1185,10 +1457,23 @@ impl Default for PoolConfig {
             max_bytes_per_file: 512_000,  // 512 KB
             max_age_secs: 120,            // 2 min
             cleanup_interval_secs: 300,   // 5 min
+            agent_rate_per_min: default_agent_rate_per_min(),
+            agent_burst: default_agent_burst(),
+            agent_max_backlog: default_agent_max_backlog(),
+            agent_weights: HashMap::new(),
+            dead_letter_retention_days: default_dead_letter_retention_days(),
+            dead_letter_max: default_dead_letter_max(),
         }
     }
 }
 
+impl PoolConfig {
+    /// Scheduling weight of an agent (at least 1).
+    pub fn agent_weight(&self, agent_id: &str) -> u32 {
+        self.agent_weights.get(agent_id).copied().unwrap_or(1).max(1)
+    }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CaptureToolToggles {
@@ -1282,9 +1567,46 @@ pub struct DaemonConfig {
     /// Each worker processes one capture at a time. Default: min(cpu_cores, 4).
     #[serde(default = "default_capture_workers")]
     pub capture_workers: usize,
-    /// Max buffered capture jobs. If full, new jobs are dropped (non-blocking).
+    /// Max capture jobs held in memory. If full, new jobs wait in the on-disk
+    /// capture journal until room frees up (dropped only if the journal is unavailable).
     #[serde(default = "default_capture_queue_capacity")]
     pub capture_queue_capacity: usize,
+    /// Port of the local REST API on 127.0.0.1 (None = disabled).
+    /// Requests authenticate with the bearer token in `{data_dir}/api_token`.
+    #[serde(default)]
+    pub http_api_port: Option<u16>,
+    /// Schedule overrides of periodic tasks, by task name (see `daemon tasks`).
+    /// Tasks not listed keep their default schedule.
+    #[serde(default)]
+    pub tasks: HashMap<String, TaskSchedule>,
+}
+
+/// Schedule of one periodic daemon task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskSchedule {
+    pub enabled: bool,
+    /// Seconds between runs (None = task default). Ignored when `cron` is set.
+    pub interval_secs: Option<u64>,
+    /// Cron expression, 5 fields (minute hour day-of-month month day-of-week), local time.
+    pub cron: Option<String>,
+    /// Random delay (0..=jitter_secs) added to each scheduled run.
+    pub jitter_secs: u64,
+    /// Time budget of one run across agents: agents not reached in time are
+    /// deferred to the next run.
+    pub max_runtime_secs: Option<u64>,
+}
+
+impl Default for TaskSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: None,
+            cron: None,
+            jitter_secs: 0,
+            max_runtime_secs: None,
+        }
+    }
 }
 
 fn default_capture_workers() -> usize {
@@ -1307,6 +1629,8 @@ impl Default for DaemonConfig {
             gossip_cross_project: false,
             capture_workers: default_capture_workers(),
             capture_queue_capacity: default_capture_queue_capacity(),
+            http_api_port: None,
+            tasks: HashMap::new(),
         }
     }
 }
@@ -1417,6 +1741,19 @@ impl GuardianConfig {
                 if let Some(v) = g.get("propagation_min_weight").and_then(|v| v.as_f64()) {
                     gc.gossip.propagation_min_weight = v;
                 }
+                // LLM relation classification
+                if let Some(v) = g.get("llm_relation_enabled").and_then(|v| v.as_bool()) {
+                    gc.gossip.llm_relation_enabled = v;
+                }
+                if let Some(v) = g.get("llm_relation_min_weight").and_then(|v| v.as_f64()) {
+                    gc.gossip.llm_relation_min_weight = v;
+                }
+                if let Some(v) = g.get("llm_relation_min_confidence").and_then(|v| v.as_f64()) {
+                    gc.gossip.llm_relation_min_confidence = v;
+                }
+                if let Some(v) = g.get("llm_relation_max_per_cycle").and_then(|v| v.as_u64()) {
+                    gc.gossip.llm_relation_max_per_cycle = v as usize;
+                }
             }
 
             // Recall config
@@ -1479,6 +1816,21 @@ impl GuardianConfig {
                     if let Some(v) = w.get("concept_coherence").and_then(|v| v.as_f64()) { vw.concept_coherence = v; }
                     if let Some(v) = w.get("truncation_penalty").and_then(|v| v.as_f64()) { vw.truncation_penalty = v; }
                 }
+                // Spreading activation (multi-hop recall)
+                if let Some(sp) = eg.get("spreading").and_then(|v| v.as_object()) {
+                    let sa = &mut gc.engram.spreading;
+                    if let Some(v) = sp.get("max_hops").and_then(|v| v.as_u64()) { sa.max_hops = v as u32; }
+                    if let Some(v) = sp.get("hop_decay").and_then(|v| v.as_f64()) { sa.hop_decay = v; }
+                    if let Some(v) = sp.get("min_activation").and_then(|v| v.as_f64()) { sa.min_activation = v; }
+                    if let Some(v) = sp.get("max_results").and_then(|v| v.as_u64()) { sa.max_results = v as usize; }
+                    if let Some(v) = sp.get("continuity_factor").and_then(|v| v.as_f64()) { sa.continuity_factor = v; }
+                    if let Some(v) = sp.get("extends_factor").and_then(|v| v.as_f64()) { sa.extends_factor = v; }
+                    if let Some(v) = sp.get("depends_factor").and_then(|v| v.as_f64()) { sa.depends_factor = v; }
+                    if let Some(v) = sp.get("replaces_factor").and_then(|v| v.as_f64()) { sa.replaces_factor = v; }
+                    if let Some(v) = sp.get("child_of_factor").and_then(|v| v.as_f64()) { sa.child_of_factor = v; }
+                    if let Some(v) = sp.get("contradicts_factor").and_then(|v| v.as_f64()) { sa.contradicts_factor = v; }
+                    if let Some(v) = sp.get("sibling_factor").and_then(|v| v.as_f64()) { sa.sibling_factor = v; }
+                }
             }
 
             // Decay & Lifecycle config
@@ -1493,6 +1845,52 @@ impl GuardianConfig {
                 if let Some(v) = d.get("bridge_death_threshold").and_then(|v| v.as_f64()) { gc.decay.bridge_death_threshold = v; }
                 if let Some(v) = d.get("bridge_use_boost").and_then(|v| v.as_f64()) { gc.decay.bridge_use_boost = v; }
                 if let Some(v) = d.get("archive_after_hours").and_then(|v| v.as_f64()) { gc.decay.archive_after_hours = v; }
+                if let Some(v) = d.get("strategy").and_then(|v| v.as_str()).and_then(parse_decay_strategy) { gc.decay.strategy = v; }
+                if let Some(m) = d.get("strategy_by_label").and_then(|v| v.as_object()) {
+                    for (k, val) in m {
+                        if let Some(kind) = val.as_str().and_then(parse_decay_strategy) {
+                            gc.decay.strategy_by_label.insert(k.clone(), kind);
+                        }
+                    }
+                }
+                if let Some(m) = d.get("strategy_by_origin").and_then(|v| v.as_object()) {
+                    for (k, val) in m {
+                        if let Some(kind) = val.as_str().and_then(parse_decay_strategy) {
+                            gc.decay.strategy_by_origin.insert(k.clone(), kind);
+                        }
+                    }
+                }
+                if let Some(v) = d.get("actr_decay").and_then(|v| v.as_f64()) { gc.decay.actr_decay = v; }
+                if let Some(v) = d.get("actr_threshold").and_then(|v| v.as_f64()) { gc.decay.actr_threshold = v; }
+                if let Some(v) = d.get("actr_noise").and_then(|v| v.as_f64()) { gc.decay.actr_noise = v; }
+                if let Some(v) = d.get("sr_initial_stability").and_then(|v| v.as_f64()) { gc.decay.sr_initial_stability = v; }
+                if let Some(v) = d.get("sr_stability_growth").and_then(|v| v.as_f64()) { gc.decay.sr_stability_growth = v; }
+                if let Some(v) = d.get("sr_max_stability").and_then(|v| v.as_f64()) { gc.decay.sr_max_stability = v; }
+                if let Some(v) = d.get("retention_rules") {
+                    match serde_json::from_value::<Vec<RetentionRule>>(v.clone()) {
+                        Ok(rules) => gc.decay.retention_rules = rules,
+                        Err(e) => tracing::warn!(error = %e, "Invalid decay.retention_rules, ignoring"),
+                    }
+                }
+                if let Some(v) = d.get("retention_dry_run").and_then(|v| v.as_bool()) { gc.decay.retention_dry_run = v; }
+            }
+
+            // Tool policy (rules are structured: parsed as a whole)
+            if let Some(v) = s.get("tool_policy") {
+                match serde_json::from_value::<crate::registry::policy::ToolPolicyConfig>(v.clone()) {
+                    Ok(tp) => gc.tool_policy = tp,
+                    Err(e) => tracing::warn!(error = %e, "Invalid tool_policy, ignoring"),
+                }
+            }
+
+            // Audit log
+            if let Some(a) = s.get("audit").and_then(|v| v.as_object()) {
+                if let Some(v) = a.get("enabled").and_then(|v| v.as_bool()) { gc.audit.enabled = v; }
+                if let Some(v) = a.get("retention_days").and_then(|v| v.as_u64()) { gc.audit.retention_days = v as u32; }
+                if let Some(v) = a.get("max_entries").and_then(|v| v.as_u64()) { gc.audit.max_entries = v as usize; }
+                if let Some(v) = a.get("exclude_tools").and_then(|v| v.as_array()) {
+                    gc.audit.exclude_tools = v.iter().filter_map(|t| t.as_str().map(String::from)).collect();
+                }
             }
 
             // Per-task detailed overrides
@@ -1560,6 +1958,44 @@ impl GuardianConfig {
             tracing::warn!(field = "decay.orphan_halving_hours", "Must be > 0, resetting to default");
             self.decay.orphan_halving_hours = 6.0;
         }
+        if self.decay.actr_decay <= 0.0 {
+            tracing::warn!(field = "decay.actr_decay", "Must be > 0, resetting to default");
+            self.decay.actr_decay = 0.5;
+        }
+        if self.decay.actr_noise <= 0.0 {
+            tracing::warn!(field = "decay.actr_noise", "Must be > 0, resetting to default");
+            self.decay.actr_noise = 0.4;
+        }
+        if self.decay.sr_initial_stability <= 0.0 {
+            tracing::warn!(field = "decay.sr_initial_stability", "Must be > 0, resetting to default");
+            self.decay.sr_initial_stability = 1.0;
+        }
+        if self.decay.sr_stability_growth < 1.0 {
+            tracing::warn!(field = "decay.sr_stability_growth", "Must be >= 1, resetting to default");
+            self.decay.sr_stability_growth = 2.0;
+        }
+        if self.decay.sr_max_stability < self.decay.sr_initial_stability {
+            tracing::warn!(field = "decay.sr_max_stability", "Must be >= sr_initial_stability, resetting to default");
+            self.decay.sr_max_stability = 180.0_f64.max(self.decay.sr_initial_stability);
+        }
+        for rule in &mut self.decay.retention_rules {
+            if let Some(ref mut w) = rule.max_weight {
+                *w = w.clamp(0.0, 1.0);
+            }
+            for (field, hours) in [
+                ("archive_after_hours", &mut rule.archive_after_hours),
+                ("delete_after_hours", &mut rule.delete_after_hours),
+            ] {
+                if matches!(hours, Some(h) if *h <= 0.0) {
+                    tracing::warn!(rule = %rule.name, field, "Retention hours must be > 0, ignoring");
+                    *hours = None;
+                }
+            }
+            if rule.never_decay && rule.delete_after_hours.is_some() {
+                tracing::warn!(rule = %rule.name, "never_decay rule cannot delete — dropping delete_after_hours");
+                rule.delete_after_hours = None;
+            }
+        }
         // Ordering: min < max
         if self.decay.thread_min_half_life >= self.decay.thread_max_half_life {
             tracing::warn!(
@@ -1575,6 +2011,8 @@ impl GuardianConfig {
         clamp_01(&mut self.gossip.concept_min_bridge_weight, "gossip.concept_min_bridge_weight");
         clamp_01(&mut self.gossip.propagation_decay_factor, "gossip.propagation_decay_factor");
         clamp_01(&mut self.gossip.propagation_min_weight, "gossip.propagation_min_weight");
+        clamp_01(&mut self.gossip.llm_relation_min_weight, "gossip.llm_relation_min_weight");
+        clamp_01(&mut self.gossip.llm_relation_min_confidence, "gossip.llm_relation_min_confidence");
 
         // min_bridges: at least 1
         if self.gossip.min_bridges == 0 {
@@ -1622,6 +2060,19 @@ impl GuardianConfig {
             );
             std::mem::swap(&mut self.engram.weak_inject_min_votes, &mut self.engram.strong_inject_min_votes);
         }
+
+        // Spreading activation: factors in [0,1], hops in [1..5]
+        let sa = &mut self.engram.spreading;
+        sa.max_hops = sa.max_hops.clamp(1, 5);
+        clamp_01(&mut sa.hop_decay, "engram.spreading.hop_decay");
+        clamp_01(&mut sa.min_activation, "engram.spreading.min_activation");
+        clamp_01(&mut sa.continuity_factor, "engram.spreading.continuity_factor");
+        clamp_01(&mut sa.extends_factor, "engram.spreading.extends_factor");
+        clamp_01(&mut sa.depends_factor, "engram.spreading.depends_factor");
+        clamp_01(&mut sa.replaces_factor, "engram.spreading.replaces_factor");
+        clamp_01(&mut sa.child_of_factor, "engram.spreading.child_of_factor");
+        clamp_01(&mut sa.contradicts_factor, "engram.spreading.contradicts_factor");
+        clamp_01(&mut sa.sibling_factor, "engram.spreading.sibling_factor");
     }
 
     fn validate_thread_matching(&mut self) {
@@ -1772,6 +2223,9 @@ mod tests {
         assert_eq!(cfg.max_bytes_per_file, 512_000);
         assert_eq!(cfg.max_age_secs, 120);
         assert_eq!(cfg.cleanup_interval_secs, 300);
+        assert_eq!(cfg.agent_rate_per_min, 120);
+        assert_eq!(cfg.agent_max_backlog, 50);
+        assert_eq!(cfg.agent_weight("anyone"), 1);
     }
 
     #[test]
@@ -26,6 +26,8 @@ pub const BRIDGE_WEAK_THRESHOLD: f64 = 0.15;
 pub const GOSSIP_OVERLAP_WEIGHT: f64 = 0.5;
 pub const GOSSIP_RICHNESS_WEIGHT: f64 = 0.5;
 pub const GOSSIP_RICHNESS_NORMALIZATION: f64 = 5.0;
+/// Cross-project gossip: max related threads kept per thread and project pair.
+pub const CROSS_GOSSIP_MAX_PER_THREAD: usize = 3;
 
 // === Content Limits ===
 pub const CONTENT_LIMIT_DEFAULT: usize = 2_000;
@@ -67,11 +69,15 @@ pub const DAEMON_WAL_AUTOCHECKPOINT: u32 = 1_000;
 pub const HOOK_WAL_AUTOCHECKPOINT: u32 = 100;
 pub const PRUNE_INTERVAL_SECS: u64 = 300; // 5 min — all periodic tasks
 pub const MAX_IPC_THREADS: usize = 32;    // max concurrent IPC handler threads
+pub const MAX_EVENT_SUBSCRIBERS: usize = 8; // open `subscribe` streams (each holds an IPC thread)
+pub const EVENT_SUBSCRIBER_BUFFER: usize = 256; // events buffered per subscriber before dropping
+pub const EVENT_HEARTBEAT_SECS: u64 = 15; // idle `subscribe` streams get a heartbeat (detects closed clients)
 
 // === Connection Pool (global daemon) ===
 pub const POOL_MAX_IDLE_SECS: u64 = 1800;           // 30 min before eviction
 pub const POOL_MAX_CONNECTIONS: usize = 50;          // max simultaneous agent connections
 pub const POOL_EVICTION_CHECK_SECS: u64 = 300;      // check for idle connections every 5 min
+pub const AGENT_TOKEN_RESYNC_SECS: u64 = 600;      // full IPC token sync even without registry change
 
 // === Attachments ===
 pub const MAX_ATTACHMENT_SIZE_BYTES: usize = 32_768;     // 32 KB per file
@@ -1,30 +1,57 @@
-//! Capture Queue — per-agent FIFO with parallel multi-agent processing.
+//! Capture Queue — per-agent FIFO with weighted fair multi-agent processing.
 //!
 //! Architecture:
-//!   IPC handler → CaptureQueue::submit(job) → per-agent VecDeque (instant)
+//!   IPC handler → CaptureQueue::submit(job) → journal + per-agent lane (instant)
 //!   N worker threads pick jobs from agents not currently being processed.
-//!   Within each agent, jobs are strictly FIFO — no interleaving.
+//!   Within each agent, prompts go first, then the other jobs — each strictly FIFO.
 //!   Across agents, workers process in parallel for GPU saturation.
 //!
 //! Designed for dozens of parallel agents: each agent's captures are processed
 //! sequentially (preserving continuity chain), while different agents run in
 //! parallel across worker threads.
+//!
+//! Fairness: free workers serve agents with a queued prompt first, then by
+//! weighted fair queuing (virtual finish tags, weights from
+//! `capture.pool.agent_weights`), so an agent flooding tool captures only gets
+//! its share of the workers. Tool captures are also limited per agent at
+//! submit (`agent_rate_per_min`/`agent_burst` token bucket, `agent_max_backlog`):
+//! over the limit they are refused as `Throttled` with a retry delay the hook
+//! honours before sending again. Prompts are never throttled.
+//!
+//! Durability: every job is first appended to the capture journal
+//! (`capture_journal.db`) and removed only when its worker is done with it, so
+//! a crash or restart replays unfinished jobs (at-least-once). When the
+//! in-memory queue is full, jobs stay journal-only ("spilled") and are loaded
+//! as room frees up. Without a journal (open failure) the queue degrades to
+//! memory-only and drops jobs when full.
+//!
+//! Jobs that fail for good (extraction errors, exhausted enrichment retries,
+//! pool write errors, repeated interruptions) are moved to the dead letters
+//! with their error, and can be requeued with `requeue_failed`.
 
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread::JoinHandle;
+use std::time::Instant;
 
-use ai_smartness::config::GuardianConfig;
+use ai_smartness::config::PoolConfig;
+use ai_smartness::metrics::{self, Metric};
 use ai_smartness::registry::registry::AgentRegistry;
+use ai_smartness::storage::capture_journal::{CaptureJournal, JournalEntry};
+use ai_smartness::storage::dead_letters::{DeadLetterFilter, DeadLetterStore, FailureStage};
 use ai_smartness::storage::database::{open_connection, ConnectionRole};
+use ai_smartness::storage::migrations;
 use ai_smartness::storage::path_utils;
+use rusqlite::Connection;
 
 use super::connection_pool::{AgentKey, ConnectionPool};
+use super::events::{self, EventKind};
 use super::pool_writer;
 use super::processor;
 
 /// A capture job to be processed asynchronously by a worker.
+#[derive(Debug)]
 pub struct CaptureJob {
     pub key: AgentKey,
     pub source_type: String,
@@ -39,91 +66,542 @@ pub struct CaptureJob {
     pub enrichment_retry: u8,
 }
 
+impl CaptureJob {
+    fn to_journal(&self) -> JournalEntry {
+        let mut entry = JournalEntry::new(&self.key.project_hash, &self.key.agent_id, &self.source_type, &self.content);
+        entry.file_path = self.file_path.clone();
+        entry.is_prompt = self.is_prompt;
+        entry.session_id = self.session_id.clone();
+        entry.enrich_thread_id = self.enrich_thread_id.clone();
+        entry.enrichment_retry = self.enrichment_retry;
+        entry
+    }
+
+    /// Count this job in a capture metric (labels: project, agent, source_type + `extra`).
+    pub fn count(&self, metric: &Metric, extra: &[(&str, &str)]) {
+        let mut labels = vec![
+            ("project", self.key.project_hash.as_str()),
+            ("agent", self.key.agent_id.as_str()),
+            ("source_type", self.source_type.as_str()),
+        ];
+        labels.extend_from_slice(extra);
+        metric.inc(&labels);
+    }
+
+    fn from_journal(entry: JournalEntry) -> Self {
+        Self {
+            key: AgentKey { project_hash: entry.project_hash, agent_id: entry.agent_id },
+            source_type: entry.source_type,
+            content: entry.content,
+            file_path: entry.file_path,
+            is_prompt: entry.is_prompt,
+            session_id: entry.session_id,
+            enrich_thread_id: entry.enrich_thread_id,
+            enrichment_retry: entry.enrichment_retry,
+        }
+    }
+}
+
 /// Maximum automatic retries for enrichment jobs.
 /// Beyond this, the failure is logged and left for user/agent to investigate.
 const MAX_ENRICHMENT_RETRIES: u8 = 2;
 
-/// Per-agent FIFO queues with processing exclusion.
+/// Maximum processing attempts of a journaled job since it was last queued.
+/// Attempts interrupted by a crash count too, so a job that keeps killing the
+/// daemon is given up; deliberate retries reset the count (see `requeue`).
+const MAX_CAPTURE_ATTEMPTS: u32 = 3;
+
+/// Retry delay suggested to an agent refused for its backlog.
+const BACKLOG_RETRY_MS: u64 = 5_000;
+
+/// What the queue did with a submitted job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submitted {
+    /// Held in memory, picked up by the next free worker.
+    Queued,
+    /// In-memory queue full: kept in the journal, loaded when room frees up.
+    Spilled,
+    /// Refused (not journaled): the agent is over its rate limit or backlog cap.
+    Throttled { reason: Throttle, retry_after_ms: u64 },
+}
+
+/// Why a tool capture was throttled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Throttle {
+    /// Over `agent_rate_per_min` (burst used up).
+    Rate,
+    /// `agent_max_backlog` jobs already queued.
+    Backlog,
+}
+
+impl Throttle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rate => "rate_limit",
+            Self::Backlog => "backlog",
+        }
+    }
+}
+
+/// Per-agent submit limits and weight, from `capture.pool`.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    weight: u32,
+    /// Tool captures per minute (0 = unlimited).
+    rate_per_min: u32,
+    burst: u32,
+    /// Queued tool captures (0 = unlimited).
+    max_backlog: usize,
+}
+
+impl Limits {
+    fn for_agent(cfg: &PoolConfig, key: &AgentKey) -> Self {
+        Self {
+            weight: cfg.agent_weight(&key.agent_id),
+            rate_per_min: cfg.agent_rate_per_min,
+            burst: cfg.agent_burst,
+            max_backlog: cfg.agent_max_backlog,
+        }
+    }
+
+    /// Same weight, no rate limit nor backlog cap.
+    fn unlimited(self) -> Self {
+        Self { rate_per_min: 0, max_backlog: 0, ..self }
+    }
+}
+
+/// A job in the in-memory queue, with its journal row.
+struct QueuedJob {
+    journal_id: Option<i64>,
+    /// Attempt number once taken by a worker (1 = first).
+    attempts: u32,
+    /// When it entered the in-memory queue (wait time stats).
+    queued_at: Instant,
+    job: CaptureJob,
+}
+
+impl QueuedJob {
+    fn new(journal_id: Option<i64>, job: CaptureJob) -> Self {
+        Self { journal_id, attempts: 0, queued_at: Instant::now(), job }
+    }
+}
+
+/// One agent's queued jobs: prompts are served before its other jobs.
+#[derive(Default)]
+struct AgentLane {
+    prompts: VecDeque<QueuedJob>,
+    jobs: VecDeque<QueuedJob>,
+}
+
+impl AgentLane {
+    fn len(&self) -> usize {
+        self.prompts.len() + self.jobs.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push(&mut self, job: QueuedJob) {
+        if job.job.is_prompt {
+            self.prompts.push_back(job);
+        } else {
+            self.jobs.push_back(job);
+        }
+    }
+
+    fn pop(&mut self) -> Option<QueuedJob> {
+        self.prompts.pop_front().or_else(|| self.jobs.pop_front())
+    }
+}
+
+/// Fair queuing state and counters of one agent (kept while it is idle).
+struct AgentShare {
+    weight: u32,
+    /// Virtual finish time of its last served job.
+    finish: f64,
+    /// Rate limit token bucket.
+    tokens: f64,
+    refilled_at: Instant,
+    /// Spilled jobs of this agent (best effort: unknown for replayed jobs).
+    spilled: usize,
+    served: u64,
+    throttled: u64,
+    wait_ms_total: u64,
+    wait_ms_max: u64,
+}
+
+impl AgentShare {
+    fn new(weight: u32) -> Self {
+        Self {
+            weight,
+            finish: 0.0,
+            // Full bucket: capped to the burst on first use
+            tokens: f64::MAX,
+            refilled_at: Instant::now(),
+            spilled: 0,
+            served: 0,
+            throttled: 0,
+            wait_ms_total: 0,
+            wait_ms_max: 0,
+        }
+    }
+
+    /// Take a rate limit token, or the delay until one is available.
+    fn take_token(&mut self, limits: &Limits) -> Result<(), u64> {
+        if limits.rate_per_min == 0 {
+            return Ok(());
+        }
+        let per_ms = limits.rate_per_min as f64 / 60_000.0;
+        let elapsed_ms = self.refilled_at.elapsed().as_secs_f64() * 1000.0;
+        self.tokens = (self.tokens + elapsed_ms * per_ms).min(limits.burst.max(1) as f64);
+        self.refilled_at = Instant::now();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - self.tokens) / per_ms).ceil() as u64)
+        }
+    }
+}
+
+/// Per-agent lanes with processing exclusion and fair scheduling.
 struct ShardedInner {
-    /// Per-agent job queues. Jobs within each agent are strictly FIFO.
-    agent_queues: HashMap<AgentKey, VecDeque<CaptureJob>>,
-    /// Agents currently being processed by a worker. Only one worker per agent.
-    processing: HashSet<AgentKey>,
+    /// Per-agent job lanes (dropped when empty).
+    agent_queues: HashMap<AgentKey, AgentLane>,
+    /// Agents currently being processed by a worker (only one worker per
+    /// agent), with the journal row of their in-flight job.
+    processing: HashMap<AgentKey, Option<i64>>,
+    /// Fair queuing state per agent seen since startup.
+    shares: HashMap<AgentKey, AgentShare>,
+    /// Virtual time: latest start tag handed to a worker.
+    clock: f64,
+    /// Journal-only jobs waiting for room in memory.
+    spilled: usize,
+    /// A refill is reading the journal (one at a time).
+    refilling: bool,
     /// Set to true when shutdown is requested.
     shutdown: bool,
 }
 
+impl ShardedInner {
+    fn queued_count(&self) -> usize {
+        self.agent_queues.values().map(|q| q.len()).sum()
+    }
+
+    fn share(&mut self, key: &AgentKey) -> &mut AgentShare {
+        self.shares.entry(key.clone()).or_insert_with(|| AgentShare::new(1))
+    }
+
+    fn push(&mut self, job: QueuedJob) {
+        let key = job.job.key.clone();
+        if self.agent_queues.get(&key).is_none_or(|l| l.is_empty()) {
+            // Agent becomes backlogged: no credit for the time it was idle
+            let clock = self.clock;
+            let share = self.share(&key);
+            share.finish = share.finish.max(clock);
+        }
+        self.agent_queues.entry(key).or_default().push(job);
+    }
+
+    /// Refuse a tool capture over its agent's backlog cap or rate limit.
+    fn admit(&mut self, job: &CaptureJob, limits: &Limits) -> Result<(), Submitted> {
+        let queued = self.agent_queues.get(&job.key).map_or(0, |l| l.len());
+        let share = self.shares.entry(job.key.clone()).or_insert_with(|| AgentShare::new(limits.weight));
+        share.weight = limits.weight;
+        if job.is_prompt {
+            return Ok(());
+        }
+        let refused = if limits.max_backlog > 0 && queued + share.spilled >= limits.max_backlog {
+            Some((Throttle::Backlog, BACKLOG_RETRY_MS))
+        } else {
+            share.take_token(limits).err().map(|ms| (Throttle::Rate, ms))
+        };
+        match refused {
+            Some((reason, retry_after_ms)) => {
+                share.throttled += 1;
+                Err(Submitted::Throttled { reason, retry_after_ms })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Next agent to serve: one with a queued prompt first, then the lowest
+    /// virtual finish tag (ties broken by key for determinism).
+    fn next_agent(&self) -> Option<AgentKey> {
+        self.agent_queues
+            .iter()
+            .filter(|(key, lane)| !lane.is_empty() && !self.processing.contains_key(*key))
+            .map(|(key, lane)| (lane.prompts.is_empty(), self.finish_tag(key), key))
+            .min_by(|a, b| {
+                a.0.cmp(&b.0)
+                    .then(a.1.total_cmp(&b.1))
+                    .then_with(|| (&a.2.project_hash, &a.2.agent_id).cmp(&(&b.2.project_hash, &b.2.agent_id)))
+            })
+            .map(|(_, _, key)| key.clone())
+    }
+
+    /// Virtual finish time of the agent's next job.
+    fn finish_tag(&self, key: &AgentKey) -> f64 {
+        let (finish, weight) = self.shares.get(key).map_or((0.0, 1), |s| (s.finish, s.weight));
+        finish + 1.0 / weight.max(1) as f64
+    }
+
+    /// Charge a job handed to a worker to its agent's share.
+    fn charge(&mut self, job: &QueuedJob) {
+        let wait_ms = job.queued_at.elapsed().as_millis() as u64;
+        let share = self.share(&job.job.key);
+        let start = share.finish;
+        share.finish = start + 1.0 / share.weight.max(1) as f64;
+        share.served += 1;
+        share.wait_ms_total += wait_ms;
+        share.wait_ms_max = share.wait_ms_max.max(wait_ms);
+        self.clock = self.clock.max(start);
+    }
+}
+
 /// Thread-safe sharded queue: per-agent FIFO + cross-agent parallelism.
 struct ShardedQueue {
     inner: Mutex<ShardedInner>,
     notify: Condvar,
     capacity: usize,
+    /// Durable journal. Never locked while `inner` is held.
+    journal: Option<Mutex<Connection>>,
 }
 
 impl ShardedQueue {
-    fn new(capacity: usize) -> Self {
-        Self {
+    fn new(capacity: usize, journal: Option<Connection>) -> Self {
+        let queue = Self {
             inner: Mutex::new(ShardedInner {
                 agent_queues: HashMap::new(),
-                processing: HashSet::new(),
+                processing: HashMap::new(),
+                shares: HashMap::new(),
+                clock: 0.0,
+                spilled: 0,
+                refilling: false,
                 shutdown: false,
             }),
             notify: Condvar::new(),
             capacity,
+            journal: journal.map(Mutex::new),
+        };
+        queue.replay();
+        queue
+    }
+
+    /// Run `f` on the journal connection (None without a journal or on error).
+    /// Never called with `inner` held: journal I/O does not block the queue.
+    fn with_journal<T>(&self, what: &str, f: impl FnOnce(&Connection) -> ai_smartness::AiResult<T>) -> Option<T> {
+        let conn = self.journal.as_ref()?.lock().unwrap_or_else(|e| e.into_inner());
+        match f(&conn) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                tracing::error!(error = %e, "Capture journal: {} failed", what);
+                None
+            }
+        }
+    }
+
+    /// Startup: reload jobs left in the journal by the previous daemon.
+    fn replay(&self) {
+        let Some(count) = self.with_journal("replay", CaptureJournal::spill_all) else { return };
+        if count == 0 {
+            return;
+        }
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).spilled = count;
+        self.refill();
+        tracing::info!(
+            replayed = count,
+            in_memory = self.counts().0,
+            "Capture journal: replaying unfinished captures"
+        );
+    }
+
+    /// Load spilled jobs into free in-memory slots (oldest first). The journal
+    /// is read with the queue unlocked, one refill at a time.
+    fn refill(&self) {
+        let (room, spilled_before) = {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            let room = self.capacity.saturating_sub(inner.queued_count());
+            if inner.spilled == 0 || room == 0 || inner.refilling {
+                return;
+            }
+            inner.refilling = true;
+            (room, inner.spilled)
+        };
+        let entries = self.with_journal("reload", |c| CaptureJournal::take_spilled(c, room));
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.refilling = false;
+        let Some(entries) = entries else { return };
+        inner.spilled = if entries.len() < room {
+            // Journal drained — resync the counter (jobs spilled meanwhile stay counted)
+            inner.spilled.saturating_sub(spilled_before)
+        } else {
+            inner.spilled.saturating_sub(entries.len())
+        };
+        for entry in entries {
+            let journal_id = Some(entry.id);
+            let job = CaptureJob::from_journal(entry);
+            let share = inner.share(&job.key);
+            share.spilled = share.spilled.saturating_sub(1);
+            inner.push(QueuedJob::new(journal_id, job));
         }
+        self.notify.notify_all();
     }
 
-    /// Submit a job to the per-agent queue. Returns Err if total capacity exceeded.
-    fn submit(&self, job: CaptureJob) -> Result<(), CaptureJob> {
+    /// Count a job kept in the journal only. Done after the journal write: a
+    /// refill in between may load the row early, which only over-counts until
+    /// the next drained refill resyncs the counter.
+    fn count_spilled(&self, key: &AgentKey) -> Submitted {
         let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
-        let total: usize = inner.agent_queues.values().map(|q| q.len()).sum();
-        if total >= self.capacity {
+        inner.spilled += 1;
+        inner.share(key).spilled += 1;
+        drop(inner);
+        // Room may have freed up during the journal write
+        self.refill();
+        Submitted::Spilled
+    }
+
+    /// Submit a job: check its agent's limits, journal it, then queue it in
+    /// memory if there is room.
+    /// With `spill = false`, a full queue refuses the job instead of spilling it.
+    fn submit(&self, job: CaptureJob, spill: bool, limits: &Limits) -> Result<Submitted, CaptureJob> {
+        let full = {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(throttled) = inner.admit(&job, limits) {
+                return Ok(throttled);
+            }
+            inner.queued_count() >= self.capacity
+        };
+        if full && !spill {
             return Err(job);
         }
-        inner.agent_queues
-            .entry(job.key.clone())
-            .or_default()
-            .push_back(job);
+        let journal_id = self.with_journal("append", |c| CaptureJournal::append(c, &job.to_journal(), full));
+        if full {
+            return match journal_id {
+                Some(_) => Ok(self.count_spilled(&job.key)),
+                None => Err(job),
+            };
+        }
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).push(QueuedJob::new(journal_id, job));
         self.notify.notify_one();
-        Ok(())
+        Ok(Submitted::Queued)
     }
 
-    /// Block until a job is available from an agent not currently being processed.
-    /// Returns None on shutdown.
-    fn take(&self) -> Option<CaptureJob> {
-        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
-        loop {
-            if inner.shutdown {
-                return None;
+    /// Put the in-flight job of `job.job.key` back for another attempt.
+    /// Its journal row is kept (not acked by the following `done`), with its
+    /// attempt count reset: only interrupted attempts count toward
+    /// MAX_CAPTURE_ATTEMPTS, retries have their own limit.
+    fn requeue(&self, job: QueuedJob) -> Result<Submitted, QueuedJob> {
+        let full = {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(slot) = inner.processing.get_mut(&job.job.key) {
+                *slot = None;
+            }
+            inner.queued_count() >= self.capacity
+        };
+        if let Some(id) = job.journal_id {
+            let retry = job.job.enrichment_retry;
+            let kept = self.with_journal("requeue", |c| CaptureJournal::requeue(c, id, retry, full));
+            if full && kept.is_some() {
+                return Ok(self.count_spilled(&job.job.key));
             }
-            // Find an agent with pending jobs that's NOT being processed
-            let ready_agent = inner.agent_queues.iter()
-                .find(|(key, queue)| !queue.is_empty() && !inner.processing.contains(*key))
-                .map(|(key, _)| key.clone());
-
-            if let Some(agent_key) = ready_agent {
-                let job = inner.agent_queues
-                    .get_mut(&agent_key)
-                    .and_then(|q| q.pop_front());
-                if let Some(job) = job {
-                    inner.processing.insert(agent_key.clone());
-                    // Clean up empty queues to avoid unbounded HashMap growth
-                    if inner.agent_queues.get(&agent_key).map_or(false, |q| q.is_empty()) {
-                        inner.agent_queues.remove(&agent_key);
+        }
+        if full {
+            // Not journaled: ack the row if any, the job is lost
+            if let Some(id) = job.journal_id {
+                self.with_journal("ack", |c| CaptureJournal::ack(c, id));
+            }
+            return Err(job);
+        }
+        let job = QueuedJob { attempts: 0, queued_at: Instant::now(), ..job };
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).push(job);
+        self.notify.notify_one();
+        Ok(Submitted::Queued)
+    }
+
+    /// Block until a job is available from an agent not currently being processed.
+    /// The agent is picked fairly (see module doc). Returns None on shutdown.
+    fn take(&self) -> Option<QueuedJob> {
+        let mut job = {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            loop {
+                if inner.shutdown {
+                    return None;
+                }
+                // Fairest agent with pending jobs that's NOT being processed
+                if let Some(agent_key) = inner.next_agent() {
+                    let job = inner.agent_queues
+                        .get_mut(&agent_key)
+                        .and_then(|q| q.pop());
+                    if let Some(job) = job {
+                        inner.charge(&job);
+                        inner.processing.insert(agent_key.clone(), job.journal_id);
+                        // Clean up empty queues to avoid unbounded HashMap growth
+                        if inner.agent_queues.get(&agent_key).is_some_and(|q| q.is_empty()) {
+                            inner.agent_queues.remove(&agent_key);
+                        }
+                        break job;
                     }
-                    return Some(job);
                 }
+                // No ready work — wait for notification
+                inner = self.notify.wait(inner).unwrap_or_else(|e| e.into_inner());
             }
-            // No ready work — wait for notification
-            inner = self.notify.wait(inner).unwrap_or_else(|e| e.into_inner());
+        };
+        job.attempts = match job.journal_id {
+            Some(id) => self
+                .with_journal("attempt", |c| CaptureJournal::begin_attempt(c, id))
+                .unwrap_or(job.attempts + 1),
+            None => job.attempts + 1,
+        };
+        self.refill();
+        Some(job)
+    }
+
+    /// Record why the in-flight job of `key` failed (kept on its journal row).
+    fn record_error(&self, key: &AgentKey, error: &str) {
+        let id = self.inner.lock().unwrap_or_else(|e| e.into_inner()).processing.get(key).copied().flatten();
+        if let Some(id) = id {
+            self.with_journal("error", |c| CaptureJournal::record_error(c, id, error));
         }
     }
 
-    /// Mark agent as done processing. Wakes workers waiting for this agent.
+    /// Mark agent as done processing: its in-flight job leaves the journal.
+    /// Wakes workers waiting for this agent.
     fn done(&self, key: &AgentKey) {
-        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
-        inner.processing.remove(key);
+        let id = self.inner.lock().unwrap_or_else(|e| e.into_inner()).processing.remove(key).flatten();
         self.notify.notify_all();
+        if let Some(id) = id {
+            self.with_journal("ack", |c| CaptureJournal::ack(c, id));
+        }
+        self.refill();
+    }
+
+    /// Mark agent as done after a final failure: its in-flight job moves to
+    /// the dead letters (replayable) instead of leaving the journal.
+    fn fail(&self, job: &CaptureJob, stage: FailureStage, error: &str) {
+        let key = &job.key;
+        job.count(&metrics::CAPTURES_DROPPED, &[("reason", stage.as_str())]);
+        let id = self.inner.lock().unwrap_or_else(|e| e.into_inner()).processing.remove(key).flatten();
+        self.notify.notify_all();
+        let dead_letter_id = id.and_then(|id| {
+            self.with_journal("dead letter", |c| DeadLetterStore::bury(c, id, stage, error)).flatten()
+        });
+        self.refill();
+        events::emit(
+            EventKind::CaptureFailed,
+            Some(key),
+            serde_json::json!({"stage": stage.as_str(), "error": error, "dead_letter_id": dead_letter_id}),
+        );
+    }
+
+    /// Move matching dead letters back into the journal and queue them.
+    /// Returns the number of jobs requeued (None without a journal).
+    fn restore_dead_letters(&self, filter: &DeadLetterFilter) -> Option<usize> {
+        let restored = self.with_journal("dead letter restore", |c| DeadLetterStore::restore(c, filter))?;
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).spilled += restored;
+        self.refill();
+        Some(restored)
     }
 
     /// Signal shutdown to all waiting workers.
@@ -133,17 +611,59 @@ impl ShardedQueue {
         self.notify.notify_all();
     }
 
-    /// Current total pending jobs across all agents.
-    fn pending_count(&self) -> usize {
+    /// (in memory, in flight, spilled) job counts.
+    fn counts(&self) -> (usize, usize, usize) {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        (inner.queued_count(), inner.processing.len(), inner.spilled)
+    }
+
+    /// Per-agent fairness stats (queue_status), sorted by agent.
+    fn fairness(&self) -> Vec<serde_json::Value> {
         let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
-        inner.agent_queues.values().map(|q| q.len()).sum::<usize>()
-            + inner.processing.len()
+        let mut keys: Vec<&AgentKey> = inner.shares.keys().collect();
+        keys.sort_by(|a, b| (&a.project_hash, &a.agent_id).cmp(&(&b.project_hash, &b.agent_id)));
+        keys.into_iter()
+            .map(|key| {
+                let share = &inner.shares[key];
+                let lane = inner.agent_queues.get(key);
+                serde_json::json!({
+                    "project_hash": key.project_hash,
+                    "agent_id": key.agent_id,
+                    "weight": share.weight,
+                    "queued": lane.map_or(0, |l| l.len()),
+                    "queued_prompts": lane.map_or(0, |l| l.prompts.len()),
+                    "spilled": share.spilled,
+                    "in_flight": inner.processing.contains_key(key),
+                    "served": share.served,
+                    "throttled": share.throttled,
+                    "wait_ms_avg": share.wait_ms_total.checked_div(share.served).unwrap_or(0),
+                    "wait_ms_max": share.wait_ms_max,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Open the capture journal, or None (memory-only queue) on failure.
+pub(super) fn open_journal() -> Option<Connection> {
+    let path = path_utils::capture_journal_path();
+    let conn = open_connection(&path, ConnectionRole::Daemon)
+        .and_then(|c| migrations::migrate_journal_db(&c).map(|()| c));
+    match conn {
+        Ok(c) => Some(c),
+        Err(e) => {
+            tracing::error!(
+                path = %path.display(),
+                error = %e,
+                "Capture journal unavailable — queue is memory-only (not crash-safe)"
+            );
+            None
+        }
     }
 }
 
 /// Live stats for the capture queue, shared across workers.
 pub struct QueueStats {
-    pending: AtomicUsize,
     processed: AtomicU64,
     errors: AtomicU64,
     workers: usize,
@@ -152,7 +672,6 @@ pub struct QueueStats {
 impl QueueStats {
     fn new(workers: usize) -> Self {
         Self {
-            pending: AtomicUsize::new(0),
             processed: AtomicU64::new(0),
             errors: AtomicU64::new(0),
             workers,
@@ -176,14 +695,14 @@ impl CaptureQueue {
         num_workers: usize,
         capacity: usize,
     ) -> Self {
-        let queue = Arc::new(ShardedQueue::new(capacity));
+        let queue = Arc::new(ShardedQueue::new(capacity, open_journal()));
         let stats = Arc::new(QueueStats::new(num_workers));
         let mut handles = Vec::with_capacity(num_workers);
 
         tracing::info!(
             workers = num_workers,
             capacity = capacity,
-            "Capture queue initialized (per-agent FIFO sharding)"
+            "Capture queue initialized (per-agent FIFO, weighted fair sharing)"
         );
 
         for worker_id in 0..num_workers {
@@ -220,32 +739,78 @@ impl CaptureQueue {
     }
 
     /// Submit a capture job. Returns immediately.
-    /// Returns Err if queue is full (job is NOT processed — acceptable under load).
-    pub fn submit(&self, job: CaptureJob) -> Result<(), CaptureJob> {
-        self.stats.pending.fetch_add(1, Ordering::Relaxed);
-        match self.queue.submit(job) {
-            Ok(()) => {
-                tracing::debug!("Capture job queued");
-                Ok(())
-            }
-            Err(job) => {
-                self.stats.pending.fetch_sub(1, Ordering::Relaxed);
-                tracing::warn!(
-                    pending = self.stats.pending.load(Ordering::Relaxed),
-                    "Capture queue full — job dropped"
-                );
-                Err(job)
+    /// Tool captures over their agent's limits are refused as `Throttled`.
+    /// A full queue spills the job to the journal; Err only if it could not be
+    /// journaled either (job is NOT processed).
+    pub fn submit(&self, job: CaptureJob) -> Result<Submitted, CaptureJob> {
+        job.count(&metrics::CAPTURES_RECEIVED, &[]);
+        let limits = Limits::for_agent(&super::live_config::guardian().capture.pool, &job.key);
+        let key = job.key.clone();
+        let source_type = job.source_type.clone();
+        let result = self.queue.submit(job, true, &limits);
+        match &result {
+            Err(job) => job.count(&metrics::CAPTURES_DROPPED, &[("reason", "queue_full")]),
+            Ok(Submitted::Throttled { reason, retry_after_ms }) => {
+                metrics::CAPTURES_DROPPED.inc(&[
+                    ("project", key.project_hash.as_str()),
+                    ("agent", key.agent_id.as_str()),
+                    ("source_type", source_type.as_str()),
+                    ("reason", reason.as_str()),
+                ]);
+                tracing::info!(agent = %key, reason = reason.as_str(), retry_after_ms, "Capture throttled");
             }
+            Ok(_) => {}
         }
+        self.log_submit(&result);
+        result
+    }
+
+    /// Submit only if the in-memory queue has room (best-effort jobs that are
+    /// regenerated anyway, e.g. quality-scan enrichments). Not rate limited.
+    pub fn submit_if_room(&self, job: CaptureJob) -> Result<Submitted, CaptureJob> {
+        let limits = Limits::for_agent(&super::live_config::guardian().capture.pool, &job.key);
+        self.queue.submit(job, false, &limits.unlimited())
     }
 
-    /// Get current queue statistics.
+    fn log_submit(&self, result: &Result<Submitted, CaptureJob>) {
+        let (queued, _, spilled) = self.queue.counts();
+        match result {
+            Ok(Submitted::Queued) => tracing::debug!("Capture job queued"),
+            Ok(Submitted::Spilled) => tracing::info!(spilled, "Capture queue full — job spilled to journal"),
+            Ok(Submitted::Throttled { .. }) => {}
+            Err(_) => tracing::warn!(pending = queued, "Capture queue full — job dropped"),
+        }
+    }
+
+    /// Requeue failed captures from the dead letters (see `captures failed retry`).
+    /// None if the queue has no journal.
+    pub fn requeue_failed(&self, filter: &DeadLetterFilter) -> Option<usize> {
+        let restored = self.queue.restore_dead_letters(filter)?;
+        if restored > 0 {
+            tracing::info!(restored, "Capture queue: failed captures requeued from dead letters");
+        }
+        Some(restored)
+    }
+
+    /// Get current queue statistics, including the journal backlog age.
     pub fn queue_stats(&self) -> serde_json::Value {
+        let (queued, in_flight, spilled) = self.queue.counts();
+        let journal = self.queue
+            .with_journal("backlog", CaptureJournal::backlog)
+            .map(|b| serde_json::to_value(b).unwrap_or_default());
+        let dead_letters = self.queue.with_journal("dead letter count", DeadLetterStore::count);
         serde_json::json!({
-            "pending": self.stats.pending.load(Ordering::Relaxed),
+            "pending": queued,
+            "in_flight": in_flight,
+            "spilled": spilled,
+            "capacity": self.queue.capacity,
             "processed": self.stats.processed.load(Ordering::Relaxed),
             "errors": self.stats.errors.load(Ordering::Relaxed),
             "workers": self.stats.workers,
+            "durable": journal.is_some(),
+            "journal": journal,
+            "dead_letters": dead_letters,
+            "fairness": self.queue.fairness(),
         })
     }
 
@@ -310,12 +875,11 @@ fn worker_loop(
 ) {
     loop {
         // Block until a job is available from an unoccupied agent
-        let job = match queue.take() {
+        let QueuedJob { journal_id, attempts, job, .. } = match queue.take() {
             Some(j) => j,
             None => return, // shutdown
         };
 
-        stats.pending.fetch_sub(1, Ordering::Relaxed);
         let start = std::time::Instant::now();
         let job_key = job.key.clone();
 
@@ -326,9 +890,26 @@ fn worker_loop(
             source = %job.source_type,
             content_len = job.content.len(),
             is_prompt = job.is_prompt,
+            attempt = attempts,
             "Worker processing capture"
         );
 
+        // Replayed job whose previous attempts never finished (daemon killed mid-job)
+        if attempts > MAX_CAPTURE_ATTEMPTS {
+            stats.errors.fetch_add(1, Ordering::Relaxed);
+            tracing::error!(
+                worker_id,
+                agent = %job_key,
+                source = %job.source_type,
+                attempts = attempts - 1,
+                "Capture ABANDONED: {} attempts interrupted without completing",
+                attempts - 1,
+            );
+            let error = format!("{} attempts interrupted without completing", attempts - 1);
+            queue.fail(&job, FailureStage::Interrupted, &error);
+            continue;
+        }
+
         // Ensure thread quota is cached for this agent (lazy load from registry)
         ensure_quota_cached(&pool, &job.key);
         let thread_quota = pool.get_thread_quota(&job.key);
@@ -343,14 +924,8 @@ fn worker_loop(
             }
         }
 
-        // Load GuardianConfig from config.json (reload per-job, ~1ms)
-        let guardian = {
-            let cfg_path = path_utils::data_dir().join("config.json");
-            std::fs::read_to_string(&cfg_path)
-                .ok()
-                .and_then(|s| serde_json::from_str::<GuardianConfig>(&s).ok())
-                .unwrap_or_default()
-        };
+        // Config snapshot for this job (swapped by config reloads)
+        let guardian = super::live_config::guardian();
 
         // --- Enrichment path: update existing thread via LLM, no new capture ---
         if let Some(ref thread_id) = job.enrich_thread_id {
@@ -360,7 +935,7 @@ fn worker_loop(
                     tracing::error!(worker_id, agent = %job.key, error = %e,
                         "Enrichment: failed to get DB connection");
                     stats.errors.fetch_add(1, Ordering::Relaxed);
-                    queue.done(&job_key);
+                    queue.fail(&job, FailureStage::Enrichment, &e.to_string());
                     continue;
                 }
             };
@@ -370,17 +945,19 @@ fn worker_loop(
                     tracing::error!(worker_id, error = %e,
                         "Enrichment: failed to lock DB connection");
                     stats.errors.fetch_add(1, Ordering::Relaxed);
-                    queue.done(&job_key);
+                    queue.fail(&job, FailureStage::Enrichment, &e.to_string());
                     continue;
                 }
             };
 
             let tid = thread_id.clone();
+            let mut abandoned = None;
             match processor::enrich_existing_thread(
                 &conn_guard, &tid, &job.content, &guardian,
             ) {
                 Ok(()) => {
                     stats.processed.fetch_add(1, Ordering::Relaxed);
+                    job.count(&metrics::CAPTURES_PROCESSED, &[]);
                     tracing::info!(
                         worker_id,
                         agent = %job.key,
@@ -388,8 +965,14 @@ fn worker_loop(
                         duration_ms = start.elapsed().as_millis() as u64,
                         "Thread enrichment complete"
                     );
+                    events::emit(
+                        EventKind::ThreadUpdated,
+                        Some(&job.key),
+                        serde_json::json!({"thread_id": tid, "enriched": true}),
+                    );
                 }
                 Err(e) => {
+                    queue.record_error(&job_key, &e.to_string());
                     if job.enrichment_retry < MAX_ENRICHMENT_RETRIES {
                         let retry_job = CaptureJob {
                             key: job.key.clone(),
@@ -401,10 +984,11 @@ fn worker_loop(
                             enrich_thread_id: job.enrich_thread_id.clone(),
                             enrichment_retry: job.enrichment_retry + 1,
                         };
-                        // Re-submit through the sharded queue (preserves per-agent FIFO)
-                        match queue.submit(retry_job) {
-                            Ok(()) => {
-                                stats.pending.fetch_add(1, Ordering::Relaxed);
+                        // Re-queue the same journal row (preserves per-agent FIFO)
+                        let retry = QueuedJob::new(journal_id, retry_job);
+                        match queue.requeue(retry) {
+                            Ok(_) => {
+                                metrics::RETRIES.inc(&[("stage", "enrichment")]);
                                 tracing::warn!(
                                     worker_id,
                                     agent = %job.key,
@@ -417,6 +1001,7 @@ fn worker_loop(
                             }
                             Err(_) => {
                                 stats.errors.fetch_add(1, Ordering::Relaxed);
+                                job.count(&metrics::CAPTURES_DROPPED, &[("reason", "queue_full")]);
                                 tracing::error!(
                                     worker_id,
                                     thread_id = %tid,
@@ -434,14 +1019,18 @@ fn worker_loop(
                             error = %e,
                             retries = MAX_ENRICHMENT_RETRIES,
                             duration_ms = start.elapsed().as_millis() as u64,
-                            "Enrichment ABANDONED after {} retries — user/agent must investigate",
+                            "Enrichment ABANDONED after {} retries — kept in dead letters",
                             MAX_ENRICHMENT_RETRIES,
                         );
+                        abandoned = Some(e.to_string());
                     }
                 }
             }
             drop(conn_guard);
-            queue.done(&job_key);
+            match abandoned {
+                Some(error) => queue.fail(&job, FailureStage::Enrichment, &error),
+                None => queue.done(&job_key),
+            }
             continue;
         }
 
@@ -451,6 +1040,7 @@ fn worker_loop(
             let agent_data = path_utils::agent_data_dir(&job.key.project_hash, &job.key.agent_id);
             let pool_dir = agent_data.join("pool");
             let mut pw = pool_writer::PoolWriter::new(&pool_dir, guardian.capture.pool.clone());
+            let mut write_error = None;
             match pw.append(&job.source_type, &job.content, job.file_path.as_deref()) {
                 Ok(()) => {
                     pw.seal_all().ok(); // Seal immediately for consumer pickup
@@ -470,11 +1060,15 @@ fn worker_loop(
                         worker_id,
                         agent = %job.key,
                         error = %e,
-                        "Pool write failed — capture dropped"
+                        "Pool write failed — capture kept in dead letters"
                     );
+                    write_error = Some(e.to_string());
                 }
             }
-            queue.done(&job_key);
+            match write_error {
+                Some(error) => queue.fail(&job, FailureStage::PoolWrite, &error),
+                None => queue.done(&job_key),
+            }
             continue;
         }
 
@@ -491,7 +1085,7 @@ fn worker_loop(
                     "Worker failed to get DB connection"
                 );
                 stats.errors.fetch_add(1, Ordering::Relaxed);
-                queue.done(&job_key);
+                queue.fail(&job, FailureStage::Prompt, &e.to_string());
                 continue;
             }
         };
@@ -506,7 +1100,7 @@ fn worker_loop(
                     "Worker failed to get pending context"
                 );
                 stats.errors.fetch_add(1, Ordering::Relaxed);
-                queue.done(&job_key);
+                queue.fail(&job, FailureStage::Prompt, &e.to_string());
                 continue;
             }
         };
@@ -522,7 +1116,7 @@ fn worker_loop(
                     tracing::error!(worker_id, error = %e,
                         "Failed to reconnect after eviction");
                     stats.errors.fetch_add(1, Ordering::Relaxed);
-                    queue.done(&job_key);
+                    queue.fail(&job, FailureStage::Prompt, &e.to_string());
                     continue;
                 }
             }
@@ -535,7 +1129,7 @@ fn worker_loop(
             Err(e) => {
                 tracing::error!(worker_id, error = %e, "Worker failed to lock DB connection");
                 stats.errors.fetch_add(1, Ordering::Relaxed);
-                queue.done(&job_key);
+                queue.fail(&job, FailureStage::Prompt, &e.to_string());
                 continue;
             }
         };
@@ -550,6 +1144,7 @@ fn worker_loop(
         };
 
         // Wrap processing in catch_unwind to prevent future Mutex poisoning
+        let started = ai_smartness::time_utils::now();
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             if job.is_prompt {
                 processor::process_prompt(
@@ -578,10 +1173,12 @@ fn worker_loop(
         drop(pending_guard);
 
         let duration_ms = start.elapsed().as_millis() as u64;
+        let stage = if job.is_prompt { FailureStage::Prompt } else { FailureStage::Capture };
 
         match result {
             Ok(Ok(tid)) => {
                 stats.processed.fetch_add(1, Ordering::Relaxed);
+                job.count(&metrics::CAPTURES_PROCESSED, &[]);
                 tracing::info!(
                     worker_id,
                     agent = %job_key,
@@ -590,12 +1187,17 @@ fn worker_loop(
                     "Worker capture complete"
                 );
                 // Clear backpressure on successful extraction
-                if tid.is_some() {
+                if let Some(ref tid) = tid {
                     clear_backpressure(&job_key);
+                    if let Ok(c) = conn.lock() {
+                        events::emit_thread_change(&c, &job_key, tid, started);
+                    }
                 }
+                queue.done(&job_key);
             }
             Ok(Err(e)) => {
                 stats.errors.fetch_add(1, Ordering::Relaxed);
+                queue.record_error(&job_key, &e.to_string());
                 tracing::error!(
                     worker_id,
                     agent = %job_key,
@@ -605,6 +1207,7 @@ fn worker_loop(
                 );
                 // Signal backpressure on extraction failure
                 set_backpressure(&job_key);
+                queue.fail(&job, stage, &e.to_string());
             }
             Err(panic_payload) => {
                 stats.errors.fetch_add(1, Ordering::Relaxed);
@@ -615,6 +1218,7 @@ fn worker_loop(
                 } else {
                     "unknown panic (non-string payload)".to_string()
                 };
+                queue.record_error(&job_key, &format!("panic: {}", panic_msg));
                 tracing::error!(
                     worker_id,
                     agent = %job_key,
@@ -623,11 +1227,9 @@ fn worker_loop(
                     "Worker capture PANICKED — evicting connection to prevent poison cascade"
                 );
                 pool.force_evict(&job_key);
+                queue.fail(&job, stage, &format!("panic: {}", panic_msg));
             }
         }
-
-        // Release agent for next job in its queue
-        queue.done(&job_key);
     }
 }
 
@@ -654,3 +1256,137 @@ fn clear_backpressure(key: &AgentKey) {
         tracing::info!(agent = %key.agent_id, "Backpressure OFF: extraction succeeded");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal(path: &std::path::Path) -> Option<Connection> {
+        let conn = Connection::open(path).unwrap();
+        migrations::migrate_journal_db(&conn).unwrap();
+        Some(conn)
+    }
+
+    fn job(agent: &str, content: &str) -> CaptureJob {
+        CaptureJob {
+            key: AgentKey { project_hash: "ph".into(), agent_id: agent.into() },
+            source_type: "Read".into(),
+            content: content.into(),
+            file_path: None,
+            is_prompt: false,
+            session_id: None,
+            enrich_thread_id: None,
+            enrichment_retry: 0,
+        }
+    }
+
+    const UNLIMITED: Limits = Limits { weight: 1, rate_per_min: 0, burst: 0, max_backlog: 0 };
+
+    fn prompt(agent: &str, content: &str) -> CaptureJob {
+        CaptureJob { is_prompt: true, source_type: "prompt".into(), ..job(agent, content) }
+    }
+
+    /// Take the next job and finish it right away.
+    fn serve(queue: &ShardedQueue) -> String {
+        let taken = queue.take().unwrap();
+        queue.done(&taken.job.key);
+        format!("{}:{}", taken.job.key.agent_id, taken.job.content)
+    }
+
+    #[test]
+    fn test_spill_and_replay_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture_journal.db");
+
+        let queue = ShardedQueue::new(1, journal(&path));
+        assert_eq!(queue.submit(job("a", "one"), true, &UNLIMITED).ok(), Some(Submitted::Queued));
+        assert_eq!(queue.submit(job("a", "two"), true, &UNLIMITED).ok(), Some(Submitted::Spilled));
+        assert!(queue.submit(job("b", "best-effort"), false, &UNLIMITED).is_err(), "Full queue refuses without spill");
+
+        // "one" is taken (in flight, room frees up: "two" is reloaded), then the daemon dies
+        let taken = queue.take().unwrap();
+        assert_eq!((taken.job.content.as_str(), taken.attempts), ("one", 1));
+        assert_eq!(queue.counts(), (1, 1, 0));
+        drop(queue);
+
+        // Restart: both unfinished jobs come back, in order, attempts preserved
+        let queue = ShardedQueue::new(10, journal(&path));
+        let first = queue.take().unwrap();
+        assert_eq!((first.job.content.as_str(), first.attempts), ("one", 2));
+        queue.done(&first.job.key);
+        let second = queue.take().unwrap();
+        assert_eq!(second.job.content, "two");
+        queue.done(&second.job.key);
+        let backlog = queue.with_journal("backlog", CaptureJournal::backlog).unwrap();
+        assert_eq!(backlog.pending, 0, "Finished jobs leave the journal");
+    }
+
+    #[test]
+    fn test_failed_job_is_dead_lettered_and_requeued() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ShardedQueue::new(10, journal(&dir.path().join("capture_journal.db")));
+        queue.submit(job("a", "lost"), true, &UNLIMITED).unwrap();
+
+        let taken = queue.take().unwrap();
+        queue.record_error(&taken.job.key, "Provider error: LLM unavailable");
+        queue.fail(&taken.job, FailureStage::Capture, "fallback");
+        assert_eq!(queue.counts(), (0, 0, 0));
+
+        let letters = queue
+            .with_journal("list", |c| DeadLetterStore::list(c, &DeadLetterFilter::default(), 10))
+            .unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].error, "Provider error: LLM unavailable");
+        assert_eq!(letters[0].content, "lost");
+
+        assert_eq!(queue.restore_dead_letters(&DeadLetterFilter::default()), Some(1));
+        let retried = queue.take().unwrap();
+        assert_eq!((retried.job.content.as_str(), retried.attempts), ("lost", 1));
+        queue.done(&retried.job.key);
+        assert_eq!(queue.with_journal("count", DeadLetterStore::count), Some(0));
+    }
+
+    #[test]
+    fn test_fair_share_prompts_first_and_weights() {
+        let queue = ShardedQueue::new(100, None);
+        for i in 0..6 {
+            queue.submit(job("flood", &i.to_string()), true, &UNLIMITED).unwrap();
+        }
+        let boss = Limits { weight: 2, ..UNLIMITED };
+        queue.submit(job("boss", "a"), true, &boss).unwrap();
+        queue.submit(job("boss", "b"), true, &boss).unwrap();
+        queue.submit(prompt("boss", "p"), true, &boss).unwrap();
+
+        // The prompt jumps both queues, then the weight-2 agent gets two turns out of three
+        let order: Vec<String> = (0..6).map(|_| serve(&queue)).collect();
+        assert_eq!(order, ["boss:p", "boss:a", "flood:0", "boss:b", "flood:1", "flood:2"]);
+
+        let stats = queue.fairness();
+        assert_eq!(stats[0]["agent_id"], "boss");
+        assert_eq!((stats[0]["served"].as_u64(), stats[1]["queued"].as_u64()), (Some(3), Some(3)));
+    }
+
+    #[test]
+    fn test_rate_limit_and_backlog_cap_throttle_tool_captures() {
+        let queue = ShardedQueue::new(100, None);
+        let limits = Limits { weight: 1, rate_per_min: 60, burst: 2, max_backlog: 3 };
+        assert_eq!(queue.submit(job("a", "1"), true, &limits).ok(), Some(Submitted::Queued));
+        assert_eq!(queue.submit(job("a", "2"), true, &limits).ok(), Some(Submitted::Queued));
+        match queue.submit(job("a", "3"), true, &limits).ok() {
+            Some(Submitted::Throttled { reason: Throttle::Rate, retry_after_ms }) => {
+                assert!(retry_after_ms > 0 && retry_after_ms <= 1000, "1 token/s: {}", retry_after_ms);
+            }
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+        // Prompts are never throttled, but count in the backlog
+        assert_eq!(queue.submit(prompt("a", "p"), true, &limits).ok(), Some(Submitted::Queued));
+        let capped = Limits { rate_per_min: 0, ..limits };
+        assert_eq!(
+            queue.submit(job("a", "4"), true, &capped).ok(),
+            Some(Submitted::Throttled { reason: Throttle::Backlog, retry_after_ms: BACKLOG_RETRY_MS })
+        );
+        // Other agents are not affected
+        assert_eq!(queue.submit(job("b", "1"), true, &limits).ok(), Some(Submitted::Queued));
+        assert_eq!(queue.fairness()[0]["throttled"], 2);
+    }
+}
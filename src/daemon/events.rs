@@ -0,0 +1,317 @@
+//! Event bus — typed change events streamed to `subscribe` IPC clients.
+//!
+//! Producers call `emit()` from anywhere in the daemon (capture workers, pool
+//! consumer, prune cycle). Processes that write memory without the daemon
+//! (MCP servers: messages, tasks, thread tools) report their changes through
+//! the `publish_event` IPC method, batched by one background sender per
+//! process and skipped while the daemon's subscriber marker is absent (see
+//! `set_marker`). Each subscriber gets a bounded channel: a
+//! subscriber that does not keep up loses events (counted, reported as `lagged`)
+//! instead of slowing the producers down.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+
+use ai_smartness::constants::{EVENT_SUBSCRIBER_BUFFER, MAX_EVENT_SUBSCRIBERS};
+use ai_smartness::storage::threads::ThreadStorage;
+use ai_smartness::time_utils;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+
+use super::connection_pool::AgentKey;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    ThreadCreated,
+    ThreadUpdated,
+    ThreadSuspended,
+    BridgeCreated,
+    BridgeDied,
+    MessageReceived,
+    TaskStatusChanged,
+    CaptureFailed,
+    PruneCycleCompleted,
+    ConfigReloaded,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 10] = [
+        Self::ThreadCreated,
+        Self::ThreadUpdated,
+        Self::ThreadSuspended,
+        Self::BridgeCreated,
+        Self::BridgeDied,
+        Self::MessageReceived,
+        Self::TaskStatusChanged,
+        Self::CaptureFailed,
+        Self::PruneCycleCompleted,
+        Self::ConfigReloaded,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ThreadCreated => "thread_created",
+            Self::ThreadUpdated => "thread_updated",
+            Self::ThreadSuspended => "thread_suspended",
+            Self::BridgeCreated => "bridge_created",
+            Self::BridgeDied => "bridge_died",
+            Self::MessageReceived => "message_received",
+            Self::TaskStatusChanged => "task_status_changed",
+            Self::CaptureFailed => "capture_failed",
+            Self::PruneCycleCompleted => "prune_cycle_completed",
+            Self::ConfigReloaded => "config_reloaded",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == s)
+    }
+}
+
+/// One event as sent to subscribers.
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    /// Daemon-wide sequence number (gaps = events dropped for this subscriber).
+    pub seq: u64,
+    #[serde(rename = "type")]
+    pub kind: EventKind,
+    pub timestamp: String,
+    /// None = not tied to one project/agent (e.g. a project-wide broadcast).
+    pub project_hash: Option<String>,
+    pub agent_id: Option<String>,
+    pub data: serde_json::Value,
+}
+
+/// Subscription filter (all fields optional, AND-ed).
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub project_hash: Option<String>,
+    pub agent_id: Option<String>,
+    pub kinds: Option<HashSet<EventKind>>,
+}
+
+impl EventFilter {
+    /// Events without a project/agent reach every subscriber of the scope above.
+    fn matches(&self, event: &Event) -> bool {
+        let scoped = |want: &Option<String>, got: &Option<String>| match (want, got) {
+            (Some(w), Some(g)) => w == g,
+            _ => true,
+        };
+        scoped(&self.project_hash, &event.project_hash)
+            && scoped(&self.agent_id, &event.agent_id)
+            && self.kinds.as_ref().is_none_or(|k| k.contains(&event.kind))
+    }
+}
+
+struct Subscriber {
+    id: u64,
+    filter: EventFilter,
+    tx: SyncSender<Event>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// A live subscription. Unsubscribes on drop.
+pub struct Subscription {
+    pub id: u64,
+    pub rx: Receiver<Event>,
+    dropped: Arc<AtomicU64>,
+    bus: &'static EventBus,
+}
+
+impl Subscription {
+    /// Events lost since the last call (subscriber too slow).
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.bus.unsubscribe(self.id);
+    }
+}
+
+pub struct EventBus {
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_seq: AtomicU64,
+    next_id: AtomicU64,
+    /// File present while at least one subscriber is registered (set by the daemon).
+    marker: OnceLock<PathBuf>,
+}
+
+static BUS: LazyLock<EventBus> = LazyLock::new(|| EventBus {
+    subscribers: Mutex::new(Vec::new()),
+    next_seq: AtomicU64::new(1),
+    next_id: AtomicU64::new(1),
+    marker: OnceLock::new(),
+});
+
+/// The daemon-wide event bus.
+pub fn bus() -> &'static EventBus {
+    &BUS
+}
+
+/// Publish an event on the daemon bus (no-op without subscribers).
+pub fn emit(kind: EventKind, key: Option<&AgentKey>, data: serde_json::Value) {
+    bus().publish(
+        kind,
+        key.map(|k| k.project_hash.clone()),
+        key.map(|k| k.agent_id.clone()),
+        data,
+    );
+}
+
+/// thread_created or thread_updated for a capture that landed in `thread_id`:
+/// created if the thread is not older than the capture (`started`).
+pub fn emit_thread_change(conn: &Connection, key: &AgentKey, thread_id: &str, started: DateTime<Utc>) {
+    if bus().subscriber_count() == 0 {
+        return;
+    }
+    let Ok(Some(thread)) = ThreadStorage::get(conn, thread_id) else { return };
+    let kind = if thread.created_at >= started { EventKind::ThreadCreated } else { EventKind::ThreadUpdated };
+    emit(
+        kind,
+        Some(key),
+        serde_json::json!({
+            "thread_id": thread.id,
+            "title": thread.title,
+            "status": thread.status.as_str(),
+            "weight": thread.weight,
+        }),
+    );
+}
+
+impl EventBus {
+    /// Keep `path` in sync with the subscriber list, so that other processes
+    /// skip `publish_event` while nobody listens. A stale marker left by a
+    /// previous daemon is removed.
+    pub fn set_marker(&self, path: PathBuf) {
+        let subs = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if self.marker.set(path).is_ok() {
+            self.sync_marker(subs.len());
+        }
+    }
+
+    /// Called with the subscriber lock held, after every change of the list.
+    fn sync_marker(&self, subscribers: usize) {
+        let Some(path) = self.marker.get() else { return };
+        let result = if subscribers == 0 {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                other => other,
+            }
+        } else if path.exists() {
+            Ok(())
+        } else {
+            std::fs::write(path, b"")
+        };
+        if let Err(e) = result {
+            tracing::warn!(path = %path.display(), error = %e, "Event subscriber marker not updated");
+        }
+    }
+
+    pub fn publish(
+        &self,
+        kind: EventKind,
+        project_hash: Option<String>,
+        agent_id: Option<String>,
+        data: serde_json::Value,
+    ) {
+        let mut subs = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if subs.is_empty() {
+            return;
+        }
+        let event = Event {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            kind,
+            timestamp: time_utils::now().to_rfc3339(),
+            project_hash,
+            agent_id,
+            data,
+        };
+        let before = subs.len();
+        subs.retain(|s| {
+            if !s.filter.matches(&event) {
+                return true;
+            }
+            match s.tx.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    s.dropped.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                // Subscriber gone (connection closed)
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+        if subs.len() != before {
+            self.sync_marker(subs.len());
+        }
+    }
+
+    /// Register a subscriber. Err if MAX_EVENT_SUBSCRIBERS streams are open.
+    pub fn subscribe(&'static self, filter: EventFilter) -> Result<Subscription, String> {
+        let mut subs = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if subs.len() >= MAX_EVENT_SUBSCRIBERS {
+            return Err(format!("Too many event subscribers (max {})", MAX_EVENT_SUBSCRIBERS));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::sync_channel(EVENT_SUBSCRIBER_BUFFER);
+        let dropped = Arc::new(AtomicU64::new(0));
+        subs.push(Subscriber { id, filter, tx, dropped: dropped.clone() });
+        self.sync_marker(subs.len());
+        Ok(Subscription { id, rx, dropped, bus: self })
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        let mut subs = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        subs.retain(|s| s.id != id);
+        self.sync_marker(subs.len());
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filtered_delivery_and_lag() {
+        let key = AgentKey { project_hash: "ph".into(), agent_id: "dev".into() };
+        let filter = EventFilter {
+            agent_id: Some("dev".into()),
+            kinds: Some([EventKind::ThreadCreated, EventKind::MessageReceived].into_iter().collect()),
+            ..Default::default()
+        };
+        let sub = bus().subscribe(filter).unwrap();
+
+        emit(EventKind::ThreadCreated, Some(&key), serde_json::json!({"thread_id": "t1"}));
+        emit(EventKind::ThreadCreated, Some(&AgentKey { project_hash: "ph".into(), agent_id: "ops".into() }), serde_json::json!({}));
+        emit(EventKind::CaptureFailed, Some(&key), serde_json::json!({}));
+        // Project-wide broadcast: no agent, still delivered
+        bus().publish(EventKind::MessageReceived, Some("ph".into()), None, serde_json::json!({"to": "*"}));
+
+        let got: Vec<Event> = sub.rx.try_iter().collect();
+        assert_eq!(got.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![EventKind::ThreadCreated, EventKind::MessageReceived]);
+        assert_eq!(got[0].data["thread_id"], "t1");
+
+        for _ in 0..EVENT_SUBSCRIBER_BUFFER + 3 {
+            emit(EventKind::ThreadCreated, Some(&key), serde_json::json!({}));
+        }
+        assert_eq!(sub.take_dropped(), 3);
+        assert_eq!(EventKind::parse("bridge_died"), Some(EventKind::BridgeDied));
+
+        let id = sub.id;
+        drop(sub);
+        let subs = bus().subscribers.lock().unwrap();
+        assert!(subs.iter().all(|s| s.id != id), "Dropped subscription is removed");
+    }
+}
@@ -0,0 +1,573 @@
+//! Local REST/JSON API — optional HTTP listener of the daemon on 127.0.0.1.
+//!
+//! Enabled by `DaemonConfig::http_api_port`. Every request (except the
+//! OpenAPI document) carries `Authorization: Bearer <token>`, the token being
+//! the content of `{data_dir}/api_token` (created owner-only on first start,
+//! restricted to its owner when found readable by others).
+//!
+//! Routes:
+//!   GET  /api/v1/openapi.json                          → OpenAPI 3 document (no auth)
+//!   GET  /api/v1/status[?project_hash=&agent_id=]      → daemon status (IPC `status`)
+//!   GET  /api/v1/pool                                  → connection pool stats
+//!   GET  /api/v1/queue                                 → capture queue stats
+//!   GET  /api/v1/tasks                                 → periodic daemon task schedules (IPC `tasks`)
+//!   GET  /api/v1/metrics                               → daemon metrics (OpenMetrics text)
+//!   GET  /api/v1/projects/{p}/agents/{a}/{resource}    → threads, bridges, agents, tasks, messages
+//!   POST /api/v1/projects/{p}/agents/{a}/tools/{tool}  → any MCP tool, JSON body = arguments
+//!
+//! Read endpoints are served by the list tools (query string = tool arguments,
+//! same pagination/projection); tool calls go through `route_tool`, so policy,
+//! two-phase confirmation, validation and audit apply as for MCP clients.
+//! The OpenAPI document is generated from the tool registry.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ai_smartness::metrics;
+use ai_smartness::processing::ipc_auth;
+use ai_smartness::registry::registry::AgentRegistry;
+use ai_smartness::storage::database::{self, ConnectionRole};
+use ai_smartness::storage::{migrations, path_utils};
+use ai_smartness::{AiError, AiResult};
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::mcp::http::{self as http, Request};
+use crate::mcp::tools::{self, registry, ToolContext, ToolOutput};
+
+use super::capture_queue::CaptureQueue;
+use super::connection_pool::{AgentKey, ConnectionPool};
+use super::ipc_server;
+
+const PREFIX: &str = "/api/v1";
+/// Concurrent requests served (extra connections get 503).
+const MAX_API_CONNECTIONS: usize = 16;
+/// A client that stalls mid-request does not hold a thread forever.
+const READ_TIMEOUT_SECS: u64 = 10;
+/// Accept loop tick (shutdown latency).
+const ACCEPT_TICK_MS: u64 = 200;
+
+/// Read endpoint served by a list tool.
+struct ReadRoute {
+    resource: &'static str,
+    tool: &'static str,
+    /// Arguments always passed (override the query string).
+    fixed: &'static [(&'static str, &'static str)],
+    summary: &'static str,
+}
+
+const READ_ROUTES: &[ReadRoute] = &[
+    ReadRoute { resource: "threads", tool: "ai_thread_list", fixed: &[], summary: "List threads" },
+    ReadRoute { resource: "bridges", tool: "ai_bridges", fixed: &[], summary: "List bridges" },
+    ReadRoute { resource: "agents", tool: "agent_list", fixed: &[], summary: "List the project's agents" },
+    ReadRoute { resource: "tasks", tool: "agent_tasks", fixed: &[("action", "list")], summary: "List tasks" },
+    ReadRoute {
+        resource: "messages",
+        tool: "msg_inbox",
+        fixed: &[("peek", "true")],
+        summary: "Pending messages (left unread)",
+    },
+];
+
+/// Daemon stats endpoints → IPC method.
+const STATS_ROUTES: &[(&str, &str, &str)] = &[
+    ("status", "status", "Daemon status (per agent with project_hash + agent_id)"),
+    ("pool", "pool_status", "Connection pool stats"),
+    ("queue", "queue_status", "Capture queue stats"),
+    ("tasks", "tasks", "Periodic daemon task schedules and run state"),
+];
+
+struct Shared {
+    token: String,
+    port: u16,
+    pool: Arc<ConnectionPool>,
+    capture_queue: Arc<CaptureQueue>,
+    running: Arc<AtomicBool>,
+    start_time: Instant,
+    /// Registry DB, opened and migrated once.
+    registry_conn: Mutex<Connection>,
+    /// shared.db per project, opened and migrated on first use.
+    shared_conns: Mutex<HashMap<String, Arc<Mutex<Connection>>>>,
+}
+
+impl Shared {
+    fn shared_conn(&self, project_hash: &str) -> AiResult<Arc<Mutex<Connection>>> {
+        let mut conns = self.shared_conns.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(conn) = conns.get(project_hash) {
+            return Ok(conn.clone());
+        }
+        let conn = database::open_connection(&path_utils::shared_db_path(project_hash), ConnectionRole::Daemon)?;
+        migrations::migrate_shared_db(&conn)?;
+        let conn = Arc::new(Mutex::new(conn));
+        conns.insert(project_hash.to_string(), conn.clone());
+        Ok(conn)
+    }
+}
+
+/// Serve the API until `running` is cleared.
+pub fn run(
+    port: u16,
+    pool: Arc<ConnectionPool>,
+    capture_queue: Arc<CaptureQueue>,
+    running: Arc<AtomicBool>,
+) -> AiResult<()> {
+    let token = load_or_create_token()?;
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| AiError::Storage(format!("Bind {} failed: {}", addr, e)))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| AiError::Storage(format!("Listener setup failed: {}", e)))?;
+    tracing::info!(%addr, "HTTP API listening");
+
+    let registry_conn = database::open_connection(&path_utils::registry_db_path(), ConnectionRole::Daemon)?;
+    migrations::migrate_registry_db(&registry_conn)?;
+    let shared = Arc::new(Shared {
+        token,
+        port,
+        pool,
+        capture_queue,
+        running,
+        start_time: Instant::now(),
+        registry_conn: Mutex::new(registry_conn),
+        shared_conns: Mutex::new(HashMap::new()),
+    });
+    let active = Arc::new(AtomicUsize::new(0));
+
+    while shared.running.load(Ordering::Relaxed) {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(ACCEPT_TICK_MS));
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("HTTP API accept error: {}", e);
+                std::thread::sleep(Duration::from_millis(ACCEPT_TICK_MS));
+                continue;
+            }
+        };
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(READ_TIMEOUT_SECS)));
+        let admitted = active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < MAX_API_CONNECTIONS).then_some(n + 1))
+            .is_ok();
+        if !admitted {
+            let _ = write_json(&mut stream, "503 Service Unavailable", &json!({"error": "Too many requests"}));
+            continue;
+        }
+        let shared = shared.clone();
+        let counter = active.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &shared) {
+                tracing::debug!("HTTP API connection error: {}", e);
+            }
+            counter.fetch_sub(1, Ordering::AcqRel);
+        });
+    }
+    tracing::info!("HTTP API stopped");
+    Ok(())
+}
+
+/// Read the API token, creating it (owner-only) if missing. An existing file
+/// readable by others is restricted to its owner.
+fn load_or_create_token() -> AiResult<String> {
+    let path = path_utils::api_token_path();
+    if let Ok(token) = std::fs::read_to_string(&path) {
+        let token = token.trim().to_string();
+        if !token.is_empty() {
+            ipc_auth::restrict_to_owner(&path)?;
+            return Ok(token);
+        }
+    }
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    ipc_auth::write_owner_only(&path, &token)?;
+    tracing::info!(path = %path.display(), "HTTP API token created");
+    Ok(token)
+}
+
+/// Constant-time comparison (no early exit on the first differing byte).
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn write_json(stream: &mut TcpStream, status: &str, body: &Value) -> std::io::Result<()> {
+    http::write_response(stream, status, &[], &body.to_string())
+}
+
+fn handle_connection(mut stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    let req = http::read_request(&stream)?;
+    // The only non-JSON response
+    if req.method == "GET" && req.path.strip_prefix(PREFIX) == Some("/metrics") {
+        if let Err((status, body)) = check_origin(&req).and_then(|()| check_token(&req, shared)) {
+            return write_json(&mut stream, status, &body);
+        }
+        let text = ipc_server::render_metrics(&shared.pool, &shared.capture_queue, &shared.start_time);
+        return http::write_typed_response(&mut stream, "200 OK", metrics::CONTENT_TYPE, &[], &text);
+    }
+    let (status, body) = respond(&req, shared);
+    write_json(&mut stream, status, &body)
+}
+
+fn error(status: &'static str, message: impl Into<String>) -> (&'static str, Value) {
+    (status, json!({"error": message.into()}))
+}
+
+fn check_origin(req: &Request) -> Result<(), (&'static str, Value)> {
+    match req.header("origin") {
+        Some(origin) if !http::is_local_origin(origin) => Err(error("403 Forbidden", "Origin not allowed")),
+        _ => Ok(()),
+    }
+}
+
+fn check_token(req: &Request, shared: &Shared) -> Result<(), (&'static str, Value)> {
+    let authorized = req
+        .header("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(|t| token_matches(t.trim(), &shared.token));
+    if authorized {
+        Ok(())
+    } else {
+        Err(error("401 Unauthorized", "Missing or invalid bearer token"))
+    }
+}
+
+fn respond(req: &Request, shared: &Shared) -> (&'static str, Value) {
+    if let Err(e) = check_origin(req) {
+        return e;
+    }
+    let Some(route) = req.path.strip_prefix(PREFIX) else {
+        return error("404 Not Found", format!("Unknown endpoint (API root is {})", PREFIX));
+    };
+    if req.method == "GET" && route == "/openapi.json" {
+        return ("200 OK", openapi(shared.port));
+    }
+    if let Err(e) = check_token(req, shared) {
+        return e;
+    }
+
+    let segments: Vec<&str> = route.trim_matches('/').split('/').collect();
+    let started = Instant::now();
+    let result = match (req.method.as_str(), segments.as_slice()) {
+        ("GET", [name]) => match STATS_ROUTES.iter().find(|(n, ..)| n == name) {
+            Some((_, method, _)) => {
+                let params = Value::Object(query_args(&req.query));
+                return match ipc_server::dispatch(
+                    method,
+                    &params,
+                    &shared.pool,
+                    &shared.capture_queue,
+                    &shared.running,
+                    &shared.start_time,
+                ) {
+                    Ok(v) => ("200 OK", v),
+                    Err(e) => error("400 Bad Request", e),
+                };
+            }
+            None => return error("404 Not Found", format!("Unknown endpoint {}", req.path)),
+        },
+        ("GET", ["projects", project, "agents", agent, resource]) => {
+            let Some(read) = READ_ROUTES.iter().find(|r| r.resource == *resource) else {
+                return error("404 Not Found", format!("Unknown resource '{}'", resource));
+            };
+            let mut args = query_args(&req.query);
+            for (k, v) in read.fixed {
+                args.insert(k.to_string(), json!(v));
+            }
+            call_tool(shared, project, agent, read.tool, Value::Object(args))
+        }
+        ("POST", ["projects", project, "agents", agent, "tools", tool]) => {
+            let args = if req.body.iter().all(|b| b.is_ascii_whitespace()) {
+                json!({})
+            } else {
+                match serde_json::from_slice::<Value>(&req.body) {
+                    Ok(v) => v,
+                    Err(e) => return error("400 Bad Request", format!("Invalid JSON body: {}", e)),
+                }
+            };
+            call_tool(shared, project, agent, tool, args)
+        }
+        (_, ["projects", _, "agents", _, ..]) | (_, [_]) => {
+            return error("405 Method Not Allowed", "Use GET for reads, POST for tool calls")
+        }
+        _ => return error("404 Not Found", format!("Unknown endpoint {}", req.path)),
+    };
+
+    tracing::debug!(
+        method = %req.method,
+        path = %req.path,
+        ok = result.is_ok(),
+        duration_ms = started.elapsed().as_millis() as u64,
+        "HTTP API request"
+    );
+    match result {
+        Ok(v) => ("200 OK", v),
+        Err(e) => {
+            let status = match e {
+                AiError::InvalidInput(_) => "400 Bad Request",
+                AiError::PolicyDenied { .. } => "403 Forbidden",
+                AiError::AgentNotFound(_)
+                | AiError::ThreadNotFound(_)
+                | AiError::BridgeNotFound(_)
+                | AiError::MessageNotFound(_)
+                | AiError::ProjectNotFound(_) => "404 Not Found",
+                _ => "500 Internal Server Error",
+            };
+            error(status, e.to_string())
+        }
+    }
+}
+
+/// Run one tool as `agent_id` of `project_hash` (same path as an MCP tools/call).
+/// The agent DB comes from the daemon pool; registry and shared DBs are kept
+/// open by the API. Locks are taken in that order.
+fn call_tool(shared: &Shared, project_hash: &str, agent_id: &str, tool: &str, args: Value) -> AiResult<Value> {
+    if registry::find(tool).is_none() {
+        return Err(AiError::InvalidInput(format!("Unknown tool: {}", tool)));
+    }
+    // Never create a DB for an agent that does not exist
+    let known = {
+        let registry_conn = shared.registry_conn.lock().unwrap_or_else(|e| e.into_inner());
+        AgentRegistry::get(&registry_conn, agent_id, project_hash)?.is_some()
+    };
+    if !known {
+        return Err(AiError::AgentNotFound(format!("{} in project {}", agent_id, project_hash)));
+    }
+    let key = AgentKey { project_hash: project_hash.to_string(), agent_id: agent_id.to_string() };
+    let agent_conn = shared.pool.get_or_open(&key).map_err(AiError::Storage)?;
+    let shared_conn = shared.shared_conn(project_hash)?;
+
+    let agent_conn = agent_conn.lock().unwrap_or_else(|e| e.into_inner());
+    let registry_conn = shared.registry_conn.lock().unwrap_or_else(|e| e.into_inner());
+    let shared_conn = shared_conn.lock().unwrap_or_else(|e| e.into_inner());
+    let ctx = ToolContext::new(&agent_conn, &registry_conn, &shared_conn, project_hash, agent_id);
+    // An agent switch only concerns MCP sessions: keep the result
+    match tools::route_tool(tool, &args, &ctx)? {
+        ToolOutput::Plain(v) | ToolOutput::AgentSwitch { result: v, .. } => Ok(v),
+    }
+}
+
+/// Query string → tool arguments (all strings; the registry coerces them).
+fn query_args(query: &str) -> serde_json::Map<String, Value> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), Value::String(percent_decode(v)))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match bytes
+                .get(i + 1..i + 3)
+                .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok())
+            {
+                Some(b) => {
+                    out.push(b);
+                    i += 2;
+                }
+                None => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ── OpenAPI ──
+
+fn error_responses() -> Value {
+    let err = json!({
+        "description": "Error",
+        "content": {"application/json": {"schema": {
+            "type": "object", "properties": {"error": {"type": "string"}}
+        }}}
+    });
+    json!({
+        "400": err, "401": {"description": "Missing or invalid bearer token"},
+        "403": {"description": "Denied by tool policy"}, "404": {"description": "Unknown agent, tool or item"},
+    })
+}
+
+fn ok_response(description: &str) -> Value {
+    json!({"description": description, "content": {"application/json": {"schema": {"type": "object"}}}})
+}
+
+fn agent_path_params() -> Vec<Value> {
+    ["project_hash", "agent_id"]
+        .iter()
+        .map(|name| json!({"name": name, "in": "path", "required": true, "schema": {"type": "string"}}))
+        .collect()
+}
+
+/// OpenAPI 3 document generated from the tool registry and the route tables.
+pub fn openapi(port: u16) -> Value {
+    let mut paths = serde_json::Map::new();
+
+    for (name, method, summary) in STATS_ROUTES {
+        let mut op = json!({
+            "operationId": format!("daemon_{}", name),
+            "summary": summary,
+            "tags": ["daemon"],
+            "responses": {"200": ok_response(&format!("IPC `{}` result", method))},
+        });
+        if *name == "status" {
+            op["parameters"] = json!(["project_hash", "agent_id"]
+                .iter()
+                .map(|p| json!({"name": p, "in": "query", "required": false, "schema": {"type": "string"}}))
+                .collect::<Vec<_>>());
+        }
+        paths.insert(format!("{}/{}", PREFIX, name), json!({"get": op}));
+    }
+
+    for read in READ_ROUTES {
+        let Some(spec) = registry::find(read.tool) else { continue };
+        let schema = spec.input_schema();
+        let mut parameters = agent_path_params();
+        for p in spec.params.iter().filter(|p| !read.fixed.iter().any(|(k, _)| *k == p.name)) {
+            let mut param_schema = schema["properties"][p.name].clone();
+            let description = param_schema
+                .as_object_mut()
+                .and_then(|o| o.remove("description"))
+                .unwrap_or(Value::Null);
+            parameters.push(json!({
+                "name": p.name, "in": "query", "required": p.required,
+                "description": description, "schema": param_schema,
+            }));
+        }
+        paths.insert(
+            format!("{}/projects/{{project_hash}}/agents/{{agent_id}}/{}", PREFIX, read.resource),
+            json!({"get": {
+                "operationId": format!("list_{}", read.resource),
+                "summary": read.summary,
+                "description": format!("Served by the `{}` tool.", read.tool),
+                "tags": ["read"],
+                "parameters": parameters,
+                "responses": {"200": ok_response("Page of items")},
+            }}),
+        );
+    }
+
+    for spec in registry::registry() {
+        let mut op = json!({
+            "operationId": spec.name,
+            "summary": spec.description,
+            "tags": [spec.category],
+            "parameters": agent_path_params(),
+            "requestBody": {
+                "required": spec.params.iter().any(|p| p.required),
+                "content": {"application/json": {"schema": spec.input_schema()}},
+            },
+            "responses": {"200": ok_response("Tool result")},
+        });
+        if spec.preview.is_some() {
+            op["description"] = json!(
+                "Two-phase: the first call returns a preview and a confirm_token; call again with the token to execute."
+            );
+        }
+        paths.insert(
+            format!("{}/projects/{{project_hash}}/agents/{{agent_id}}/tools/{}", PREFIX, spec.name),
+            json!({"post": op}),
+        );
+    }
+
+    paths.insert(
+        format!("{}/metrics", PREFIX),
+        json!({"get": {
+            "operationId": "daemon_metrics",
+            "summary": "Daemon and pipeline metrics",
+            "tags": ["daemon"],
+            "responses": {"200": {
+                "description": "OpenMetrics text exposition",
+                "content": {(metrics::CONTENT_TYPE): {"schema": {"type": "string"}}},
+            }},
+        }}),
+    );
+
+    for item in paths.values_mut() {
+        for op in item.as_object_mut().into_iter().flat_map(|o| o.values_mut()) {
+            if let Some(responses) = op["responses"].as_object_mut() {
+                if let Value::Object(errors) = error_responses() {
+                    responses.extend(errors);
+                }
+            }
+        }
+    }
+    paths.insert(
+        format!("{}/openapi.json", PREFIX),
+        json!({"get": {
+            "operationId": "openapi",
+            "summary": "This document",
+            "tags": ["daemon"],
+            "security": [],
+            "responses": {"200": ok_response("OpenAPI document")},
+        }}),
+    );
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {"title": "ai-smartness daemon API", "version": env!("CARGO_PKG_VERSION")},
+        "servers": [{"url": format!("http://127.0.0.1:{}", port)}],
+        "components": {"securitySchemes": {"bearer": {"type": "http", "scheme": "bearer"}}},
+        "security": [{"bearer": []}],
+        "paths": paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_args_decoding() {
+        let args = query_args("status=suspended&limit=5&fields=id%2Ctitle&q=a+b%20c&flag");
+        assert_eq!(args["status"], "suspended");
+        assert_eq!(args["limit"], "5");
+        assert_eq!(args["fields"], "id,title");
+        assert_eq!(args["q"], "a b c");
+        assert_eq!(args["flag"], "");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+
+        assert!(token_matches("abc", "abc"));
+        assert!(!token_matches("abd", "abc"));
+        assert!(!token_matches("ab", "abc"));
+    }
+
+    #[test]
+    fn test_openapi_covers_registry_and_reads() {
+        let doc = openapi(8787);
+        let paths = doc["paths"].as_object().unwrap();
+        for spec in registry::registry() {
+            let path = format!("/api/v1/projects/{{project_hash}}/agents/{{agent_id}}/tools/{}", spec.name);
+            assert!(paths.contains_key(&path), "missing {}", path);
+        }
+        let threads = &paths["/api/v1/projects/{project_hash}/agents/{agent_id}/threads"]["get"];
+        let names: Vec<&str> = threads["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"status") && names.contains(&"cursor"));
+        // Fixed arguments are not exposed
+        let messages = &paths["/api/v1/projects/{project_hash}/agents/{agent_id}/messages"]["get"];
+        assert!(!messages["parameters"].as_array().unwrap().iter().any(|p| p["name"] == "peek"));
+        assert_eq!(paths["/api/v1/openapi.json"]["get"]["security"], json!([]));
+        assert!(paths["/api/v1/metrics"]["get"]["responses"]["200"]["content"][metrics::CONTENT_TYPE].is_object());
+    }
+}
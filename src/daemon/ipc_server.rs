@@ -13,19 +13,42 @@
 //! **Async captures**: tool_capture and prompt_capture are dispatched to a
 //! bounded CaptureQueue with N worker threads — hooks get instant responses.
 //!
+//! **Authentication**: every connection opens with `auth` (params: `token`,
+//! see `ipc_auth`), answered with the session's scopes; the connection is
+//! closed on a bad token. The following request is then checked against the
+//! scopes: agent methods need the agent (or its project) in scope,
+//! `publish_event` the agent every event speaks for, and daemon-wide methods
+//! (shutdown, restart, pool_flush, reload_config, stats...) an admin token.
+//! The socket is created owner-only (umask set around the bind) and the
+//! secret and data directory permissions are checked. Each registered agent
+//! gets its own token file, issued on startup and every prune tick.
+//!
 //! Methods:
+//!   auth            → handshake (first line of every connection)
 //!   ping            → {"pong": true}
 //!   status          → daemon status JSON (global or per-agent)
 //!   shutdown        → initiate graceful shutdown
 //!   restart         → graceful shutdown + re-exec (apply config changes)
-//!   tool_capture    → queue tool output capture (instant response)
+//!   reload_config   → re-read and validate config.json + daemon_config.json, swap them in (no restart)
+//!   tool_capture    → queue tool output capture (instant response; throttled agents get retry_after_ms)
 //!   prompt_capture  → queue user prompt capture (instant response)
 //!   injection_usage → record thread injection usage
 //!   pool_status     → connection pool stats
-//!   queue_status    → capture queue stats (pending, processed, errors, workers)
+//!   queue_status    → capture queue stats (pending, in flight, spilled, journal backlog age, dead letters, workers, per-agent fairness)
+//!   metrics         → {"text": ...} daemon metrics in OpenMetrics text format
+//!   tasks           → periodic task schedules and last/next run state
+//!   run_task        → run one periodic task now (params: task, project_hash + agent_id for agent tasks)
+//!   requeue_failed  → requeue dead-lettered captures (ids, or project/agent/stage filter, or all)
+//!   subscribe       → keep the connection open and stream events (see below)
+//!   publish_event   → put events on the bus (changes made outside the daemon, e.g. MCP tools; one event or an `events` batch)
 //!   list_active_agents → list all agents in pool
 //!   lock / unlock   → per-agent memory lock
 //!   mind_coherence_chain → async coherence gate for __mind__ threads (instant response)
+//!
+//! **Event stream**: `subscribe` (params: optional `project_hash`, `agent_id`,
+//! `types` array) answers with a normal response, then writes one JSON-RPC
+//! notification per line: `event` (params = the event), `lagged` (events lost
+//! by a slow reader) and `heartbeat` (idle stream), until the client closes.
 
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
@@ -36,12 +59,17 @@ use std::time::{Duration, Instant};
 use serde::Serialize;
 
 use ai_smartness::agent::ThreadMode;
+use ai_smartness::metrics;
 use ai_smartness::intelligence::thread_manager::ThreadManager;
+use ai_smartness::processing::ipc_auth::{self, Grant};
+use ai_smartness::processing::topic_normalizer::TopicNormalizer;
+use ai_smartness::storage::dead_letters::{DeadLetterFilter, FailureStage};
 use ai_smartness::thread::ThreadStatus;
 use ai_smartness::storage::threads::ThreadStorage;
 
-use super::capture_queue::{CaptureJob, CaptureQueue};
+use super::capture_queue::{CaptureJob, CaptureQueue, Submitted};
 use super::connection_pool::{AgentKey, ConnectionPool};
+use super::events::{self, EventFilter, EventKind};
 use super::processor::PendingContext;
 
 /// Flag set by the `restart` IPC method — checked after graceful shutdown.
@@ -70,6 +98,14 @@ struct JsonRpcError {
     message: String,
 }
 
+/// JSON-RPC notification (no id) — used by the `subscribe` event stream.
+#[derive(Debug, Serialize)]
+struct JsonRpcNotification<'a> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: serde_json::Value,
+}
+
 /// Run the IPC listener using interprocess for cross-platform support.
 /// Multi-threaded: each incoming connection spawns a dedicated handler thread.
 pub fn run(
@@ -82,10 +118,27 @@ pub fn run(
 
     // Remove stale socket file (harmless on Windows)
     let _ = std::fs::remove_file(socket_path);
+    events::bus().set_marker(ai_smartness::processing::daemon_ipc_client::subscribers_marker_path());
 
+    ipc_auth::load_or_create_secret()?;
+    match ipc_auth::issue_registered_agent_tokens() {
+        Ok(issued) => tracing::info!(issued, "Agent IPC tokens issued"),
+        Err(e) => tracing::warn!(error = %e, "Agent IPC token issuance failed"),
+    }
+    // Owner-only from the bind on: no window before the chmod below where
+    // other users could connect
+    #[cfg(unix)]
+    let previous_umask = unsafe { libc::umask(0o077) };
     let listener = ListenerOptions::new()
         .name(socket_path.to_fs_name::<GenericFilePath>()?)
-        .create_sync()?;
+        .create_sync();
+    #[cfg(unix)]
+    unsafe {
+        libc::umask(previous_umask);
+    }
+    let listener = listener?;
+    #[cfg(unix)]
+    check_permissions(socket_path)?;
 
     tracing::info!("IPC listening on {:?} (multi-threaded)", socket_path);
 
@@ -136,6 +189,44 @@ pub fn run(
     Ok(())
 }
 
+/// Startup: owner-only socket (refuse to serve otherwise), owner-only secret,
+/// and a warning when other users could replace files in the data directory.
+#[cfg(unix)]
+fn check_permissions(socket_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode_of = |path: &Path| std::fs::metadata(path).map(|m| m.permissions().mode() & 0o777);
+    std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+    let mode = mode_of(socket_path)?;
+    if mode & 0o077 != 0 {
+        return Err(format!("IPC socket {} is open to other users (mode {:o})", socket_path.display(), mode).into());
+    }
+
+    let secret_path = ai_smartness::storage::path_utils::ipc_secret_path();
+    let mode = mode_of(&secret_path)?;
+    if mode & 0o077 != 0 {
+        std::fs::set_permissions(&secret_path, std::fs::Permissions::from_mode(0o600))?;
+        tracing::warn!(
+            path = %secret_path.display(),
+            mode = %format!("{:o}", mode),
+            "IPC secret was readable by other users — restricted to owner, rotate it with `daemon token --rotate`"
+        );
+    }
+
+    if let Some(dir) = socket_path.parent() {
+        if let Ok(mode) = mode_of(dir) {
+            if mode & 0o022 != 0 {
+                tracing::warn!(
+                    dir = %dir.display(),
+                    mode = %format!("{:o}", mode),
+                    "Data directory is writable by other users — they could replace the IPC socket"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Wake the listener by connecting to it (for clean shutdown).
 pub fn wake(socket_path: &Path) {
     use interprocess::local_socket::{prelude::*, GenericFilePath};
@@ -163,6 +254,46 @@ fn extract_agent_key(params: &serde_json::Value) -> Result<AgentKey, String> {
     })
 }
 
+/// Capture submit outcome as the hook sees it.
+fn submit_response(result: Result<Submitted, CaptureJob>) -> serde_json::Value {
+    match result {
+        Ok(Submitted::Queued) => serde_json::json!({"queued": true}),
+        Ok(Submitted::Spilled) => serde_json::json!({"queued": true, "spilled": true}),
+        Ok(Submitted::Throttled { reason, retry_after_ms }) => serde_json::json!({
+            "queued": false,
+            "reason": "throttled",
+            "throttle": reason.as_str(),
+            "retry_after_ms": retry_after_ms,
+        }),
+        Err(_) => serde_json::json!({"queued": false, "reason": "queue_full"}),
+    }
+}
+
+/// Dead-letter selection from `requeue_failed` params. An empty selection must
+/// be explicit (`"all": true`).
+fn dead_letter_filter(params: &serde_json::Value) -> Result<DeadLetterFilter, String> {
+    let str_param = |name: &str| params.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let stage = match str_param("stage") {
+        Some(s) => Some(FailureStage::parse(&s).ok_or_else(|| format!("Unknown stage '{}'", s))?),
+        None => None,
+    };
+    let filter = DeadLetterFilter {
+        ids: params
+            .get("ids")
+            .and_then(|v| v.as_array())
+            .map(|ids| ids.iter().filter_map(|id| id.as_i64()).collect()),
+        project_hash: str_param("project_hash"),
+        agent_id: str_param("agent_id"),
+        stage,
+        failed_before: None,
+    };
+    let all = params.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !all && filter.ids.is_none() && filter.project_hash.is_none() && filter.agent_id.is_none() && filter.stage.is_none() {
+        return Err("Missing selection: pass 'ids', a project/agent/stage filter, or 'all': true".to_string());
+    }
+    Ok(filter)
+}
+
 fn handle_connection(
     stream: interprocess::local_socket::Stream,
     pool: &Arc<ConnectionPool>,
@@ -170,44 +301,81 @@ fn handle_connection(
     running: &Arc<AtomicBool>,
     start_time: &Instant,
 ) {
-    let mut stream = stream;
-    let mut line = String::new();
-    {
-        let mut reader = BufReader::new(&mut stream);
-        if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+    let mut reader = BufReader::new(stream);
+
+    // Handshake
+    let Some((method, params, id)) = read_request(&mut reader) else { return };
+    let grant = match authenticate(&method, &params) {
+        Ok(grant) => grant,
+        Err(msg) => {
+            tracing::warn!(method = %method, error = %msg, "IPC: connection refused");
+            write_response(reader.get_mut(), id, Err(msg));
             return;
         }
+    };
+    let session = serde_json::json!({
+        "authenticated": true,
+        "admin": grant.is_admin(),
+        "scopes": grant.scopes.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+    });
+    if !write_response(reader.get_mut(), id, Ok(session)) {
+        return;
     }
 
-    let data: serde_json::Value = match serde_json::from_str(&line) {
-        Ok(v) => v,
-        Err(e) => {
-            tracing::warn!(error = %e, "IPC: invalid JSON received");
-            return;
-        }
-    };
+    let Some((method, params, id)) = read_request(&mut reader) else { return };
+    if let Err(msg) = authorize(&grant, &method, &params) {
+        tracing::warn!(method = %method, scopes = ?grant.scopes, "IPC: {}", msg);
+        write_response(reader.get_mut(), id, Err(msg));
+        return;
+    }
 
-    let method = data
-        .get("method")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let params = data
-        .get("params")
-        .cloned()
-        .unwrap_or(serde_json::json!({}));
-    let id = data.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+    if method == "subscribe" {
+        stream_events(reader.into_inner(), &params, id, running);
+        return;
+    }
 
     let request_start = Instant::now();
-    tracing::debug!(method = method, id = id, "IPC request received");
+    tracing::debug!(method = %method, id = id, "IPC request received");
 
-    let result = dispatch(method, &params, pool, capture_queue, running, start_time);
+    let result = dispatch(&method, &params, pool, capture_queue, running, start_time);
 
     tracing::debug!(
-        method = method,
+        method = %method,
         duration_ms = request_start.elapsed().as_millis() as u64,
         "IPC request completed"
     );
 
+    write_response(reader.get_mut(), id, result);
+}
+
+/// Read one JSON-RPC request line: (method, params, id).
+/// None when the client is gone or sent invalid JSON.
+fn read_request(
+    reader: &mut BufReader<interprocess::local_socket::Stream>,
+) -> Option<(String, serde_json::Value, u64)> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+        return None;
+    }
+    let data: serde_json::Value = match serde_json::from_str(&line) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::warn!(error = %e, "IPC: invalid JSON received");
+            return None;
+        }
+    };
+    let method = data.get("method").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let params = data.get("params").cloned().unwrap_or(serde_json::json!({}));
+    let id = data.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+    Some((method, params, id))
+}
+
+/// Write the response to request `id`. False if the client is gone.
+fn write_response(
+    stream: &mut interprocess::local_socket::Stream,
+    id: u64,
+    result: Result<serde_json::Value, String>,
+) -> bool {
     let response = match result {
         Ok(r) => JsonRpcResponse {
             jsonrpc: "2.0",
@@ -225,18 +393,186 @@ fn handle_connection(
             id,
         },
     };
+    write_line(stream, &response)
+}
+
+/// Check the `auth` handshake against the current secret (re-read each time,
+/// so a rotation applies to the next connection) and the issued agent tokens
+/// (an unregistered agent's token is refused).
+fn authenticate(method: &str, params: &serde_json::Value) -> Result<Grant, String> {
+    if method != "auth" {
+        return Err("Authentication required: open the connection with 'auth'".to_string());
+    }
+    let token = params
+        .get("token")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing 'token' in params".to_string())?;
+    let secret = ipc_auth::read_secret().map_err(|e| e.to_string())?;
+    let grant = ipc_auth::verify(&secret, token)?;
+    ipc_auth::check_issued(&grant)?;
+    Ok(grant)
+}
+
+/// Which sessions may call a method.
+enum Access {
+    /// Any authenticated session.
+    Open,
+    /// Sessions holding the agent of `project_hash` + `agent_id` (or its project).
+    Agent,
+    /// `publish_event`: sessions holding the agent the event speaks for
+    /// (`event_author`), the whole project for project-wide events.
+    Publish,
+    /// Admin tokens only (daemon control, daemon-wide stats).
+    Admin,
+}
 
-    if let Ok(json) = serde_json::to_string(&response) {
-        if let Err(e) = stream.write_all(json.as_bytes()) {
-            tracing::debug!(error = %e, "IPC write failed — client disconnected?");
+fn method_access(method: &str, params: &serde_json::Value) -> Access {
+    match method {
+        "ping" => Access::Open,
+        "tool_capture" | "prompt_capture" | "injection_usage" | "lock" | "unlock" | "set_thread_mode"
+        | "engram_query" | "mind_coherence_chain" | "enrich_thread" | "update_pending_context"
+        | "get_pending_context" | "subscribe" => Access::Agent,
+        "status" if params.get("project_hash").is_some() => Access::Agent,
+        "run_task" if params.get("agent_id").is_some() => Access::Agent,
+        "publish_event" => Access::Publish,
+        _ => Access::Admin,
+    }
+}
+
+/// Events of a `publish_event` request: the `events` batch, or the params
+/// themselves for a single event.
+fn published_events(params: &serde_json::Value) -> Vec<&serde_json::Value> {
+    match params.get("events").and_then(|v| v.as_array()) {
+        Some(batch) => batch.iter().collect(),
+        None => vec![params],
+    }
+}
+
+/// Agent a published event speaks for: the sender of notifications addressed
+/// to another agent (message, task), else the agent it is about (None = the
+/// whole project).
+fn event_author(params: &serde_json::Value) -> Option<&str> {
+    let field = match params.get("type").and_then(|v| v.as_str()) {
+        Some("message_received") => "/data/from",
+        Some("task_status_changed") => "/data/updated_by",
+        _ => "/agent_id",
+    };
+    params.pointer(field).and_then(|v| v.as_str())
+}
+
+/// Check a request against the session's scopes.
+fn authorize(grant: &Grant, method: &str, params: &serde_json::Value) -> Result<(), String> {
+    if grant.is_admin() {
+        return Ok(());
+    }
+    let str_param = |name: &str| params.get(name).and_then(|v| v.as_str());
+    let allowed = match method_access(method, params) {
+        Access::Open => true,
+        Access::Agent => str_param("project_hash").is_some_and(|ph| grant.covers(ph, str_param("agent_id"))),
+        Access::Publish => str_param("project_hash").is_some_and(|ph| {
+            let events = published_events(params);
+            !events.is_empty() && events.into_iter().all(|e| grant.covers(ph, event_author(e)))
+        }),
+        Access::Admin => return Err(format!("Permission denied: '{}' requires an admin token", method)),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!(
+            "Permission denied: '{}' on {}/{} is outside the session scopes",
+            method,
+            str_param("project_hash").unwrap_or("?"),
+            str_param("agent_id").unwrap_or("*"),
+        ))
+    }
+}
+
+/// Write one JSON line. False if the client is gone.
+fn write_line(stream: &mut interprocess::local_socket::Stream, value: &impl Serialize) -> bool {
+    let Ok(json) = serde_json::to_string(value) else { return true };
+    stream.write_all(json.as_bytes()).is_ok()
+        && stream.write_all(b"\n").is_ok()
+        && stream.flush().is_ok()
+}
+
+fn notify(stream: &mut interprocess::local_socket::Stream, method: &str, params: serde_json::Value) -> bool {
+    write_line(stream, &JsonRpcNotification { jsonrpc: "2.0", method, params })
+}
+
+/// Event filter from `subscribe` params.
+fn event_filter(params: &serde_json::Value) -> Result<EventFilter, String> {
+    let str_param = |name: &str| params.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let kinds = match params.get("types").and_then(|v| v.as_array()) {
+        Some(types) => Some(
+            types
+                .iter()
+                .map(|t| {
+                    let name = t.as_str().unwrap_or("");
+                    EventKind::parse(name).ok_or_else(|| format!("Unknown event type '{}'", name))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        None => None,
+    };
+    Ok(EventFilter {
+        project_hash: str_param("project_hash"),
+        agent_id: str_param("agent_id"),
+        kinds,
+    })
+}
+
+/// `subscribe`: acknowledge, then stream matching events until the client
+/// disconnects or the daemon stops. Holds this connection's thread.
+fn stream_events(
+    mut stream: interprocess::local_socket::Stream,
+    params: &serde_json::Value,
+    id: u64,
+    running: &Arc<AtomicBool>,
+) {
+    let sub = match event_filter(params).and_then(|f| events::bus().subscribe(f)) {
+        Ok(s) => s,
+        Err(msg) => {
+            write_response(&mut stream, id, Err(msg));
             return;
         }
-        let _ = stream.write_all(b"\n");
-        let _ = stream.flush();
+    };
+    let ack = serde_json::json!({"subscribed": true, "subscription": sub.id});
+    if !write_response(&mut stream, id, Ok(ack)) {
+        return;
     }
+    tracing::info!(subscription = sub.id, subscribers = events::bus().subscriber_count(), "IPC: event stream opened");
+
+    let tick = Duration::from_secs(1);
+    let heartbeat = Duration::from_secs(ai_smartness::constants::EVENT_HEARTBEAT_SECS);
+    let mut idle = Duration::ZERO;
+    while running.load(Ordering::Relaxed) {
+        let alive = match sub.rx.recv_timeout(tick) {
+            Ok(event) => {
+                idle = Duration::ZERO;
+                let dropped = sub.take_dropped();
+                (dropped == 0 || notify(&mut stream, "lagged", serde_json::json!({"dropped": dropped})))
+                    && notify(&mut stream, "event", serde_json::to_value(&event).unwrap_or_default())
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                idle += tick;
+                if idle >= heartbeat {
+                    idle = Duration::ZERO;
+                    notify(&mut stream, "heartbeat", serde_json::json!({}))
+                } else {
+                    true
+                }
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => false,
+        };
+        if !alive {
+            break;
+        }
+    }
+    tracing::info!(subscription = sub.id, "IPC: event stream closed");
 }
 
-fn dispatch(
+/// Shared with the HTTP API (stats endpoints).
+pub(super) fn dispatch(
     method: &str,
     params: &serde_json::Value,
     pool: &Arc<ConnectionPool>,
@@ -314,10 +650,7 @@ fn dispatch(
                 enrichment_retry: 0,
             };
 
-            match capture_queue.submit(job) {
-                Ok(()) => Ok(serde_json::json!({"queued": true})),
-                Err(_) => Ok(serde_json::json!({"queued": false, "reason": "queue_full"})),
-            }
+            Ok(submit_response(capture_queue.submit(job)))
         }
 
         "prompt_capture" => {
@@ -353,10 +686,7 @@ fn dispatch(
                 enrichment_retry: 0,
             };
 
-            match capture_queue.submit(job) {
-                Ok(()) => Ok(serde_json::json!({"queued": true})),
-                Err(_) => Ok(serde_json::json!({"queued": false, "reason": "queue_full"})),
-            }
+            Ok(submit_response(capture_queue.submit(job)))
         }
 
         "injection_usage" => {
@@ -400,6 +730,64 @@ fn dispatch(
             Ok(capture_queue.queue_stats())
         }
 
+        "metrics" => {
+            Ok(serde_json::json!({"text": render_metrics(pool, capture_queue, start_time)}))
+        }
+
+        "tasks" => {
+            Ok(super::scheduler::snapshot(&super::live_config::daemon()))
+        }
+
+        "reload_config" => {
+            let report = super::live_config::reload()?;
+            Ok(serde_json::to_value(report).unwrap_or_default())
+        }
+
+        "run_task" => {
+            let task = params
+                .get("task")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Missing 'task' in params".to_string())?;
+            let key = match params.get("agent_id") {
+                Some(_) => Some(extract_agent_key(params)?),
+                None => None,
+            };
+            super::periodic_tasks::run_task_now(task, key.as_ref(), pool, Some(&**capture_queue))
+        }
+
+        "publish_event" => {
+            let project_hash = params.get("project_hash").and_then(|v| v.as_str()).map(|s| s.to_string());
+            // Validate the whole batch before publishing any of it
+            let batch = published_events(params)
+                .into_iter()
+                .map(|event| {
+                    let kind = event
+                        .get("type")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| "Missing 'type' in params".to_string())?;
+                    let kind = EventKind::parse(kind).ok_or_else(|| format!("Unknown event type '{}'", kind))?;
+                    Ok((kind, event))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            for (kind, event) in &batch {
+                events::bus().publish(
+                    *kind,
+                    project_hash.clone(),
+                    event.get("agent_id").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                    event.get("data").cloned().unwrap_or(serde_json::json!({})),
+                );
+            }
+            Ok(serde_json::json!({"published": batch.len()}))
+        }
+
+        "requeue_failed" => {
+            let filter = dead_letter_filter(params)?;
+            match capture_queue.requeue_failed(&filter) {
+                Some(requeued) => Ok(serde_json::json!({"requeued": requeued})),
+                None => Err("Capture journal unavailable — dead letters cannot be requeued".to_string()),
+            }
+        }
+
         "pool_flush" => {
             tracing::info!("IPC: pool_flush requested");
             let (processed, errors) = super::periodic_tasks::flush_all_pools(pool);
@@ -479,7 +867,7 @@ fn dispatch(
             let conn_guard = conn.lock().map_err(|e| e.to_string())?;
 
             // Construct retriever on-the-fly (stateless — no shared state needed)
-            let config = ai_smartness::config::EngramConfig::default();
+            let config = super::live_config::guardian().engram.clone();
             let retriever = ai_smartness::intelligence::engram_retriever::EngramRetriever::new(
                 &conn_guard, config,
             ).map_err(|e| format!("Retriever init: {}", e))?;
@@ -558,10 +946,7 @@ fn dispatch(
                 enrichment_retry: 0,
             };
 
-            match capture_queue.submit(job) {
-                Ok(()) => Ok(serde_json::json!({"queued": true})),
-                Err(_) => Ok(serde_json::json!({"queued": false, "reason": "queue_full"})),
-            }
+            Ok(submit_response(capture_queue.submit(job)))
         }
 
         // Update PendingContext for an agent — called by MCP thread_create
@@ -689,7 +1074,7 @@ fn process_mind_coherence(
     }
 
     // 4. LLM extraction (labels, topics, summary, concepts, importance)
-    let guardian = load_guardian_config_for_coherence();
+    let guardian = super::live_config::guardian();
     let extraction = match ai_smartness::processing::extractor::extract(
         new_content,
         ai_smartness::processing::extractor::ExtractionSource::Response,
@@ -726,7 +1111,8 @@ fn process_mind_coherence(
 
     thread.summary = Some(extraction.summary.clone());
     thread.labels = extraction.labels.clone();
-    thread.concepts = ai_smartness::constants::normalize_concepts(&extraction.concepts);
+    let normalizer = TopicNormalizer::load(&conn_guard);
+    thread.concepts = normalizer.dedup_concepts(&extraction.concepts);
     thread.importance = extraction.importance;
     thread.confidence = extraction.confidence;
 
@@ -734,16 +1120,9 @@ fn process_mind_coherence(
     let new_weight = (thread.importance + thread.confidence) / 10.0;
     thread.weight = thread.weight.max(new_weight);
 
-    // Merge topics (dedup case-insensitive)
-    if thread.topics.is_empty() {
-        thread.topics = extraction.subjects.clone();
-    } else {
-        for t in &extraction.subjects {
-            if !thread.topics.iter().any(|existing| existing.to_lowercase() == t.to_lowercase()) {
-                thread.topics.push(t.clone());
-            }
-        }
-    }
+    // Merge topics (dedup by canonical key)
+    thread.topics.extend(extraction.subjects.iter().cloned());
+    thread.topics = normalizer.dedup_topics(&thread.topics);
 
     // Compute embedding from enriched text
     let embed_text =
@@ -759,6 +1138,7 @@ fn process_mind_coherence(
         let bridge_count =
             ai_smartness::intelligence::thread_manager::ThreadManager::create_thinkbridges(
                 &conn_guard,
+                &normalizer,
                 new_thread_id,
                 &thread.concepts,
                 &guardian.gossip,
@@ -860,17 +1240,6 @@ fn capture_mind_transcript(
     }
 }
 
-/// Load GuardianConfig from config.json, falling back to defaults.
-fn load_guardian_config_for_coherence() -> ai_smartness::config::GuardianConfig {
-    let config_path = ai_smartness::storage::path_utils::data_dir().join("config.json");
-    if let Ok(content) = std::fs::read_to_string(&config_path) {
-        if let Ok(cfg) = serde_json::from_str(&content) {
-            return cfg;
-        }
-    }
-    ai_smartness::config::GuardianConfig::default()
-}
-
 fn build_global_status(
     pool: &ConnectionPool,
     capture_queue: &CaptureQueue,
@@ -892,6 +1261,36 @@ fn build_global_status(
     })
 }
 
+/// Refresh the state gauges, then render the metrics registry (OpenMetrics text).
+pub(super) fn render_metrics(
+    pool: &ConnectionPool,
+    capture_queue: &CaptureQueue,
+    start_time: &Instant,
+) -> String {
+    let queue = capture_queue.queue_stats();
+    let count = |field: &str| queue.get(field).and_then(|v| v.as_f64()).unwrap_or(0.0);
+    metrics::QUEUE_PENDING.set(&[], count("pending"));
+    metrics::QUEUE_IN_FLIGHT.set(&[], count("in_flight"));
+    metrics::QUEUE_SPILLED.set(&[], count("spilled"));
+    metrics::DEAD_LETTERS.set(&[], count("dead_letters"));
+
+    let stats = pool.stats();
+    metrics::POOL_CONNECTIONS.set(&[("state", "active")], stats.active as f64);
+    metrics::POOL_CONNECTIONS.set(&[("state", "idle")], stats.idle as f64);
+    metrics::POOL_CONNECTIONS.set(&[("state", "locked")], stats.locked as f64);
+
+    // Never loads the model: before initialization the gauge stays unset
+    if let Some(llm) = ai_smartness::processing::local_llm::LocalLlm::loaded() {
+        let current = llm.status();
+        for state in ["available", "degraded", "cooldown", "unavailable"] {
+            metrics::LLM_STATUS.set(&[("state", state)], if state == current { 1.0 } else { 0.0 });
+        }
+    }
+
+    metrics::UPTIME.set(&[], start_time.elapsed().as_secs_f64());
+    metrics::render()
+}
+
 fn build_agent_status(
     conn: &rusqlite::Connection,
     key: &AgentKey,
@@ -917,3 +1316,45 @@ fn build_agent_status(
         "health": "ok",
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_smartness::processing::ipc_auth::Scope;
+
+    #[test]
+    fn test_authorize_scoped_session() {
+        let grant = Grant { scopes: vec![Scope::Agent { project_hash: "ph".into(), agent_id: "dev".into() }] };
+        let own = serde_json::json!({"project_hash": "ph", "agent_id": "dev"});
+        let other = serde_json::json!({"project_hash": "ph", "agent_id": "ops"});
+
+        assert!(authorize(&grant, "ping", &serde_json::json!({})).is_ok());
+        assert!(authorize(&grant, "engram_query", &own).is_ok());
+        assert!(authorize(&grant, "get_pending_context", &other).is_err());
+        assert!(authorize(&grant, "set_thread_mode", &serde_json::json!({"agent_id": "dev"})).is_err());
+        let event = |agent_id: &str, kind: &str, data: serde_json::Value| {
+            serde_json::json!({"project_hash": "ph", "agent_id": agent_id, "type": kind, "data": data})
+        };
+        assert!(authorize(&grant, "publish_event", &event("dev", "thread_created", serde_json::json!({}))).is_ok());
+        assert!(authorize(&grant, "publish_event", &event("ops", "thread_created", serde_json::json!({}))).is_err());
+        assert!(authorize(&grant, "publish_event", &serde_json::json!({"project_hash": "ph"})).is_err());
+        let from = |sender: &str| serde_json::json!({"from": sender});
+        assert!(authorize(&grant, "publish_event", &event("ops", "message_received", from("dev"))).is_ok());
+        assert!(authorize(&grant, "publish_event", &event("dev", "message_received", from("ops"))).is_err());
+        let batch = |events: Vec<serde_json::Value>| serde_json::json!({"project_hash": "ph", "events": events});
+        let own_event = serde_json::json!({"agent_id": "dev", "type": "thread_suspended"});
+        let other_event = serde_json::json!({"agent_id": "ops", "type": "thread_suspended"});
+        assert!(authorize(&grant, "publish_event", &batch(vec![own_event.clone(), own_event.clone()])).is_ok());
+        assert!(authorize(&grant, "publish_event", &batch(vec![own_event, other_event])).is_err());
+        assert!(authorize(&grant, "publish_event", &batch(vec![])).is_err());
+        assert!(authorize(&grant, "subscribe", &serde_json::json!({"project_hash": "ph"})).is_err());
+        assert!(authorize(&grant, "status", &own).is_ok());
+        for admin_only in ["shutdown", "restart", "pool_flush", "status", "list_active_agents"] {
+            assert!(authorize(&grant, admin_only, &serde_json::json!({})).is_err(), "{}", admin_only);
+        }
+
+        let admin = Grant { scopes: vec![Scope::Admin] };
+        assert!(authorize(&admin, "shutdown", &serde_json::json!({})).is_ok());
+        assert!(authorize(&admin, "get_pending_context", &other).is_ok());
+    }
+}
@@ -0,0 +1,228 @@
+//! Live configuration — `config.json` (GuardianConfig) and `daemon_config.json`
+//! (DaemonConfig) as used by the running daemon.
+//!
+//! Loaded once at startup. `reload` (SIGHUP or the `reload_config` IPC method)
+//! re-reads both files, validates them and swaps them in together. Readers
+//! take an `Arc` snapshot, so a capture job or prune task keeps one consistent
+//! config even when a reload lands mid-run. A file that does not parse is
+//! rejected and the current config stays in place.
+//!
+//! Applied by a reload: engram, decay, gossip, capture and extraction settings
+//! (read per job/task), LLM routing (backend, remote LLM) and task schedules.
+//! The local model is reloaded only when its parameters changed. Pool sizes,
+//! capture workers/capacity and the HTTP API port still need a restart.
+//!
+//! Model reloads run on a background thread, one at a time, and each carries
+//! a generation number: a load that finishes after a newer reload was asked
+//! for is dropped, so a stale model never replaces a newer one. While a model
+//! loads the previous one stays resident (VRAM for both at the peak), which
+//! can push the new model's GPU layer cascade down to CPU.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+use ai_smartness::config::{DaemonConfig, GuardianConfig};
+use ai_smartness::processing::llm_subprocess;
+use ai_smartness::processing::local_llm::LocalLlm;
+use ai_smartness::storage::path_utils;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::events::{self, EventKind};
+use super::scheduler::{self, Schedule};
+
+/// DaemonConfig fields only read at startup.
+const RESTART_FIELDS: &[&str] = &[
+    "pool_max_connections",
+    "pool_max_idle_secs",
+    "capture_workers",
+    "capture_queue_capacity",
+    "http_api_port",
+];
+
+struct Live {
+    guardian: Arc<GuardianConfig>,
+    daemon: Arc<DaemonConfig>,
+}
+
+static LIVE: LazyLock<RwLock<Live>> = LazyLock::new(|| {
+    let guardian = read_guardian().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Invalid guardian config, using defaults");
+        GuardianConfig::default()
+    });
+    RwLock::new(Live { guardian: Arc::new(guardian), daemon: Arc::new(DaemonConfig::load()) })
+});
+
+/// Serializes reloads (SIGHUP and IPC may race).
+static RELOADING: Mutex<()> = Mutex::new(());
+
+/// Latest requested local model reload.
+static MODEL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Serializes model loads: at most one new model in memory next to the current one.
+static MODEL_LOADING: Mutex<()> = Mutex::new(());
+
+/// Current guardian config (config.json).
+pub fn guardian() -> Arc<GuardianConfig> {
+    LIVE.read().unwrap_or_else(|e| e.into_inner()).guardian.clone()
+}
+
+/// Current daemon config (daemon_config.json).
+pub fn daemon() -> Arc<DaemonConfig> {
+    LIVE.read().unwrap_or_else(|e| e.into_inner()).daemon.clone()
+}
+
+/// What a reload changed.
+#[derive(Debug, Serialize)]
+pub struct ReloadReport {
+    /// Top-level fields that changed (`daemon.` prefix for daemon_config.json).
+    pub changed: Vec<String>,
+    /// Local model parameters changed: the model is reloading in the background.
+    pub model_reload: bool,
+    /// Changed fields that only apply after a daemon restart.
+    pub restart_required: Vec<String>,
+}
+
+/// Re-read, validate and apply both config files.
+pub fn reload() -> Result<ReloadReport, String> {
+    let _reloading = RELOADING.lock().unwrap_or_else(|e| e.into_inner());
+    let guardian = read_guardian()?;
+    let daemon = read_daemon()?;
+
+    let (old_guardian, old_daemon) = {
+        let live = LIVE.read().unwrap_or_else(|e| e.into_inner());
+        (live.guardian.clone(), live.daemon.clone())
+    };
+    let mut changed = changed_fields(&*old_guardian, &guardian);
+    changed.extend(changed_fields(&*old_daemon, &daemon).into_iter().map(|f| format!("daemon.{}", f)));
+    let restart_required: Vec<String> = RESTART_FIELDS
+        .iter()
+        .map(|f| format!("daemon.{}", f))
+        .filter(|f| changed.contains(f))
+        .collect();
+    let model_reload = model_params(&old_guardian) != model_params(&guardian);
+
+    // Everything but the model applies right away
+    let guardian = Arc::new(guardian);
+    llm_subprocess::init_routing(&guardian);
+    {
+        let mut live = LIVE.write().unwrap_or_else(|e| e.into_inner());
+        live.guardian = guardian.clone();
+        live.daemon = Arc::new(daemon);
+    }
+
+    if model_reload {
+        // Can take a while (download, GPU load): callers of the local LLM keep
+        // the old model until the new one is swapped in.
+        let generation = MODEL_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        std::thread::spawn(move || reload_model(&guardian, generation));
+    }
+
+    let report = ReloadReport { changed, model_reload, restart_required };
+    tracing::info!(
+        changed = ?report.changed,
+        model_reload = report.model_reload,
+        restart_required = ?report.restart_required,
+        "Configuration reloaded"
+    );
+    events::emit(EventKind::ConfigReloaded, None, serde_json::to_value(&report).unwrap_or_default());
+    Ok(report)
+}
+
+/// Load and swap in the local model of `guardian`, unless a newer reload
+/// (`generation` no longer the latest) supersedes it before or during the load.
+fn reload_model(guardian: &GuardianConfig, generation: u64) {
+    let current = || MODEL_GENERATION.load(Ordering::SeqCst) == generation;
+    let _loading = MODEL_LOADING.lock().unwrap_or_else(|e| e.into_inner());
+    if !current() {
+        tracing::debug!(generation, "Local LLM reload superseded before loading");
+        return;
+    }
+    match LocalLlm::reload(
+        &guardian.local_model_size,
+        &guardian.local_llm,
+        &guardian.hardware.runtime_device,
+        current,
+    ) {
+        Some(llm) => tracing::info!(
+            generation,
+            available = llm.is_available(),
+            status = llm.status(),
+            model = %llm.model_path().display(),
+            "Local LLM reloaded"
+        ),
+        None => tracing::info!(generation, "Local LLM reload superseded: loaded model dropped"),
+    }
+}
+
+/// config.json, strictly parsed (missing file = defaults) then clamped.
+fn read_guardian() -> Result<GuardianConfig, String> {
+    let mut cfg: GuardianConfig = read_json("config.json")?.unwrap_or_default();
+    cfg.validate();
+    Ok(cfg)
+}
+
+/// daemon_config.json, strictly parsed (missing file = defaults), with the
+/// checks startup would only find out about later.
+fn read_daemon() -> Result<DaemonConfig, String> {
+    let cfg: DaemonConfig = read_json("daemon_config.json")?.unwrap_or_default();
+    if cfg.pool_max_connections == 0 {
+        return Err("daemon_config.json: pool_max_connections must be > 0".into());
+    }
+    if cfg.capture_workers == 0 {
+        return Err("daemon_config.json: capture_workers must be > 0".into());
+    }
+    for name in cfg.tasks.keys() {
+        let task = scheduler::find(name)
+            .ok_or_else(|| format!("daemon_config.json: unknown task '{}'", name))?;
+        Schedule::resolve(task, &cfg).map_err(|e| format!("daemon_config.json: tasks.{}: {}", name, e))?;
+    }
+    Ok(cfg)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(file: &str) -> Result<Option<T>, String> {
+    let path = path_utils::data_dir().join(file);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map(Some).map_err(|e| format!("{}: {}", file, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", file, e)),
+    }
+}
+
+/// Top-level fields whose value differs.
+fn changed_fields<T: Serialize>(old: &T, new: &T) -> Vec<String> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+    let mut changed: Vec<String> = new
+        .iter()
+        .filter(|(k, v)| old.get(*k) != Some(*v))
+        .map(|(k, _)| k.clone())
+        .collect();
+    changed.sort();
+    changed
+}
+
+/// What the loaded local model depends on.
+fn model_params(cfg: &GuardianConfig) -> Value {
+    serde_json::json!([cfg.local_model_size, cfg.local_llm, cfg.hardware.runtime_device])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_fields_and_model_params() {
+        let old = GuardianConfig::default();
+        let mut new = old.clone();
+        new.decay.archive_after_hours += 1.0;
+        new.remote_llm.model = "other-model".into();
+        assert_eq!(changed_fields(&old, &new), vec!["decay", "remote_llm"]);
+        // Remote LLM settings only re-route: no local model reload
+        assert_eq!(model_params(&old), model_params(&new));
+
+        new.local_llm.ctx_size += 512;
+        assert_ne!(model_params(&old), model_params(&new));
+    }
+}
@@ -1,18 +1,21 @@
 pub mod capture_queue;
 pub mod connection_pool;
 pub mod controller;
+pub mod events;
+pub mod http_api;
 pub mod ipc_server;
+pub mod live_config;
 pub mod periodic_tasks;
 pub mod pool_processor;
 pub mod pool_writer;
 pub mod processor;
+pub mod scheduler;
 pub mod watchdog;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use ai_smartness::config::DaemonConfig;
 use ai_smartness::storage::path_utils;
 
 use capture_queue::CaptureQueue;
@@ -28,17 +31,19 @@ use connection_pool::ConnectionPool;
 ///   - IPC server: multi-threaded (1 thread per connection)
 ///   - Capture queue: N worker threads for LLM extraction (configurable)
 ///   - Prune loop: 1 thread for periodic maintenance
+///   - HTTP API (optional): local REST/JSON listener on 127.0.0.1
 pub fn run() {
     // Init global tracing to {data_dir}/daemon.log
     ai_smartness::tracing_init::init_global_tracing();
 
-    let config = DaemonConfig::load();
+    let config = live_config::daemon();
     tracing::info!(
         pool_max = config.pool_max_connections,
         idle_secs = config.pool_max_idle_secs,
         prune_secs = config.prune_interval_secs,
         capture_workers = config.capture_workers,
         capture_queue_capacity = config.capture_queue_capacity,
+        http_api_port = ?config.http_api_port,
         "Starting global ai-daemon"
     );
 
@@ -122,10 +127,21 @@ pub fn run() {
         })
     };
 
+    // Optional local REST API (same handlers as IPC + MCP tools)
+    let http_api_handle = config.http_api_port.map(|port| {
+        let pool = pool.clone();
+        let queue = capture_queue.clone();
+        let running = running.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = http_api::run(port, pool, queue, running) {
+                tracing::error!("HTTP API error: {}", e);
+            }
+        })
+    });
+
     // Heavy initialization in background thread — does not block IPC or GUI.
-    // Workers that need ONNX/LocalLLM will block on OnceLock until init completes.
+    // Workers that need ONNX/LocalLLM will block on first access until init completes.
     let init_handle = {
-        let data_dir = data_dir.clone();
         std::thread::spawn(move || {
             // 1. Startup validation: integrity check + missed backups
             startup_validation();
@@ -136,13 +152,7 @@ pub fn run() {
             tracing::info!(use_onnx = emb.use_onnx, "EmbeddingManager initialized (eager)");
 
             // 3. Eagerly initialize local LLM with configured model size + routing.
-            let guardian_cfg = {
-                let cfg_path = data_dir.join("config.json");
-                std::fs::read_to_string(&cfg_path)
-                    .ok()
-                    .and_then(|s| serde_json::from_str::<ai_smartness::config::GuardianConfig>(&s).ok())
-                    .unwrap_or_default()
-            };
+            let guardian_cfg = live_config::guardian();
             let llm = ai_smartness::processing::local_llm::LocalLlm::init_with_size(
                 &guardian_cfg.local_model_size,
                 &guardian_cfg.local_llm,
@@ -166,9 +176,8 @@ pub fn run() {
         let pool = pool.clone();
         let cq = capture_queue.clone();
         let running = running.clone();
-        let prune_interval = config.prune_interval_secs;
         std::thread::spawn(move || {
-            periodic_tasks::run_prune_loop(pool, Some(cq), running, prune_interval);
+            periodic_tasks::run_prune_loop(pool, Some(cq), running);
         })
     };
 
@@ -193,10 +202,20 @@ pub fn run() {
     signal_hook::flag::register(signal_hook::consts::SIGINT, running.clone()).ok();
     #[cfg(unix)]
     signal_hook::flag::register(signal_hook::consts::SIGTERM, running.clone()).ok();
+    // SIGHUP: reload config.json + daemon_config.json
+    let reload_requested = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, reload_requested.clone()).ok();
 
     // Main loop: wait for shutdown
     while running.load(Ordering::Relaxed) {
         std::thread::sleep(Duration::from_secs(1));
+        if reload_requested.swap(false, Ordering::Relaxed) {
+            tracing::info!("SIGHUP received — reloading configuration");
+            if let Err(e) = live_config::reload() {
+                tracing::error!(error = %e, "Configuration reload rejected — keeping current config");
+            }
+        }
     }
 
     tracing::info!("Shutting down...");
@@ -230,13 +249,15 @@ pub fn run() {
     };
     let join_deadline = std::time::Instant::now() + join_timeout;
 
-    for (name, handle) in [
-        ("ipc", ipc_handle),
-        ("prune", prune_handle),
-        ("pool_consumer", pool_consumer_handle),
-        ("controller", controller_handle),
-        ("init", init_handle),
-    ] {
+    let threads = [
+        ("ipc", Some(ipc_handle)),
+        ("http_api", http_api_handle),
+        ("prune", Some(prune_handle)),
+        ("pool_consumer", Some(pool_consumer_handle)),
+        ("controller", Some(controller_handle)),
+        ("init", Some(init_handle)),
+    ];
+    for (name, handle) in threads.into_iter().filter_map(|(n, h)| Some((n, h?))) {
         let remaining = join_deadline.saturating_duration_since(std::time::Instant::now());
         if remaining.is_zero() {
             tracing::warn!(thread = name, "Skipping join — deadline exceeded");
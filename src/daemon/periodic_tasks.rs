@@ -1,290 +1,470 @@
-//! Prune loop — iterates all active agents in the connection pool.
-//! Runs every prune_interval_secs (default 300 = 5 min).
-//! Respects per-agent memory lock: skips locked agents.
+//! Prune loop — runs the periodic tasks when the scheduler says they are due
+//! (per-task interval or cron, see `scheduler`), over all active agents in
+//! the connection pool. Respects per-agent memory lock: skips locked agents.
 //!
 //! PAS DE COMPACTION. Le systeme utilise merge/suspend/archive
 //! geres par l'agent via les MCP tools.
 
+use std::cell::OnceCell;
+use std::collections::HashMap;
 use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{Duration, Instant};
 
-use ai_smartness::config::GuardianConfig;
+use ai_smartness::bridge::BridgeStatus;
+use ai_smartness::config::{DaemonConfig, GuardianConfig};
 use ai_smartness::intelligence::archiver::Archiver;
-use ai_smartness::intelligence::decayer::Decayer;
+use ai_smartness::intelligence::cross_gossip::{self, MemorySnapshot};
+use ai_smartness::intelligence::decayer::{DecayChanges, Decayer};
 use ai_smartness::intelligence::gossip::Gossip;
+use ai_smartness::intelligence::retention::{Retention, RetentionReport};
+use ai_smartness::intelligence::topic_alias_suggester::TopicAliasSuggester;
+use ai_smartness::metrics;
+use ai_smartness::processing::ipc_auth;
+use ai_smartness::processing::topic_normalizer::TopicNormalizer;
 use ai_smartness::registry::registry::AgentRegistry;
+use ai_smartness::storage::audit::{AuditEntry, AuditLog};
 use ai_smartness::storage::backup::{BackupConfig, BackupManager};
 use ai_smartness::storage::beat::BeatState;
 use ai_smartness::storage::cognitive_inbox::CognitiveInbox;
 use ai_smartness::storage::database::{self, ConnectionRole};
+use ai_smartness::storage::dead_letters::DeadLetterStore;
+use ai_smartness::storage::migrations;
 use ai_smartness::storage::path_utils;
 use ai_smartness::storage::threads::ThreadStorage;
 use ai_smartness::thread::Thread;
+use ai_smartness::time_utils;
 use rusqlite::Connection;
 
 use super::connection_pool::{ConnectionPool, AgentKey};
+use super::events::{self, EventKind};
+use super::live_config;
 use super::pool_processor;
 use super::pool_writer;
+use super::scheduler::{self, RunReport, Schedule, Scope, TaskDef};
 
 /// Run a single periodic task inside catch_unwind for panic isolation.
 /// Uses AssertUnwindSafe because rusqlite::Connection is not RefUnwindSafe
 /// (contains RefCell), but we accept this for daemon resilience.
-fn run_task(name: &str, task: impl FnOnce()) {
+/// A panic is reported as the task error.
+fn run_task(name: &str, task: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
     match std::panic::catch_unwind(AssertUnwindSafe(task)) {
-        Ok(()) => {}
+        Ok(result) => result,
         Err(_) => {
             tracing::error!("Task '{}' panicked. Daemon continues.", name);
+            Err("panicked".to_string())
         }
     }
 }
 
-/// Main prune loop — iterates all active agents every `prune_interval` seconds.
+/// Keeps agent IPC token files in line with the registry. The set of token
+/// holders is one query on a kept connection; tokens are only minted and
+/// written when it or the secret changed, or every AGENT_TOKEN_RESYNC_SECS
+/// (token file removed by hand).
+#[derive(Default)]
+struct TokenSync {
+    conn: Option<Connection>,
+    /// Holders and secret mtime of the last sync.
+    holders: Vec<(String, String)>,
+    secret_mtime: Option<std::time::SystemTime>,
+    /// None until the first sync.
+    last_sync: Option<Instant>,
+}
+
+impl TokenSync {
+    fn tick(&mut self) {
+        if let Err(e) = self.sync() {
+            // Reopen next tick (registry moved, connection broken)
+            self.conn = None;
+            tracing::warn!(error = %e, "Agent IPC token sync failed");
+        }
+    }
+
+    fn sync(&mut self) -> ai_smartness::AiResult<()> {
+        let conn = match self.conn.take() {
+            Some(conn) => conn,
+            None => database::open_connection(&path_utils::registry_db_path(), ConnectionRole::Daemon)?,
+        };
+        let holders = ipc_auth::token_holders(&conn)?;
+        self.conn = Some(conn);
+        let secret_mtime = std::fs::metadata(path_utils::ipc_secret_path()).and_then(|m| m.modified()).ok();
+        let resync_due = self
+            .last_sync
+            .is_none_or(|t| t.elapsed() >= Duration::from_secs(ai_smartness::constants::AGENT_TOKEN_RESYNC_SECS));
+        if !resync_due && holders == self.holders && secret_mtime == self.secret_mtime {
+            return Ok(());
+        }
+        let changed = ipc_auth::sync_agent_tokens(&ipc_auth::read_secret()?, &holders)?;
+        if changed > 0 {
+            tracing::info!(changed, holders = holders.len(), "Agent IPC tokens synced");
+        }
+        self.holders = holders;
+        self.secret_mtime = secret_mtime;
+        self.last_sync = Some(Instant::now());
+        Ok(())
+    }
+}
+
+/// Main prune loop — every `scheduler::TICK_SECS`, runs the periodic tasks
+/// that are due (per-task schedules, see `scheduler`).
 pub fn run_prune_loop(
     pool: Arc<ConnectionPool>,
     capture_queue: Option<Arc<super::capture_queue::CaptureQueue>>,
     running: Arc<AtomicBool>,
-    prune_interval_secs: u64,
 ) {
-    let interval = Duration::from_secs(prune_interval_secs);
+    let tick = Duration::from_secs(scheduler::TICK_SECS);
     let eviction_interval = Duration::from_secs(
         ai_smartness::constants::POOL_EVICTION_CHECK_SECS,
     );
     let mut last_eviction = Instant::now();
+    let mut tokens = TokenSync::default();
+    // Per task: first agent its last pass did not reach (max runtime), where
+    // its next pass starts
+    let mut resume_from: HashMap<&'static str, AgentKey> = HashMap::new();
 
     while running.load(Ordering::Relaxed) {
-        std::thread::sleep(interval);
+        std::thread::sleep(tick);
         if !running.load(Ordering::Relaxed) {
             break;
         }
 
-        // System watchdog — collect once per cycle (shared across all agents)
-        let system_metrics = super::watchdog::collect();
-        if system_metrics.cpu_usage_percent > 90.0 {
-            tracing::warn!(
-                cpu = system_metrics.cpu_usage_percent,
-                "HIGH CPU usage detected by watchdog"
-            );
+        // Agents registered since the last tick get their IPC token,
+        // unregistered ones lose it
+        tokens.tick();
+
+        let config = live_config::daemon();
+        let (agent_tasks, global_tasks): (Vec<_>, Vec<_>) = scheduler::due(&config, chrono::Utc::now())
+            .into_iter()
+            .partition(|(task, _)| task.scope == Scope::Agent);
+
+        if !agent_tasks.is_empty() {
+            run_agent_tasks(&agent_tasks, &pool, capture_queue.as_deref(), &running, &mut resume_from);
         }
-        if let (Some(vram_used), Some(vram_total)) = (system_metrics.gpu_vram_used_mb, system_metrics.gpu_vram_total_mb) {
-            if vram_total > 0 {
-                let pct = (vram_used as f64 / vram_total as f64) * 100.0;
-                if pct > 90.0 {
-                    tracing::warn!(vram_used, vram_total, pct = format!("{:.0}", pct), "HIGH GPU VRAM usage");
-                }
+
+        for (task, schedule) in &global_tasks {
+            let started = chrono::Utc::now();
+            let t = Instant::now();
+            let result = run_task(task.name, || run_global_task(task.name, &pool, &config));
+            if let Err(ref e) = result {
+                tracing::warn!(task = task.name, error = %e, "Periodic task failed");
             }
+            let report = RunReport {
+                started,
+                duration: t.elapsed(),
+                errors: result.err().into_iter().collect(),
+                agents: 0,
+                deferred: 0,
+            };
+            scheduler::record(task, &report, Some(schedule));
         }
-        tracing::debug!(
-            cpu = format!("{:.1}", system_metrics.cpu_usage_percent),
-            ram_used_mb = system_metrics.ram_used_mb,
-            ram_available_mb = system_metrics.ram_available_mb,
-            gpu_vram_used = ?system_metrics.gpu_vram_used_mb,
-            threads = ?system_metrics.thread_count,
-            "Watchdog metrics collected"
-        );
 
-        let keys = pool.active_keys();
-        let agent_count = keys.len();
+        // Periodically evict idle connections
+        if last_eviction.elapsed() >= eviction_interval {
+            pool.evict_idle();
+            last_eviction = Instant::now();
+        }
+    }
 
-        if agent_count == 0 {
-            tracing::debug!("No active agents in pool, skipping prune cycle");
-        } else {
-            let cycle_start = Instant::now();
-            tracing::info!(agent_count = agent_count, "Starting global prune cycle");
+    tracing::info!("Prune loop stopped");
+}
 
-            for key in &keys {
-                if !running.load(Ordering::Relaxed) {
-                    break;
+/// Run one task now (`daemon tasks run`): for the given agent, or once for a
+/// global task. Recorded as a manual run (the schedule is unchanged).
+pub fn run_task_now(
+    name: &str,
+    key: Option<&AgentKey>,
+    pool: &ConnectionPool,
+    capture_queue: Option<&super::capture_queue::CaptureQueue>,
+) -> Result<serde_json::Value, String> {
+    let task = scheduler::find(name).ok_or_else(|| format!("Unknown task '{}'", name))?;
+    let key = match task.scope {
+        Scope::Agent => {
+            let key = key.ok_or_else(|| format!("Task '{}' runs per agent: project_hash and agent_id required", name))?;
+            if pool.is_locked(key) {
+                return Err(format!("Memory of agent '{}' is locked", key.agent_id));
+            }
+            Some(key)
+        }
+        Scope::Global => None,
+    };
+
+    let started = chrono::Utc::now();
+    let t = Instant::now();
+    let result = match key {
+        Some(key) => {
+            let system_metrics = (name == "beat").then(super::watchdog::collect);
+            let ctx = AgentTaskContext::new(key, pool, capture_queue, system_metrics.as_ref());
+            run_task(name, || run_agent_task(name, &ctx))
+        }
+        None => run_task(name, || run_global_task(name, pool, &live_config::daemon())),
+    };
+    let report = RunReport {
+        started,
+        duration: t.elapsed(),
+        errors: result.clone().err().into_iter().collect(),
+        agents: usize::from(key.is_some()),
+        deferred: 0,
+    };
+    scheduler::record(task, &report, None);
+    tracing::info!(task = name, agent = ?key.map(|k| &k.agent_id), ok = result.is_ok(), "Task run on demand");
+
+    Ok(serde_json::json!({
+        "task": name,
+        "project_hash": key.map(|k| &k.project_hash),
+        "agent_id": key.map(|k| &k.agent_id),
+        "duration_ms": report.duration.as_millis() as u64,
+        "ok": result.is_ok(),
+        "error": result.err(),
+    }))
+}
+
+/// Per-task totals of one scheduled pass.
+#[derive(Default)]
+struct PassTotals {
+    elapsed: Duration,
+    agents: usize,
+    deferred: usize,
+    errors: Vec<String>,
+}
+
+/// One scheduled pass of the due agent tasks over every unlocked agent.
+/// Without agents nothing runs: the tasks stay due until an agent shows up.
+/// A task whose max runtime is spent skips the remaining agents; its next
+/// pass starts with the first agent it skipped (one cursor per task).
+fn run_agent_tasks(
+    tasks: &[(&'static TaskDef, Schedule)],
+    pool: &ConnectionPool,
+    capture_queue: Option<&super::capture_queue::CaptureQueue>,
+    running: &AtomicBool,
+    resume_from: &mut HashMap<&'static str, AgentKey>,
+) {
+    let mut keys: Vec<AgentKey> = pool
+        .active_keys()
+        .into_iter()
+        .filter(|key| {
+            let locked = pool.is_locked(key);
+            if locked {
+                tracing::debug!(project = %key.project_hash, agent = %key.agent_id, "Skipping locked agent");
+            }
+            !locked
+        })
+        .collect();
+    if keys.is_empty() {
+        tracing::debug!("No active agents in pool, due tasks wait");
+        return;
+    }
+    keys.sort_by(|a, b| (&a.project_hash, &a.agent_id).cmp(&(&b.project_hash, &b.agent_id)));
+    let starts: Vec<usize> = tasks
+        .iter()
+        .map(|(task, _)| {
+            resume_from
+                .remove(task.name)
+                .and_then(|k| keys.iter().position(|x| *x == k))
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let pass_start = Instant::now();
+    tracing::info!(
+        agent_count = keys.len(),
+        tasks = ?tasks.iter().map(|(t, _)| t.name).collect::<Vec<_>>(),
+        "Starting scheduled task pass"
+    );
+
+    // System watchdog — collected once per pass (shared across all agents)
+    let system_metrics = tasks.iter().any(|(t, _)| t.name == "beat").then(collect_system_metrics);
+    let started = chrono::Utc::now();
+    let mut totals: Vec<PassTotals> = tasks.iter().map(|_| PassTotals::default()).collect();
+
+    for step in 0..keys.len() {
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+        // Agent each task is at on this step (the same one unless a task
+        // resumes from a deferral), grouped to share the agent's context
+        let mut by_agent: Vec<(&AgentKey, Vec<usize>)> = Vec::new();
+        for (i, ((task, schedule), total)) in tasks.iter().zip(totals.iter_mut()).enumerate() {
+            let key = &keys[(starts[i] + step) % keys.len()];
+            if total.deferred > 0 || schedule.max_runtime.is_some_and(|max| total.elapsed >= max) {
+                if total.deferred == 0 {
+                    resume_from.insert(task.name, key.clone());
                 }
+                total.deferred += 1;
+                continue;
+            }
+            match by_agent.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, indices)) => indices.push(i),
+                None => by_agent.push((key, vec![i])),
+            }
+        }
 
-                // Skip locked agents
-                if pool.is_locked(key) {
-                    tracing::debug!(
-                        project = %key.project_hash,
-                        agent = %key.agent_id,
-                        "Skipping locked agent"
-                    );
-                    continue;
+        for (key, indices) in by_agent {
+            tracing::debug!(project = %key.project_hash, agent = %key.agent_id, "Running due tasks for agent");
+            let ctx = AgentTaskContext::new(key, pool, capture_queue, system_metrics.as_ref());
+            let mut timed = TimedTasks::new();
+            for i in indices {
+                let (task, total) = (tasks[i].0, &mut totals[i]);
+                let t = Instant::now();
+                let result = timed.run(task.name, || run_agent_task(task.name, &ctx));
+                total.elapsed += t.elapsed();
+                total.agents += 1;
+                if let Err(e) = result {
+                    tracing::warn!(task = task.name, agent = %key, error = %e, "Periodic task failed");
+                    total.errors.push(format!("{}: {}", key.agent_id, e));
                 }
+            }
+            timed.finish(key);
+        }
+    }
 
-                // 0. Beat increment + watchdog metrics + LLM status + backpressure auto-clear
-                let metrics_clone = system_metrics.clone();
-                run_task("beat", || {
-                    let data_dir = path_utils::agent_data_dir(&key.project_hash, &key.agent_id);
-                    let mut beat = BeatState::load(&data_dir);
-                    beat.increment();
-                    // Write system watchdog metrics into beat.json
-                    beat.system_metrics = Some(metrics_clone);
-                    // Write LLM observability into beat.json
-                    let llm = ai_smartness::processing::local_llm::LocalLlm::global();
-                    beat.llm_status = Some(llm.status().to_string());
-                    let guardian_cfg = {
-                        let cfg_path = path_utils::data_dir().join("config.json");
-                        std::fs::read_to_string(&cfg_path)
-                            .ok()
-                            .and_then(|s| serde_json::from_str::<GuardianConfig>(&s).ok())
-                            .unwrap_or_default()
-                    };
-                    beat.llm_backend = Some(format!("{:?}", guardian_cfg.llm_backend));
-                    beat.llm_ctx_size = Some(llm.current_ctx_size());
-                    beat.llm_gpu_layers = Some(llm.current_gpu_layers());
-                    // Auto-clear backpressure if stale (> 10 min safety timeout)
-                    if beat.processing_backpressure {
-                        if let Some(ref since) = beat.backpressure_since {
-                            if let Ok(t) = since.parse::<chrono::DateTime<chrono::Utc>>() {
-                                let age_secs = (chrono::Utc::now() - t).num_seconds();
-                                if age_secs > 600 {
-                                    beat.processing_backpressure = false;
-                                    beat.backpressure_since = None;
-                                    tracing::info!(
-                                        agent = %key.agent_id,
-                                        age_secs,
-                                        "Backpressure auto-cleared (>10min timeout)"
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    beat.save(&data_dir);
-                    tracing::debug!(
-                        agent = %key.agent_id,
-                        beat = beat.beat,
-                        "Beat incremented"
-                    );
-                });
-
-                // 0b. Quota sync from registry → BeatState + pool cache
-                run_task("quota_sync", || {
-                    let reg_path = path_utils::registry_db_path();
-                    if let Ok(reg_conn) = database::open_connection(&reg_path, ConnectionRole::Daemon) {
-                        if let Ok(Some(agent)) = AgentRegistry::get(&reg_conn, &key.agent_id, &key.project_hash) {
-                            let quota = agent.thread_mode.quota();
-                            let data_dir = path_utils::agent_data_dir(&key.project_hash, &key.agent_id);
-                            let mut beat = BeatState::load(&data_dir);
-                            if beat.quota != quota {
-                                tracing::info!(
-                                    agent = %key.agent_id,
-                                    old_quota = beat.quota,
-                                    new_quota = quota,
-                                    "Quota sync: registry → beat.json"
-                                );
-                                beat.quota = quota;
-                                beat.save(&data_dir);
-                            }
-                            pool.refresh_quota(key, quota);
-                        }
-                    }
-                });
-
-                // Get connection for this agent
-                let conn = match pool.get_or_open(key) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        tracing::error!(
-                            project = %key.project_hash,
-                            agent = %key.agent_id,
-                            error = %e,
-                            "Failed to get connection for prune"
-                        );
-                        continue;
-                    }
-                };
+    for ((task, schedule), total) in tasks.iter().zip(totals) {
+        if total.deferred > 0 {
+            tracing::warn!(
+                task = task.name,
+                deferred = total.deferred,
+                elapsed_ms = total.elapsed.as_millis() as u64,
+                "Task max runtime reached — remaining agents deferred to the next run"
+            );
+        }
+        let report = RunReport {
+            started,
+            duration: total.elapsed,
+            errors: total.errors,
+            agents: total.agents,
+            deferred: total.deferred,
+        };
+        scheduler::record(task, &report, Some(schedule));
+    }
 
-                // If connection mutex is poisoned, evict and reconnect before locking
-                let conn = if conn.is_poisoned() {
-                    tracing::warn!(
-                        project = %key.project_hash,
-                        agent = %key.agent_id,
-                        "Prune: DB mutex poisoned — evicting and reconnecting"
-                    );
-                    pool.force_evict(key);
-                    match pool.get_or_open(key) {
-                        Ok(c) => c,
-                        Err(e) => {
-                            tracing::error!(agent = %key.agent_id, error = %e,
-                                "Prune: failed to reconnect after eviction");
-                            continue;
-                        }
-                    }
-                } else {
-                    conn
-                };
+    tracing::info!(
+        agent_count = keys.len(),
+        duration_ms = pass_start.elapsed().as_millis() as u64,
+        "Scheduled task pass complete"
+    );
+}
 
-                tracing::debug!(
-                    project = %key.project_hash,
-                    agent = %key.agent_id,
-                    "Running prune cycle for agent"
-                );
-
-                // Load GuardianConfig for this prune cycle
-                let guardian = {
-                    let cfg_path = path_utils::data_dir().join("config.json");
-                    std::fs::read_to_string(&cfg_path)
-                        .ok()
-                        .and_then(|s| serde_json::from_str::<GuardianConfig>(&s).ok())
-                        .unwrap_or_default()
-                };
+/// Watchdog metrics for beat.json, with high CPU/VRAM warnings.
+fn collect_system_metrics() -> super::watchdog::SystemMetrics {
+    let system_metrics = super::watchdog::collect();
+    if system_metrics.cpu_usage_percent > 90.0 {
+        tracing::warn!(
+            cpu = system_metrics.cpu_usage_percent,
+            "HIGH CPU usage detected by watchdog"
+        );
+    }
+    if let (Some(vram_used), Some(vram_total)) = (system_metrics.gpu_vram_used_mb, system_metrics.gpu_vram_total_mb) {
+        if vram_total > 0 {
+            let pct = (vram_used as f64 / vram_total as f64) * 100.0;
+            if pct > 90.0 {
+                tracing::warn!(vram_used, vram_total, pct = format!("{:.0}", pct), "HIGH GPU VRAM usage");
+            }
+        }
+    }
+    tracing::debug!(
+        cpu = format!("{:.1}", system_metrics.cpu_usage_percent),
+        ram_used_mb = system_metrics.ram_used_mb,
+        ram_available_mb = system_metrics.ram_available_mb,
+        gpu_vram_used = ?system_metrics.gpu_vram_used_mb,
+        threads = ?system_metrics.thread_count,
+        "Watchdog metrics collected"
+    );
+    system_metrics
+}
 
-                let data_dir = path_utils::agent_data_dir(&key.project_hash, &key.agent_id);
-                run_prune_cycle(
-                    &conn, &guardian, &data_dir, &key.project_hash, &key.agent_id,
-                    capture_queue.as_deref(),
-                );
-
-                // 7. Scheduled backup (outside prune cycle — needs key context)
-                run_task("backup", || {
-                    let config = BackupConfig::load();
-                    if !config.is_backup_due() {
-                        return;
-                    }
-                    let backup_dir = std::path::PathBuf::from(
-                        path_utils::expand_tilde(&config.backup_path),
-                    );
-                    match BackupManager::backup_agent(
-                        &key.project_hash,
-                        &key.agent_id,
-                        &backup_dir,
-                    ) {
-                        Ok(path) => {
-                            tracing::info!(
-                                agent = %key.agent_id,
-                                path = %path.display(),
-                                "Scheduled backup"
-                            );
-                            let mut cfg = BackupConfig::load();
-                            cfg.last_backup_at =
-                                Some(chrono::Utc::now().to_rfc3339());
-                            cfg.save();
-                            BackupManager::enforce_retention(
-                                &backup_dir,
-                                config.retention_count,
-                            );
-                        }
-                        Err(e) => tracing::warn!(
-                            agent = %key.agent_id,
-                            error = %e,
-                            "Scheduled backup failed"
-                        ),
-                    }
-                });
+/// Global (cross-project) tasks.
+fn run_global_task(name: &str, pool: &ConnectionPool, config: &DaemonConfig) -> Result<(), String> {
+    match name {
+        // Cross-project gossip (registry DB, all projects) — opt-in
+        "cross_gossip" if config.gossip_cross_project => run_cross_project_gossip(pool),
+        "cross_gossip" => Ok(()),
+        // Audit log retention (registry DB, all projects)
+        "audit_retention" => {
+            let guardian = live_config::guardian();
+            let reg_path = path_utils::registry_db_path();
+            let reg_conn = database::open_connection(&reg_path, ConnectionRole::Daemon).map_err(|e| e.to_string())?;
+            let n = AuditLog::prune(&reg_conn, &guardian.audit).map_err(|e| e.to_string())?;
+            if n > 0 {
+                tracing::info!(removed = n, "Audit log pruned");
             }
+            Ok(())
+        }
+        // Dead letter retention (capture journal DB)
+        "dead_letter_retention" => {
+            let pool_cfg = live_config::guardian().capture.pool.clone();
+            let journal_path = path_utils::capture_journal_path();
+            let conn = database::open_connection(&journal_path, ConnectionRole::Daemon).map_err(|e| e.to_string())?;
+            migrations::migrate_journal_db(&conn).map_err(|e| e.to_string())?;
+            let n = DeadLetterStore::prune(&conn, pool_cfg.dead_letter_retention_days, pool_cfg.dead_letter_max)
+                .map_err(|e| e.to_string())?;
+            if n > 0 {
+                tracing::info!(removed = n, "Dead letters pruned");
+            }
+            Ok(())
+        }
+        _ => Err(format!("Unknown global task '{}'", name)),
+    }
+}
 
-            tracing::info!(
-                agent_count = agent_count,
-                duration_ms = cycle_start.elapsed().as_millis() as u64,
-                "Global prune cycle complete"
-            );
+/// Start of the previous cross-project pass per agent (RFC 3339): only threads
+/// active since then are compared. Agents not seen since the daemon started
+/// get a full comparison.
+static CROSS_GOSSIP_SINCE: LazyLock<Mutex<HashMap<AgentKey, String>>> = LazyLock::new(Default::default);
+
+/// Cross-project gossip pass over every registered agent memory.
+/// Agent DBs are read through their own short-lived connections, so agents
+/// that are not in the pool are compared too; locked agents are skipped.
+fn run_cross_project_gossip(pool: &ConnectionPool) -> Result<(), String> {
+    let started = time_utils::to_sqlite(&time_utils::now());
+    let reg_path = path_utils::registry_db_path();
+    let reg_conn = database::open_connection(&reg_path, ConnectionRole::Daemon).map_err(|e| e.to_string())?;
+    // cross_bridges may be newer than the last registry migration run by a client
+    migrations::migrate_registry_db(&reg_conn).map_err(|e| format!("registry migration: {}", e))?;
+    let agents = AgentRegistry::list(&reg_conn, None, None, None).map_err(|e| format!("agent list: {}", e))?;
+
+    let mut conns = Vec::new();
+    for agent in &agents {
+        let key = AgentKey { project_hash: agent.project_hash.clone(), agent_id: agent.id.clone() };
+        let db_path = path_utils::agent_db_path(&key.project_hash, &key.agent_id);
+        if pool.is_locked(&key) || !db_path.exists() {
+            continue;
+        }
+        match database::open_connection(&db_path, ConnectionRole::Daemon) {
+            Ok(conn) => conns.push((key, conn)),
+            Err(e) => tracing::warn!(agent = %key, error = %e, "Cross-project gossip: open failed"),
         }
+    }
 
-        // Periodically evict idle connections
-        if last_eviction.elapsed() >= eviction_interval {
-            pool.evict_idle();
-            last_eviction = Instant::now();
+    let since = CROSS_GOSSIP_SINCE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let mut snapshots = Vec::new();
+    let mut keys = Vec::new();
+    for (key, conn) in &conns {
+        match MemorySnapshot::load(conn, &key.project_hash, &key.agent_id, since.get(key).map(String::as_str)) {
+            Ok(s) => {
+                snapshots.push(s);
+                keys.push(key.clone());
+            }
+            Err(e) => tracing::warn!(agent = %key, error = %e, "Cross-project gossip: snapshot failed"),
         }
     }
 
-    tracing::info!("Prune loop stopped");
+    let projects: std::collections::HashSet<&str> =
+        snapshots.iter().map(|s| s.project_hash.as_str()).collect();
+    if projects.len() < 2 {
+        tracing::debug!(projects = projects.len(), "Cross-project gossip skipped: fewer than 2 projects");
+        return Ok(());
+    }
+
+    let guardian = live_config::guardian();
+    let n = cross_gossip::run_cycle(&reg_conn, &snapshots, &guardian.gossip).map_err(|e| e.to_string())?;
+    let mut since = CROSS_GOSSIP_SINCE.lock().unwrap_or_else(|e| e.into_inner());
+    for key in keys {
+        since.insert(key, started.clone());
+    }
+    if n > 0 {
+        tracing::info!("Cross-project gossip: created {} bridges", n);
+    }
+    Ok(())
 }
 
 /// Pool consumer loop — processes .pending pool files at LLM speed.
@@ -367,16 +547,11 @@ pub fn run_pool_consumer_loop(
                 Err(poison) => poison.into_inner(),
             };
 
-            let guardian = {
-                let cfg_path = path_utils::data_dir().join("config.json");
-                std::fs::read_to_string(&cfg_path)
-                    .ok()
-                    .and_then(|s| serde_json::from_str::<GuardianConfig>(&s).ok())
-                    .unwrap_or_default()
-            };
+            let guardian = live_config::guardian();
             let thread_quota = pool.get_thread_quota(key);
 
             match pool_processor::process_pending_files(
+                key,
                 &pool_dir,
                 &conn_guard,
                 &mut pending_guard,
@@ -449,16 +624,11 @@ pub fn flush_all_pools(pool: &ConnectionPool) -> (usize, Vec<String>) {
             Err(poison) => poison.into_inner(),
         };
 
-        let guardian = {
-            let cfg_path = path_utils::data_dir().join("config.json");
-            std::fs::read_to_string(&cfg_path)
-                .ok()
-                .and_then(|s| serde_json::from_str::<GuardianConfig>(&s).ok())
-                .unwrap_or_default()
-        };
+        let guardian = live_config::guardian();
         let thread_quota = pool.get_thread_quota(key);
 
         match pool_processor::process_pending_files(
+            key,
             &pool_dir,
             &conn_guard,
             &mut pending_guard,
@@ -550,193 +720,494 @@ fn clear_backpressure(key: &AgentKey) {
     }
 }
 
-/// Single prune cycle for one agent — each task acquires/releases the lock
-/// independently to reduce contention (~5s per task instead of ~60s continuous).
-fn run_prune_cycle(
-    conn_mtx: &Mutex<Connection>,
+/// Audit entry of a retention pass with every id it touched (None when
+/// nothing changed).
+fn retention_audit_entry(report: &RetentionReport, project_hash: &str, agent_id: &str) -> Option<AuditEntry> {
+    let changed = report.rules.iter().any(|r| r.archived > 0 || r.deleted > 0);
+    if report.dry_run || !changed {
+        return None;
+    }
+    let params = serde_json::json!({ "rules": report.rules });
+    let mut entry = AuditEntry::new(project_hash, agent_id, "daemon:retention", &params);
+    entry.affected_ids = report.affected_ids();
+    Some(entry)
+}
+
+/// Audit the memory mutations of a retention pass (skipped when nothing changed).
+fn record_retention_audit(
+    report: &RetentionReport,
     guardian: &GuardianConfig,
-    agent_data_dir: &std::path::Path,
     project_hash: &str,
     agent_id: &str,
-    capture_queue: Option<&super::capture_queue::CaptureQueue>,
 ) {
-    // 1. Gossip v2: concept-based bridge discovery (config-driven limits)
-    run_task("gossip", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        let gossip = match Gossip::new(&conn) {
-            Ok(g) => g,
-            Err(e) => {
-                tracing::warn!("Gossip init error: {}", e);
-                return;
-            }
-        };
-        match gossip.run_cycle(&conn, &guardian.gossip) {
-            Ok(n) => {
-                if n > 0 {
-                    tracing::info!("Gossip v2: created {} bridges", n);
-                }
-            }
-            Err(e) => tracing::warn!("Gossip error: {}", e),
+    if !guardian.audit.enabled {
+        return;
+    }
+    let Some(entry) = retention_audit_entry(report, project_hash, agent_id) else { return };
+    let reg_path = path_utils::registry_db_path();
+    let Ok(reg_conn) = database::open_connection(&reg_path, ConnectionRole::Daemon) else { return };
+    if let Err(e) = AuditLog::record(&reg_conn, &entry) {
+        tracing::debug!(error = %e, "Retention audit failed (non-critical)");
+    }
+}
+
+/// What the agent tasks of one pass share: the agent, its connection (opened
+/// on first use, so file-only tasks still run when the DB can't be opened)
+/// and the configs loaded once per agent.
+struct AgentTaskContext<'a> {
+    key: &'a AgentKey,
+    pool: &'a ConnectionPool,
+    capture_queue: Option<&'a super::capture_queue::CaptureQueue>,
+    system_metrics: Option<&'a super::watchdog::SystemMetrics>,
+    guardian: Arc<GuardianConfig>,
+    data_dir: std::path::PathBuf,
+    conn: OnceCell<Result<Arc<Mutex<Connection>>, String>>,
+}
+
+impl<'a> AgentTaskContext<'a> {
+    fn new(
+        key: &'a AgentKey,
+        pool: &'a ConnectionPool,
+        capture_queue: Option<&'a super::capture_queue::CaptureQueue>,
+        system_metrics: Option<&'a super::watchdog::SystemMetrics>,
+    ) -> Self {
+        Self {
+            key,
+            pool,
+            capture_queue,
+            system_metrics,
+            guardian: live_config::guardian(),
+            data_dir: path_utils::agent_data_dir(&key.project_hash, &key.agent_id),
+            conn: OnceCell::new(),
         }
-    });
+    }
 
-    // 2. Decay: reduce weights, suspend low-weight threads
-    run_task("decay", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        match Decayer::decay_active(&conn, &guardian.decay) {
-            Ok(n) => {
-                if n > 0 {
-                    tracing::info!("Decay: {} threads affected", n);
-                }
-            }
-            Err(e) => tracing::warn!("Decay error: {}", e),
+    /// Run `f` with the agent connection locked. Each task acquires/releases
+    /// the lock independently to reduce contention (~5s per task instead of
+    /// ~60s continuous).
+    fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> Result<T, String>) -> Result<T, String> {
+        let conn = self.conn.get_or_init(|| self.open()).clone()?;
+        let guard = conn.lock().map_err(|_| "connection mutex poisoned".to_string())?;
+        f(&guard)
+    }
+
+    fn open(&self) -> Result<Arc<Mutex<Connection>>, String> {
+        let key = self.key;
+        let conn = self.pool.get_or_open(key).inspect_err(|e| {
+            tracing::error!(project = %key.project_hash, agent = %key.agent_id, error = %e,
+                "Failed to get connection for periodic tasks");
+        })?;
+        if !conn.is_poisoned() {
+            return Ok(conn);
         }
-    });
+        // If connection mutex is poisoned, evict and reconnect before locking
+        tracing::warn!(
+            project = %key.project_hash,
+            agent = %key.agent_id,
+            "Periodic tasks: DB mutex poisoned — evicting and reconnecting"
+        );
+        self.pool.force_evict(key);
+        self.pool.get_or_open(key).inspect_err(|e| {
+            tracing::error!(agent = %key.agent_id, error = %e,
+                "Periodic tasks: failed to reconnect after eviction");
+        })
+    }
+}
 
-    // 3. Archive: stale suspended -> archived (after config hours)
-    run_task("archive", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        match Archiver::archive_stale(&conn, &guardian.decay) {
-            Ok(n) => {
-                if n > 0 {
-                    tracing::info!("Archived: {} threads", n);
+/// Agent tasks, by scheduler name.
+fn run_agent_task(name: &str, ctx: &AgentTaskContext) -> Result<(), String> {
+    match name {
+        "beat" => task_beat(ctx),
+        "quota_sync" => task_quota_sync(ctx),
+        "gossip" => task_gossip(ctx),
+        "decay" => task_decay(ctx),
+        "archive" => task_archive(ctx),
+        "retention" => task_retention(ctx),
+        "inbox_cleanup" => task_inbox_cleanup(ctx),
+        "work_context_and_injection" => task_work_context_and_injection(ctx),
+        "concept_backfill" => task_concept_backfill(ctx),
+        "topic_alias_suggest" => task_topic_alias_suggest(ctx),
+        "quality_scan" => task_quality_scan(ctx),
+        "shared_orphan_cleanup" => task_shared_orphan_cleanup(ctx),
+        "wal_checkpoint" => task_wal_checkpoint(ctx),
+        "session_gc" => task_session_gc(ctx),
+        "pool_done_cleanup" => task_pool_done_cleanup(ctx),
+        "backup" => task_backup(ctx),
+        _ => Err(format!("Unknown agent task '{}'", name)),
+    }
+}
+
+/// Beat increment + watchdog metrics + LLM status + backpressure auto-clear.
+fn task_beat(ctx: &AgentTaskContext) -> Result<(), String> {
+    let key = ctx.key;
+    let mut beat = BeatState::load(&ctx.data_dir);
+    beat.increment();
+    // Write system watchdog metrics into beat.json
+    if let Some(system_metrics) = ctx.system_metrics {
+        beat.system_metrics = Some(system_metrics.clone());
+    }
+    // Write LLM observability into beat.json
+    let llm = ai_smartness::processing::local_llm::LocalLlm::global();
+    beat.llm_status = Some(llm.status().to_string());
+    beat.llm_backend = Some(format!("{:?}", ctx.guardian.llm_backend));
+    beat.llm_ctx_size = Some(llm.current_ctx_size());
+    beat.llm_gpu_layers = Some(llm.current_gpu_layers());
+    // Auto-clear backpressure if stale (> 10 min safety timeout)
+    if beat.processing_backpressure {
+        if let Some(ref since) = beat.backpressure_since {
+            if let Ok(t) = since.parse::<chrono::DateTime<chrono::Utc>>() {
+                let age_secs = (chrono::Utc::now() - t).num_seconds();
+                if age_secs > 600 {
+                    beat.processing_backpressure = false;
+                    beat.backpressure_since = None;
+                    tracing::info!(
+                        agent = %key.agent_id,
+                        age_secs,
+                        "Backpressure auto-cleared (>10min timeout)"
+                    );
                 }
             }
-            Err(e) => tracing::warn!("Archive error: {}", e),
         }
-    });
+    }
+    beat.save(&ctx.data_dir);
+    tracing::debug!(
+        agent = %key.agent_id,
+        beat = beat.beat,
+        "Beat incremented"
+    );
+    Ok(())
+}
 
-    // 4. Cognitive inbox cleanup: expire stale messages
-    run_task("inbox_cleanup", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        if let Err(e) = CognitiveInbox::expire_stale(&conn) {
-            tracing::warn!("Inbox cleanup error: {}", e);
+/// Quota sync from registry → BeatState + pool cache.
+fn task_quota_sync(ctx: &AgentTaskContext) -> Result<(), String> {
+    let key = ctx.key;
+    let reg_path = path_utils::registry_db_path();
+    let reg_conn = database::open_connection(&reg_path, ConnectionRole::Daemon).map_err(|e| e.to_string())?;
+    let Some(agent) = AgentRegistry::get(&reg_conn, &key.agent_id, &key.project_hash).map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    let quota = agent.thread_mode.quota();
+    let mut beat = BeatState::load(&ctx.data_dir);
+    if beat.quota != quota {
+        tracing::info!(
+            agent = %key.agent_id,
+            old_quota = beat.quota,
+            new_quota = quota,
+            "Quota sync: registry → beat.json"
+        );
+        beat.quota = quota;
+        beat.save(&ctx.data_dir);
+    }
+    ctx.pool.refresh_quota(key, quota);
+    Ok(())
+}
+
+/// Gossip v2: concept-based bridge discovery (config-driven limits).
+/// LLM relation calls run between two connection locks: candidate pairs are
+/// read first, classified unlocked, and the cycle then runs on a fresh index.
+fn task_gossip(ctx: &AgentTaskContext) -> Result<(), String> {
+    let cfg = &ctx.guardian.gossip;
+    let candidates = if cfg.llm_relation_enabled {
+        ctx.with_conn(|conn| {
+            let gossip = Gossip::new(conn).map_err(|e| format!("init: {}", e))?;
+            gossip.relation_candidates(conn, cfg).map_err(|e| e.to_string())
+        })?
+    } else {
+        Vec::new()
+    };
+    let verdicts = Gossip::classify_relations(&candidates, &ctx.guardian.local_model_size);
+
+    ctx.with_conn(|conn| {
+        let gossip = Gossip::new(conn)
+            .map_err(|e| format!("init: {}", e))?
+            .with_relation_verdicts(verdicts);
+        let started = chrono::Utc::now();
+        let n = gossip.run_cycle(conn, cfg).map_err(|e| e.to_string())?;
+        if n > 0 {
+            emit_bridges_created(conn, ctx.key, started);
+            metrics::GOSSIP_BRIDGES_CREATED.add(
+                &[("project", ctx.key.project_hash.as_str()), ("agent", ctx.key.agent_id.as_str())],
+                n as f64,
+            );
+            tracing::info!("Gossip v2: created {} bridges", n);
         }
-    });
+        Ok(())
+    })
+}
 
-    // 5+6. Work context cleanup + injection decay (shared list_active cache)
-    run_task("work_context_and_injection", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        let active = ThreadStorage::list_active(&conn).unwrap_or_default();
-        match cleanup_stale_work_contexts(&conn, &active) {
-            Ok(n) => {
-                if n > 0 {
-                    tracing::info!("WorkContext cleanup: {} expired", n);
-                }
+/// Decay: reduce weights, suspend low-weight threads.
+fn task_decay(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| {
+        let changes = Decayer::decay_active_changes(conn, &ctx.guardian.decay).map_err(|e| e.to_string())?;
+        emit_decay_changes(&changes, ctx.key);
+        if changes.affected > 0 {
+            tracing::info!("Decay: {} threads affected", changes.affected);
+        }
+        Ok(())
+    })
+}
+
+/// Archive: stale suspended -> archived (after config hours).
+fn task_archive(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| {
+        let n = Archiver::archive_stale(conn, &ctx.guardian.decay).map_err(|e| e.to_string())?;
+        if n > 0 {
+            tracing::info!("Archived: {} threads", n);
+        }
+        Ok(())
+    })
+}
+
+/// Retention rules: per label/origin archive/delete/protect (report persisted for ai_status).
+fn task_retention(ctx: &AgentTaskContext) -> Result<(), String> {
+    let guardian = &ctx.guardian;
+    ctx.with_conn(|conn| {
+        let report = Retention::run(conn, &guardian.decay, guardian.decay.retention_dry_run)
+            .map_err(|e| e.to_string())?;
+        report.save(&ctx.data_dir);
+        record_retention_audit(&report, guardian, &ctx.key.project_hash, &ctx.key.agent_id);
+        Ok(())
+    })
+}
+
+/// Cognitive inbox cleanup: expire stale messages.
+fn task_inbox_cleanup(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| CognitiveInbox::expire_stale(conn).map(|_| ()).map_err(|e| e.to_string()))
+}
+
+/// Work context cleanup + injection decay (shared list_active cache).
+fn task_work_context_and_injection(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| {
+        let active = ThreadStorage::list_active(conn).unwrap_or_default();
+        let cleaned = cleanup_stale_work_contexts(conn, &active);
+        let decayed = decay_injection_scores(conn, &active);
+        let n = cleaned.map_err(|e| format!("work context cleanup: {}", e))?;
+        if n > 0 {
+            tracing::info!("WorkContext cleanup: {} expired", n);
+        }
+        let n = decayed.map_err(|e| format!("injection decay: {}", e))?;
+        if n > 0 {
+            tracing::info!("Injection decay: {} threads", n);
+        }
+        Ok(())
+    })
+}
+
+/// Concept backfill — populate empty concepts from topics.
+fn task_concept_backfill(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| {
+        let threads = ThreadStorage::list_active(conn).unwrap_or_default();
+        let normalizer = TopicNormalizer::load(conn);
+        let mut count = 0usize;
+        for thread in threads.iter()
+            .filter(|t| t.concepts.is_empty())
+            .take(10)
+        {
+            if !thread.topics.is_empty() {
+                let concepts_json = serde_json::to_string(&normalizer.dedup_concepts(&thread.topics))
+                    .unwrap_or_default();
+                ThreadStorage::update_concepts(conn, &thread.id, &concepts_json).ok();
+                count += 1;
             }
-            Err(e) => tracing::warn!("WorkContext cleanup error: {}", e),
         }
-        match decay_injection_scores(&conn, &active) {
-            Ok(n) => {
-                if n > 0 {
-                    tracing::info!("Injection decay: {} threads", n);
+        if count > 0 {
+            tracing::info!(count, "Concept backfill: populated {} threads", count);
+        }
+        Ok(())
+    })
+}
+
+/// Topic alias suggestions — embedding + co-occurrence.
+fn task_topic_alias_suggest(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| TopicAliasSuggester::run(conn, 20).map(|_| ()).map_err(|e| e.to_string()))
+}
+
+/// Quality scan: detect threads with empty/degenerate fields, auto-queue enrichment.
+/// Lightweight scan — no LLM call. Only submits jobs to the capture queue.
+/// Retry max 2x is handled by the capture queue worker (enrichment_retry field).
+fn task_quality_scan(ctx: &AgentTaskContext) -> Result<(), String> {
+    let Some(cq) = ctx.capture_queue else { return Ok(()) };
+    ctx.with_conn(|conn| {
+        let threads = ThreadStorage::list_active(conn).unwrap_or_default();
+        let mut queued = 0usize;
+
+        for thread in threads.iter().take(20) {
+            if needs_enrichment(thread) {
+                let job = super::capture_queue::CaptureJob {
+                    key: ctx.key.clone(),
+                    source_type: "quality_scan".to_string(),
+                    content: String::new(),
+                    file_path: None,
+                    is_prompt: false,
+                    session_id: None,
+                    enrich_thread_id: Some(thread.id.clone()),
+                    enrichment_retry: 0,
+                };
+                if cq.submit_if_room(job).is_ok() {
+                    queued += 1;
+                } else {
+                    // Queue full — stop submitting this cycle
+                    break;
                 }
             }
-            Err(e) => tracing::warn!("Injection decay error: {}", e),
-        }
-    });
-
-    // 7. Concept backfill — populate empty concepts from topics (1x per day, ~288 beats)
-    {
-        let beat_state = BeatState::load(agent_data_dir);
-        if beat_state.beat % 288 == 0 {
-            run_task("concept_backfill", || {
-                let Ok(conn) = conn_mtx.lock() else { return };
-                let threads = ThreadStorage::list_active(&conn).unwrap_or_default();
-                let mut count = 0usize;
-                for thread in threads.iter()
-                    .filter(|t| t.concepts.is_empty())
-                    .take(10)
-                {
-                    if !thread.topics.is_empty() {
-                        let concepts_json = serde_json::to_string(&thread.topics)
-                            .unwrap_or_default();
-                        ThreadStorage::update_concepts(&conn, &thread.id, &concepts_json).ok();
-                        count += 1;
-                    }
-                }
-                if count > 0 {
-                    tracing::info!(count, "Concept backfill: populated {} threads", count);
-                }
-            });
         }
+
+        if queued > 0 {
+            tracing::info!(queued, "Quality scan: queued {} threads for enrichment", queued);
+        }
+        Ok(())
+    })
+}
+
+/// Shared orphan cleanup: remove shared_threads entries whose source thread is gone.
+fn task_shared_orphan_cleanup(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| {
+        cleanup_shared_orphans(conn, &ctx.key.project_hash).map(|_| ()).map_err(|e| e.to_string())
+    })
+}
+
+/// SQLite checkpoint (WAL mode).
+fn task_wal_checkpoint(ctx: &AgentTaskContext) -> Result<(), String> {
+    ctx.with_conn(|conn| conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);").map_err(|e| e.to_string()))
+}
+
+/// Session GC: remove stale session_agents/ files older than 48h.
+fn task_session_gc(ctx: &AgentTaskContext) -> Result<(), String> {
+    let dir = path_utils::session_agents_dir(&ctx.key.project_hash);
+    let cutoff = std::time::SystemTime::now()
+        .checked_sub(std::time::Duration::from_secs(48 * 3600))
+        .unwrap_or(std::time::UNIX_EPOCH);
+    gc_session_agents(&dir, cutoff);
+    Ok(())
+}
+
+/// Pool .done cleanup: remove processed pool files.
+fn task_pool_done_cleanup(ctx: &AgentTaskContext) -> Result<(), String> {
+    let pool_dir = ctx.data_dir.join("pool");
+    if !pool_dir.exists() {
+        return Ok(());
+    }
+    let n = pool_writer::cleanup_done_files(&pool_dir, ctx.guardian.capture.pool.cleanup_interval_secs)
+        .map_err(|e| e.to_string())?;
+    if n > 0 {
+        tracing::info!(cleaned = n, "Pool .done cleanup");
     }
+    Ok(())
+}
 
-    // 7b. Quality scan: detect threads with empty/degenerate fields, auto-queue enrichment.
-    // Lightweight scan — no LLM call. Only submits jobs to the capture queue.
-    // Retry max 2x is handled by the capture queue worker (enrichment_retry field).
-    if let Some(cq) = capture_queue {
-        run_task("quality_scan", || {
-            let Ok(conn) = conn_mtx.lock() else { return };
-            let threads = ThreadStorage::list_active(&conn).unwrap_or_default();
-            let mut queued = 0usize;
-
-            for thread in threads.iter().take(20) {
-                if needs_enrichment(thread) {
-                    let job = super::capture_queue::CaptureJob {
-                        key: AgentKey {
-                            project_hash: project_hash.to_string(),
-                            agent_id: agent_id.to_string(),
-                        },
-                        source_type: "quality_scan".to_string(),
-                        content: String::new(),
-                        file_path: None,
-                        is_prompt: false,
-                        session_id: None,
-                        enrich_thread_id: Some(thread.id.clone()),
-                        enrichment_retry: 0,
-                    };
-                    if cq.submit(job).is_ok() {
-                        queued += 1;
-                    } else {
-                        // Queue full — stop submitting this cycle
-                        break;
-                    }
-                }
-            }
+/// Backup of the agent DB, when the backup settings say one is due.
+fn task_backup(ctx: &AgentTaskContext) -> Result<(), String> {
+    let key = ctx.key;
+    let config = BackupConfig::load();
+    if !config.is_backup_due() {
+        return Ok(());
+    }
+    let backup_dir = std::path::PathBuf::from(
+        path_utils::expand_tilde(&config.backup_path),
+    );
+    let path = BackupManager::backup_agent(&key.project_hash, &key.agent_id, &backup_dir)
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        agent = %key.agent_id,
+        path = %path.display(),
+        "Scheduled backup"
+    );
+    let mut cfg = BackupConfig::load();
+    cfg.last_backup_at = Some(chrono::Utc::now().to_rfc3339());
+    cfg.save();
+    BackupManager::enforce_retention(&backup_dir, config.retention_count);
+    Ok(())
+}
 
-            if queued > 0 {
-                tracing::info!(queued, "Quality scan: queued {} threads for enrichment", queued);
-            }
-        });
+/// Per-task timings of one agent's pass, reported as `prune_cycle_completed`.
+struct TimedTasks {
+    start: Instant,
+    tasks: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TimedTasks {
+    fn new() -> Self {
+        Self { start: Instant::now(), tasks: serde_json::Map::new() }
     }
 
-    // 8. Backup: moved to run_prune_loop (needs key context)
-
-    // 9. Shared orphan cleanup: remove shared_threads entries whose source thread is gone
-    run_task("shared_orphan_cleanup", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        if let Err(e) = cleanup_shared_orphans(&conn, project_hash) {
-            tracing::warn!("Shared orphan cleanup error: {}", e);
-        }
-    });
-
-    // 10. SQLite checkpoint (WAL mode)
-    run_task("wal_checkpoint", || {
-        let Ok(conn) = conn_mtx.lock() else { return };
-        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);").ok();
-    });
-
-    // 11. Session GC: remove stale session_agents/ files older than 48h
-    run_task("session_gc", || {
-        let dir = path_utils::session_agents_dir(project_hash);
-        let cutoff = std::time::SystemTime::now()
-            .checked_sub(std::time::Duration::from_secs(48 * 3600))
-            .unwrap_or(std::time::UNIX_EPOCH);
-        gc_session_agents(&dir, cutoff);
-    });
-
-    // 12. Pool .done cleanup: remove processed pool files
-    run_task("pool_done_cleanup", || {
-        let pool_dir = agent_data_dir.join("pool");
-        if pool_dir.exists() {
-            match pool_writer::cleanup_done_files(&pool_dir, guardian.capture.pool.cleanup_interval_secs) {
-                Ok(n) if n > 0 => tracing::info!(cleaned = n, "Pool .done cleanup"),
-                Ok(_) => {}
-                Err(e) => tracing::warn!(error = %e, "Pool .done cleanup failed"),
+    fn run(&mut self, name: &str, task: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+        let t = Instant::now();
+        let result = run_task(name, task);
+        self.tasks.insert(name.to_string(), serde_json::json!(t.elapsed().as_millis() as u64));
+        result
+    }
+
+    fn finish(self, key: &AgentKey) {
+        events::emit(
+            EventKind::PruneCycleCompleted,
+            Some(key),
+            serde_json::json!({
+                "duration_ms": self.start.elapsed().as_millis() as u64,
+                "tasks": self.tasks,
+            }),
+        );
+    }
+}
+
+/// bridge_created for the bridges gossip stored since `since` (the task start);
+/// bridges other writers created meanwhile are not gossip's and are left out.
+fn emit_bridges_created(conn: &Connection, key: &AgentKey, since: chrono::DateTime<chrono::Utc>) {
+    if events::bus().subscriber_count() == 0 {
+        return;
+    }
+    let created = conn
+        .prepare(
+            "SELECT id, source_id, target_id, relation_type FROM bridges
+             WHERE created_at >= ?1 AND created_by LIKE 'gossip%' AND status != 'invalid'",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([time_utils::to_sqlite(&since)], |r| {
+                Ok(serde_json::json!({
+                    "bridge_id": r.get::<_, String>(0)?,
+                    "source_id": r.get::<_, String>(1)?,
+                    "target_id": r.get::<_, String>(2)?,
+                    "relation_type": r.get::<_, String>(3)?,
+                }))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+        });
+    match created {
+        Ok(created) => {
+            for data in created {
+                events::emit(EventKind::BridgeCreated, Some(key), data);
             }
         }
-    });
+        Err(e) => tracing::debug!(error = %e, "Created bridges query failed"),
+    }
+}
+
+/// thread_suspended / bridge_died for what a decay cycle changed.
+fn emit_decay_changes(changes: &DecayChanges, key: &AgentKey) {
+    if !changes.suspended.is_empty() {
+        events::emit(
+            EventKind::ThreadSuspended,
+            Some(key),
+            serde_json::json!({"thread_ids": changes.suspended, "reason": "decay"}),
+        );
+    }
+    let died = changes
+        .dead_bridges
+        .iter()
+        .map(|b| (b, "decay"))
+        .chain(changes.orphan_bridges.iter().filter(|b| b.status != BridgeStatus::Invalid).map(|b| (b, "orphan")));
+    for (bridge, reason) in died {
+        events::emit(
+            EventKind::BridgeDied,
+            Some(key),
+            serde_json::json!({
+                "bridge_id": bridge.id,
+                "source_id": bridge.source_id,
+                "target_id": bridge.target_id,
+                "relation_type": bridge.relation_type.as_str(),
+                "reason": reason,
+            }),
+        );
+    }
 }
 
 /// Delete files in `dir` whose mtime is older than `cutoff`.
@@ -787,6 +1258,23 @@ mod tests {
         assert!(!old_file.exists(), "old file should be deleted");
         assert!(new_file.exists(), "new file should survive");
     }
+
+    #[test]
+    fn test_retention_audit_entry_lists_touched_ids() {
+        let mut report = RetentionReport {
+            rules: vec![ai_smartness::intelligence::retention::RuleCount { rule: "fetch-ttl".into(), matched: 2, deleted: 1, archived: 1, ..Default::default() }],
+            deleted_thread_ids: vec!["t-old".into()],
+            archived_thread_ids: vec!["t-stale".into()],
+            deleted_bridge_ids: vec!["b-old".into()],
+            ..Default::default()
+        };
+        let entry = retention_audit_entry(&report, "ph", "dev").unwrap();
+        assert_eq!(entry.tool, "daemon:retention");
+        assert_eq!(entry.affected_ids, vec!["t-old", "t-stale", "b-old"]);
+
+        report.dry_run = true;
+        assert!(retention_audit_entry(&report, "ph", "dev").is_none());
+    }
 }
 
 /// Clean work_contexts expired > 24h (freshness_factor == 0.0).
@@ -3,19 +3,27 @@
 //! Scans the pool directory for .pending files (sorted by timestamp),
 //! reads JSONL entries, processes each via processor::process_capture(),
 //! and renames .pending → .done after successful processing.
+//! Entries whose processing fails are kept in the dead letters (capture journal).
 
 use std::path::Path;
 
 use ai_smartness::config::GuardianConfig;
+use ai_smartness::metrics;
+use ai_smartness::storage::capture_journal::JournalEntry;
+use ai_smartness::storage::dead_letters::{DeadLetter, DeadLetterStore, FailureStage};
 use ai_smartness::AiResult;
 use rusqlite::Connection;
 
+use super::capture_queue;
+use super::connection_pool::AgentKey;
+use super::events::{self, EventKind};
 use super::pool_writer::PoolEntry;
 use super::processor::{self, PendingContext};
 
 /// Process all .pending files in the pool directory.
 /// Returns the total number of captures processed.
 pub fn process_pending_files(
+    key: &AgentKey,
     pool_dir: &Path,
     conn: &Connection,
     pending: &mut Option<PendingContext>,
@@ -44,7 +52,7 @@ pub fn process_pending_files(
 
     for entry in &pending_files {
         let path = entry.path();
-        match process_single_file(&path, conn, pending, thread_quota, guardian) {
+        match process_single_file(key, &path, conn, pending, thread_quota, guardian) {
             Ok(count) => {
                 // Rename .pending → .done
                 let done_path = path.with_extension("done");
@@ -87,6 +95,7 @@ pub fn process_pending_files(
 
 /// Process a single .pending file (JSONL lines).
 fn process_single_file(
+    key: &AgentKey,
     path: &Path,
     conn: &Connection,
     pending: &mut Option<PendingContext>,
@@ -119,6 +128,8 @@ fn process_single_file(
     );
 
     let mut processed = 0;
+    // Opened on the first failed entry
+    let mut journal: Option<Connection> = None;
 
     for (line_idx, line) in content.lines().enumerate() {
         if line.trim().is_empty() {
@@ -148,6 +159,12 @@ fn process_single_file(
         );
 
         let entry_start = std::time::Instant::now();
+        let started = ai_smartness::time_utils::now();
+        let labels = [
+            ("project", key.project_hash.as_str()),
+            ("agent", key.agent_id.as_str()),
+            ("source_type", entry.source_type.as_str()),
+        ];
         match processor::process_capture(
             conn,
             pending,
@@ -159,12 +176,16 @@ fn process_single_file(
         ) {
             Ok(thread_id) => {
                 processed += 1;
+                metrics::CAPTURES_PROCESSED.inc(&labels);
                 tracing::info!(
                     line = line_idx + 1,
                     thread_id = ?thread_id,
                     elapsed_ms = entry_start.elapsed().as_millis(),
                     "Pool entry: complete"
                 );
+                if let Some(ref tid) = thread_id {
+                    events::emit_thread_change(conn, key, tid, started);
+                }
             }
             Err(e) => {
                 tracing::warn!(
@@ -172,7 +193,31 @@ fn process_single_file(
                     source_type = %entry.source_type,
                     error = %e,
                     elapsed_ms = entry_start.elapsed().as_millis(),
-                    "Pool entry: processing failed"
+                    "Pool entry: processing failed — kept in dead letters"
+                );
+                let mut dropped = labels.to_vec();
+                dropped.push(("reason", FailureStage::Capture.as_str()));
+                metrics::CAPTURES_DROPPED.inc(&dropped);
+                if journal.is_none() {
+                    journal = capture_queue::open_journal();
+                }
+                if let Some(ref jconn) = journal {
+                    let mut job = JournalEntry::new(&key.project_hash, &key.agent_id, &entry.source_type, &entry.content);
+                    job.file_path = entry.file_path.clone();
+                    job.attempts = 1;
+                    let letter = DeadLetter::from_journal(&job, FailureStage::Capture, &e.to_string());
+                    if let Err(de) = DeadLetterStore::record(jconn, &letter) {
+                        tracing::error!(error = %de, "Pool entry: dead letter write failed — capture lost");
+                    }
+                }
+                events::emit(
+                    EventKind::CaptureFailed,
+                    Some(key),
+                    serde_json::json!({
+                        "stage": FailureStage::Capture.as_str(),
+                        "error": e.to_string(),
+                        "source_type": entry.source_type,
+                    }),
                 );
             }
         }
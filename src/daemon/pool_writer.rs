@@ -193,6 +193,7 @@ mod tests {
             max_bytes_per_file: 10_000,
             max_age_secs: 60,
             cleanup_interval_secs: 300,
+            ..PoolConfig::default()
         }
     }
 
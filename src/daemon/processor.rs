@@ -14,6 +14,7 @@ use ai_smartness::intelligence::thread_manager::ThreadManager;
 use ai_smartness::processing::cleaner;
 use ai_smartness::processing::extractor::{self, ExtractionSource};
 use ai_smartness::processing::toolextractor;
+use ai_smartness::processing::topic_normalizer::TopicNormalizer;
 use ai_smartness::storage::threads::ThreadStorage;
 use rusqlite::Connection;
 
@@ -139,11 +140,14 @@ pub fn process_capture(
         return Ok(None);
     }
 
+    // One alias table read per capture, shared by every stage below
+    let normalizer = TopicNormalizer::load(conn);
+
     // Stage 1.5: Changelog shortcut for known files (Read/Write/Edit)
     // 3-case logic:  1) no file_path → full LLM   2) same hash → skip total   3) diff hash → changelog
     if let Some(fp) = file_path {
         if is_file_tool_source(source_type) {
-            match try_changelog_shortcut(conn, pending, source_type, fp, &cleaned, guardian) {
+            match try_changelog_shortcut(conn, &normalizer, pending, source_type, fp, &cleaned, guardian) {
                 Ok(Some(thread_id)) => {
                     tracing::info!(
                         thread_id = %thread_id,
@@ -196,7 +200,7 @@ pub fn process_capture(
             None => {
                 tracing::info!(
                     elapsed_ms = pipeline_start.elapsed().as_millis(),
-                    "Pipeline DROP at stage 2 — tool extraction skip or failure"
+                    "Pipeline DROP at stage 2 — tool extraction skip"
                 );
                 return Ok(None);
             }
@@ -239,7 +243,7 @@ pub fn process_capture(
             None => {
                 tracing::info!(
                     elapsed_ms = pipeline_start.elapsed().as_millis(),
-                    "Pipeline DROP at stage 2 — LLM skip or failure"
+                    "Pipeline DROP at stage 2 — LLM skip"
                 );
                 return Ok(None);
             }
@@ -287,6 +291,7 @@ pub fn process_capture(
     );
     let thread_id = ThreadManager::process_input(
         conn,
+        &normalizer,
         &extraction,
         &cleaned,
         source_type,
@@ -390,6 +395,7 @@ fn is_file_tool_source(source_type: &str) -> bool {
 /// Returns Some(thread_id) if shortcut applied, None if no matching thread found.
 fn try_changelog_shortcut(
     conn: &Connection,
+    normalizer: &TopicNormalizer,
     pending: &mut Option<PendingContext>,
     source_type: &str,
     file_path: &str,
@@ -418,7 +424,7 @@ fn try_changelog_shortcut(
 
     // add_changelog handles reactivation + LLM extraction internally
     let result = ThreadManager::add_changelog(
-        conn, &target.id, file_path, source_type, content,
+        conn, normalizer, &target.id, file_path, source_type, content,
         continuity_from.as_deref(), guardian,
     )?;
 
@@ -508,8 +514,9 @@ pub fn enrich_existing_thread(
     // This enables full re-enrichment when upgrading LLM or reprocessing with better hardware.
     thread.summary = Some(extraction.summary.clone());
     thread.labels = extraction.labels.clone();
-    thread.concepts = ai_smartness::constants::normalize_concepts(&extraction.concepts);
-    thread.topics = extraction.subjects.clone();
+    let normalizer = TopicNormalizer::load(conn);
+    thread.concepts = normalizer.dedup_concepts(&extraction.concepts);
+    thread.topics = normalizer.dedup_topics(&extraction.subjects);
 
     // Exception: respect importance_manually_set — user/agent intentionally set this value
     if !thread.importance_manually_set {
@@ -535,6 +542,7 @@ pub fn enrich_existing_thread(
     if !thread.concepts.is_empty() {
         let bridge_count = ThreadManager::create_thinkbridges(
             conn,
+            &normalizer,
             thread_id,
             &thread.concepts,
             &guardian.gossip,
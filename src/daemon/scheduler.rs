@@ -0,0 +1,505 @@
+//! Task scheduler — when each periodic daemon task runs.
+//!
+//! Every task of the prune loop has its own schedule: an interval (default
+//! `prune_interval_secs`, daily for the backfill/alias passes) or a cron
+//! expression, overridable per task in `DaemonConfig::tasks` together with an
+//! enable flag, jitter and a max runtime. The prune loop ticks every
+//! `TICK_SECS` and runs the tasks that are due (see `periodic_tasks`).
+//!
+//! Run state (last/next run, duration, error, counters) is kept in
+//! `{data_dir}/task_state.json`, so schedules survive daemon restarts and
+//! `daemon tasks` can show it while the daemon is down.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use ai_smartness::config::{DaemonConfig, TaskSchedule};
+use ai_smartness::storage::path_utils;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Scheduler resolution: how often the prune loop looks for due tasks.
+pub const TICK_SECS: u64 = 5;
+
+/// Longest error message kept in the state.
+const MAX_ERROR_LEN: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Runs once per active (unlocked) agent of the pool.
+    Agent,
+    /// Runs once per pass, across projects.
+    Global,
+}
+
+/// A periodic task known to the scheduler.
+pub struct TaskDef {
+    pub name: &'static str,
+    pub scope: Scope,
+    pub summary: &'static str,
+    /// Default interval (None = `prune_interval_secs`).
+    default_every_secs: Option<u64>,
+}
+
+const DAILY: Option<u64> = Some(24 * 3600);
+
+/// All periodic tasks, in run order (agent tasks run in this order per agent).
+pub const TASKS: &[TaskDef] = &[
+    TaskDef { name: "beat", scope: Scope::Agent, summary: "Beat counter, watchdog and LLM status in beat.json", default_every_secs: None },
+    TaskDef { name: "quota_sync", scope: Scope::Agent, summary: "Thread quota from the registry", default_every_secs: None },
+    TaskDef { name: "gossip", scope: Scope::Agent, summary: "Concept-based bridge discovery", default_every_secs: None },
+    TaskDef { name: "decay", scope: Scope::Agent, summary: "Thread/bridge weight decay, suspension", default_every_secs: None },
+    TaskDef { name: "archive", scope: Scope::Agent, summary: "Archive stale suspended threads", default_every_secs: None },
+    TaskDef { name: "retention", scope: Scope::Agent, summary: "Retention rules per label/origin", default_every_secs: None },
+    TaskDef { name: "inbox_cleanup", scope: Scope::Agent, summary: "Expire stale cognitive messages", default_every_secs: None },
+    TaskDef { name: "work_context_and_injection", scope: Scope::Agent, summary: "Expired work contexts, injection score decay", default_every_secs: None },
+    TaskDef { name: "concept_backfill", scope: Scope::Agent, summary: "Concepts for threads that have none", default_every_secs: DAILY },
+    TaskDef { name: "topic_alias_suggest", scope: Scope::Agent, summary: "Topic alias suggestions", default_every_secs: DAILY },
+    TaskDef { name: "quality_scan", scope: Scope::Agent, summary: "Queue enrichment of incomplete threads", default_every_secs: None },
+    TaskDef { name: "shared_orphan_cleanup", scope: Scope::Agent, summary: "Unpublish shared threads whose source is gone", default_every_secs: None },
+    TaskDef { name: "wal_checkpoint", scope: Scope::Agent, summary: "SQLite WAL checkpoint", default_every_secs: None },
+    TaskDef { name: "session_gc", scope: Scope::Agent, summary: "Remove session files older than 48h", default_every_secs: None },
+    TaskDef { name: "pool_done_cleanup", scope: Scope::Agent, summary: "Remove processed pool files", default_every_secs: None },
+    TaskDef { name: "backup", scope: Scope::Agent, summary: "Scheduled backup (when due per backup config)", default_every_secs: None },
+    TaskDef { name: "cross_gossip", scope: Scope::Global, summary: "Cross-project gossip (needs gossip_cross_project)", default_every_secs: None },
+    TaskDef { name: "audit_retention", scope: Scope::Global, summary: "Audit log retention", default_every_secs: None },
+    TaskDef { name: "dead_letter_retention", scope: Scope::Global, summary: "Failed capture (dead letter) retention", default_every_secs: None },
+];
+
+pub fn find(name: &str) -> Option<&'static TaskDef> {
+    TASKS.iter().find(|t| t.name == name)
+}
+
+// ── Cron ──
+
+/// 5-field cron expression: minute hour day-of-month month day-of-week.
+/// Fields accept `*`, numbers, ranges `a-b`, steps `*/n` / `a-b/n` / `a/n`
+/// and lists. Day-of-week 0-7 (0 and 7 = Sunday). When both day fields are
+/// restricted, a day matching either runs (classic cron).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("cron '{}': expected 5 fields, got {}", expr, fields.len()));
+        };
+        let field = |f: &str, name: &str, min: u32, max: u32| {
+            parse_field(f, min, max).map_err(|e| format!("cron '{}': {} field: {}", expr, name, e))
+        };
+        let mut weekdays = field(weekday, "day-of-week", 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: field(minute, "minute", 0, 59)?,
+            hours: field(hour, "hour", 0, 23)?,
+            days: field(day, "day-of-month", 1, 31)?,
+            months: field(month, "month", 1, 12)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// First matching minute strictly after `after`.
+    /// None if nothing matches within 5 years (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let limit = after + chrono::Duration::days(5 * 366);
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        while t <= limit {
+            if !has(self.months, t.month()) {
+                let (y, m) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(y, m, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + chrono::Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += chrono::Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |s: &str| -> Result<u32, String> {
+        let v: u32 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+        if v < min || v > max {
+            return Err(format!("{} out of range {}-{}", v, min, max));
+        }
+        Ok(v)
+    };
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("bad step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be > 0".into());
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (number(a)?, number(b)?)
+        } else {
+            // `a/n` runs from a to the end of the range
+            let v = number(range)?;
+            (v, if step.is_some() { max } else { v })
+        };
+        if lo > hi {
+            return Err(format!("empty range {}-{}", lo, hi));
+        }
+        for v in (lo..=hi).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+// ── Schedules ──
+
+enum Every {
+    Interval(Duration),
+    Cron(CronExpr),
+}
+
+/// Effective schedule of a task: its defaults merged with `DaemonConfig::tasks`.
+pub struct Schedule {
+    pub enabled: bool,
+    every: Every,
+    jitter_secs: u64,
+    pub max_runtime: Option<Duration>,
+}
+
+impl Schedule {
+    /// Invalid cron expressions are an error (the task does not run).
+    pub fn resolve(task: &TaskDef, cfg: &DaemonConfig) -> Result<Self, String> {
+        let custom = cfg.tasks.get(task.name).cloned().unwrap_or_default();
+        let every = match custom.cron.as_deref() {
+            Some(expr) => Every::Cron(CronExpr::parse(expr)?),
+            None => {
+                let secs = custom
+                    .interval_secs
+                    .or(task.default_every_secs)
+                    .unwrap_or(cfg.prune_interval_secs)
+                    .max(TICK_SECS);
+                Every::Interval(Duration::from_secs(secs))
+            }
+        };
+        Ok(Self {
+            enabled: custom.enabled,
+            every,
+            jitter_secs: custom.jitter_secs,
+            max_runtime: custom.max_runtime_secs.map(Duration::from_secs),
+        })
+    }
+
+    /// Next run after a run started at `last` (or after `now` for cron and
+    /// never-run tasks), jitter included.
+    fn next_run(&self, last: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let next = match &self.every {
+            Every::Interval(d) => last.unwrap_or(now) + chrono::Duration::from_std(*d).ok()?,
+            Every::Cron(expr) => next_local(expr, now)?,
+        };
+        Some(next + chrono::Duration::seconds(jitter(self.jitter_secs)))
+    }
+
+    fn describe(&self) -> String {
+        let mut s = match &self.every {
+            Every::Interval(d) => format!("every {}s", d.as_secs()),
+            Every::Cron(_) => String::new(),
+        };
+        if self.jitter_secs > 0 {
+            s.push_str(&format!(" +{}s jitter", self.jitter_secs));
+        }
+        s
+    }
+}
+
+/// Next cron match in local time (skips local times that do not exist).
+fn next_local(expr: &CronExpr, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let mut t = after.with_timezone(&Local).naive_local();
+    for _ in 0..4 {
+        t = expr.next_after(t)?;
+        if let Some(local) = Local.from_local_datetime(&t).earliest() {
+            return Some(local.with_timezone(&Utc));
+        }
+    }
+    None
+}
+
+fn jitter(max_secs: u64) -> i64 {
+    if max_secs == 0 {
+        return 0;
+    }
+    (uuid::Uuid::new_v4().as_u128() % (max_secs as u128 + 1)) as i64
+}
+
+/// Text shown for a task's schedule (`daemon tasks`).
+fn schedule_label(task: &TaskDef, cfg: &DaemonConfig) -> String {
+    match (Schedule::resolve(task, cfg), cfg.tasks.get(task.name).and_then(|c| c.cron.as_deref())) {
+        (Ok(s), Some(expr)) => format!("cron {}{}", expr, s.describe()),
+        (Ok(s), None) => s.describe(),
+        (Err(e), _) => format!("invalid: {}", e),
+    }
+}
+
+// ── State ──
+
+/// Persisted run state of one task.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskState {
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_duration_ms: Option<u64>,
+    /// Error(s) of the last run (None = success).
+    pub last_error: Option<String>,
+    /// "schedule" or "manual".
+    pub last_trigger: Option<String>,
+    /// Agents processed by the last scheduled run.
+    pub last_agents: usize,
+    /// Agents skipped by the last scheduled run (max runtime reached).
+    pub last_deferred: usize,
+    pub runs: u64,
+    pub failures: u64,
+    /// Schedule the next run was computed from (recomputed when it changes).
+    schedule: Option<String>,
+}
+
+/// Outcome of one run of a task.
+pub struct RunReport {
+    pub started: DateTime<Utc>,
+    pub duration: Duration,
+    /// One entry per failing agent ("agent: error") or one for a global task.
+    pub errors: Vec<String>,
+    pub agents: usize,
+    pub deferred: usize,
+}
+
+static STATES: LazyLock<Mutex<HashMap<String, TaskState>>> = LazyLock::new(|| Mutex::new(load_states()));
+
+fn load_states() -> HashMap<String, TaskState> {
+    std::fs::read_to_string(path_utils::task_state_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Written to a temp file next to it, then renamed over it: a crash mid-write
+/// leaves the previous state, never a truncated file (which would reset every
+/// schedule). Callers hold the STATES lock, so one temp name is enough.
+fn save_states(states: &HashMap<String, TaskState>) {
+    let path = path_utils::task_state_path();
+    let tmp = path.with_extension("json.tmp");
+    let written = serde_json::to_string_pretty(states)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&tmp, json).map_err(|e| e.to_string()))
+        .and_then(|()| std::fs::rename(&tmp, &path).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        tracing::warn!(path = %path.display(), error = %e, "Task state save failed");
+    }
+}
+
+/// Enabled tasks whose next run is due at `now`, with their schedule.
+/// Computes (and persists) the next run of tasks that have none yet.
+pub fn due(cfg: &DaemonConfig, now: DateTime<Utc>) -> Vec<(&'static TaskDef, Schedule)> {
+    let mut states = STATES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut changed = false;
+    let mut due = Vec::new();
+    for task in TASKS {
+        let state = states.entry(task.name.to_string()).or_default();
+        let schedule = match Schedule::resolve(task, cfg) {
+            Ok(s) => s,
+            Err(e) => {
+                if state.last_error.as_deref() != Some(e.as_str()) {
+                    tracing::warn!(task = task.name, error = %e, "Task not scheduled: invalid schedule");
+                    state.last_error = Some(e);
+                    state.next_run_at = None;
+                    changed = true;
+                }
+                continue;
+            }
+        };
+        if !schedule.enabled {
+            if state.next_run_at.take().is_some() {
+                changed = true;
+            }
+            continue;
+        }
+        let label = schedule_label(task, cfg);
+        if state.next_run_at.is_none() || state.schedule.as_deref() != Some(label.as_str()) {
+            state.next_run_at = schedule.next_run(state.last_run_at, now);
+            state.schedule = Some(label);
+            changed = true;
+        }
+        if state.next_run_at.is_some_and(|next| next <= now) {
+            due.push((task, schedule));
+        }
+    }
+    if changed {
+        save_states(&states);
+    }
+    due
+}
+
+/// Record a run. Scheduled runs (`schedule` given) also get their next run;
+/// manual runs leave the schedule alone.
+pub fn record(task: &TaskDef, report: &RunReport, schedule: Option<&Schedule>) {
+    let mut states = STATES.lock().unwrap_or_else(|e| e.into_inner());
+    let state = states.entry(task.name.to_string()).or_default();
+    state.last_run_at = Some(report.started);
+    state.last_duration_ms = Some(report.duration.as_millis() as u64);
+    state.last_error = (!report.errors.is_empty()).then(|| {
+        let mut error = report.errors.join("; ");
+        if error.len() > MAX_ERROR_LEN {
+            let cut = (0..=MAX_ERROR_LEN).rev().find(|i| error.is_char_boundary(*i)).unwrap_or(0);
+            error.truncate(cut);
+            error.push('…');
+        }
+        error
+    });
+    state.runs += 1;
+    if !report.errors.is_empty() {
+        state.failures += 1;
+    }
+    match schedule {
+        Some(schedule) => {
+            state.last_trigger = Some("schedule".into());
+            state.last_agents = report.agents;
+            state.last_deferred = report.deferred;
+            state.next_run_at = schedule.next_run(Some(report.started), Utc::now());
+        }
+        None => state.last_trigger = Some("manual".into()),
+    }
+    save_states(&states);
+}
+
+/// Every task with its schedule and run state (IPC `tasks`, `daemon tasks`).
+pub fn snapshot(cfg: &DaemonConfig) -> serde_json::Value {
+    let states = STATES.lock().unwrap_or_else(|e| e.into_inner());
+    let tasks: Vec<serde_json::Value> = TASKS
+        .iter()
+        .map(|task| {
+            let custom: TaskSchedule = cfg.tasks.get(task.name).cloned().unwrap_or_default();
+            let state = states.get(task.name).cloned().unwrap_or_default();
+            serde_json::json!({
+                "name": task.name,
+                "scope": task.scope,
+                "summary": task.summary,
+                "enabled": custom.enabled,
+                "schedule": schedule_label(task, cfg),
+                "jitter_secs": custom.jitter_secs,
+                "max_runtime_secs": custom.max_runtime_secs,
+                "last_run_at": state.last_run_at,
+                "next_run_at": if custom.enabled { state.next_run_at } else { None },
+                "last_duration_ms": state.last_duration_ms,
+                "last_error": state.last_error,
+                "last_trigger": state.last_trigger,
+                "last_agents": state.last_agents,
+                "last_deferred": state.last_deferred,
+                "runs": state.runs,
+                "failures": state.failures,
+            })
+        })
+        .collect();
+    serde_json::json!({ "tick_secs": TICK_SECS, "tasks": tasks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let every_5 = CronExpr::parse("*/5 * * * *").unwrap();
+        assert_eq!(every_5.next_after(at("2026-03-01 10:03")), Some(at("2026-03-01 10:05")));
+        assert_eq!(every_5.next_after(at("2026-03-01 10:05")), Some(at("2026-03-01 10:10")));
+
+        let nightly = CronExpr::parse("30 2 * * *").unwrap();
+        assert_eq!(nightly.next_after(at("2026-03-01 10:00")), Some(at("2026-03-02 02:30")));
+
+        // Weekdays at 9:00, 2026-03-06 is a Friday
+        let weekdays = CronExpr::parse("0 9 * * 1-5").unwrap();
+        assert_eq!(weekdays.next_after(at("2026-03-06 09:00")), Some(at("2026-03-09 09:00")));
+
+        // Sunday as 7, year rollover
+        let sundays = CronExpr::parse("0 0 * 12 7").unwrap();
+        assert_eq!(sundays.next_after(at("2026-12-31 12:00")), Some(at("2027-12-05 00:00")));
+
+        assert_eq!(CronExpr::parse("0 0 30 2 *").unwrap().next_after(at("2026-01-01 00:00")), None);
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid() {
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("5-1 * * * *").is_err());
+        assert!(CronExpr::parse("0 0 * jan *").is_err());
+    }
+
+    #[test]
+    fn test_schedule_resolution() {
+        let mut cfg = DaemonConfig { prune_interval_secs: 300, ..Default::default() };
+        let now = Utc::now();
+        let gossip = find("gossip").unwrap();
+        let default = Schedule::resolve(gossip, &cfg).unwrap();
+        assert!(default.enabled);
+        assert_eq!(default.next_run(None, now), Some(now + chrono::Duration::seconds(300)));
+
+        let daily = Schedule::resolve(find("concept_backfill").unwrap(), &cfg).unwrap();
+        assert_eq!(daily.describe(), "every 86400s");
+
+        cfg.tasks.insert(
+            "gossip".into(),
+            TaskSchedule { interval_secs: Some(60), max_runtime_secs: Some(10), ..Default::default() },
+        );
+        let custom = Schedule::resolve(gossip, &cfg).unwrap();
+        assert_eq!(custom.next_run(Some(now), now), Some(now + chrono::Duration::seconds(60)));
+        assert_eq!(custom.max_runtime, Some(Duration::from_secs(10)));
+
+        cfg.tasks.insert("gossip".into(), TaskSchedule { cron: Some("bad".into()), ..Default::default() });
+        assert!(Schedule::resolve(gossip, &cfg).is_err());
+        assert!(schedule_label(gossip, &cfg).starts_with("invalid"));
+    }
+}
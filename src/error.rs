@@ -30,6 +30,14 @@ pub enum AiError {
     #[error("Capacity exceeded: {0}")]
     CapacityExceeded(String),
 
+    /// Tool call refused (or awaiting confirmation) by the tool policy.
+    #[error("Tool call {effect} by policy '{rule}': {reason}")]
+    PolicyDenied {
+        effect: String,
+        rule: String,
+        reason: String,
+    },
+
     #[error("Provider error: {0}")]
     Provider(String),
 
@@ -1,4 +1,6 @@
 use ai_smartness::config::GuardianConfig;
+use ai_smartness::intelligence::retention::RetentionReport;
+use ai_smartness::storage::audit::{AuditFilter, AuditLog};
 use ai_smartness::storage::database::{open_connection, ConnectionRole};
 use ai_smartness::storage::path_utils;
 use ai_smartness::storage::migrations;
@@ -87,6 +89,8 @@ pub fn get_project_overview(project_hash: String) -> Result<serde_json::Value, S
         total_archived += archived;
         total_bridges += bridges;
 
+        let retention = RetentionReport::load(&path_utils::agent_data_dir(&project_hash, &agent.id));
+
         agent_metrics.push(serde_json::json!({
             "id": agent.id,
             "name": agent.name,
@@ -95,6 +99,7 @@ pub fn get_project_overview(project_hash: String) -> Result<serde_json::Value, S
             "suspended": suspended,
             "archived": archived,
             "bridges": bridges,
+            "retention": retention,
         }));
     }
 
@@ -117,6 +122,32 @@ pub fn get_project_overview(project_hash: String) -> Result<serde_json::Value, S
     }))
 }
 
+// ─── Audit feed ──────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_audit_log(
+    project_hash: String,
+    agent_id: Option<String>,
+    outcome: Option<String>,
+    limit: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    tracing::info!(project = %&project_hash[..8.min(project_hash.len())], "GUI: get_audit_log");
+    let reg_path = path_utils::registry_db_path();
+    let reg_conn = open_connection(&reg_path, ConnectionRole::Cli)
+        .map_err(|e| e.to_string())?;
+    migrations::migrate_registry_db(&reg_conn).map_err(|e| e.to_string())?;
+
+    let filter = AuditFilter {
+        project_hash: Some(project_hash),
+        agent_id: agent_id.filter(|a| !a.is_empty()),
+        outcome: outcome.filter(|o| !o.is_empty()),
+        limit: limit.unwrap_or(50),
+        ..Default::default()
+    };
+    let entries = AuditLog::query(&reg_conn, &filter).map_err(|e| e.to_string())?;
+    serde_json::to_value(&entries).map_err(|e| e.to_string())
+}
+
 // ─── Daemon control ──────────────────────────────────────────
 
 #[tauri::command]
@@ -812,7 +843,9 @@ pub fn save_settings(settings: serde_json::Value) -> Result<serde_json::Value, S
     // Sync MCP permissions to all project .claude/settings.json files
     sync_mcp_permissions(config.hooks.mcp_auto_allow);
 
-    Ok(serde_json::json!({ "saved": true }))
+    // Apply to the running daemon (null when it is not running)
+    let reload = ai_smartness::processing::daemon_ipc_client::reload_config().ok();
+    Ok(serde_json::json!({ "saved": true, "reload": reload }))
 }
 
 /// Sync MCP auto-allow permissions to all registered projects' .claude/settings.json.
@@ -1430,10 +1463,17 @@ pub fn get_daemon_settings() -> Result<serde_json::Value, String> {
 #[tauri::command]
 pub fn save_daemon_settings(settings: serde_json::Value) -> Result<serde_json::Value, String> {
     tracing::info!("GUI: save_daemon_settings");
-    let config: ai_smartness::config::DaemonConfig = serde_json::from_value(settings)
+    // Task schedules are not edited by the GUI form: keep the saved ones
+    let keep_tasks = settings.get("tasks").is_none();
+    let mut config: ai_smartness::config::DaemonConfig = serde_json::from_value(settings)
         .map_err(|e| format!("Invalid daemon config: {}", e))?;
+    if keep_tasks {
+        config.tasks = ai_smartness::config::DaemonConfig::load().tasks;
+    }
     config.save()?;
-    Ok(serde_json::json!({ "saved": true }))
+    // Apply to the running daemon (pool/worker sizes and API port still need a restart)
+    let reload = ai_smartness::processing::daemon_ipc_client::reload_config().ok();
+    Ok(serde_json::json!({ "saved": true, "reload": reload }))
 }
 
 // ─── Global Debug Logs ───────────────────────────────────────
@@ -805,6 +805,34 @@ function updateDashboardCards(agent) {
         document.getElementById('thread-archived').textContent = tc.archived;
         document.getElementById('bridge-count').textContent = tc.bridges;
     }
+    renderRetention(agent ? [agent] : overviewAgents);
+}
+
+// Per-rule retention counts from the last prune cycle, summed over the given agents
+function renderRetention(agents) {
+    const section = document.getElementById('dashboard-retention');
+    const reports = agents.map(a => a.retention).filter(r => r && r.rules && r.rules.length);
+    if (!reports.length) {
+        section.style.display = 'none';
+        return;
+    }
+    const byRule = new Map();
+    for (const r of reports) {
+        for (const c of r.rules) {
+            const acc = byRule.get(c.rule) || { matched: 0, protected: 0, archived: 0, deleted: 0 };
+            acc.matched += c.matched;
+            acc.protected += c.protected;
+            acc.archived += c.archived;
+            acc.deleted += c.deleted;
+            byRule.set(c.rule, acc);
+        }
+    }
+    document.getElementById('retention-body').innerHTML = [...byRule.entries()].map(([name, c]) =>
+        `<tr><td>${esc(name)}</td><td>${c.matched}</td><td>${c.protected}</td><td>${c.archived}</td><td>${c.deleted}</td></tr>`
+    ).join('');
+    document.getElementById('retention-mode').textContent =
+        reports.some(r => r.dry_run) ? '(dry-run — nothing archived or deleted)' : '';
+    section.style.display = '';
 }
 
 async function loadDashboard() {
@@ -844,6 +872,8 @@ async function loadDashboard() {
         console.error('Dashboard error:', e);
     }
 
+    loadAuditFeed();
+
     // Load role tree for dashboard
     try {
         const hierarchy = await invoke('get_hierarchy', { projectHash });
@@ -853,6 +883,35 @@ async function loadDashboard() {
     }
 }
 
+// Latest audited tool calls (focused agent only when one is selected)
+async function loadAuditFeed() {
+    const body = document.getElementById('audit-body');
+    if (!body || !projectHash) return;
+    try {
+        const outcome = document.getElementById('audit-outcome').value;
+        const entries = await invoke('get_audit_log', {
+            projectHash, agentId: dashboardFocusedAgent || null, outcome: outcome || null, limit: 25,
+        });
+        if (!entries.length) {
+            body.innerHTML = '<tr><td colspan="6" style="color:var(--text-dim)">No audited calls yet</td></tr>';
+            return;
+        }
+        body.innerHTML = entries.map(e => {
+            const ids = e.affected_ids || [];
+            const affected = ids.length > 3 ? `${ids.slice(0, 2).join(', ')} … (${ids.length})` : ids.join(', ');
+            const cls = e.outcome === 'ok' ? '' : ' style="color:var(--danger, #e55)"';
+            return `<tr title="${esc(e.error || JSON.stringify(e.params))}">
+                <td>${esc(new Date(e.timestamp).toLocaleString())}</td><td>${esc(e.agent_id)}</td>
+                <td>${esc(e.tool)}</td><td${cls}>${esc(e.outcome)}</td><td>${e.duration_ms}</td>
+                <td>${esc(affected || '-')}</td></tr>`;
+        }).join('');
+    } catch (e) {
+        console.error('Audit feed error:', e);
+    }
+}
+
+document.getElementById('audit-outcome')?.addEventListener('change', loadAuditFeed);
+
 function renderDashboardHierarchy(nodes) {
     const view = document.getElementById('dashboard-hierarchy');
     if (!view) return;
@@ -1464,10 +1523,6 @@ function collectForm() {
 
 async function saveSettings() {
     const settings = collectForm();
-    // Detect hardware config changes that require daemon restart
-    const hwChanged = currentSettings?.hardware &&
-        (settings?.hardware?.runtime_device !== currentSettings.hardware.runtime_device ||
-         settings?.hardware?.provider_device !== currentSettings.hardware.provider_device);
     const modelChanged = settings?.local_model_size !== currentSettings?.local_model_size;
     try {
         if (modelChanged) {
@@ -1477,14 +1532,9 @@ async function saveSettings() {
         if (result.saved) {
             showSaveStatus(T[currentLang]?.['settings.saved'] || 'Settings saved successfully');
             currentSettings = settings;
-            // Auto-restart daemon if hardware or model config changed
-            if (hwChanged || modelChanged) {
-                try {
-                    await invoke('restart_daemon');
-                    showSaveStatus('Settings saved — daemon restarting to apply changes');
-                } catch (re) {
-                    showSaveStatus('Settings saved — daemon restart failed: ' + re, true);
-                }
+            // The daemon reloads its config on save; model/hardware changes reload the model
+            if (result.reload?.model_reload) {
+                showSaveStatus('Settings saved — daemon reloading the model');
             }
         } else {
             showSaveStatus(T[currentLang]?.['settings.failed'] || 'Save failed', true);
@@ -1581,7 +1631,10 @@ async function loadResources() {
         const q = res.daemon?.capture_queue;
         if (queueEl && q) {
             queueEl.textContent = `${q.pending}p ${q.processed}d ${q.errors}e`;
-            queueEl.title = `Pending: ${q.pending} | Workers: ${q.workers} | Processed: ${q.processed} | Errors: ${q.errors}`;
+            const age = q.journal?.backlog_age_secs;
+            queueEl.title = `Pending: ${q.pending} | Spilled: ${q.spilled || 0} | Workers: ${q.workers} | Processed: ${q.processed} | Errors: ${q.errors}`
+                + (age != null ? ` | Oldest: ${age}s` : '')
+                + (q.dead_letters ? ` | Failed: ${q.dead_letters} (ai-smartness captures failed list)` : '');
         } else if (queueEl) {
             queueEl.textContent = '-';
         }
@@ -1605,6 +1658,7 @@ async function loadDaemonSettings() {
         if (el('daemon-cross-gossip')) el('daemon-cross-gossip').checked = cfg.gossip_cross_project || false;
         if (el('daemon-capture-workers')) el('daemon-capture-workers').value = cfg.capture_workers || 2;
         if (el('daemon-capture-queue')) el('daemon-capture-queue').value = cfg.capture_queue_capacity || 100;
+        if (el('daemon-http-api-port')) el('daemon-http-api-port').value = cfg.http_api_port || '';
     } catch (e) {
         console.error('Daemon settings load error:', e);
     }
@@ -1628,10 +1682,17 @@ async function saveDaemonSettings() {
             gossip_cross_project: document.getElementById('daemon-cross-gossip')?.checked || false,
             capture_workers: parseInt(document.getElementById('daemon-capture-workers')?.value) || 2,
             capture_queue_capacity: parseInt(document.getElementById('daemon-capture-queue')?.value) || 100,
+            http_api_port: parseInt(document.getElementById('daemon-http-api-port')?.value) || null,
         };
         const result = await invoke('save_daemon_settings', { settings });
         if (result.saved) {
-            showDaemonSaveStatus(T[currentLang]?.['settings.saved'] || 'Settings saved successfully');
+            const restart = result.reload?.restart_required || [];
+            if (restart.length) {
+                showDaemonSaveStatus('Settings saved — restart the daemon to apply: ' +
+                    restart.map(f => f.replace('daemon.', '')).join(', '));
+            } else {
+                showDaemonSaveStatus(T[currentLang]?.['settings.saved'] || 'Settings saved successfully');
+            }
         } else {
             showDaemonSaveStatus('Save failed', true);
         }
@@ -268,7 +268,10 @@ async function pollQueueStats() {
         const res = await invoke('get_system_resources');
         const q = res?.daemon?.capture_queue;
         if (q && queueStatsEl) {
-            queueStatsEl.textContent = `Queue: ${q.pending}/${q.workers}w | Done: ${q.processed} | Err: ${q.errors}`;
+            const age = q.journal?.backlog_age_secs;
+            queueStatsEl.textContent = `Queue: ${q.pending}/${q.workers}w | Spilled: ${q.spilled || 0} | Done: ${q.processed} | Err: ${q.errors}`
+                + (age != null ? ` | Oldest: ${age}s` : '')
+                + (q.dead_letters ? ` | Failed: ${q.dead_letters}` : '');
         } else if (queueStatsEl) {
             queueStatsEl.textContent = '';
         }
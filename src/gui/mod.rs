@@ -15,6 +15,7 @@ pub fn launch() {
             .invoke_handler(tauri::generate_handler![
                 commands::get_dashboard,
                 commands::get_project_overview,
+                commands::get_audit_log,
                 commands::get_threads,
                 commands::get_settings,
                 commands::save_settings,
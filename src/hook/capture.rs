@@ -64,6 +64,13 @@ pub fn run(project_hash: &str, agent_id: &str, input: &str, session_id: Option<&
         return;
     }
 
+    // 3.6 Daemon throttled this agent's captures: drop until the retry delay is over
+    if is_throttled(project_hash, agent_id) {
+        tracing::info!(tool = tool_name, "Capture: agent throttled by daemon, skipping");
+        print_continue();
+        return;
+    }
+
     // 4. Extract tool output
     let tool_response = data.get("tool_response");
     if let Some(resp) = tool_response {
@@ -107,7 +114,18 @@ pub fn run(project_hash: &str, agent_id: &str, input: &str, session_id: Option<&
 
     // 8. Send to daemon via IPC (fire-and-forget, non-blocking)
     tracing::info!(tool = %tool_name, content_len = enriched.len(), file_path = ?file_path.as_deref(), "Capture sending to daemon");
-    let _ = daemon_ipc_client::send_capture(project_hash, agent_id, tool_name, &enriched, file_path.as_deref());
+    if let Ok(resp) = daemon_ipc_client::send_capture(project_hash, agent_id, tool_name, &enriched, file_path.as_deref()) {
+        if resp.get("reason").and_then(|v| v.as_str()) == Some("throttled") {
+            let retry_after_ms = resp.get("retry_after_ms").and_then(|v| v.as_u64()).unwrap_or(1000);
+            tracing::info!(
+                tool = %tool_name,
+                throttle = resp.get("throttle").and_then(|v| v.as_str()).unwrap_or(""),
+                retry_after_ms,
+                "Capture throttled by daemon"
+            );
+            set_throttled(project_hash, agent_id, retry_after_ms);
+        }
+    }
 
     // 8. Always continue (hook must never block)
     print_continue();
@@ -181,6 +199,23 @@ fn extract_tool_context(
     }
 }
 
+/// True while a daemon throttle delay (capture_throttle marker) is running.
+fn is_throttled(project_hash: &str, agent_id: &str) -> bool {
+    std::fs::read_to_string(path_utils::capture_throttle_path(project_hash, agent_id))
+        .ok()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s.trim()).ok())
+        .is_some_and(|until| until > chrono::Utc::now())
+}
+
+/// Remember the daemon's retry delay so the next captures skip the IPC round trip.
+fn set_throttled(project_hash: &str, agent_id: &str, retry_after_ms: u64) {
+    let until = chrono::Utc::now() + chrono::Duration::milliseconds(retry_after_ms as i64);
+    let path = path_utils::capture_throttle_path(project_hash, agent_id);
+    if let Err(e) = std::fs::write(&path, until.to_rfc3339()) {
+        tracing::warn!(path = %path.display(), error = %e, "Capture: failed to write throttle marker");
+    }
+}
+
 /// Print continue response for Claude Code.
 fn print_continue() {
     println!("{{\"continue\":true}}");
@@ -9,6 +9,8 @@ pub mod virtual_paths;
 
 use std::io::Read;
 
+use ai_smartness::processing::ipc_auth::{self, Scope};
+
 /// Hook subcommands.
 pub enum HookAction {
     Inject { project_hash: String, agent_id: Option<String> },
@@ -168,6 +170,15 @@ pub fn run(action: HookAction) {
             tracing::warn!(source = "no_agents", "No agents registered for this project");
             format!("anon-{}", &project_hash[..8.min(project_hash.len())])
         };
+        // IPC calls of the hook authenticate with the resolved agent's token
+        let resolve_agent = |explicit: &Option<String>, project_hash: &str| -> String {
+            let agent = resolve_agent(explicit, project_hash);
+            ipc_auth::set_client_scope(Scope::Agent {
+                project_hash: project_hash.to_string(),
+                agent_id: agent.clone(),
+            });
+            agent
+        };
 
         match &action {
             HookAction::Inject { project_hash, agent_id } => {
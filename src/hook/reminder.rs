@@ -9,6 +9,7 @@
 use ai_smartness::config::EngramConfig;
 use ai_smartness::intelligence::engram_retriever::EngramRetriever;
 use ai_smartness::registry::registry::AgentRegistry;
+use ai_smartness::storage::bridges::BridgeStorage;
 use ai_smartness::storage::cognitive_inbox::CognitiveInbox;
 use ai_smartness::storage::mcp_messages::McpMessages;
 use ai_smartness::storage::threads::ThreadStorage;
@@ -76,6 +77,7 @@ fn build_inner(
         database::ConnectionRole::Hook,
     ) {
         append_threads_pins_focus(&mut lines, &agent_conn);
+        append_conflicts(&mut lines, &agent_conn);
         append_pending_messages(&mut lines, &agent_conn, project_hash, agent_id, beat);
 
         // ── Session Handoff (when context > 60% or compaction suspected) ──
@@ -160,6 +162,45 @@ fn append_threads_pins_focus(lines: &mut Vec<String>, conn: &rusqlite::Connectio
 
 }
 
+/// Append unresolved contradictions (LLM-classified), max 3 pairs. Omitted if none.
+fn append_conflicts(lines: &mut Vec<String>, conn: &rusqlite::Connection) {
+    let conflicts = match BridgeStorage::list_conflicts(conn) {
+        Ok(c) if !c.is_empty() => c,
+        _ => return,
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let mut entries = Vec::new();
+    for b in &conflicts {
+        let key = if b.source_id < b.target_id {
+            (b.source_id.clone(), b.target_id.clone())
+        } else {
+            (b.target_id.clone(), b.source_id.clone())
+        };
+        if !seen.insert(key) {
+            continue;
+        }
+        let (Ok(Some(a)), Ok(Some(t))) = (
+            ThreadStorage::get(conn, &b.source_id),
+            ThreadStorage::get(conn, &b.target_id),
+        ) else {
+            continue;
+        };
+        let a8 = if a.id.len() > 8 { &a.id[..8] } else { &a.id };
+        let t8 = if t.id.len() > 8 { &t.id[..8] } else { &t.id };
+        entries.push(format!("- {} \"{}\" <> {} \"{}\"", a8, a.title, t8, t.title));
+        if entries.len() >= 3 {
+            break;
+        }
+    }
+
+    if !entries.is_empty() {
+        lines.push(String::new());
+        lines.push("conflicts (resolve with ai_resolve_conflict):".to_string());
+        lines.extend(entries);
+    }
+}
+
 /// Append alerts line (tasks + messages pending). Omitted if both are 0.
 fn append_pending_messages(
     lines: &mut Vec<String>,
@@ -34,6 +34,11 @@ pub fn message_id() -> String {
     Uuid::new_v4().simple().to_string()
 }
 
+/// Genere un jeton de confirmation d'outil destructif ("ct_" + UUID v4 hex)
+pub fn confirm_token() -> String {
+    format!("ct_{}", Uuid::new_v4().simple())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
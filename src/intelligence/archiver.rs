@@ -1,8 +1,12 @@
 //! Archiver -- move stale suspended threads to archived.
+//!
+//! Threads matched by a retention rule that sets its own archive policy
+//! (`never_decay` or `archive_after_hours`) are left to `Retention`.
 
 use crate::config::DecayConfig;
 use crate::thread::ThreadStatus;
 use crate::AiResult;
+use crate::intelligence::retention::Retention;
 use crate::storage::threads::ThreadStorage;
 use chrono::Utc;
 use rusqlite::Connection;
@@ -23,6 +27,10 @@ impl Archiver {
             if thread.tags.contains(&"__shared__".to_string()) {
                 continue;
             }
+            // Retention rules with their own archive policy are handled by Retention
+            if Retention::overrides_archive(thread, cfg) {
+                continue;
+            }
             let hours_inactive = (now - thread.last_active).num_hours();
             if hours_inactive >= threshold_hours {
                 tracing::debug!(thread_id = %thread.id, hours_inactive = hours_inactive, "Archiving stale thread");
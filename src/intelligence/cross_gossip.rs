@@ -0,0 +1,294 @@
+//! Cross-project gossip — bridge discovery between agents of different projects.
+//!
+//! Enabled by `DaemonConfig::gossip_cross_project`. The daemon opens every
+//! agent memory (ConceptIndex + titles; embeddings are read only for the pairs
+//! being scored) and compares the threads changed since that agent's previous
+//! pass (`last_active`, everything on the first pass) with every thread of each
+//! memory belonging to another project (including the same agent id in two
+//! projects), in both directions:
+//!   1. Candidates: threads sharing ≥ concept_overlap_min_shared concepts
+//!   2. Score: gossip v2 concept weight, averaged with the embedding cosine
+//!      similarity when both threads have a comparable embedding
+//!   3. Keep the CROSS_GOSSIP_MAX_PER_THREAD best pairs per thread above
+//!      concept_min_bridge_weight, and upsert them into the registry DB
+//!
+//! Existing bridges touching a changed thread are re-scored and dropped when
+//! they fall below concept_min_bridge_weight. Bridges whose local thread
+//! disappeared are dropped on the next pass.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::config::GossipConfig;
+use crate::constants::CROSS_GOSSIP_MAX_PER_THREAD;
+use crate::intelligence::gossip::Gossip;
+use crate::processing::embeddings::cosine_similarity;
+use crate::processing::topic_normalizer::TopicNormalizer;
+use crate::storage::concept_index::ConceptIndex;
+use crate::storage::cross_bridges::{CrossBridge, CrossBridgeStorage, CrossEndpoint};
+use crate::storage::threads::ThreadStorage;
+use crate::AiResult;
+use rusqlite::Connection;
+
+/// View of one agent memory for one pass.
+pub struct MemorySnapshot<'c> {
+    pub project_hash: String,
+    pub agent_id: String,
+    conn: &'c Connection,
+    index: ConceptIndex,
+    titles: HashMap<String, String>,
+    /// Threads active since the previous pass: the ones compared this pass.
+    changed: HashSet<String>,
+    /// Embeddings read so far (None = thread without embedding).
+    embeddings: RefCell<HashMap<String, Option<Rc<[f32]>>>>,
+}
+
+impl<'c> MemorySnapshot<'c> {
+    /// `since` = start of this agent's previous pass (RFC 3339), None = compare everything.
+    pub fn load(conn: &'c Connection, project_hash: &str, agent_id: &str, since: Option<&str>) -> AiResult<Self> {
+        let normalizer = TopicNormalizer::load(conn);
+        let index = ConceptIndex::build_from_db(conn, &|c| normalizer.normalize(c))?;
+        let mut titles = HashMap::new();
+        let mut changed = HashSet::new();
+        for (id, title, is_changed) in ThreadStorage::list_titles_changed_since(conn, since)? {
+            if is_changed {
+                changed.insert(id.clone());
+            }
+            titles.insert(id, title);
+        }
+        Ok(Self {
+            project_hash: project_hash.to_string(),
+            agent_id: agent_id.to_string(),
+            conn,
+            index,
+            titles,
+            changed,
+            embeddings: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Threads compared this pass.
+    pub fn changed_count(&self) -> usize {
+        self.changed.len()
+    }
+
+    fn endpoint(&self, thread_id: &str) -> CrossEndpoint {
+        CrossEndpoint {
+            project_hash: self.project_hash.clone(),
+            agent_id: self.agent_id.clone(),
+            thread_id: thread_id.to_string(),
+            title: self.titles.get(thread_id).cloned().unwrap_or_default(),
+        }
+    }
+
+    fn embedding(&self, thread_id: &str) -> Option<Rc<[f32]>> {
+        self.embeddings
+            .borrow_mut()
+            .entry(thread_id.to_string())
+            .or_insert_with(|| {
+                ThreadStorage::get_embedding(self.conn, thread_id).ok().flatten().map(Rc::from)
+            })
+            .clone()
+    }
+
+    fn owns(&self, end: &CrossEndpoint) -> bool {
+        end.project_hash == self.project_hash && end.agent_id == self.agent_id
+    }
+}
+
+/// Score of a thread pair above the bridge threshold.
+struct Scored {
+    weight: f64,
+    similarity: Option<f64>,
+    shared: Vec<String>,
+}
+
+/// Run one cross-project pass. Returns the number of new cross bridges.
+pub fn run_cycle(reg_conn: &Connection, snapshots: &[MemorySnapshot], config: &GossipConfig) -> AiResult<u32> {
+    let mut created = 0u32;
+    let mut refreshed = 0usize;
+
+    for (i, a) in snapshots.iter().enumerate() {
+        for b in &snapshots[i + 1..] {
+            if a.project_hash == b.project_hash {
+                continue;
+            }
+            for bridge in compare(a, b, config).into_iter().chain(compare(b, a, config)) {
+                if CrossBridgeStorage::upsert(reg_conn, &bridge)? {
+                    created += 1;
+                } else {
+                    refreshed += 1;
+                }
+            }
+        }
+    }
+
+    let removed = prune(reg_conn, snapshots, config)?;
+    tracing::info!(
+        memories = snapshots.len(),
+        changed = snapshots.iter().map(|s| s.changed.len()).sum::<usize>(),
+        created = created,
+        refreshed = refreshed,
+        removed = removed,
+        "Cross-project gossip cycle complete"
+    );
+    Ok(created)
+}
+
+/// Best related threads of `b` for each changed thread of `a`.
+fn compare(a: &MemorySnapshot, b: &MemorySnapshot, config: &GossipConfig) -> Vec<CrossBridge> {
+    let mut out = Vec::new();
+
+    for thread_a in &a.changed {
+        let Some(concepts_a) = a.index.concepts_of(thread_a) else { continue };
+        let query: Vec<String> = concepts_a.iter().cloned().collect();
+        let mut scored: Vec<(Scored, String)> = b
+            .index
+            .lookup(&query)
+            .into_iter()
+            .filter(|thread_b| b.titles.contains_key(thread_b))
+            .filter_map(|thread_b| score(a, thread_a, b, &thread_b, config).map(|s| (s, thread_b)))
+            .collect();
+
+        scored.sort_by(|x, y| {
+            y.0.weight.partial_cmp(&x.0.weight).unwrap_or(std::cmp::Ordering::Equal).then_with(|| x.1.cmp(&y.1))
+        });
+        for (s, thread_b) in scored.into_iter().take(CROSS_GOSSIP_MAX_PER_THREAD) {
+            let mut bridge = CrossBridge::new(a.endpoint(thread_a), b.endpoint(&thread_b), s.weight, s.shared);
+            bridge.embedding_similarity = s.similarity;
+            out.push(bridge);
+        }
+    }
+    out
+}
+
+/// Score of a thread pair (symmetric). None below concept_min_bridge_weight.
+fn score(a: &MemorySnapshot, thread_a: &str, b: &MemorySnapshot, thread_b: &str, config: &GossipConfig) -> Option<Scored> {
+    let concepts_a = a.index.concepts_of(thread_a)?;
+    let concepts_b = b.index.concepts_of(thread_b)?;
+    let mut shared: Vec<String> = concepts_a.intersection(concepts_b).cloned().collect();
+    if shared.len() < config.concept_overlap_min_shared.max(1) {
+        return None;
+    }
+    shared.sort();
+    let ratio = shared.len() as f64 / concepts_a.len().min(concepts_b.len()).max(1) as f64;
+    let concept_weight = Gossip::compute_weight(shared.len(), ratio);
+
+    let similarity = match (a.embedding(thread_a), b.embedding(thread_b)) {
+        (Some(ea), Some(eb)) if ea.len() == eb.len() => Some(cosine_similarity(&ea, &eb).max(0.0)),
+        _ => None,
+    };
+    let weight = match similarity {
+        Some(sim) => (concept_weight + sim) / 2.0,
+        None => concept_weight,
+    };
+    (weight >= config.concept_min_bridge_weight).then_some(Scored { weight, similarity, shared })
+}
+
+/// Drop bridges whose thread no longer exists in its snapshot, and bridges
+/// touching a changed thread whose score fell below the threshold.
+fn prune(reg_conn: &Connection, snapshots: &[MemorySnapshot], config: &GossipConfig) -> AiResult<usize> {
+    let snapshot_of = |end: &CrossEndpoint| snapshots.iter().find(|s| s.owns(end));
+    let mut removed = 0;
+    let mut seen: HashSet<String> = HashSet::new();
+    for snap in snapshots {
+        for bridge in CrossBridgeStorage::list_for_agent(reg_conn, &snap.project_hash, &snap.agent_id)? {
+            if !seen.insert(bridge.id.clone()) {
+                continue;
+            }
+            let (source, target) = (snapshot_of(&bridge.source), snapshot_of(&bridge.target));
+            let gone = [(&bridge.source, source), (&bridge.target, target)]
+                .iter()
+                .any(|(end, snap)| snap.is_some_and(|s| !s.titles.contains_key(&end.thread_id)));
+            let weakened = match (source, target) {
+                (Some(s), Some(t)) if s.changed.contains(&bridge.source.thread_id)
+                    || t.changed.contains(&bridge.target.thread_id) =>
+                {
+                    score(s, &bridge.source.thread_id, t, &bridge.target.thread_id, config).is_none()
+                }
+                _ => false,
+            };
+            if gone || weakened {
+                CrossBridgeStorage::delete(reg_conn, &bridge.id)?;
+                removed += 1;
+            }
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_agent_db, setup_registry_db, ThreadBuilder};
+
+    fn memory(threads: &[(&str, Vec<&str>)]) -> Connection {
+        let conn = setup_agent_db();
+        for (id, concepts) in threads {
+            let t = ThreadBuilder::new().id(id).title(&format!("Thread {}", id)).concepts(concepts.clone()).build();
+            ThreadStorage::insert(&conn, &t).unwrap();
+        }
+        conn
+    }
+
+    fn snapshot<'c>(conn: &'c Connection, project: &str, agent: &str, since: Option<&str>) -> MemorySnapshot<'c> {
+        MemorySnapshot::load(conn, project, agent, since).unwrap()
+    }
+
+    #[test]
+    fn test_cross_project_bridges_and_stale_removal() {
+        let reg = setup_registry_db();
+        let config = GossipConfig::default();
+        let shared = vec!["sqlite", "wal", "checkpoint", "migration"];
+        let a = memory(&[("a1", shared.clone()), ("a2", vec!["css", "layout", "flexbox"])]);
+        let b = memory(&[("b1", shared.clone())]);
+        // Same project as `a`: never compared with it
+        let c = memory(&[("c1", shared.clone())]);
+
+        let snaps = [snapshot(&a, "p1", "dev", None), snapshot(&b, "p2", "dev", None), snapshot(&c, "p1", "ops", None)];
+        let created = run_cycle(&reg, &snaps, &config).unwrap();
+        assert_eq!(created, 2, "a1↔b1 and c1↔b1, not a1↔c1");
+        let related = CrossBridgeStorage::list_for_thread(&reg, "p2", "dev", "b1").unwrap();
+        assert_eq!(related.len(), 2);
+        assert!(related.iter().all(|r| r.other_side("p2", "dev", "b1").unwrap().project_hash == "p1"));
+        assert_eq!(related[0].shared_concepts.len(), 4);
+
+        // Second pass: a1 is gone from p1 → its bridge is removed, nothing new
+        let a = memory(&[("a2", vec!["css", "layout", "flexbox"])]);
+        let b = memory(&[("b1", shared)]);
+        let snaps = [snapshot(&a, "p1", "dev", None), snapshot(&b, "p2", "dev", None)];
+        assert_eq!(run_cycle(&reg, &snaps, &config).unwrap(), 0);
+        assert_eq!(CrossBridgeStorage::count(&reg).unwrap(), 1);
+        assert!(CrossBridgeStorage::list_for_thread(&reg, "p1", "dev", "a1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_incremental_pass_both_directions_and_weakened_bridges() {
+        let reg = setup_registry_db();
+        let config = GossipConfig::default();
+        let shared = vec!["sqlite", "wal", "checkpoint", "migration"];
+        let since = crate::time_utils::to_sqlite(&(crate::time_utils::now() - chrono::Duration::hours(1)));
+        let a = setup_agent_db();
+        let old = ThreadBuilder::new()
+            .id("a1")
+            .concepts(shared.clone())
+            .last_active(crate::time_utils::now() - chrono::Duration::days(1))
+            .build();
+        ThreadStorage::insert(&a, &old).unwrap();
+        let b = memory(&[("b1", shared)]);
+
+        // Only b1 changed: the unchanged a1 is still found from p2's side
+        let snaps = [snapshot(&a, "p1", "dev", Some(&since)), snapshot(&b, "p2", "dev", Some(&since))];
+        assert_eq!((snaps[0].changed_count(), snaps[1].changed_count()), (0, 1));
+        assert_eq!(run_cycle(&reg, &snaps, &config).unwrap(), 1);
+
+        // b1 drifts to other concepts: the bridge is re-scored and dropped
+        let mut b1 = ThreadStorage::get(&b, "b1").unwrap().unwrap();
+        b1.concepts = vec!["css".into(), "layout".into(), "flexbox".into()];
+        ThreadStorage::update(&b, &b1).unwrap();
+        let snaps = [snapshot(&a, "p1", "dev", Some(&since)), snapshot(&b, "p2", "dev", Some(&since))];
+        assert_eq!(run_cycle(&reg, &snaps, &config).unwrap(), 0);
+        assert_eq!(CrossBridgeStorage::count(&reg).unwrap(), 0);
+    }
+}
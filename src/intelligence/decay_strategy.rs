@@ -0,0 +1,262 @@
+//! Decay strategies — pluggable thread weight decay models.
+//!
+//! - HalfLife (default): exponential half-life by importance + orphan acceleration.
+//! - ActR: ACT-R base-level activation B = ln(Σ t_j^-d) over the access history,
+//!   mapped to a retrieval probability 1 / (1 + e^-(B-τ)/s).
+//! - SpacedRepetition: retention R = e^(-t/S), where stability S grows with
+//!   every successful reuse (used injection or positive rating).
+//!
+//! Memory-model strategies (ActR, SpacedRepetition) compute a retention ceiling:
+//! the weight is lowered to it, never raised — boosts stay with Engram/recall.
+//!
+//! Per-thread selection: label override → origin override → default strategy.
+
+use crate::config::{DecayConfig, DecayStrategyKind};
+use crate::storage::threads::ThreadStorage;
+use crate::thread::Thread;
+use crate::AiResult;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+/// Minimum age of an access (days) — avoids t^-d blowing up for fresh accesses.
+const MIN_ACCESS_AGE_DAYS: f64 = 1.0 / 24.0;
+
+pub trait DecayStrategy {
+    fn kind(&self) -> DecayStrategyKind;
+
+    /// Whether `decay` reads the access history (costs one query per thread).
+    fn needs_history(&self) -> bool {
+        false
+    }
+
+    /// New weight for `thread` at `now`. `history` is the access history
+    /// (oldest first), empty unless `needs_history()`.
+    fn decay(&self, thread: &Thread, history: &[DateTime<Utc>], now: DateTime<Utc>) -> f64;
+}
+
+/// Pick the strategy for a thread: first matching label, then origin, then default.
+pub fn resolve_kind(thread: &Thread, cfg: &DecayConfig) -> DecayStrategyKind {
+    thread.labels.iter()
+        .find_map(|l| cfg.strategy_by_label.get(l))
+        .or_else(|| cfg.strategy_by_origin.get(thread.origin_type.as_str()))
+        .unwrap_or(&cfg.strategy)
+        .clone()
+}
+
+pub fn strategy_for<'a>(kind: &DecayStrategyKind, cfg: &'a DecayConfig) -> Box<dyn DecayStrategy + 'a> {
+    match kind {
+        DecayStrategyKind::HalfLife => Box::new(HalfLifeStrategy { cfg }),
+        DecayStrategyKind::ActR => Box::new(ActRStrategy { cfg }),
+        DecayStrategyKind::SpacedRepetition => Box::new(SpacedRepetitionStrategy { cfg }),
+    }
+}
+
+/// Access history: message timestamps, rating timestamps and last use.
+pub fn access_history(conn: &Connection, thread: &Thread) -> AiResult<Vec<DateTime<Utc>>> {
+    let mut history = ThreadStorage::message_timestamps(conn, &thread.id)?;
+    history.extend(thread.ratings.iter().filter_map(|r| {
+        r.get("timestamp").and_then(|v| v.as_str()).and_then(parse_ts)
+    }));
+    if let Some(ts) = thread.injection_stats.as_ref()
+        .and_then(|s| s.last_used_at.as_deref())
+        .and_then(parse_ts)
+    {
+        history.push(ts);
+    }
+    if history.is_empty() {
+        history.push(thread.created_at);
+    }
+    history.sort();
+    Ok(history)
+}
+
+fn parse_ts(s: &str) -> Option<DateTime<Utc>> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Compute effective half-life based on importance.
+/// Range: min_half_life (disposable, importance=0) to max_half_life (critical, importance=1).
+pub fn effective_half_life(importance: f64, cfg: &DecayConfig) -> f64 {
+    cfg.thread_min_half_life
+        + (importance.clamp(0.0, 1.0) * (cfg.thread_max_half_life - cfg.thread_min_half_life))
+}
+
+// ── HalfLife ──
+
+pub struct HalfLifeStrategy<'a> {
+    cfg: &'a DecayConfig,
+}
+
+impl DecayStrategy for HalfLifeStrategy<'_> {
+    fn kind(&self) -> DecayStrategyKind {
+        DecayStrategyKind::HalfLife
+    }
+
+    fn decay(&self, thread: &Thread, _history: &[DateTime<Utc>], now: DateTime<Utc>) -> f64 {
+        let cfg = self.cfg;
+        let age_days = (now - thread.last_active).num_hours() as f64 / 24.0;
+        if age_days <= 0.0 {
+            return thread.weight;
+        }
+
+        let base_half_life = effective_half_life(thread.importance, cfg);
+        // Orphan acceleration: threads not re-injected decay faster.
+        // Use last_injected_at (if available) instead of last_active for orphan time,
+        // since last_active updates on any interaction but injection is what matters.
+        let orphan_ref = thread.injection_stats.as_ref()
+            .and_then(|s| s.last_injected_at.as_deref())
+            .and_then(parse_ts);
+        let orphan_since = orphan_ref.unwrap_or(thread.last_active);
+        let orphan_hours = (now - orphan_since).num_hours() as f64;
+        let orphan_factor = 0.5f64
+            .powf(orphan_hours / cfg.orphan_halving_hours)
+            .max(cfg.orphan_min_half_life_factor);
+        let half_life = base_half_life * orphan_factor;
+        let decay_factor = 0.5f64.powf(age_days / half_life);
+        (thread.weight * decay_factor).max(0.0)
+    }
+}
+
+// ── ACT-R ──
+
+pub struct ActRStrategy<'a> {
+    cfg: &'a DecayConfig,
+}
+
+impl ActRStrategy<'_> {
+    /// Base-level activation B = ln(Σ t_j^-d), t in days.
+    pub fn base_level(&self, history: &[DateTime<Utc>], now: DateTime<Utc>) -> f64 {
+        let sum: f64 = history.iter()
+            .map(|ts| {
+                let age = ((now - *ts).num_minutes() as f64 / 1440.0).max(MIN_ACCESS_AGE_DAYS);
+                age.powf(-self.cfg.actr_decay)
+            })
+            .sum();
+        if sum <= 0.0 { f64::NEG_INFINITY } else { sum.ln() }
+    }
+}
+
+impl DecayStrategy for ActRStrategy<'_> {
+    fn kind(&self) -> DecayStrategyKind {
+        DecayStrategyKind::ActR
+    }
+
+    fn needs_history(&self) -> bool {
+        true
+    }
+
+    fn decay(&self, thread: &Thread, history: &[DateTime<Utc>], now: DateTime<Utc>) -> f64 {
+        let b = self.base_level(history, now);
+        let recall = 1.0 / (1.0 + (-(b - self.cfg.actr_threshold) / self.cfg.actr_noise).exp());
+        thread.weight.min(recall).max(0.0)
+    }
+}
+
+// ── Spaced repetition ──
+
+pub struct SpacedRepetitionStrategy<'a> {
+    cfg: &'a DecayConfig,
+}
+
+impl SpacedRepetitionStrategy<'_> {
+    /// Stability in days: initial × growth^reuses, scaled by importance (×0.5–1.5), capped.
+    pub fn stability(&self, thread: &Thread) -> f64 {
+        let used = thread.injection_stats.as_ref().map(|s| s.used_count).unwrap_or(0);
+        let positive_ratings = thread.ratings.iter()
+            .filter(|r| r.get("useful").and_then(|v| v.as_bool()) == Some(true))
+            .count() as u32;
+        let reuses = (used + positive_ratings).min(32) as i32;
+        let importance_scale = 0.5 + thread.importance.clamp(0.0, 1.0);
+        (self.cfg.sr_initial_stability * self.cfg.sr_stability_growth.powi(reuses) * importance_scale)
+            .min(self.cfg.sr_max_stability)
+    }
+}
+
+impl DecayStrategy for SpacedRepetitionStrategy<'_> {
+    fn kind(&self) -> DecayStrategyKind {
+        DecayStrategyKind::SpacedRepetition
+    }
+
+    fn decay(&self, thread: &Thread, _history: &[DateTime<Utc>], now: DateTime<Utc>) -> f64 {
+        // Elapsed since the last successful reuse (falls back to last activity)
+        let last_review = thread.injection_stats.as_ref()
+            .and_then(|s| s.last_used_at.as_deref())
+            .and_then(parse_ts)
+            .map(|ts| ts.max(thread.last_active))
+            .unwrap_or(thread.last_active);
+        let elapsed_days = ((now - last_review).num_minutes() as f64 / 1440.0).max(0.0);
+        let retention = (-elapsed_days / self.stability(thread)).exp();
+        thread.weight.min(retention).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::*;
+    use crate::thread::InjectionStats;
+    use chrono::Duration;
+
+    #[test]
+    fn test_resolve_kind_label_beats_origin() {
+        let mut cfg = DecayConfig::default();
+        cfg.strategy_by_origin.insert("prompt".into(), DecayStrategyKind::SpacedRepetition);
+        cfg.strategy_by_label.insert("decision".into(), DecayStrategyKind::ActR);
+
+        let labelled = ThreadBuilder::new().labels(vec!["decision"]).build();
+        assert_eq!(resolve_kind(&labelled, &cfg), DecayStrategyKind::ActR);
+
+        let plain = ThreadBuilder::new().build();
+        assert_eq!(resolve_kind(&plain, &cfg), DecayStrategyKind::SpacedRepetition);
+
+        assert_eq!(resolve_kind(&plain, &DecayConfig::default()), DecayStrategyKind::HalfLife);
+    }
+
+    #[test]
+    fn test_actr_more_accesses_retain_more() {
+        let cfg = DecayConfig::default();
+        let s = ActRStrategy { cfg: &cfg };
+        let now = Utc::now();
+        let thread = ThreadBuilder::new().weight(1.0).build();
+
+        let once = vec![now - Duration::days(10)];
+        let often: Vec<_> = (1..=8).map(|d| now - Duration::days(d)).collect();
+
+        let w_once = s.decay(&thread, &once, now);
+        let w_often = s.decay(&thread, &often, now);
+        assert!(w_once < 1.0);
+        assert!(w_often > w_once, "frequent access should keep more weight");
+    }
+
+    #[test]
+    fn test_spaced_repetition_stability_grows_with_reuse() {
+        let cfg = DecayConfig::default();
+        let s = SpacedRepetitionStrategy { cfg: &cfg };
+        let now = Utc::now();
+
+        let fresh = ThreadBuilder::new().weight(1.0).importance(0.5).last_active(days_ago(3)).build();
+        let mut reused = fresh.clone();
+        reused.injection_stats = Some(InjectionStats {
+            injection_count: 5,
+            used_count: 4,
+            last_injected_at: None,
+            last_used_at: None,
+        });
+
+        assert!(s.stability(&reused) > s.stability(&fresh));
+        assert!(s.decay(&reused, &[], now) > s.decay(&fresh, &[], now));
+    }
+
+    #[test]
+    fn test_memory_models_never_raise_weight() {
+        let cfg = DecayConfig::default();
+        let now = Utc::now();
+        let thread = ThreadBuilder::new().weight(0.2).last_active(now).build();
+        for kind in [DecayStrategyKind::ActR, DecayStrategyKind::SpacedRepetition] {
+            let s = strategy_for(&kind, &cfg);
+            assert!(s.decay(&thread, &[now], now) <= 0.2);
+        }
+    }
+}
@@ -3,24 +3,60 @@
 //! Does NOT delete or merge anything. Only reduces weights.
 //! Suspends threads below DecayConfig.thread_suspend_threshold.
 //! Cleans orphan bridges (both endpoints missing).
+//!
+//! Thread decay model is pluggable (see `decay_strategy`): HalfLife by default,
+//! ActR or SpacedRepetition selectable globally, per label or per origin type.
 
-use crate::bridge::BridgeStatus;
-use crate::config::DecayConfig;
-use crate::thread::ThreadStatus;
-use crate::AiResult;
+use crate::bridge::{BridgeStatus, ThinkBridge};
+use crate::config::{DecayConfig, DecayStrategyKind};
+use crate::thread::{Thread, ThreadStatus};
+use crate::{metrics, AiResult};
 use crate::storage::bridges::BridgeStorage;
 use crate::storage::threads::ThreadStorage;
-use chrono::Utc;
+use super::decay_strategy::{self, DecayStrategy};
+use super::retention::Retention;
+use chrono::{DateTime, Duration, Utc};
 use rusqlite::Connection;
+use serde::Serialize;
 
 pub struct Decayer;
 
+/// What one decay cycle changed (reported as daemon change events).
+#[derive(Debug, Default)]
+pub struct DecayChanges {
+    /// Threads whose weight changed.
+    pub affected: u32,
+    /// Threads auto-suspended (weight reached 0).
+    pub suspended: Vec<String>,
+    /// Bridges invalidated by decay.
+    pub dead_bridges: Vec<ThinkBridge>,
+    /// Bridges deleted because both endpoints are gone.
+    pub orphan_bridges: Vec<ThinkBridge>,
+}
+
+/// Projected weight trajectory of one thread (decay simulation).
+#[derive(Debug, Clone, Serialize)]
+pub struct DecayProjection {
+    pub thread_id: String,
+    pub title: String,
+    pub strategy: DecayStrategyKind,
+    pub current_weight: f64,
+    /// Weight at the end of each simulated day (index 0 = day 1).
+    pub daily: Vec<f64>,
+    /// First simulated day the thread would be auto-suspended, if any.
+    pub suspended_on_day: Option<u32>,
+}
+
 impl Decayer {
     /// Decay active thread/bridge weights. Returns count of affected threads.
     pub fn decay_active(conn: &Connection, cfg: &DecayConfig) -> AiResult<u32> {
+        Self::decay_active_changes(conn, cfg).map(|changes| changes.affected)
+    }
+
+    /// Decay active thread/bridge weights, returning what changed.
+    pub fn decay_active_changes(conn: &Connection, cfg: &DecayConfig) -> AiResult<DecayChanges> {
         let now = Utc::now();
-        let mut affected = 0u32;
-        let mut suspended_count = 0u32;
+        let mut changes = DecayChanges::default();
 
         // 1. Decay thread weights
         let active = ThreadStorage::list_active(conn)?;
@@ -29,35 +65,31 @@ impl Decayer {
             if thread.tags.contains(&"__shared__".to_string()) {
                 continue;
             }
+            // Skip threads covered by a never_decay retention rule
+            if Retention::is_protected(thread, cfg) {
+                continue;
+            }
 
             let age_days = (now - thread.last_active).num_hours() as f64 / 24.0;
             if age_days <= 0.0 {
                 continue;
             }
 
-            let base_half_life = effective_half_life(thread.importance, cfg);
-            // Orphan acceleration: threads not re-injected decay faster.
-            // Use last_injected_at (if available) instead of last_active for orphan time,
-            // since last_active updates on any interaction but injection is what matters.
-            let orphan_ref = thread.injection_stats.as_ref()
-                .and_then(|s| s.last_injected_at.as_ref())
-                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
-                .map(|dt| dt.with_timezone(&Utc));
-            let orphan_since = orphan_ref.unwrap_or(thread.last_active);
-            let orphan_hours = (now - orphan_since).num_hours() as f64;
-            let orphan_factor = 0.5f64
-                .powf(orphan_hours / cfg.orphan_halving_hours)
-                .max(cfg.orphan_min_half_life_factor);
-            let half_life = base_half_life * orphan_factor;
-            let decay_factor = 0.5f64.powf(age_days / half_life);
-            let new_weight = (thread.weight * decay_factor).max(0.0);
+            let kind = decay_strategy::resolve_kind(thread, cfg);
+            let strategy = decay_strategy::strategy_for(&kind, cfg);
+            let history = if strategy.needs_history() {
+                decay_strategy::access_history(conn, thread)?
+            } else {
+                Vec::new()
+            };
+            let new_weight = strategy.decay(thread, &history, now).max(0.0);
 
             if (new_weight - thread.weight).abs() < 0.001 {
                 continue;
             }
 
             ThreadStorage::update_weight(conn, &thread.id, new_weight)?;
-            affected += 1;
+            changes.affected += 1;
 
             // Auto-suspend when weight reaches near-zero — weight is the sole lifecycle authority
             // Use epsilon because multiplicative decay never truly reaches 0.0 in floating point
@@ -65,7 +97,7 @@ impl Decayer {
                 ThreadStorage::update_weight(conn, &thread.id, 0.0)?;
                 tracing::warn!(thread_id = %thread.id, "Thread auto-suspended by decay (weight → 0)");
                 ThreadStorage::update_status(conn, &thread.id, ThreadStatus::Suspended)?;
-                suspended_count += 1;
+                changes.suspended.push(thread.id.clone());
                 continue;
             }
         }
@@ -75,7 +107,7 @@ impl Decayer {
         // decay only for the time since last_reinforced, then updates it.
         let mut bridges = BridgeStorage::list_active(conn)?;
         bridges.extend(BridgeStorage::list_by_status(conn, BridgeStatus::Weak)?);
-        for bridge in &bridges {
+        for bridge in bridges {
             let reference = bridge.last_reinforced.unwrap_or(bridge.created_at);
             let delta_days = (now - reference).num_hours() as f64 / 24.0;
             if delta_days <= 0.0 {
@@ -88,6 +120,7 @@ impl Decayer {
             if new_weight < cfg.bridge_death_threshold {
                 BridgeStorage::update_weight(conn, &bridge.id, 0.0)?;
                 BridgeStorage::update_status(conn, &bridge.id, BridgeStatus::Invalid)?;
+                changes.dead_bridges.push(bridge);
             } else if new_weight < crate::constants::BRIDGE_WEAK_THRESHOLD {
                 BridgeStorage::update_status(conn, &bridge.id, BridgeStatus::Weak)?;
                 BridgeStorage::update_weight(conn, &bridge.id, new_weight)?;
@@ -113,22 +146,99 @@ impl Decayer {
             ThreadStorage::cleanup_orphan_continuity(conn)?;
         }
 
-        tracing::info!(threads_affected = affected, threads_suspended = suspended_count, orphans_cleaned = orphans.len(), continuity_orphans = continuity_orphans.len(), "Decay cycle complete");
+        if !changes.suspended.is_empty() {
+            metrics::DECAY_SUSPENSIONS.add(&[], changes.suspended.len() as f64);
+        }
+        tracing::info!(threads_affected = changes.affected, threads_suspended = changes.suspended.len(), orphans_cleaned = orphans.len(), continuity_orphans = continuity_orphans.len(), "Decay cycle complete");
 
-        Ok(affected)
+        changes.orphan_bridges = orphans;
+        Ok(changes)
+    }
+
+    /// Project thread weights over `days` without touching the DB.
+    ///
+    /// Replays one decay pass every `step_secs` (the prune interval) assuming
+    /// no further access, on an evenly spread sample of active threads.
+    /// `strategy` forces one model for all threads (None = per-thread resolution).
+    pub fn simulate(
+        conn: &Connection,
+        cfg: &DecayConfig,
+        strategy: Option<DecayStrategyKind>,
+        days: u32,
+        sample: usize,
+        step_secs: u64,
+    ) -> AiResult<Vec<DecayProjection>> {
+        let candidates: Vec<Thread> = ThreadStorage::list_active(conn)?
+            .into_iter()
+            .filter(|t| !t.tags.contains(&"__shared__".to_string()))
+            .collect();
+        if candidates.is_empty() || sample == 0 {
+            return Ok(Vec::new());
+        }
+        let stride = (candidates.len() / sample).max(1);
+        let step = Duration::seconds(step_secs.max(60) as i64);
+        let start = Utc::now();
+
+        let mut out = Vec::new();
+        for thread in candidates.iter().step_by(stride).take(sample) {
+            let kind = strategy.clone().unwrap_or_else(|| decay_strategy::resolve_kind(thread, cfg));
+            let model = decay_strategy::strategy_for(&kind, cfg);
+            let history = if model.needs_history() {
+                decay_strategy::access_history(conn, thread)?
+            } else {
+                Vec::new()
+            };
+            let (daily, suspended_on_day) =
+                project(thread, model.as_ref(), &history, start, days, step);
+            out.push(DecayProjection {
+                thread_id: thread.id.clone(),
+                title: thread.title.clone(),
+                strategy: kind,
+                current_weight: thread.weight,
+                daily,
+                suspended_on_day,
+            });
+        }
+        Ok(out)
     }
 }
 
-/// Compute effective half-life based on importance.
-/// Range: min_half_life (disposable, importance=0) to max_half_life (critical, importance=1).
-fn effective_half_life(importance: f64, cfg: &DecayConfig) -> f64 {
-    cfg.thread_min_half_life
-        + (importance.clamp(0.0, 1.0) * (cfg.thread_max_half_life - cfg.thread_min_half_life))
+/// Step a thread copy through simulated prune cycles, sampling the weight daily.
+fn project(
+    thread: &Thread,
+    strategy: &dyn DecayStrategy,
+    history: &[DateTime<Utc>],
+    start: DateTime<Utc>,
+    days: u32,
+    step: Duration,
+) -> (Vec<f64>, Option<u32>) {
+    let mut sim = thread.clone();
+    let mut daily = Vec::with_capacity(days as usize);
+    let mut suspended_on_day = None;
+    let mut t = start;
+
+    for day in 1..=days {
+        let day_end = start + Duration::days(day as i64);
+        while t + step <= day_end {
+            t += step;
+            if suspended_on_day.is_some() {
+                continue;
+            }
+            let w = strategy.decay(&sim, history, t).max(0.0);
+            sim.weight = if w < 0.001 { 0.0 } else { w };
+        }
+        if suspended_on_day.is_none() && sim.weight == 0.0 {
+            suspended_on_day = Some(day);
+        }
+        daily.push(sim.weight);
+    }
+    (daily, suspended_on_day)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::intelligence::decay_strategy::effective_half_life;
     use crate::test_helpers::*;
 
     fn default_cfg() -> DecayConfig {
@@ -290,4 +400,19 @@ mod tests {
         let got = ThreadStorage::get(&conn, "t2").unwrap().unwrap();
         assert_eq!(got.continuity_parent_id, None);
     }
+
+    #[test]
+    fn test_simulate_does_not_write() {
+        let conn = setup_agent_db();
+        let t = ThreadBuilder::new().id("t1").weight(1.0).importance(0.5).build();
+        ThreadStorage::insert(&conn, &t).unwrap();
+
+        let proj = Decayer::simulate(&conn, &default_cfg(), Some(DecayStrategyKind::SpacedRepetition), 7, 5, 3600).unwrap();
+        assert_eq!(proj.len(), 1);
+        assert_eq!(proj[0].daily.len(), 7);
+        assert!(proj[0].daily[6] < proj[0].daily[0], "weight should keep falling");
+
+        let got = ThreadStorage::get(&conn, "t1").unwrap().unwrap();
+        assert_eq!(got.weight, 1.0, "simulation must not touch stored weights");
+    }
 }
@@ -12,11 +12,14 @@
 //! Only V1 (SemanticSimilarity) costs compute.
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
 
 use crate::thread::{Thread, ThreadStatus, OriginType, WorkContext, InjectionStats};
 use crate::config::EngramConfig;
-use crate::AiResult;
+use crate::{metrics, AiResult};
 use crate::processing::embeddings::EmbeddingManager;
+use crate::processing::topic_normalizer::TopicNormalizer;
 use crate::storage::bridges::BridgeStorage;
 use crate::storage::concept_index::ConceptIndex;
 use crate::storage::topic_index::TopicIndex;
@@ -72,6 +75,8 @@ pub struct EngramRetriever {
     validator_weights: Vec<f64>,
     topic_index: TopicIndex,
     concept_index: ConceptIndex,
+    /// Key function of both indexes (agent aliases, reloaded with them).
+    normalizer: Arc<TopicNormalizer>,
     config: EngramConfig,
     strong_inject_min: u8,
     weak_inject_min: u8,
@@ -81,8 +86,9 @@ impl EngramRetriever {
     /// Create a new EngramRetriever from config.
     /// Builds the TopicIndex + ConceptIndex from the database and initializes all 10 validators.
     pub fn new(conn: &Connection, config: EngramConfig) -> AiResult<Self> {
-        let topic_index = TopicIndex::build_from_db(conn)?;
-        let concept_index = ConceptIndex::build_from_db(conn)?;
+        let normalizer = Arc::new(TopicNormalizer::load(conn));
+        let topic_index = TopicIndex::build_from_db(conn, &|t| normalizer.normalize(t))?;
+        let concept_index = ConceptIndex::build_from_db(conn, &|c| normalizer.normalize(c))?;
 
         // V1 threshold: use active threshold based on ONNX availability
         let use_onnx = EmbeddingManager::global().use_onnx;
@@ -110,6 +116,7 @@ impl EngramRetriever {
             validator_weights,
             topic_index,
             concept_index,
+            normalizer,
             strong_inject_min: strong,
             weak_inject_min: weak,
             config,
@@ -119,8 +126,10 @@ impl EngramRetriever {
     /// Refresh topic and concept indexes from the database.
     /// Called periodically by the daemon prune loop.
     pub fn refresh_index(&mut self, conn: &Connection) -> AiResult<()> {
-        self.topic_index = TopicIndex::build_from_db(conn)?;
-        self.concept_index = ConceptIndex::build_from_db(conn)?;
+        let normalizer = Arc::new(TopicNormalizer::load(conn));
+        self.topic_index = TopicIndex::build_from_db(conn, &|t| normalizer.normalize(t))?;
+        self.concept_index = ConceptIndex::build_from_db(conn, &|c| normalizer.normalize(c))?;
+        self.normalizer = normalizer;
         Ok(())
     }
 
@@ -132,12 +141,13 @@ impl EngramRetriever {
         topics: Option<&[String]>,
         concepts: Option<&[String]>,
     ) {
+        let keys = |raw: &[String]| -> Vec<String> { raw.iter().map(|r| self.normalizer.normalize(r)).collect() };
         match topics {
-            Some(t) => self.topic_index.update(thread_id, t),
+            Some(t) => self.topic_index.update(thread_id, &keys(t)),
             None => self.topic_index.remove(thread_id),
         }
         match concepts {
-            Some(c) => self.concept_index.update(thread_id, c),
+            Some(c) => self.concept_index.update(thread_id, &keys(c)),
             None => self.concept_index.remove(thread_id),
         }
     }
@@ -154,10 +164,11 @@ impl EngramRetriever {
         limit: usize,
     ) -> AiResult<Vec<ScoredThread>> {
         tracing::info!(query_len = user_message.len(), limit = limit, "Engram retrieval starting");
+        let mut phase_start = Instant::now();
 
         // === Phase 1: Topic + concept extraction + hash index pre-filter ===
-        let query_topics = self.topic_index.extract_matching_topics(user_message);
-        let query_concepts = self.concept_index.extract_matching_concepts(user_message);
+        let query_topics = self.topic_index.extract_matching_topics(user_message, &|w| self.normalizer.normalize(w));
+        let query_concepts = self.concept_index.extract_matching_concepts(user_message, &|w| self.normalizer.normalize(w));
 
         let candidate_ids = if self.config.hash_index_enabled
             && (!query_topics.is_empty() || !query_concepts.is_empty())
@@ -183,6 +194,7 @@ impl EngramRetriever {
             query_concepts = ?query_concepts,
             "Phase 1 pre-filter complete"
         );
+        record_phase("context", "prefilter", &mut phase_start);
 
         if candidate_ids.is_empty() {
             tracing::debug!("No candidates found, returning empty");
@@ -206,7 +218,9 @@ impl EngramRetriever {
             focus_topics: load_focus_topics(conn),
             label_hint: None,
             bridge_connections,
+            normalizer: self.normalizer.clone(),
         };
+        record_phase("context", "load", &mut phase_start);
 
         // === Phase 2: Score each candidate with 10 validators ===
         let mut scores: Vec<EngramScore> = candidates.iter()
@@ -227,6 +241,8 @@ impl EngramRetriever {
             }
         }
 
+        record_phase("context", "score", &mut phase_start);
+
         // === Phase 3: Consensus → sort, filter, return ===
         scores.sort_by(|a, b| b.weighted_score.partial_cmp(&a.weighted_score)
             .unwrap_or(std::cmp::Ordering::Equal));
@@ -243,6 +259,7 @@ impl EngramRetriever {
                 })
             })
             .collect();
+        record_phase("context", "consensus", &mut phase_start);
 
         tracing::info!(
             candidates_scored = scores.len(),
@@ -278,10 +295,11 @@ impl EngramRetriever {
         limit: usize,
     ) -> AiResult<Vec<ScoredThread>> {
         tracing::info!(query_len = thinking_text.len(), "Engram thinking query starting");
+        let mut phase_start = Instant::now();
 
         // Phase 1: same pre-filter
-        let query_topics = self.topic_index.extract_matching_topics(thinking_text);
-        let query_concepts = self.concept_index.extract_matching_concepts(thinking_text);
+        let query_topics = self.topic_index.extract_matching_topics(thinking_text, &|w| self.normalizer.normalize(w));
+        let query_concepts = self.concept_index.extract_matching_concepts(thinking_text, &|w| self.normalizer.normalize(w));
 
         let candidate_ids = if self.config.hash_index_enabled
             && (!query_topics.is_empty() || !query_concepts.is_empty())
@@ -297,6 +315,7 @@ impl EngramRetriever {
             load_active_thread_ids(conn, self.config.max_candidates)?
         };
 
+        record_phase("thinking", "prefilter", &mut phase_start);
         if candidate_ids.is_empty() {
             return Ok(Vec::new());
         }
@@ -313,12 +332,15 @@ impl EngramRetriever {
             focus_topics: Vec::new(),
             label_hint: None,
             bridge_connections: HashMap::new(),
+            normalizer: self.normalizer.clone(),
         };
+        record_phase("thinking", "load", &mut phase_start);
 
         // Phase 2: score with validators
         let mut scores: Vec<EngramScore> = candidates.iter()
             .filter_map(|t| self.score_thread_engram(t, &ctx))
             .collect();
+        record_phase("thinking", "score", &mut phase_start);
 
         scores.sort_by(|a, b| b.weighted_score.partial_cmp(&a.weighted_score)
             .unwrap_or(std::cmp::Ordering::Equal));
@@ -336,6 +358,7 @@ impl EngramRetriever {
                 })
             })
             .collect();
+        record_phase("thinking", "consensus", &mut phase_start);
 
         tracing::info!(
             candidates_scored = scores.len(),
@@ -407,8 +430,8 @@ impl EngramRetriever {
         query: &str,
         limit: usize,
     ) -> AiResult<Vec<Thread>> {
-        let query_topics = self.topic_index.extract_matching_topics(query);
-        let query_concepts = self.concept_index.extract_matching_concepts(query);
+        let query_topics = self.topic_index.extract_matching_topics(query, &|w| self.normalizer.normalize(w));
+        let query_concepts = self.concept_index.extract_matching_concepts(query, &|w| self.normalizer.normalize(w));
 
         let candidate_ids = if !query_topics.is_empty() || !query_concepts.is_empty() {
             let mut ids = self.topic_index.lookup(&query_topics);
@@ -435,6 +458,7 @@ impl EngramRetriever {
             focus_topics: Vec::new(),
             label_hint: None,
             bridge_connections: HashMap::new(),
+            normalizer: self.normalizer.clone(),
         };
 
         let mut scores: Vec<EngramScore> = candidates.iter()
@@ -472,6 +496,13 @@ impl EngramRetriever {
     }
 }
 
+/// Record the latency of a retrieval phase in ENGRAM_QUERY_DURATION, labelled
+/// by query kind and phase, and restart the phase timer.
+fn record_phase(query: &str, phase: &str, start: &mut Instant) {
+    metrics::ENGRAM_QUERY_DURATION.observe_since(&[("query", query), ("phase", phase)], *start);
+    *start = Instant::now();
+}
+
 /// Continuity expansion: for each injected thread, include its continuity parent
 /// and continuity children so the agent sees the full workflow chain.
 /// Neighbors are appended with WeakInject if not already present.
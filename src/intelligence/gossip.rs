@@ -6,26 +6,175 @@
 //!   3. Create bridges between conceptually related threads
 //!   4. Legacy topic overlap fallback for threads without concepts
 //!   5. Transitive propagation (A↔B + B↔C → A↔C)
+//!
+//! Optionally (gossip.llm_relation_enabled), new high-weight pairs are
+//! classified by the LLM; its verdict overrides the structural heuristics
+//! and is the only source of real `Contradicts` conflicts. Classification is
+//! a separate step (`relation_candidates` → `classify_relations` →
+//! `with_relation_verdicts`) so callers can run the LLM without holding the
+//! DB connection.
 
 use crate::{id_gen, time_utils};
 use crate::bridge::{BridgeStatus, BridgeType, ThinkBridge};
-use crate::config::GossipConfig;
+use crate::config::{GossipConfig, LocalModelSize};
+use crate::processing::relation_classifier::{self, RelationVerdict};
 use crate::thread::{OriginType, Thread, ThreadStatus};
 use crate::AiResult;
-use crate::storage::bridges::BridgeStorage;
+use crate::storage::bridges::{BridgeStorage, LLM_RELATION_CREATOR};
+use crate::processing::topic_normalizer::TopicNormalizer;
 use crate::storage::concept_index::ConceptIndex;
 use crate::storage::threads::ThreadStorage;
 use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// LLM verdicts by (thread_a, thread_b) concept-overlap pair.
+pub type RelationVerdicts = HashMap<(String, String), RelationVerdict>;
 
 pub struct Gossip {
     concept_index: ConceptIndex,
+    /// LLM verdicts for this cycle (empty = heuristics only).
+    verdicts: RelationVerdicts,
+}
+
+/// P1 pair that passed the connection and weight limits.
+struct ScoredPair<'t> {
+    /// Bridges of thread_a (per-shard dedup, previous LLM verdict).
+    existing_a: Vec<ThinkBridge>,
+    thread_a: Option<&'t Thread>,
+    thread_b: Option<&'t Thread>,
+    pair_weight: f64,
+    shard_weight: f64,
+}
+
+impl ScoredPair<'_> {
+    fn links(b: &ThinkBridge, thread_b: &str) -> bool {
+        b.source_id == thread_b || b.target_id == thread_b
+    }
+
+    fn has_shard(&self, thread_b: &str, concept: &str) -> Option<&ThinkBridge> {
+        self.existing_a
+            .iter()
+            .find(|b| Self::links(b, thread_b) && b.shard_concept.as_deref() == Some(concept))
+    }
+
+    /// High-weight pair with missing shards: worth an LLM verdict.
+    fn wants_llm(&self, thread_b: &str, shared_concepts: &[String], config: &GossipConfig) -> bool {
+        self.pair_weight >= config.llm_relation_min_weight
+            && shared_concepts.iter().any(|c| self.has_shard(thread_b, c).is_none())
+    }
+
+    fn previous_llm(&self, thread_b: &str) -> Option<&ThinkBridge> {
+        self.existing_a
+            .iter()
+            .find(|b| Self::links(b, thread_b) && b.created_by == LLM_RELATION_CREATOR)
+    }
 }
 
 impl Gossip {
     /// Build gossip engine with ConceptIndex loaded from DB.
     pub fn new(conn: &Connection) -> AiResult<Self> {
-        let concept_index = ConceptIndex::build_from_db(conn)?;
-        Ok(Self { concept_index })
+        let normalizer = TopicNormalizer::load(conn);
+        let concept_index = ConceptIndex::build_from_db(conn, &|c| normalizer.normalize(c))?;
+        Ok(Self { concept_index, verdicts: HashMap::new() })
+    }
+
+    /// Use these LLM verdicts (from `classify_relations`) in `run_cycle`.
+    pub fn with_relation_verdicts(mut self, verdicts: RelationVerdicts) -> Self {
+        self.verdicts = verdicts;
+        self
+    }
+
+    /// Pairs `run_cycle` would ask the LLM about: high-weight, missing shards,
+    /// no previous LLM verdict. At most `llm_relation_max_per_cycle`.
+    pub fn relation_candidates(&self, conn: &Connection, config: &GossipConfig) -> AiResult<Vec<(Thread, Thread)>> {
+        if !config.llm_relation_enabled || !config.concept_gossip_enabled {
+            return Ok(Vec::new());
+        }
+        let all_threads = Self::gossip_threads(conn)?;
+        let (max_per, _) = Self::dynamic_limits(all_threads.len(), config);
+        let mut pairs = Vec::new();
+        for overlap in self.concept_index.find_overlaps(config.concept_overlap_min_shared) {
+            if pairs.len() >= config.llm_relation_max_per_cycle {
+                break;
+            }
+            let Some(pair) = self.score_pair(conn, &all_threads, &overlap, config, max_per)? else {
+                continue;
+            };
+            let (_, thread_b, _, shared_concepts) = &overlap;
+            if !pair.wants_llm(thread_b, shared_concepts, config) || pair.previous_llm(thread_b).is_some() {
+                continue;
+            }
+            if let (Some(ta), Some(tb)) = (pair.thread_a, pair.thread_b) {
+                pairs.push((ta.clone(), tb.clone()));
+            }
+        }
+        Ok(pairs)
+    }
+
+    /// LLM verdicts for `pairs` (no DB access; failures are logged and skipped).
+    pub fn classify_relations(pairs: &[(Thread, Thread)], model: &LocalModelSize) -> RelationVerdicts {
+        let mut verdicts = HashMap::new();
+        for (a, b) in pairs {
+            match relation_classifier::classify(a, b, model) {
+                Ok(v) => {
+                    verdicts.insert((a.id.clone(), b.id.clone()), v);
+                }
+                Err(e) => tracing::warn!(error = %e, "Gossip v2 P1: relation classification failed"),
+            }
+        }
+        verdicts
+    }
+
+    /// Threads gossip bridges: all statuses (archived memories can bridge to
+    /// active topics when a subject resurfaces), except Command (Bash) threads,
+    /// which pollute the concept graph with build output noise (they still get
+    /// continuity links).
+    fn gossip_threads(conn: &Connection) -> AiResult<Vec<Thread>> {
+        Ok(ThreadStorage::list_all(conn)?
+            .into_iter()
+            .filter(|t| t.origin_type != OriginType::Command)
+            .collect())
+    }
+
+    /// Connection limits and weights of a P1 pair (None = skipped).
+    fn score_pair<'t>(
+        &self,
+        conn: &Connection,
+        all_threads: &'t [Thread],
+        overlap: &(String, String, usize, Vec<String>),
+        config: &GossipConfig,
+        max_per: usize,
+    ) -> AiResult<Option<ScoredPair<'t>>> {
+        let (thread_a, thread_b, shared_count, shared_concepts) = overlap;
+        let shared_count = *shared_count;
+        // Min bridges: skip pairs without enough shared concepts to form a valid connection
+        if shared_count < config.min_bridges {
+            return Ok(None);
+        }
+
+        // Check distinct-thread connection limits
+        if BridgeStorage::count_connected_threads(conn, thread_a)? >= max_per
+            || BridgeStorage::count_connected_threads(conn, thread_b)? >= max_per
+        {
+            return Ok(None);
+        }
+
+        let concept_count = shared_concepts.len().max(1);
+        let (_, overlap_ratio, _) = self.concept_index.overlap_score(thread_a, thread_b);
+        let pair_weight = Self::compute_weight(shared_count, overlap_ratio);
+        let shard_weight = pair_weight / concept_count as f64;
+        if shard_weight < config.concept_min_bridge_weight / concept_count as f64 {
+            return Ok(None);
+        }
+
+        Ok(Some(ScoredPair {
+            // Load existing bridges between this pair for per-shard dedup
+            existing_a: BridgeStorage::list_for_thread(conn, thread_a)?,
+            thread_a: all_threads.iter().find(|t| t.id == *thread_a),
+            thread_b: all_threads.iter().find(|t| t.id == *thread_b),
+            pair_weight,
+            shard_weight,
+        }))
     }
 
     /// Main gossip cycle — concept-based bridge discovery.
@@ -38,14 +187,7 @@ impl Gossip {
         // One-time v1 bridge migration (idempotent — 0 rows after first run)
         Self::migrate_v1_bridges(conn)?;
 
-        // Load all threads including archived — archived memories can bridge to active topics
-        // when a related subject resurfaces (enables reactivation/merge of long-term memory)
-        // Exclude Command (Bash) threads: they pollute the concept graph with build output noise.
-        // Command threads still get continuity links but no gossip bridges.
-        let all_threads: Vec<Thread> = ThreadStorage::list_all(conn)?
-            .into_iter()
-            .filter(|t| t.origin_type != OriginType::Command)
-            .collect();
+        let all_threads = Self::gossip_threads(conn)?;
         if all_threads.len() < 2 {
             tracing::debug!(threads = all_threads.len(), "Gossip v2 skipped: not enough threads");
             return Ok(0);
@@ -63,10 +205,10 @@ impl Gossip {
 
         let (max_per, _max_total) = Self::dynamic_limits(thread_count, config);
         let mut created = 0u32;
+        let mut llm_verdicts = 0usize;
 
         // Phase 1: Concept overlap discovery via inverted index
         let min_shared = config.concept_overlap_min_shared;
-        let min_weight = config.concept_min_bridge_weight;
 
         if indexed_count >= 2 && config.concept_gossip_enabled {
             let overlaps = self.concept_index.find_overlaps(min_shared);
@@ -76,55 +218,50 @@ impl Gossip {
                 "Gossip v2 P1: concept overlap pairs found"
             );
 
-            for (thread_a, thread_b, shared_count, shared_concepts) in &overlaps {
-                // Min bridges: skip pairs without enough shared concepts to form a valid connection
-                if *shared_count < config.min_bridges {
-                    continue;
-                }
-
-                // Check distinct-thread connection limits
-                let connections_a = BridgeStorage::count_connected_threads(conn, thread_a)?;
-                if connections_a >= max_per {
-                    continue;
-                }
-                let connections_b = BridgeStorage::count_connected_threads(conn, thread_b)?;
-                if connections_b >= max_per {
+            for overlap in &overlaps {
+                let Some(pair) = self.score_pair(conn, &all_threads, overlap, config, max_per)? else {
                     continue;
-                }
-
-                // Load existing bridges between this pair for per-shard dedup
-                let existing_a = BridgeStorage::list_for_thread(conn, thread_a)?;
+                };
+                let (thread_a, thread_b, shared_count, shared_concepts) = overlap;
+                let shard_weight = pair.shard_weight;
 
                 // Determine relation type once for all shards
-                let relation = all_threads
-                    .iter()
-                    .find(|t| t.id == *thread_a)
-                    .and_then(|ta| {
-                        all_threads.iter().find(|t| t.id == *thread_b).map(|tb| {
-                            Self::determine_relation(ta, tb, 0.5)
-                        })
-                    })
-                    .unwrap_or(BridgeType::Sibling);
-
-                let concept_count = shared_concepts.len().max(1);
-                let (_, overlap_ratio, _) =
-                    self.concept_index.overlap_score(thread_a, thread_b);
-                let shard_weight = Self::compute_weight(*shared_count, overlap_ratio)
-                    / concept_count as f64;
-
-                if shard_weight < min_weight / concept_count as f64 {
-                    continue;
+                let mut relation = match (pair.thread_a, pair.thread_b) {
+                    (Some(ta), Some(tb)) => Self::determine_relation(ta, tb, 0.5),
+                    _ => BridgeType::Sibling,
+                };
+
+                // LLM relation override: only for high-weight pairs with missing shards.
+                // A previous LLM verdict on the same pair is reused instead of re-asking.
+                let mut created_by = "gossip_v2".to_string();
+                let mut confidence = shard_weight;
+                let mut llm_reason: Option<String> = None;
+                if pair.wants_llm(thread_b, shared_concepts, config) {
+                    if let Some(prev) = pair.previous_llm(thread_b) {
+                        relation = prev.relation_type.clone();
+                        confidence = prev.confidence;
+                        llm_reason = prev.reason.split_once(" | ").map(|(_, r)| r.to_string());
+                        created_by = LLM_RELATION_CREATOR.to_string();
+                    } else if let Some(v) = self.verdicts.get(&(thread_a.clone(), thread_b.clone())) {
+                        llm_verdicts += 1;
+                        if v.confidence >= config.llm_relation_min_confidence {
+                            relation = v.relation.clone();
+                            confidence = v.confidence;
+                            llm_reason = Some(v.reason.clone());
+                            created_by = LLM_RELATION_CREATOR.to_string();
+                        } else {
+                            tracing::debug!(
+                                confidence = v.confidence,
+                                "Gossip v2 P1: LLM verdict below confidence, keeping heuristic"
+                            );
+                        }
+                    }
                 }
 
                 // Per-shard: one bridge per shared concept
                 for concept in shared_concepts {
                     // Check if shard-bridge already exists
-                    let existing_shard = existing_a.iter().find(|b| {
-                        (b.source_id == *thread_b || b.target_id == *thread_b)
-                            && b.shard_concept.as_deref() == Some(concept.as_str())
-                    });
-
-                    if let Some(existing) = existing_shard {
+                    if let Some(existing) = pair.has_shard(thread_b, concept) {
                         // Reinforce if gossip bridge and shard weight improved
                         if existing.created_by.starts_with("gossip") && shard_weight > existing.weight {
                             BridgeStorage::reinforce_weight(conn, &existing.id, shard_weight)?;
@@ -137,14 +274,17 @@ impl Gossip {
                         source_id: thread_a.clone(),
                         target_id: thread_b.clone(),
                         relation_type: relation.clone(),
-                        reason: format!("gossip:shard({})", concept),
+                        reason: match llm_reason {
+                            Some(ref r) if !r.is_empty() => format!("gossip:shard({}) | {}", concept, r),
+                            _ => format!("gossip:shard({})", concept),
+                        },
                         shared_concepts: vec![concept.clone()],
                         weight: shard_weight,
-                        confidence: shard_weight,
+                        confidence,
                         status: BridgeStatus::Active,
                         propagated_from: None,
                         propagation_depth: 0,
-                        created_by: "gossip_v2".to_string(),
+                        created_by: created_by.clone(),
                         use_count: 0,
                         created_at: time_utils::now(),
                         last_reinforced: None,
@@ -185,6 +325,7 @@ impl Gossip {
 
         tracing::info!(
             bridges_created = created,
+            llm_verdicts = llm_verdicts,
             "Gossip v2 cycle complete"
         );
 
@@ -193,7 +334,7 @@ impl Gossip {
 
     /// Compute bridge weight from concept overlap metrics.
     /// weight = overlap_ratio × GOSSIP_OVERLAP_WEIGHT + richness × GOSSIP_RICHNESS_WEIGHT
-    fn compute_weight(shared_count: usize, overlap_ratio: f64) -> f64 {
+    pub(crate) fn compute_weight(shared_count: usize, overlap_ratio: f64) -> f64 {
         use crate::constants::{GOSSIP_OVERLAP_WEIGHT, GOSSIP_RICHNESS_WEIGHT, GOSSIP_RICHNESS_NORMALIZATION};
         let richness = (shared_count as f64 / GOSSIP_RICHNESS_NORMALIZATION).min(1.0);
         overlap_ratio * GOSSIP_OVERLAP_WEIGHT + richness * GOSSIP_RICHNESS_WEIGHT
@@ -286,6 +427,7 @@ impl Gossip {
     /// Cascade (most specific → fallback): ChildOf → Extends (structural) →
     /// Contradicts (split siblings) → Sibling (same parent) → Replaces
     /// (active/inactive) → Depends (concept superset) → Extends (heuristic) → Sibling.
+    /// When LLM classification is enabled, a confident verdict overrides this.
     fn determine_relation(source: &Thread, target: &Thread, weight: f64) -> BridgeType {
         // 1. Structural: parent-child
         if source.parent_id.as_deref() == Some(&*target.id) {
@@ -1,10 +1,15 @@
 pub mod archiver;
+pub mod cross_gossip;
+pub mod decay_strategy;
 pub mod decayer;
 pub mod engram_retriever;
 pub mod gossip;
 pub mod memory_retriever;
 pub mod metadata_utils;
 pub mod reactivation_decider;
+pub mod retention;
+pub mod spreading_activation;
 pub mod synthesis;
 pub mod thread_manager;
+pub mod topic_alias_suggester;
 pub mod validators;
@@ -0,0 +1,284 @@
+//! Retention -- declarative lifecycle rules per label / origin type.
+//!
+//! Rules live in `DecayConfig.retention_rules` and are evaluated in order
+//! (first match wins). A rule can:
+//!   - protect threads from decay, suspension and archival (`never_decay`)
+//!   - archive after N hours of inactivity (`archive_after_hours`)
+//!   - delete after N hours of inactivity (`delete_after_hours`)
+//!
+//! Evaluated by the prune loop after the Archiver. In dry-run mode nothing is
+//! changed; per-rule counts are still computed and persisted for ai_status/GUI.
+
+use std::path::Path;
+
+use crate::config::{DecayConfig, RetentionRule};
+use crate::storage::bridges::BridgeStorage;
+use crate::storage::database::NestedTx;
+use crate::storage::threads::ThreadStorage;
+use crate::thread::{Thread, ThreadStatus};
+use crate::{time_utils, AiResult};
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+const REPORT_FILE: &str = "retention_report.json";
+
+/// Per-rule counts for one evaluation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleCount {
+    pub rule: String,
+    pub matched: u32,
+    pub protected: u32,
+    pub archived: u32,
+    pub deleted: u32,
+}
+
+/// Result of the last retention evaluation (persisted in the agent data dir).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub evaluated_at: String,
+    pub rules: Vec<RuleCount>,
+    /// Ids changed by this pass (empty in dry-run). Not persisted: the audit
+    /// log records them.
+    #[serde(skip)]
+    pub deleted_thread_ids: Vec<String>,
+    #[serde(skip)]
+    pub archived_thread_ids: Vec<String>,
+    /// Bridges deleted along with their thread.
+    #[serde(skip)]
+    pub deleted_bridge_ids: Vec<String>,
+}
+
+impl RetentionReport {
+    /// Every thread and bridge id this pass touched.
+    pub fn affected_ids(&self) -> Vec<String> {
+        self.deleted_thread_ids
+            .iter()
+            .chain(&self.archived_thread_ids)
+            .chain(&self.deleted_bridge_ids)
+            .cloned()
+            .collect()
+    }
+}
+
+impl RetentionReport {
+    pub fn load(agent_data_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(agent_data_dir.join(REPORT_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, agent_data_dir: &Path) {
+        if let Err(e) = std::fs::create_dir_all(agent_data_dir) {
+            tracing::warn!(error = %e, "Failed to create agent data dir for retention report");
+            return;
+        }
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(agent_data_dir.join(REPORT_FILE), json) {
+                    tracing::warn!(error = %e, "Failed to write retention report");
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to serialize retention report"),
+        }
+    }
+}
+
+pub struct Retention;
+
+impl Retention {
+    /// First rule matching the thread, if any.
+    pub fn matching_rule<'a>(thread: &Thread, rules: &'a [RetentionRule]) -> Option<&'a RetentionRule> {
+        rules.iter().find(|r| rule_matches(r, thread))
+    }
+
+    /// True if a `never_decay` rule covers this thread.
+    pub fn is_protected(thread: &Thread, cfg: &DecayConfig) -> bool {
+        Self::matching_rule(thread, &cfg.retention_rules)
+            .map(|r| r.never_decay)
+            .unwrap_or(false)
+    }
+
+    /// True if a rule owns archival timing for this thread (protected or custom archive delay).
+    pub fn overrides_archive(thread: &Thread, cfg: &DecayConfig) -> bool {
+        Self::matching_rule(thread, &cfg.retention_rules)
+            .map(|r| r.never_decay || r.archive_after_hours.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Evaluate all rules. With `dry_run`, only counts are produced.
+    pub fn run(conn: &Connection, cfg: &DecayConfig, dry_run: bool) -> AiResult<RetentionReport> {
+        let mut report = RetentionReport {
+            dry_run,
+            evaluated_at: time_utils::now().to_rfc3339(),
+            ..Default::default()
+        };
+        let mut counts: Vec<RuleCount> = cfg.retention_rules.iter()
+            .map(|r| RuleCount { rule: r.name.clone(), ..Default::default() })
+            .collect();
+
+        if !cfg.retention_rules.is_empty() {
+            let now = Utc::now();
+            for thread in ThreadStorage::list_all(conn)? {
+                // Shared and pinned threads are never touched by retention
+                if thread.tags.iter().any(|t| t == "__shared__" || t == "__pin__") {
+                    continue;
+                }
+                let Some(idx) = cfg.retention_rules.iter().position(|r| rule_matches(r, &thread)) else {
+                    continue;
+                };
+                let rule = &cfg.retention_rules[idx];
+                let count = &mut counts[idx];
+                count.matched += 1;
+
+                if rule.never_decay {
+                    count.protected += 1;
+                    continue;
+                }
+
+                let inactive_hours = (now - thread.last_active).num_minutes() as f64 / 60.0;
+
+                if rule.delete_after_hours.is_some_and(|h| inactive_hours >= h) {
+                    count.deleted += 1;
+                    if !dry_run {
+                        // Bridges, messages and thread go together or not at all
+                        let tx = NestedTx::begin(conn)?;
+                        let bridge_ids: Vec<String> =
+                            BridgeStorage::list_for_thread(&tx, &thread.id)?.into_iter().map(|b| b.id).collect();
+                        BridgeStorage::delete_for_thread(&tx, &thread.id)?;
+                        ThreadStorage::delete_messages(&tx, &thread.id)?;
+                        ThreadStorage::delete(&tx, &thread.id)?;
+                        tx.commit()?;
+                        report.deleted_bridge_ids.extend(bridge_ids);
+                        tracing::debug!(thread_id = %thread.id, rule = %rule.name, "Retention: thread deleted");
+                        report.deleted_thread_ids.push(thread.id.clone());
+                    }
+                    continue;
+                }
+
+                if thread.status != ThreadStatus::Archived
+                    && rule.archive_after_hours.is_some_and(|h| inactive_hours >= h)
+                {
+                    count.archived += 1;
+                    if !dry_run {
+                        ThreadStorage::update_status(conn, &thread.id, ThreadStatus::Archived)?;
+                        tracing::debug!(thread_id = %thread.id, rule = %rule.name, "Retention: thread archived");
+                        report.archived_thread_ids.push(thread.id.clone());
+                    }
+                }
+            }
+        }
+
+        let archived: u32 = counts.iter().map(|c| c.archived).sum();
+        let deleted: u32 = counts.iter().map(|c| c.deleted).sum();
+        if archived + deleted > 0 {
+            tracing::info!(archived, deleted, dry_run, "Retention cycle complete");
+        }
+
+        report.rules = counts;
+        Ok(report)
+    }
+}
+
+fn rule_matches(rule: &RetentionRule, thread: &Thread) -> bool {
+    rule.origin.as_deref().is_none_or(|o| o == thread.origin_type.as_str())
+        && rule.label.as_deref().is_none_or(|l| thread.labels.iter().any(|tl| tl == l))
+        && rule.max_weight.is_none_or(|w| thread.weight < w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::decayer::Decayer;
+    use crate::test_helpers::*;
+    use crate::thread::OriginType;
+
+    fn cfg_with(rules: Vec<RetentionRule>) -> DecayConfig {
+        DecayConfig { retention_rules: rules, ..DecayConfig::default() }
+    }
+
+    fn fetch_ttl() -> RetentionRule {
+        RetentionRule {
+            name: "fetch-ttl".into(),
+            origin: Some("fetch".into()),
+            archive_after_hours: Some(24.0),
+            delete_after_hours: Some(168.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retention_archives_and_deletes_by_origin() {
+        let conn = setup_agent_db();
+        let cfg = cfg_with(vec![fetch_ttl()]);
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("old")
+            .origin_type(OriginType::Fetch).last_active(days_ago(10)).build()).unwrap();
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("stale")
+            .origin_type(OriginType::Fetch).last_active(days_ago(2)).build()).unwrap();
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("prompt")
+            .last_active(days_ago(10)).build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b-old").source_id("old").target_id("prompt").build()).unwrap();
+
+        let report = Retention::run(&conn, &cfg, false).unwrap();
+        assert_eq!(report.rules[0].matched, 2);
+        assert_eq!(report.rules[0].deleted, 1);
+        assert_eq!(report.rules[0].archived, 1);
+
+        assert!(ThreadStorage::get(&conn, "old").unwrap().is_none());
+        assert_eq!(report.affected_ids(), vec!["old", "stale", "b-old"]);
+        assert_eq!(ThreadStorage::get(&conn, "stale").unwrap().unwrap().status, ThreadStatus::Archived);
+        assert_eq!(ThreadStorage::get(&conn, "prompt").unwrap().unwrap().status, ThreadStatus::Active);
+    }
+
+    #[test]
+    fn test_retention_delete_is_atomic_per_thread() {
+        let conn = setup_agent_db();
+        let cfg = cfg_with(vec![fetch_ttl()]);
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("old")
+            .origin_type(OriginType::Fetch).last_active(days_ago(10)).build()).unwrap();
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("other").build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b-old").source_id("old").target_id("other").build()).unwrap();
+        conn.execute_batch(
+            "CREATE TRIGGER keep_old BEFORE DELETE ON threads WHEN OLD.id = 'old'
+             BEGIN SELECT RAISE(ABORT, 'locked'); END;",
+        ).unwrap();
+
+        assert!(Retention::run(&conn, &cfg, false).is_err());
+        assert!(BridgeStorage::get(&conn, "b-old").unwrap().is_some(), "Bridge delete rolled back");
+        assert!(ThreadStorage::get(&conn, "old").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_retention_dry_run_changes_nothing() {
+        let conn = setup_agent_db();
+        let cfg = cfg_with(vec![fetch_ttl()]);
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("old")
+            .origin_type(OriginType::Fetch).last_active(days_ago(10)).build()).unwrap();
+
+        let report = Retention::run(&conn, &cfg, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.rules[0].deleted, 1);
+        assert!(ThreadStorage::get(&conn, "old").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_never_decay_label_skips_decayer() {
+        let conn = setup_agent_db();
+        let cfg = cfg_with(vec![RetentionRule {
+            name: "decisions".into(),
+            label: Some("decision".into()),
+            never_decay: true,
+            ..Default::default()
+        }]);
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("d").weight(0.8)
+            .labels(vec!["decision"]).last_active(days_ago(30)).build()).unwrap();
+
+        Decayer::decay_active(&conn, &cfg).unwrap();
+        let got = ThreadStorage::get(&conn, "d").unwrap().unwrap();
+        assert_eq!(got.weight, 0.8);
+        assert_eq!(got.status, ThreadStatus::Active);
+
+        let report = Retention::run(&conn, &cfg, false).unwrap();
+        assert_eq!(report.rules[0].protected, 1);
+    }
+}
@@ -0,0 +1,293 @@
+//! Spreading Activation — multi-hop recall over bridges and continuity edges.
+//!
+//! Seeds come from direct query hits (Engram search). Activation then flows
+//! outward along ThinkBridge edges (weight × confidence × relation factor)
+//! and continuity edges (parent ↔ child), decaying at every hop.
+//!
+//! Used by ai_recall to surface threads reached only indirectly, e.g.
+//! "the design decision behind the bug behind this file".
+
+use std::collections::{HashMap, HashSet};
+
+use crate::bridge::{BridgeStatus, BridgeType};
+use crate::config::SpreadingActivationConfig;
+use crate::storage::bridges::BridgeStorage;
+use crate::storage::threads::ThreadStorage;
+use crate::AiResult;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// One edge traversed while spreading activation.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivationStep {
+    pub from: String,
+    pub to: String,
+    /// "bridge" or "continuity".
+    pub via: &'static str,
+    /// Bridge relation type, or "parent"/"child" for continuity edges.
+    pub relation: String,
+    /// Edge factor applied (before per-hop decay).
+    pub factor: f64,
+}
+
+/// A thread reached indirectly from the seed set.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivatedThread {
+    pub thread_id: String,
+    pub activation: f64,
+    pub hops: u32,
+    /// Seed thread the activation originated from.
+    pub seed_id: String,
+    pub path: Vec<ActivationStep>,
+}
+
+pub struct SpreadingActivation;
+
+impl SpreadingActivation {
+    /// Propagate activation from `seeds` (thread_id, initial activation).
+    ///
+    /// Returns only non-seed threads whose activation stayed above
+    /// `cfg.min_activation`, sorted by activation (highest first) and capped
+    /// at `cfg.max_results`. Each thread keeps its strongest path only.
+    /// A seed reached through a stronger path than its own initial
+    /// activation is still a seed: it is dropped before the cap applies.
+    pub fn spread(
+        conn: &Connection,
+        seeds: &[(String, f64)],
+        max_hops: u32,
+        cfg: &SpreadingActivationConfig,
+    ) -> AiResult<Vec<ActivatedThread>> {
+        let mut best: HashMap<String, ActivatedThread> = HashMap::new();
+        for (id, act) in seeds {
+            best.insert(id.clone(), ActivatedThread {
+                thread_id: id.clone(),
+                activation: *act,
+                hops: 0,
+                seed_id: id.clone(),
+                path: Vec::new(),
+            });
+        }
+
+        let mut frontier: Vec<String> = seeds.iter().map(|(id, _)| id.clone()).collect();
+
+        for hop in 1..=max_hops {
+            let mut next_frontier: Vec<String> = Vec::new();
+
+            for node_id in &frontier {
+                let node = match best.get(node_id) {
+                    Some(n) => n.clone(),
+                    None => continue,
+                };
+
+                for (neighbor, step) in neighbors(conn, node_id, cfg)? {
+                    let activation = node.activation * step.factor * cfg.hop_decay;
+                    if activation < cfg.min_activation {
+                        continue;
+                    }
+                    let improves = best.get(&neighbor)
+                        .map(|existing| activation > existing.activation)
+                        .unwrap_or(true);
+                    if !improves {
+                        continue;
+                    }
+
+                    let mut path = node.path.clone();
+                    path.push(step);
+                    best.insert(neighbor.clone(), ActivatedThread {
+                        thread_id: neighbor.clone(),
+                        activation,
+                        hops: hop,
+                        seed_id: node.seed_id.clone(),
+                        path,
+                    });
+                    if !next_frontier.contains(&neighbor) {
+                        next_frontier.push(neighbor);
+                    }
+                }
+            }
+
+            if next_frontier.is_empty() {
+                break;
+            }
+            frontier = next_frontier;
+        }
+
+        let seed_ids: HashSet<&str> = seeds.iter().map(|(id, _)| id.as_str()).collect();
+        let mut reached: Vec<ActivatedThread> = best.into_values()
+            .filter(|a| a.hops > 0 && !seed_ids.contains(a.thread_id.as_str()))
+            .collect();
+        reached.sort_by(|a, b| b.activation.partial_cmp(&a.activation)
+            .unwrap_or(std::cmp::Ordering::Equal));
+        reached.truncate(cfg.max_results);
+
+        tracing::debug!(
+            seeds = seeds.len(),
+            reached = reached.len(),
+            max_hops,
+            "Spreading activation complete"
+        );
+
+        Ok(reached)
+    }
+}
+
+/// Relation-specific propagation factor.
+pub fn relation_factor(relation: &BridgeType, cfg: &SpreadingActivationConfig) -> f64 {
+    match relation {
+        BridgeType::Extends => cfg.extends_factor,
+        BridgeType::Depends => cfg.depends_factor,
+        BridgeType::Replaces => cfg.replaces_factor,
+        BridgeType::ChildOf => cfg.child_of_factor,
+        BridgeType::Sibling => cfg.sibling_factor,
+        BridgeType::Contradicts => cfg.contradicts_factor,
+    }
+}
+
+/// Collect outgoing edges of a thread: live bridges + continuity parent/children.
+/// Multiple shard-bridges between the same pair keep only the strongest.
+fn neighbors(
+    conn: &Connection,
+    thread_id: &str,
+    cfg: &SpreadingActivationConfig,
+) -> AiResult<Vec<(String, ActivationStep)>> {
+    let mut out: HashMap<String, ActivationStep> = HashMap::new();
+
+    for b in BridgeStorage::list_for_thread(conn, thread_id)? {
+        if b.status == BridgeStatus::Invalid {
+            continue;
+        }
+        let other = if b.source_id == thread_id { b.target_id } else { b.source_id };
+        if other == thread_id {
+            continue;
+        }
+        // Weak bridges propagate at 50% — same treatment as V4 graph connectivity
+        let status_factor = if b.status == BridgeStatus::Weak { 0.5 } else { 1.0 };
+        let factor = (b.weight * b.confidence * relation_factor(&b.relation_type, cfg) * status_factor)
+            .clamp(0.0, 1.0);
+        keep_strongest(&mut out, other.clone(), ActivationStep {
+            from: thread_id.to_string(),
+            to: other,
+            via: "bridge",
+            relation: b.relation_type.as_str().to_string(),
+            factor,
+        });
+    }
+
+    if cfg.continuity_factor > 0.0 {
+        if let Some(thread) = ThreadStorage::get(conn, thread_id)? {
+            if let Some(parent_id) = thread.continuity_parent_id {
+                keep_strongest(&mut out, parent_id.clone(), ActivationStep {
+                    from: thread_id.to_string(),
+                    to: parent_id,
+                    via: "continuity",
+                    relation: "parent".to_string(),
+                    factor: cfg.continuity_factor,
+                });
+            }
+        }
+        for child_id in ThreadStorage::continuity_child_ids(conn, thread_id)? {
+            keep_strongest(&mut out, child_id.clone(), ActivationStep {
+                from: thread_id.to_string(),
+                to: child_id,
+                via: "continuity",
+                relation: "child".to_string(),
+                factor: cfg.continuity_factor,
+            });
+        }
+    }
+
+    Ok(out.into_iter().collect())
+}
+
+fn keep_strongest(out: &mut HashMap<String, ActivationStep>, id: String, step: ActivationStep) {
+    match out.get(&id) {
+        Some(existing) if existing.factor >= step.factor => {}
+        _ => {
+            out.insert(id, step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::*;
+
+    fn cfg() -> SpreadingActivationConfig {
+        SpreadingActivationConfig::default()
+    }
+
+    #[test]
+    fn test_spread_follows_bridges_two_hops() {
+        let conn = setup_agent_db();
+        for id in ["file", "bug", "decision"] {
+            ThreadStorage::insert(&conn, &ThreadBuilder::new().id(id).build()).unwrap();
+        }
+        let b1 = BridgeBuilder::new().id("b1").source_id("file").target_id("bug")
+            .relation_type(BridgeType::Depends).weight(0.9).confidence(0.9).build();
+        let b2 = BridgeBuilder::new().id("b2").source_id("bug").target_id("decision")
+            .relation_type(BridgeType::Extends).weight(0.9).confidence(0.9).build();
+        BridgeStorage::insert(&conn, &b1).unwrap();
+        BridgeStorage::insert(&conn, &b2).unwrap();
+
+        let reached = SpreadingActivation::spread(&conn, &[("file".into(), 1.0)], 2, &cfg()).unwrap();
+
+        let decision = reached.iter().find(|a| a.thread_id == "decision").expect("decision reached");
+        assert_eq!(decision.hops, 2);
+        assert_eq!(decision.seed_id, "file");
+        assert_eq!(decision.path.len(), 2);
+        assert_eq!(decision.path[0].to, "bug");
+        assert!(!reached.iter().any(|a| a.thread_id == "file"), "seeds are not returned");
+    }
+
+    #[test]
+    fn test_spread_respects_max_hops() {
+        let conn = setup_agent_db();
+        for id in ["a", "b", "c"] {
+            ThreadStorage::insert(&conn, &ThreadBuilder::new().id(id).build()).unwrap();
+        }
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b1").source_id("a").target_id("b")
+            .weight(1.0).confidence(1.0).build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b2").source_id("b").target_id("c")
+            .weight(1.0).confidence(1.0).build()).unwrap();
+
+        let reached = SpreadingActivation::spread(&conn, &[("a".into(), 1.0)], 1, &cfg()).unwrap();
+        assert!(reached.iter().any(|a| a.thread_id == "b"));
+        assert!(!reached.iter().any(|a| a.thread_id == "c"));
+    }
+
+    #[test]
+    fn test_spread_follows_continuity_and_skips_invalid_bridges() {
+        let conn = setup_agent_db();
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("parent").build()).unwrap();
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("child").continuity_parent_id("parent").build()).unwrap();
+        ThreadStorage::insert(&conn, &ThreadBuilder::new().id("dead").build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b1").source_id("child").target_id("dead")
+            .weight(1.0).confidence(1.0).status(BridgeStatus::Invalid).build()).unwrap();
+
+        let reached = SpreadingActivation::spread(&conn, &[("child".into(), 1.0)], 2, &cfg()).unwrap();
+        let parent = reached.iter().find(|a| a.thread_id == "parent").expect("parent reached");
+        assert_eq!(parent.path[0].via, "continuity");
+        assert!(!reached.iter().any(|a| a.thread_id == "dead"));
+    }
+
+    #[test]
+    fn test_spread_drops_seeds_before_capping() {
+        let conn = setup_agent_db();
+        for id in ["top", "low", "other"] {
+            ThreadStorage::insert(&conn, &ThreadBuilder::new().id(id).build()).unwrap();
+        }
+        // "low" is a weaker seed reachable from "top" with a higher activation
+        // than its own; it must not take the only result slot.
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b1").source_id("top").target_id("low")
+            .relation_type(BridgeType::Extends).weight(1.0).confidence(1.0).build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("b2").source_id("top").target_id("other")
+            .relation_type(BridgeType::Sibling).weight(1.0).confidence(1.0).build()).unwrap();
+
+        let cfg = SpreadingActivationConfig { max_results: 1, ..cfg() };
+        let seeds = [("top".to_string(), 1.0), ("low".to_string(), 0.5)];
+        let reached = SpreadingActivation::spread(&conn, &seeds, 1, &cfg).unwrap();
+        assert_eq!(reached.len(), 1);
+        assert_eq!(reached[0].thread_id, "other");
+    }
+}
@@ -14,6 +14,7 @@ use crate::intelligence::gossip::Gossip;
 use crate::intelligence::metadata_utils::{self, MAX_TOPICS, MAX_LABELS};
 use crate::processing::embeddings::EmbeddingManager;
 use crate::processing::extractor::{Extraction, ExtractionMode};
+use crate::processing::topic_normalizer::TopicNormalizer;
 use crate::storage::bridges::BridgeStorage;
 use crate::storage::concept_index::find_threads_sharing_concepts_db;
 use crate::storage::threads::ThreadStorage;
@@ -76,6 +77,7 @@ impl ThreadManager {
     /// Returns the thread_id of the created/updated thread.
     pub fn process_input(
         conn: &Connection,
+        normalizer: &TopicNormalizer,
         extraction: &Extraction,
         content: &str,
         source_type: &str,
@@ -105,7 +107,7 @@ impl ThreadManager {
         tracing::info!(action = "NewThread", "Action decided");
         Self::ensure_capacity(conn, thread_quota, embeddings, &guardian.thread_matching)?;
         let id = Self::create_thread(
-            conn, extraction, content, source_type, None, file_path, embed_mode,
+            conn, normalizer, extraction, content, source_type, None, file_path, embed_mode,
             continuity_previous_id, coherence_score,
         )?;
         // Backfill continuity_to on previous thread's last message
@@ -114,7 +116,7 @@ impl ThreadManager {
         }
         // Thinkbridges: immediate concept connections
         // Skip for tool threads (file/command/task/fetch) — continuity-only, no gossip
-        let normalized = normalize_concepts(&extraction.concepts);
+        let normalized = normalizer.dedup_concepts(&extraction.concepts);
         let is_tool_thread = matches!(source_type,
             "command" | "Command" | "Bash"
             | "Read" | "file_read" | "Write" | "file_write" | "Edit"
@@ -124,7 +126,7 @@ impl ThreadManager {
         let thinkbridges = if is_tool_thread {
             0
         } else {
-            Self::create_thinkbridges(conn, &id, &normalized, &guardian.gossip)?
+            Self::create_thinkbridges(conn, normalizer, &id, &normalized, &guardian.gossip)?
         };
         if thinkbridges > 0 {
             tracing::info!(thread_id = %id, bridges = thinkbridges, "Thinkbridges");
@@ -135,6 +137,7 @@ impl ThreadManager {
     /// Create a new thread from extraction.
     pub fn create_thread(
         conn: &Connection,
+        normalizer: &TopicNormalizer,
         extraction: &Extraction,
         content: &str,
         source_type: &str,
@@ -192,7 +195,7 @@ impl ThreadManager {
             child_ids: vec![],
             summary: Some(extraction.summary.clone()),
             topics: {
-                let mut t = extraction.subjects.clone();
+                let mut t = normalizer.dedup_topics(&extraction.subjects);
                 t.truncate(MAX_TOPICS);
                 t
             },
@@ -202,7 +205,7 @@ impl ThreadManager {
                 l.truncate(MAX_LABELS);
                 l
             },
-            concepts: normalize_concepts(&extraction.concepts),
+            concepts: normalizer.dedup_concepts(&extraction.concepts),
             embedding,
             relevance_score,
             ratings: vec![],
@@ -313,6 +316,7 @@ impl ThreadManager {
     /// Update an existing thread with new content.
     pub fn update_thread(
         conn: &Connection,
+        normalizer: &TopicNormalizer,
         thread_id: &str,
         extraction: &Extraction,
         content: &str,
@@ -374,11 +378,9 @@ impl ThreadManager {
         // Boost weight
         thread.weight = (thread.weight + THREAD_USE_BOOST).min(1.0);
 
-        // Merge topics (case-insensitive dedup + cap)
-        for topic in &extraction.subjects {
-            thread.topics.push(topic.clone());
-        }
-        thread.topics = metadata_utils::dedup_case_insensitive(thread.topics.clone());
+        // Merge topics (dedup by canonical key + cap)
+        thread.topics.extend(extraction.subjects.iter().cloned());
+        thread.topics = normalizer.dedup_topics(&thread.topics);
         thread.topics.truncate(MAX_TOPICS);
 
         // Merge labels (case-insensitive dedup + cap, filter blocked)
@@ -578,6 +580,7 @@ impl ThreadManager {
     /// Returns Some(thread_id) on success, None if thread not found.
     pub fn add_changelog(
         conn: &Connection,
+        normalizer: &TopicNormalizer,
         thread_id: &str,
         file_path: &str,
         source_type: &str,
@@ -737,12 +740,9 @@ impl ThreadManager {
             // Summary: always latest (most relevant for recall)
             thread.summary = Some(ext.summary.clone());
 
-            // Topics: union + dedup, capped
-            for topic in &ext.subjects {
-                if !thread.topics.iter().any(|t| t.eq_ignore_ascii_case(topic)) {
-                    thread.topics.push(topic.clone());
-                }
-            }
+            // Topics: union + dedup by canonical key, capped
+            thread.topics.extend(ext.subjects.iter().cloned());
+            thread.topics = normalizer.dedup_topics(&thread.topics);
             thread.topics.truncate(MAX_TOPICS);
 
             // Labels: union + dedup, capped
@@ -758,7 +758,7 @@ impl ThreadManager {
             let old_concept_count = thread.concepts.len();
             let mut all_concepts = thread.concepts.clone();
             all_concepts.extend(ext.concepts.clone());
-            thread.concepts = normalize_concepts(&all_concepts);
+            thread.concepts = normalizer.dedup_concepts(&all_concepts);
 
             // Re-embed thread with enriched metadata
             let embed_text = build_enriched_embed_text_from_thread(&thread);
@@ -772,7 +772,7 @@ impl ThreadManager {
                 .collect();
             if !new_concepts.is_empty() {
                 let bridges = Self::create_thinkbridges(
-                    conn, thread_id, &new_concepts, &guardian.gossip,
+                    conn, normalizer, thread_id, &new_concepts, &guardian.gossip,
                 )?;
                 if bridges > 0 {
                     tracing::info!(
@@ -824,6 +824,7 @@ impl ThreadManager {
     /// Returns the number of bridges created.
     pub fn create_thinkbridges(
         conn: &Connection,
+        normalizer: &TopicNormalizer,
         thread_id: &str,
         concepts: &[String],
         gossip_config: &GossipConfig,
@@ -832,7 +833,9 @@ impl ThreadManager {
             return Ok(0);
         }
 
-        let candidates = find_threads_sharing_concepts_db(conn, concepts, Some(thread_id))?;
+        let candidates = find_threads_sharing_concepts_db(
+            conn, concepts, Some(thread_id), &|c| normalizer.normalize(c),
+        )?;
         if candidates.is_empty() {
             return Ok(0);
         }
@@ -920,6 +923,7 @@ impl ThreadManager {
     /// Then re-create with current concepts. Returns (deleted, created).
     pub fn refresh_thinkbridges(
         conn: &Connection,
+        normalizer: &TopicNormalizer,
         thread_id: &str,
         concepts: &[String],
         gossip_config: &GossipConfig,
@@ -936,7 +940,7 @@ impl ThreadManager {
             );
         }
 
-        let created = Self::create_thinkbridges(conn, thread_id, concepts, gossip_config)?;
+        let created = Self::create_thinkbridges(conn, normalizer, thread_id, concepts, gossip_config)?;
         Ok((deleted, created))
     }
 }
@@ -973,7 +977,7 @@ mod tests {
         ThreadStorage::insert(&conn, &thread).unwrap();
 
         let result = ThreadManager::add_changelog(
-            &conn, "cl-active", "src/config.rs", "Read", "fn main() {}\n", None,
+            &conn, &TopicNormalizer::default(), "cl-active", "src/config.rs", "Read", "fn main() {}\n", None,
             &GuardianConfig::default(),
         ).unwrap();
 
@@ -1005,7 +1009,7 @@ mod tests {
         ThreadStorage::insert(&conn, &thread).unwrap();
 
         let result = ThreadManager::add_changelog(
-            &conn, "cl-suspended", "src/lib.rs", "Write", "pub mod config;\n", None,
+            &conn, &TopicNormalizer::default(), "cl-suspended", "src/lib.rs", "Write", "pub mod config;\n", None,
             &GuardianConfig::default(),
         ).unwrap();
 
@@ -1029,7 +1033,7 @@ mod tests {
         ThreadStorage::insert(&conn, &thread).unwrap();
 
         let result = ThreadManager::add_changelog(
-            &conn, "cl-archived", "src/old.rs", "Read", "// old code\n", None,
+            &conn, &TopicNormalizer::default(), "cl-archived", "src/old.rs", "Read", "// old code\n", None,
             &GuardianConfig::default(),
         ).unwrap();
 
@@ -1052,12 +1056,12 @@ mod tests {
         let content = "fn stable() { 42 }\n";
 
         // First read — always "changed" (no previous hash)
-        ThreadManager::add_changelog(&conn, "cl-unchanged", "src/stable.rs", "Read", content, None, &GuardianConfig::default()).unwrap();
+        ThreadManager::add_changelog(&conn, &TopicNormalizer::default(), "cl-unchanged", "src/stable.rs", "Read", content, None, &GuardianConfig::default()).unwrap();
         let msgs = ThreadStorage::get_messages(&conn, "cl-unchanged").unwrap();
         assert_eq!(msgs[0].metadata["changed"], true);
 
         // Second read with same content — skip total (no new message)
-        let result = ThreadManager::add_changelog(&conn, "cl-unchanged", "src/stable.rs", "Read", content, None, &GuardianConfig::default()).unwrap();
+        let result = ThreadManager::add_changelog(&conn, &TopicNormalizer::default(), "cl-unchanged", "src/stable.rs", "Read", content, None, &GuardianConfig::default()).unwrap();
         assert_eq!(result, Some("cl-unchanged".to_string())); // still returns thread_id
         let msgs = ThreadStorage::get_messages(&conn, "cl-unchanged").unwrap();
         assert_eq!(msgs.len(), 1); // no second message — content unchanged
@@ -1074,10 +1078,10 @@ mod tests {
         ThreadStorage::insert(&conn, &thread).unwrap();
 
         // First write
-        ThreadManager::add_changelog(&conn, "cl-changed", "src/evolve.rs", "Write", "v1\n", None, &GuardianConfig::default()).unwrap();
+        ThreadManager::add_changelog(&conn, &TopicNormalizer::default(), "cl-changed", "src/evolve.rs", "Write", "v1\n", None, &GuardianConfig::default()).unwrap();
 
         // Second write with different content
-        ThreadManager::add_changelog(&conn, "cl-changed", "src/evolve.rs", "Write", "v2\n", None, &GuardianConfig::default()).unwrap();
+        ThreadManager::add_changelog(&conn, &TopicNormalizer::default(), "cl-changed", "src/evolve.rs", "Write", "v2\n", None, &GuardianConfig::default()).unwrap();
         let msgs = ThreadStorage::get_messages(&conn, "cl-changed").unwrap();
         assert_eq!(msgs[1].metadata["changed"], true);
         assert!(msgs[1].content.contains("[changelog] Write"));
@@ -1087,7 +1091,7 @@ mod tests {
     fn test_add_changelog_nonexistent_thread() {
         let conn = setup_agent_db();
         let result = ThreadManager::add_changelog(
-            &conn, "nonexistent", "src/main.rs", "Read", "content", None,
+            &conn, &TopicNormalizer::default(), "nonexistent", "src/main.rs", "Read", "content", None,
             &GuardianConfig::default(),
         ).unwrap();
         assert_eq!(result, None);
@@ -1104,7 +1108,7 @@ mod tests {
         ThreadStorage::insert(&conn, &thread).unwrap();
 
         // Edit action should be added to work_context.actions
-        ThreadManager::add_changelog(&conn, "cl-actions", "src/main.rs", "Edit", "diff here", None, &GuardianConfig::default()).unwrap();
+        ThreadManager::add_changelog(&conn, &TopicNormalizer::default(), "cl-actions", "src/main.rs", "Edit", "diff here", None, &GuardianConfig::default()).unwrap();
 
         let updated = ThreadStorage::get(&conn, "cl-actions").unwrap().unwrap();
         let wc = updated.work_context.unwrap();
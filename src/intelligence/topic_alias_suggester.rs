@@ -0,0 +1,162 @@
+//! Topic Alias Suggester — proposes alias → canonical pairs for review.
+//!
+//! Candidates are the most frequent topic keys of the agent. A pair is scored by:
+//!   - embedding similarity of the two keys (EmbeddingManager)
+//!   - context similarity: Jaccard of the topics each key co-occurs with
+//!     (true synonyms rarely share a thread but share neighbours)
+//!
+//! The more frequent key becomes the canonical form. Suggestions are stored
+//! with status `suggested` and only applied once confirmed (ai_topic_alias);
+//! a rejected pair is not suggested again.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::processing::embeddings::EmbeddingManager;
+use crate::processing::topic_normalizer::TopicNormalizer;
+use crate::storage::topic_aliases::{TopicAlias, TopicAliasStorage, STATUS_REJECTED, STATUS_SUGGESTED};
+use crate::{time_utils, AiError, AiResult};
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Max distinct topic keys considered (pairwise cost is quadratic).
+const MAX_CANDIDATES: usize = 300;
+/// Below this embedding similarity a pair is never suggested.
+const MIN_EMBEDDING_SIM: f64 = 0.75;
+/// Minimum combined score to store a suggestion.
+const MIN_SCORE: f64 = 0.8;
+const EMBEDDING_WEIGHT: f64 = 0.7;
+const CONTEXT_WEIGHT: f64 = 0.3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AliasSuggestion {
+    pub alias: String,
+    pub canonical: String,
+    pub score: f64,
+    pub embedding_sim: f64,
+    pub context_sim: f64,
+}
+
+pub struct TopicAliasSuggester;
+
+impl TopicAliasSuggester {
+    /// Score candidate pairs and return the best suggestions (not stored).
+    pub fn suggest(conn: &Connection, max: usize) -> AiResult<Vec<AliasSuggestion>> {
+        let thread_topics = load_thread_topics(conn, &TopicNormalizer::load(conn))?;
+
+        // Frequency + co-occurring neighbours per key
+        let mut freq: HashMap<String, usize> = HashMap::new();
+        let mut neighbours: HashMap<String, HashSet<String>> = HashMap::new();
+        for topics in &thread_topics {
+            for t in topics {
+                *freq.entry(t.clone()).or_default() += 1;
+                let entry = neighbours.entry(t.clone()).or_default();
+                entry.extend(topics.iter().filter(|o| *o != t).cloned());
+            }
+        }
+
+        let mut keys: Vec<(String, usize)> = freq.into_iter().collect();
+        keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        keys.truncate(MAX_CANDIDATES);
+
+        // Rejected pairs are never proposed again; the other stored aliases
+        // are settled (confirmed) or pending review (suggested)
+        let (rejected, settled): (Vec<TopicAlias>, Vec<TopicAlias>) = TopicAliasStorage::list(conn, None)?
+            .into_iter()
+            .partition(|a| a.status == STATUS_REJECTED);
+        let rejected: HashSet<(String, String)> = rejected.into_iter().map(|a| (a.alias, a.canonical)).collect();
+        let known: HashSet<String> = settled.into_iter().map(|a| a.alias).collect();
+
+        let embeddings = EmbeddingManager::global();
+        let vectors: Vec<Vec<f32>> = keys.iter().map(|(k, _)| embeddings.embed(k)).collect();
+        let empty = HashSet::new();
+
+        let mut out = Vec::new();
+        for i in 0..keys.len() {
+            for j in (i + 1)..keys.len() {
+                // keys are sorted by frequency DESC → i is the canonical candidate
+                let (canonical, alias) = (&keys[i].0, &keys[j].0);
+                if known.contains(alias)
+                    || known.contains(canonical)
+                    || rejected.contains(&(alias.clone(), canonical.clone()))
+                {
+                    continue;
+                }
+                let embedding_sim = embeddings.similarity(&vectors[i], &vectors[j]);
+                if embedding_sim < MIN_EMBEDDING_SIM {
+                    continue;
+                }
+                let na = neighbours.get(canonical).unwrap_or(&empty);
+                let nb = neighbours.get(alias).unwrap_or(&empty);
+                let context_sim = jaccard(na, nb);
+                let score = EMBEDDING_WEIGHT * embedding_sim + CONTEXT_WEIGHT * context_sim;
+                if score >= MIN_SCORE {
+                    out.push(AliasSuggestion {
+                        alias: alias.clone(),
+                        canonical: canonical.clone(),
+                        score,
+                        embedding_sim,
+                        context_sim,
+                    });
+                }
+            }
+        }
+
+        out.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        // One suggestion per alias (best canonical wins)
+        let mut seen = HashSet::new();
+        out.retain(|s| seen.insert(s.alias.clone()));
+        out.truncate(max);
+        Ok(out)
+    }
+
+    /// Compute suggestions and store them as `suggested` rows. Returns the count stored.
+    pub fn run(conn: &Connection, max: usize) -> AiResult<usize> {
+        let suggestions = Self::suggest(conn, max)?;
+        let now = time_utils::now().to_rfc3339();
+        for s in &suggestions {
+            let source = if s.context_sim > 0.0 { "embedding+cooccurrence" } else { "embedding" };
+            TopicAliasStorage::upsert(conn, &TopicAlias {
+                alias: s.alias.clone(),
+                canonical: s.canonical.clone(),
+                status: STATUS_SUGGESTED.to_string(),
+                score: s.score,
+                source: source.to_string(),
+                created_at: now.clone(),
+            })?;
+        }
+        if !suggestions.is_empty() {
+            tracing::info!(count = suggestions.len(), "Topic alias suggestions stored");
+        }
+        Ok(suggestions.len())
+    }
+}
+
+/// Topic keys of every thread (stored topics keep their spelling).
+fn load_thread_topics(conn: &Connection, normalizer: &TopicNormalizer) -> AiResult<Vec<Vec<String>>> {
+    let mut stmt = conn
+        .prepare("SELECT topics FROM threads WHERE topics != '[]'")
+        .map_err(|e| AiError::Storage(e.to_string()))?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| AiError::Storage(e.to_string()))?;
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .map(|json| {
+            let topics = serde_json::from_str::<Vec<String>>(&json).unwrap_or_default();
+            let mut keys: Vec<String> = topics.iter().map(|t| normalizer.normalize(t)).collect();
+            keys.sort();
+            keys.dedup();
+            keys.retain(|k| !k.is_empty());
+            keys
+        })
+        .filter(|t| !t.is_empty())
+        .collect())
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
@@ -17,6 +17,8 @@
 //! | 10| TruncationPenalty     | Penalize truncated-origin threads   | zero     |
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use crate::processing::topic_normalizer::TopicNormalizer;
 use crate::thread::Thread;
 
 /// Result of a single validator's vote.
@@ -41,6 +43,9 @@ pub struct QueryContext {
     /// Pre-computed bridge connections from active thread.
     /// Maps thread_id → max bridge weight for threads connected to the active thread.
     pub bridge_connections: HashMap<String, f64>,
+    /// Key function of `query_topics`/`query_concepts`: thread topics and
+    /// concepts are keyed with it before comparing.
+    pub normalizer: Arc<TopicNormalizer>,
 }
 
 /// Trait for each independent validator.
@@ -95,8 +100,8 @@ impl Validator for TopicOverlapValidator {
     fn validate(&self, thread: &Thread, ctx: &QueryContext) -> ValidatorVote {
         let shared = thread.topics.iter()
             .filter(|t| {
-                let t_lower = t.to_lowercase();
-                ctx.query_topics.iter().any(|q| q.to_lowercase() == t_lower)
+                let key = ctx.normalizer.normalize(t);
+                ctx.query_topics.contains(&key)
             })
             .count();
         ValidatorVote {
@@ -246,8 +251,8 @@ impl Validator for ConceptCoherenceValidator {
         }
         let shared = thread.concepts.iter()
             .filter(|c| {
-                let c_lower = c.to_lowercase();
-                ctx.query_concepts.iter().any(|qc| qc.to_lowercase() == c_lower)
+                let key = ctx.normalizer.normalize(c);
+                ctx.query_concepts.contains(&key)
             })
             .count();
         let ratio = shared as f64 / ctx.query_concepts.len().max(1) as f64;
@@ -30,6 +30,7 @@ pub mod network;
 pub mod tracing_init;
 pub mod hook_setup;
 pub mod config_sync;
+pub mod metrics;
 
 #[cfg(test)]
 pub mod test_helpers;
@@ -5,6 +5,7 @@ mod hook;
 mod mcp;
 mod runtime;
 
+use ai_smartness::processing::ipc_auth;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -69,12 +70,15 @@ enum Commands {
         #[command(subcommand)]
         action: HookAction,
     },
-    /// Run MCP server (JSON-RPC on stdin/stdout)
+    /// Run MCP server (JSON-RPC on stdin/stdout, or Streamable HTTP with --http)
     Mcp {
         /// Project hash
         project_hash: Option<String>,
         /// Agent ID
         agent_id: Option<String>,
+        /// Serve MCP over HTTP on this loopback address (e.g. 127.0.0.1:8765)
+        #[arg(long)]
+        http: Option<String>,
     },
     /// View or modify configuration
     Config {
@@ -124,6 +128,147 @@ enum Commands {
         #[command(subcommand)]
         action: ModelAction,
     },
+    /// Inspect thread decay models
+    Decay {
+        #[command(subcommand)]
+        action: DecayAction,
+    },
+    /// Query the audit log of tool calls and memory mutations
+    Audit {
+        /// Only calls by this agent
+        #[arg(long)]
+        agent: Option<String>,
+        /// Tool name (trailing * = prefix, e.g. "ai_thread_*")
+        #[arg(long)]
+        tool: Option<String>,
+        /// Outcome: ok, error, denied, preview
+        #[arg(long)]
+        outcome: Option<String>,
+        /// Only the last N hours
+        #[arg(long)]
+        since_hours: Option<u64>,
+        /// Only calls that touched this thread/bridge/agent id
+        #[arg(long)]
+        affected: Option<String>,
+        /// Max entries
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        #[arg(long)]
+        project_hash: Option<String>,
+        /// All projects (ignores --project-hash)
+        #[arg(long)]
+        all_projects: bool,
+        /// Print raw JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Inspect captures (failed captures kept in the dead letters)
+    Captures {
+        #[command(subcommand)]
+        action: CapturesAction,
+    },
+    /// Print daemon metrics (OpenMetrics text format)
+    Metrics,
+}
+
+#[derive(Subcommand)]
+enum CapturesAction {
+    /// Captures that failed for good (list, show, retry, purge)
+    Failed {
+        #[command(subcommand)]
+        action: FailedAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum FailedAction {
+    /// List failed captures (most recent first)
+    List {
+        /// Only captures of this agent
+        #[arg(long)]
+        agent: Option<String>,
+        /// Stage: capture, prompt, enrichment, pool_write, interrupted
+        #[arg(long)]
+        stage: Option<String>,
+        /// Max entries
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        #[arg(long)]
+        project_hash: Option<String>,
+        /// All projects (ignores --project-hash)
+        #[arg(long)]
+        all_projects: bool,
+        /// Print raw JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show one failed capture with its content and error
+    Show {
+        /// Failed capture id
+        id: i64,
+        /// Print raw JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Requeue failed captures (via the daemon, or on its next start)
+    Retry {
+        /// Failed capture ids
+        ids: Vec<i64>,
+        #[arg(long)]
+        agent: Option<String>,
+        #[arg(long)]
+        stage: Option<String>,
+        /// Only captures that failed more than N days ago
+        #[arg(long)]
+        older_than_days: Option<u64>,
+        /// Every failed capture of the project (or of all projects)
+        #[arg(long)]
+        all: bool,
+        #[arg(long)]
+        project_hash: Option<String>,
+        #[arg(long)]
+        all_projects: bool,
+    },
+    /// Delete failed captures for good
+    Purge {
+        /// Failed capture ids
+        ids: Vec<i64>,
+        #[arg(long)]
+        agent: Option<String>,
+        #[arg(long)]
+        stage: Option<String>,
+        /// Only captures that failed more than N days ago
+        #[arg(long)]
+        older_than_days: Option<u64>,
+        /// Every failed capture of the project (or of all projects)
+        #[arg(long)]
+        all: bool,
+        #[arg(long)]
+        project_hash: Option<String>,
+        #[arg(long)]
+        all_projects: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DecayAction {
+    /// Project weights of a sample of real threads over N days (read-only)
+    Simulate {
+        /// Number of days to project
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// Number of threads to sample
+        #[arg(long, default_value_t = 10)]
+        sample: usize,
+        /// Force a strategy for all threads: HalfLife, ActR, SpacedRepetition
+        /// (default: per-thread resolution from config)
+        #[arg(long)]
+        strategy: Option<String>,
+        #[arg(long)]
+        project_hash: Option<String>,
+        #[arg(long)]
+        agent_id: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -203,8 +348,36 @@ enum DaemonAction {
     Stop,
     /// Restart the daemon (apply config changes)
     Restart,
+    /// Reload config.json + daemon_config.json without restarting (same as SIGHUP)
+    Reload,
     /// Show daemon status
     Status,
+    /// Periodic task schedules and last/next runs (or run one now)
+    Tasks {
+        #[command(subcommand)]
+        action: Option<TasksAction>,
+        /// Print raw JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print an IPC token (default: scoped to the current agent), e.g. for
+    /// `AI_SMARTNESS_IPC_TOKEN` of an agent that must not reach other agents
+    Token {
+        /// Agent ID (default: current agent)
+        #[arg(long)]
+        agent: Option<String>,
+        #[arg(long)]
+        project_hash: Option<String>,
+        /// Scope the token to every agent of the project
+        #[arg(long, conflicts_with = "agent")]
+        project: bool,
+        /// Admin token (daemon control: shutdown, restart, pool flush...)
+        #[arg(long, conflicts_with_all = ["agent", "project"])]
+        admin: bool,
+        /// Replace the IPC secret first (revokes every issued token)
+        #[arg(long)]
+        rotate: bool,
+    },
     /// Run daemon in foreground (used internally by 'start')
     RunForeground {
         /// [DEPRECATED] Ignored — global daemon serves all projects
@@ -216,6 +389,20 @@ enum DaemonAction {
     },
 }
 
+#[derive(Subcommand)]
+enum TasksAction {
+    /// Run a task now (agent tasks: for one agent)
+    Run {
+        /// Task name (see `daemon tasks`)
+        name: String,
+        /// Agent ID (agent tasks; default: current agent)
+        #[arg(long)]
+        agent: Option<String>,
+        #[arg(long)]
+        project_hash: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum ControllerAction {
     /// Start the CLI controller (background)
@@ -388,6 +575,20 @@ enum RuleAction {
 fn main() {
     let app = App::parse();
 
+    // Daemon control (CLI, GUI) authenticates to the daemon as admin. Hooks,
+    // MCP servers and the runtime act for their agent (set once resolved).
+    if !matches!(
+        app.command,
+        Some(
+            Commands::Hook { .. }
+                | Commands::Mcp { .. }
+                | Commands::Runtime { .. }
+                | Commands::Daemon { action: DaemonAction::RunForeground { .. } }
+        )
+    ) {
+        ipc_auth::set_client_scope(ipc_auth::Scope::Admin);
+    }
+
     match app.command {
         // No subcommand or Gui → launch GUI
         None | Some(Commands::Gui) => {
@@ -430,8 +631,8 @@ fn main() {
         }
 
         // MCP: JSON-RPC on stdin/stdout
-        Some(Commands::Mcp { project_hash, agent_id }) => {
-            mcp::run(project_hash.as_deref(), agent_id.as_deref());
+        Some(Commands::Mcp { project_hash, agent_id, http }) => {
+            mcp::run(project_hash.as_deref(), agent_id.as_deref(), http.as_deref());
         }
 
         // Daemon: run-foreground is the actual global daemon process
@@ -454,10 +655,26 @@ fn main() {
                 cli::daemon::restart()
                     .unwrap_or_else(|e| eprintln!("Error: {}", e));
             }
+            DaemonAction::Reload => {
+                cli::daemon::reload()
+                    .unwrap_or_else(|e| eprintln!("Error: {}", e));
+            }
             DaemonAction::Status => {
                 cli::daemon::status()
                     .unwrap_or_else(|e| eprintln!("Error: {}", e));
             }
+            DaemonAction::Token { agent, project_hash, project, admin, rotate } => {
+                cli::daemon::token(agent.as_deref(), project_hash.as_deref(), project, admin, rotate)
+                    .unwrap_or_else(|e| eprintln!("Error: {}", e));
+            }
+            DaemonAction::Tasks { action: None, json } => {
+                cli::tasks::list(json)
+                    .unwrap_or_else(|e| eprintln!("Error: {}", e));
+            }
+            DaemonAction::Tasks { action: Some(TasksAction::Run { name, agent, project_hash }), .. } => {
+                cli::tasks::run(&name, agent.as_deref(), project_hash.as_deref())
+                    .unwrap_or_else(|e| eprintln!("Error: {}", e));
+            }
         },
 
         // Controller: CLI-first wake signal injection
@@ -598,5 +815,61 @@ fn main() {
             };
             result.unwrap_or_else(|e| eprintln!("Error: {}", e));
         }
+        Some(Commands::Decay { action }) => {
+            let result = match action {
+                DecayAction::Simulate { days, sample, strategy, project_hash, agent_id } => {
+                    cli::decay::simulate(days, sample, strategy.as_deref(), project_hash.as_deref(), agent_id.as_deref())
+                }
+            };
+            result.unwrap_or_else(|e| eprintln!("Error: {}", e));
+        }
+        Some(Commands::Audit { agent, tool, outcome, since_hours, affected, limit, project_hash, all_projects, json }) => {
+            cli::audit::run(
+                agent.as_deref(), tool.as_deref(), outcome.as_deref(), since_hours,
+                affected.as_deref(), limit, project_hash.as_deref(), all_projects, json,
+            ).unwrap_or_else(|e| eprintln!("Error: {}", e));
+        }
+        Some(Commands::Captures { action: CapturesAction::Failed { action } }) => {
+            let result = match action {
+                FailedAction::List { agent, stage, limit, project_hash, all_projects, json } => {
+                    let selection = cli::captures::FailedSelection {
+                        ids: Vec::new(),
+                        agent_id: agent.as_deref(),
+                        stage: stage.as_deref(),
+                        older_than_days: None,
+                        project_hash: project_hash.as_deref(),
+                        all_projects,
+                    };
+                    cli::captures::list(&selection, limit, json)
+                }
+                FailedAction::Show { id, json } => cli::captures::show(id, json),
+                FailedAction::Retry { ids, agent, stage, older_than_days, all, project_hash, all_projects } => {
+                    let selection = cli::captures::FailedSelection {
+                        ids,
+                        agent_id: agent.as_deref(),
+                        stage: stage.as_deref(),
+                        older_than_days,
+                        project_hash: project_hash.as_deref(),
+                        all_projects,
+                    };
+                    cli::captures::retry(&selection, all)
+                }
+                FailedAction::Purge { ids, agent, stage, older_than_days, all, project_hash, all_projects } => {
+                    let selection = cli::captures::FailedSelection {
+                        ids,
+                        agent_id: agent.as_deref(),
+                        stage: stage.as_deref(),
+                        older_than_days,
+                        project_hash: project_hash.as_deref(),
+                        all_projects,
+                    };
+                    cli::captures::purge(&selection, all)
+                }
+            };
+            result.unwrap_or_else(|e| eprintln!("Error: {}", e));
+        }
+        Some(Commands::Metrics) => {
+            cli::metrics::run().unwrap_or_else(|e| eprintln!("Error: {}", e));
+        }
     }
 }
@@ -0,0 +1,608 @@
+//! MCP Streamable HTTP transport — `ai-smartness mcp --http 127.0.0.1:PORT`.
+//!
+//! One long-lived process serves several local clients:
+//!   POST   /mcp  — JSON-RPC request → JSON response (202 for notifications)
+//!   GET    /mcp  — SSE stream of server notifications for the session
+//!   DELETE /mcp  — terminate the session
+//!
+//! `initialize` creates a session and returns its id in `Mcp-Session-Id`; every
+//! later request must carry that header. A session maps to one
+//! (project_hash, agent_id) pair — chosen with the `X-AI-Smartness-Project` /
+//! `X-AI-Smartness-Agent` headers at initialize, falling back to the identity
+//! the server was started with — and owns its own McpServer (DB connections,
+//! subscriptions, heartbeat). Dispatch is the same as stdio (`handle_message`).
+//! The project must be in the project registry and the agent registered in
+//! it: header values never reach the filesystem unchecked.
+//!
+//! Sessions idle for SESSION_IDLE_TTL_SECS (no request, no open SSE stream)
+//! are closed by a sweep thread, which releases their DB connections and stops
+//! their heartbeat.
+//!
+//! Progress notifications of a session are queued and delivered on its SSE
+//! stream; `notifications/cancelled` is applied without waiting for the
+//! session lock held by the running tool call.
+//!
+//! Loopback only: the listener refuses non-loopback addresses and requests
+//! with a non-local `Origin` (DNS rebinding protection).
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ai_smartness::{AiError, AiResult};
+
+use super::jsonrpc::{self, JsonRpcNotification};
+use super::progress::{self, CancelRegistry};
+use super::server::McpServer;
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const PROJECT_HEADER: &str = "x-ai-smartness-project";
+const AGENT_HEADER: &str = "x-ai-smartness-agent";
+/// Max request body (JSON-RPC messages are small; batch tool args stay well below).
+const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+/// A client that stalls while sending its request is dropped after this long.
+const REQUEST_READ_TIMEOUT_SECS: u64 = 30;
+/// SSE keep-alive comment interval.
+const SSE_PING_SECS: u64 = 15;
+/// SSE loop tick: outbox flush latency for progress notifications.
+const SSE_TICK_MS: u64 = 250;
+/// Outbox cap when no SSE stream drains it (oldest dropped).
+const OUTBOX_MAX: usize = 256;
+/// Sessions without activity for this long are closed.
+const SESSION_IDLE_TTL_SECS: u64 = 30 * 60;
+/// Interval of the idle session sweep.
+const SESSION_SWEEP_SECS: u64 = 60;
+
+struct Session {
+    server: Mutex<McpServer>,
+    /// Shared with the server: cancellations are applied without taking the
+    /// server lock, which a running tool call holds.
+    cancels: CancelRegistry,
+    /// Notifications (progress) waiting for the session's SSE stream.
+    outbox: Arc<Mutex<Vec<JsonRpcNotification>>>,
+    heartbeat_running: Arc<AtomicBool>,
+    /// Cleared on DELETE (or expiry) so open SSE streams end.
+    alive: AtomicBool,
+    /// Last request or SSE tick of the session (idle expiry).
+    last_seen: Mutex<Instant>,
+}
+
+impl Session {
+    fn touch(&self) {
+        if let Ok(mut last_seen) = self.last_seen.lock() {
+            *last_seen = Instant::now();
+        }
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_seen.lock().map(|t| t.elapsed()).unwrap_or_default()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.heartbeat_running.store(false, Ordering::Relaxed);
+    }
+}
+
+type Sessions = Arc<Mutex<HashMap<String, Arc<Session>>>>;
+
+/// Opens the server of a new session and starts its heartbeat (the flag
+/// stops it).
+type OpenServer = fn(String, String) -> AiResult<(McpServer, Arc<AtomicBool>)>;
+
+/// Registered agents of a project, None if the project is not registered.
+type ProjectAgents = fn(&str) -> Option<Vec<String>>;
+
+struct Defaults {
+    project_hash: String,
+    agent_id: String,
+    open_server: OpenServer,
+    project_agents: ProjectAgents,
+}
+
+fn open_server(project_hash: String, agent_id: String) -> AiResult<(McpServer, Arc<AtomicBool>)> {
+    let server = McpServer::new(project_hash, agent_id)?;
+    let (heartbeat_running, _handle) = server.spawn_heartbeat();
+    Ok((server, heartbeat_running))
+}
+
+/// Serve MCP over HTTP until the process is killed.
+pub fn serve(addr: &str, project_hash: String, agent_id: String) -> AiResult<()> {
+    let addr: SocketAddr = addr
+        .parse()
+        .map_err(|e| AiError::InvalidInput(format!("Invalid listen address '{}': {}", addr, e)))?;
+    if !addr.ip().is_loopback() {
+        return Err(AiError::InvalidInput(format!(
+            "Refusing to listen on non-loopback address {}",
+            addr
+        )));
+    }
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| AiError::Storage(format!("Bind {} failed: {}", addr, e)))?;
+    tracing::info!(%addr, "MCP HTTP transport listening");
+
+    let sessions: Sessions = Arc::new(Mutex::new(HashMap::new()));
+    let sweep = sessions.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(SESSION_SWEEP_SECS));
+        sweep_idle_sessions(&sweep, Duration::from_secs(SESSION_IDLE_TTL_SECS));
+    });
+    let defaults = Defaults {
+        project_hash,
+        agent_id,
+        open_server,
+        project_agents: super::registered_project_agents,
+    };
+    accept_loop(listener, sessions, Arc::new(defaults));
+    Ok(())
+}
+
+fn accept_loop(listener: TcpListener, sessions: Sessions, defaults: Arc<Defaults>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let sessions = sessions.clone();
+        let defaults = defaults.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &sessions, &defaults) {
+                tracing::debug!("MCP HTTP connection error: {}", e);
+            }
+        });
+    }
+}
+
+/// Close the sessions idle for more than `ttl`. Returns how many were closed.
+fn sweep_idle_sessions(sessions: &Sessions, ttl: Duration) -> usize {
+    let expired: Vec<(String, Arc<Session>)> = match sessions.lock() {
+        Ok(mut map) => {
+            let ids: Vec<String> = map
+                .iter()
+                .filter(|(_, session)| session.idle_for() >= ttl)
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.into_iter().filter_map(|id| map.remove_entry(&id)).collect()
+        }
+        Err(_) => return 0,
+    };
+    for (id, session) in &expired {
+        session.alive.store(false, Ordering::Relaxed);
+        tracing::info!(session = %id, "MCP HTTP session expired (idle)");
+    }
+    // Dropping the last reference closes the DB connections and stops the heartbeat
+    expired.len()
+}
+
+/// Minimal HTTP/1.1 request (also used by the daemon REST API).
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    /// Raw query string (after `?`, not decoded).
+    pub(crate) query: String,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|s| s.as_str())
+    }
+}
+
+pub(crate) fn read_request(stream: &TcpStream) -> std::io::Result<Request> {
+    stream.set_read_timeout(Some(Duration::from_secs(REQUEST_READ_TIMEOUT_SECS)))?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), query.to_string());
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut h = String::new();
+        if reader.read_line(&mut h)? == 0 {
+            break;
+        }
+        let h = h.trim_end();
+        if h.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = h.split_once(':') {
+            headers.insert(k.trim().to_ascii_lowercase(), v.trim().to_string());
+        }
+    }
+
+    let len: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if len > MAX_BODY_BYTES {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "body too large"));
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok(Request { method, path, query, headers, body })
+}
+
+pub(crate) fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    extra_headers: &[(&str, String)],
+    body: &str,
+) -> std::io::Result<()> {
+    write_typed_response(stream, status, "application/json", extra_headers, body)
+}
+
+pub(crate) fn write_typed_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    extra_headers: &[(&str, String)],
+    body: &str,
+) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    for (k, v) in extra_headers {
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+fn write_error(stream: &mut TcpStream, status: &str, message: &str) -> std::io::Result<()> {
+    let resp = jsonrpc::JsonRpcResponse::error(None, -32600, message.to_string());
+    write_response(stream, status, &[], &jsonrpc::format_response(&resp))
+}
+
+pub(crate) fn is_local_origin(origin: &str) -> bool {
+    let rest = origin.split("://").nth(1).unwrap_or(origin);
+    if rest.starts_with("[::1]") {
+        return true;
+    }
+    let host = rest.split(['/', ':']).next().unwrap_or_default();
+    matches!(host, "localhost" | "127.0.0.1")
+}
+
+fn handle_connection(mut stream: TcpStream, sessions: &Sessions, defaults: &Defaults) -> std::io::Result<()> {
+    let req = read_request(&stream)?;
+
+    if req.path != "/mcp" {
+        return write_error(&mut stream, "404 Not Found", "Unknown endpoint (use /mcp)");
+    }
+    if let Some(origin) = req.header("origin") {
+        if !is_local_origin(origin) {
+            return write_error(&mut stream, "403 Forbidden", "Origin not allowed");
+        }
+    }
+
+    match req.method.as_str() {
+        "POST" => handle_post(&mut stream, &req, sessions, defaults),
+        "GET" => handle_sse(stream, &req, sessions),
+        "DELETE" => {
+            let removed = req
+                .header(SESSION_HEADER)
+                .and_then(|id| sessions.lock().ok()?.remove(id));
+            match removed {
+                Some(session) => {
+                    session.alive.store(false, Ordering::Relaxed);
+                    tracing::info!("MCP HTTP session terminated");
+                    write_response(&mut stream, "200 OK", &[], "{}")
+                }
+                None => write_error(&mut stream, "404 Not Found", "Unknown session"),
+            }
+        }
+        _ => write_error(&mut stream, "405 Method Not Allowed", "Use POST, GET or DELETE"),
+    }
+}
+
+fn handle_post(
+    stream: &mut TcpStream,
+    req: &Request,
+    sessions: &Sessions,
+    defaults: &Defaults,
+) -> std::io::Result<()> {
+    let body = String::from_utf8_lossy(&req.body).to_string();
+    let message = serde_json::from_str::<serde_json::Value>(&body).ok();
+    let is_initialize = message
+        .as_ref()
+        .and_then(|v| v.get("method").and_then(|m| m.as_str()).map(|m| m == "initialize"))
+        .unwrap_or(false);
+
+    let (session_id, session) = if is_initialize {
+        match create_session(req, defaults) {
+            Ok((id, session)) => {
+                if let Ok(mut map) = sessions.lock() {
+                    map.insert(id.clone(), session.clone());
+                }
+                (id, session)
+            }
+            Err(e) => return write_error(stream, "400 Bad Request", &e.to_string()),
+        }
+    } else {
+        let Some(id) = req.header(SESSION_HEADER) else {
+            return write_error(stream, "400 Bad Request", "Missing Mcp-Session-Id header");
+        };
+        let found = sessions.lock().ok().and_then(|m| m.get(id).cloned());
+        match found {
+            Some(s) => {
+                s.touch();
+                (id.to_string(), s)
+            }
+            None => return write_error(stream, "404 Not Found", "Unknown session"),
+        }
+    };
+
+    let headers = [("Mcp-Session-Id", session_id)];
+    if let Some(ref msg) = message {
+        // Cancellation must not wait behind the running tool call it targets.
+        if let Some(request_id) = progress::parse_cancelled(msg) {
+            session.cancels.cancel(&request_id);
+            return write_response(stream, "202 Accepted", &headers, "");
+        }
+        session.cancels.register_call(msg);
+    }
+
+    let response = match session.server.lock() {
+        Ok(mut server) => server.handle_message(&body),
+        Err(_) => return write_error(stream, "500 Internal Server Error", "Session poisoned"),
+    };
+    match response {
+        Some(resp) => write_response(stream, "200 OK", &headers, &jsonrpc::format_response(&resp)),
+        None => write_response(stream, "202 Accepted", &headers, ""),
+    }
+}
+
+/// (project_hash, agent_id) of a new session. Without headers the server's
+/// own identity is used; otherwise the project must be registered and the
+/// agent registered in it (resolved like a late stdio start when absent).
+fn session_identity(req: &Request, defaults: &Defaults) -> AiResult<(String, String)> {
+    let project_hash = req.header(PROJECT_HEADER).unwrap_or(&defaults.project_hash);
+    let agent = req.header(AGENT_HEADER);
+    if project_hash == defaults.project_hash && agent.is_none() {
+        return Ok((defaults.project_hash.clone(), defaults.agent_id.clone()));
+    }
+    // Project hashes are path components: no separators, no `..`
+    let well_formed = !project_hash.is_empty()
+        && project_hash.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !well_formed {
+        return Err(AiError::InvalidInput(format!("Invalid project hash '{}'", project_hash)));
+    }
+    let agents = (defaults.project_agents)(project_hash)
+        .ok_or_else(|| AiError::ProjectNotFound(project_hash.to_string()))?;
+    let agent_id = match agent {
+        Some(agent) if agents.iter().any(|a| a == agent) => agent.to_string(),
+        Some(agent) => return Err(AiError::AgentNotFound(agent.to_string())),
+        None => super::resolve_agent_late(project_hash, &agents)
+            .ok_or_else(|| AiError::AgentNotFound(format!("no agent for project {}", project_hash)))?,
+    };
+    Ok((project_hash.to_string(), agent_id))
+}
+
+fn create_session(req: &Request, defaults: &Defaults) -> AiResult<(String, Arc<Session>)> {
+    let (project_hash, agent_id) = session_identity(req, defaults)?;
+
+    let (mut server, heartbeat_running) = (defaults.open_server)(project_hash.clone(), agent_id.clone())?;
+    let outbox: Arc<Mutex<Vec<JsonRpcNotification>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink = outbox.clone();
+    server.set_notifier(Arc::new(move |note: &JsonRpcNotification| {
+        if let Ok(mut queue) = sink.lock() {
+            if queue.len() >= OUTBOX_MAX {
+                queue.remove(0);
+            }
+            queue.push(note.clone());
+        }
+    }));
+    let cancels = server.cancel_registry();
+    let id = uuid::Uuid::new_v4().to_string();
+    tracing::info!(session = %id, agent = %agent_id, "MCP HTTP session created");
+    Ok((
+        id,
+        Arc::new(Session {
+            server: Mutex::new(server),
+            cancels,
+            outbox,
+            heartbeat_running,
+            alive: AtomicBool::new(true),
+            last_seen: Mutex::new(Instant::now()),
+        }),
+    ))
+}
+
+/// Server-sent events: resource notifications for the session, plus keep-alive pings.
+fn handle_sse(mut stream: TcpStream, req: &Request, sessions: &Sessions) -> std::io::Result<()> {
+    let accepts_sse = req
+        .header("accept")
+        .map(|a| a.contains("text/event-stream"))
+        .unwrap_or(false);
+    if !accepts_sse {
+        return write_error(&mut stream, "406 Not Acceptable", "GET requires Accept: text/event-stream");
+    }
+    let Some(session) = req
+        .header(SESSION_HEADER)
+        .and_then(|id| sessions.lock().ok()?.get(id).cloned())
+    else {
+        return write_error(&mut stream, "404 Not Found", "Unknown session");
+    };
+
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+    )?;
+    stream.flush()?;
+
+    let mut last_ping = Instant::now();
+    while session.alive.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(SSE_TICK_MS));
+        // An open stream keeps its session alive
+        session.touch();
+        let mut notes: Vec<JsonRpcNotification> = match session.outbox.lock() {
+            Ok(mut queue) => std::mem::take(&mut *queue),
+            Err(_) => break,
+        };
+        // try_lock: a running tool call holds the server; resource polling can wait.
+        match session.server.try_lock() {
+            Ok(mut server) => notes.extend(server.poll_resource_updates()),
+            Err(std::sync::TryLockError::WouldBlock) => {}
+            Err(std::sync::TryLockError::Poisoned(_)) => break,
+        }
+        for note in &notes {
+            write!(stream, "event: message\ndata: {}\n\n", jsonrpc::format_notification(note))?;
+        }
+        if last_ping.elapsed() >= Duration::from_secs(SSE_PING_SECS) {
+            stream.write_all(b": ping\n\n")?;
+            last_ping = Instant::now();
+        }
+        stream.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_smartness::storage::migrations;
+    use serde_json::json;
+
+    fn open_in_memory(project_hash: String, agent_id: String) -> AiResult<(McpServer, Arc<AtomicBool>)> {
+        let open = |migrate: fn(&rusqlite::Connection) -> AiResult<()>| -> AiResult<rusqlite::Connection> {
+            let conn = rusqlite::Connection::open_in_memory().map_err(|e| AiError::Storage(e.to_string()))?;
+            migrate(&conn)?;
+            Ok(conn)
+        };
+        let server = McpServer::from_connections(
+            project_hash,
+            agent_id,
+            open(migrations::migrate_agent_db)?,
+            open(migrations::migrate_registry_db)?,
+            open(migrations::migrate_shared_db)?,
+        );
+        Ok((server, Arc::new(AtomicBool::new(true))))
+    }
+
+    /// Registry stub: "ph" has dev, "other" has a single agent, nothing else exists.
+    fn test_agents(project_hash: &str) -> Option<Vec<String>> {
+        match project_hash {
+            "ph" => Some(vec!["dev".into()]),
+            "other" => Some(vec!["solo".into()]),
+            _ => None,
+        }
+    }
+
+    fn test_defaults() -> Defaults {
+        Defaults {
+            project_hash: "ph".into(),
+            agent_id: "dev".into(),
+            open_server: open_in_memory,
+            project_agents: test_agents,
+        }
+    }
+
+    fn identity(project: Option<&str>, agent: Option<&str>) -> AiResult<(String, String)> {
+        let mut headers = HashMap::new();
+        if let Some(p) = project {
+            headers.insert(PROJECT_HEADER.to_string(), p.to_string());
+        }
+        if let Some(a) = agent {
+            headers.insert(AGENT_HEADER.to_string(), a.to_string());
+        }
+        let req = Request { method: "POST".into(), path: "/mcp".into(), query: String::new(), headers, body: vec![] };
+        session_identity(&req, &test_defaults())
+    }
+
+    /// One HTTP exchange: (status, Mcp-Session-Id header, body).
+    fn request(addr: SocketAddr, method: &str, session: Option<&str>, body: &str) -> (u16, Option<String>, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut head = format!("{} /mcp HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n", method, body.len());
+        if let Some(id) = session {
+            head.push_str(&format!("Mcp-Session-Id: {}\r\n", id));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.write_all(body.as_bytes()).unwrap();
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).unwrap();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        let session = head.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case(SESSION_HEADER).then(|| value.trim().to_string())
+        });
+        (status, session, body.to_string())
+    }
+
+    #[test]
+    fn test_session_round_trip_and_idle_expiry() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sessions: Sessions = Arc::new(Mutex::new(HashMap::new()));
+        let defaults = Arc::new(test_defaults());
+        let served = sessions.clone();
+        std::thread::spawn(move || accept_loop(listener, served, defaults));
+
+        let init = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+            "protocolVersion": "2025-03-26", "capabilities": {}, "clientInfo": {"name": "test", "version": "0"}
+        }})
+        .to_string();
+        let (status, session, body) = request(addr, "POST", None, &init);
+        assert_eq!(status, 200);
+        assert!(body.contains("serverInfo"), "{}", body);
+        let session = session.expect("initialize returns the session id");
+
+        let call = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "ai_thread_list", "arguments": {}}})
+            .to_string();
+        assert_eq!(request(addr, "POST", None, &call).0, 400, "Session header required");
+        let (status, _, body) = request(addr, "POST", Some(&session), &call);
+        assert_eq!(status, 200);
+        let resp: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(resp["id"], 2);
+        assert!(resp["result"]["content"][0]["text"].is_string(), "{}", body);
+
+        assert_eq!(request(addr, "DELETE", Some(&session), "").0, 200);
+        assert_eq!(request(addr, "POST", Some(&session), &call).0, 404, "Deleted session is gone");
+        assert_eq!(request(addr, "DELETE", Some(&session), "").0, 404);
+
+        let (_, idle, _) = request(addr, "POST", None, &init);
+        assert_eq!(sweep_idle_sessions(&sessions, Duration::from_secs(SESSION_IDLE_TTL_SECS)), 0);
+        assert_eq!(sweep_idle_sessions(&sessions, Duration::ZERO), 1);
+        assert_eq!(request(addr, "POST", idle.as_deref(), &call).0, 404, "Expired session is gone");
+    }
+
+    #[test]
+    fn test_session_identity_checks_registry() {
+        let ok = |p, a| identity(p, a).unwrap();
+        assert_eq!(ok(None, None), ("ph".into(), "dev".into()));
+        assert_eq!(ok(Some("ph"), Some("dev")), ("ph".into(), "dev".into()));
+        assert_eq!(ok(Some("other"), None), ("other".into(), "solo".into()), "Resolved from registered agents");
+        assert!(matches!(identity(Some("other"), Some("dev")), Err(AiError::AgentNotFound(_))));
+        assert!(matches!(identity(Some("unknown"), None), Err(AiError::ProjectNotFound(_))));
+        for bad in ["../ph", "..", "a/b", "a\\b", ""] {
+            assert!(matches!(identity(Some(bad), None), Err(AiError::InvalidInput(_))), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_local_origin_check() {
+        assert!(is_local_origin("http://localhost:3000"));
+        assert!(is_local_origin("http://127.0.0.1"));
+        assert!(is_local_origin("http://[::1]:8080"));
+        assert!(!is_local_origin("https://evil.example.com"));
+        assert!(!is_local_origin("http://localhost.evil.com"));
+    }
+
+    #[test]
+    fn test_serve_rejects_non_loopback() {
+        let err = serve("0.0.0.0:0", "ph".into(), "agent".into()).unwrap_err();
+        assert!(err.to_string().contains("non-loopback"));
+        assert!(serve("not-an-addr", "ph".into(), "agent".into()).is_err());
+    }
+}
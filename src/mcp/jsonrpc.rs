@@ -25,6 +25,25 @@ pub struct JsonRpcError {
     pub data: Option<serde_json::Value>,
 }
 
+/// Server → client notification (no id, no response expected).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcNotification {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+}
+
+impl JsonRpcNotification {
+    pub fn new(method: &str, params: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".into(),
+            method: method.into(),
+            params: Some(params),
+        }
+    }
+}
+
 impl JsonRpcResponse {
     pub fn success(id: Option<serde_json::Value>, result: serde_json::Value) -> Self {
         Self {
@@ -59,3 +78,7 @@ pub fn format_response(response: &JsonRpcResponse) -> String {
             .to_string()
     })
 }
+
+pub fn format_notification(notification: &JsonRpcNotification) -> String {
+    serde_json::to_string(notification).unwrap_or_default()
+}
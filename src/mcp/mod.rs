@@ -1,4 +1,8 @@
+pub mod http;
 pub mod jsonrpc;
+pub mod progress;
+pub mod prompts;
+pub mod resources;
 pub mod server;
 pub mod tools;
 
@@ -22,6 +26,28 @@ fn list_project_agents(project_hash: &str) -> Vec<String> {
         .collect()
 }
 
+/// Registered agents of a project, or None if the project itself is not in
+/// the registry (or the registry can't be read).
+fn registered_project_agents(project_hash: &str) -> Option<Vec<String>> {
+    let reg_path = ai_smartness::storage::path_utils::registry_db_path();
+    let conn = ai_smartness::storage::database::open_connection(
+        &reg_path,
+        ai_smartness::storage::database::ConnectionRole::Mcp,
+    )
+    .ok()?;
+    let _ = ai_smartness::storage::migrations::migrate_registry_db(&conn);
+    let agents = ai_smartness::registry::registry::AgentRegistry::list(&conn, Some(project_hash), None, None)
+        .ok()?
+        .into_iter()
+        .map(|a| a.id)
+        .collect();
+    let projects = ai_smartness::storage::project_registry_impl::SqliteProjectRegistry::new(conn);
+    ai_smartness::project_registry::ProjectRegistryTrait::get_project(&projects, project_hash)
+        .ok()
+        .flatten()?;
+    Some(agents)
+}
+
 /// Resolve agent identity at MCP startup.
 ///
 /// Cascade (no "default" — always resolves to a real agent):
@@ -184,8 +210,9 @@ fn resolve_from_parent_session(project_hash: &str, agents: &[String]) -> Option<
 }
 
 
-/// Run MCP JSON-RPC server on stdin/stdout.
-pub fn run(project_hash: Option<&str>, agent_id: Option<&str>) {
+/// Run MCP JSON-RPC server on stdin/stdout, or over Streamable HTTP when
+/// `http_addr` is set (one process shared by several local clients).
+pub fn run(project_hash: Option<&str>, agent_id: Option<&str>, http_addr: Option<&str>) {
     // Set ORT_DYLIB_PATH before any ONNX usage (EmbeddingManager::global()).
     // MCP tools like ai_recall call EngramRetriever which triggers ONNX init.
     // Without this, the `ort` crate panics when libonnxruntime.so is not found.
@@ -254,6 +281,11 @@ pub fn run(project_hash: Option<&str>, agent_id: Option<&str>) {
         }
     };
 
+    ai_smartness::processing::ipc_auth::set_client_scope(ai_smartness::processing::ipc_auth::Scope::Agent {
+        project_hash: project_hash.clone(),
+        agent_id: agent_id.clone(),
+    });
+
     // Write per-session agent file so hooks find the agent identity on first invocation.
     // This prevents hooks from falling back to the global session file when per-session
     // isolation is needed.
@@ -304,6 +336,14 @@ pub fn run(project_hash: Option<&str>, agent_id: Option<&str>) {
         }
     }
 
+    if let Some(addr) = http_addr {
+        if let Err(e) = http::serve(addr, project_hash, agent_id) {
+            eprintln!("[ai-mcp] HTTP server error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     match McpServer::new(project_hash, agent_id) {
         Ok(mut server) => {
             if let Err(e) = server.run() {
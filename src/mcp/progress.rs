@@ -0,0 +1,192 @@
+//! Progress notifications and cancellation for long-running tool calls.
+//!
+//! A tools/call carrying `_meta.progressToken` gets a ProgressSink that emits
+//! `notifications/progress` through the transport's notifier. Every in-flight
+//! request has a cancellation flag in the CancelRegistry; the stdin reader
+//! thread (or the HTTP handler) flips it on `notifications/cancelled` while the
+//! tool keeps running, and handlers poll it between batch steps.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ai_smartness::{AiError, AiResult};
+
+use super::jsonrpc::JsonRpcNotification;
+
+/// Transport callback used to push a notification to the client.
+pub type Notifier = Arc<dyn Fn(&JsonRpcNotification) + Send + Sync>;
+
+/// Handle given to tool handlers (via ToolContext) to report progress and
+/// observe cancellation. `ProgressSink::none()` is a no-op sink.
+#[derive(Clone, Default)]
+pub struct ProgressSink {
+    token: Option<serde_json::Value>,
+    cancelled: Option<Arc<AtomicBool>>,
+    notifier: Option<Notifier>,
+}
+
+impl ProgressSink {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn new(
+        token: Option<serde_json::Value>,
+        cancelled: Arc<AtomicBool>,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            token,
+            cancelled: Some(cancelled),
+            notifier: Some(notifier),
+        }
+    }
+
+    /// Emit `notifications/progress` (no-op unless the client sent a progressToken).
+    pub fn report(&self, progress: u64, total: Option<u64>, message: &str) {
+        let (Some(token), Some(notifier)) = (&self.token, &self.notifier) else {
+            return;
+        };
+        let mut params = serde_json::json!({
+            "progressToken": token,
+            "progress": progress,
+        });
+        if let Some(total) = total {
+            params["total"] = serde_json::json!(total);
+        }
+        if !message.is_empty() {
+            params["message"] = serde_json::json!(message);
+        }
+        notifier(&JsonRpcNotification::new("notifications/progress", params));
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
+    /// Err if the client cancelled the request — use with `?` between batch steps.
+    pub fn check_cancelled(&self) -> AiResult<()> {
+        if self.is_cancelled() {
+            return Err(AiError::InvalidState("Request cancelled by client".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Cancellation flags of in-flight requests, keyed by JSON-RPC id.
+/// Shared between the request reader and the tool executor.
+#[derive(Clone, Default)]
+pub struct CancelRegistry {
+    inner: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+}
+
+impl CancelRegistry {
+    fn key(id: &serde_json::Value) -> String {
+        id.to_string()
+    }
+
+    /// Register a request as soon as it is read (before it starts executing),
+    /// so a cancellation arriving while it is queued is not lost.
+    pub fn register(&self, id: &serde_json::Value) -> Arc<AtomicBool> {
+        let mut map = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        map.entry(Self::key(id)).or_default().clone()
+    }
+
+    /// Register `message` if it is a `tools/call` request — the only requests
+    /// that can be cancelled, and whose completion calls `finish`.
+    pub fn register_call(&self, message: &serde_json::Value) {
+        if message.get("method").and_then(|m| m.as_str()) != Some("tools/call") {
+            return;
+        }
+        if let Some(id) = message.get("id").filter(|id| !id.is_null()) {
+            self.register(id);
+        }
+    }
+
+    /// Flag a request as cancelled. Unknown (already finished) ids are ignored.
+    pub fn cancel(&self, id: &serde_json::Value) -> bool {
+        let map = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match map.get(&Self::key(id)) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self, id: &serde_json::Value) {
+        let mut map = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        map.remove(&Self::key(id));
+    }
+}
+
+/// If `message` is a `notifications/cancelled`, return the cancelled request id.
+pub fn parse_cancelled(message: &serde_json::Value) -> Option<serde_json::Value> {
+    if message.get("method").and_then(|m| m.as_str()) != Some("notifications/cancelled") {
+        return None;
+    }
+    message.get("params")?.get("requestId").cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_registry_lifecycle() {
+        let reg = CancelRegistry::default();
+        let id = serde_json::json!(7);
+        assert!(!reg.cancel(&id), "Unknown id is ignored");
+
+        let flag = reg.register(&id);
+        assert!(reg.cancel(&id));
+        assert!(flag.load(Ordering::Relaxed));
+        assert!(reg.register(&id).load(Ordering::Relaxed), "Same flag while in flight");
+
+        reg.finish(&id);
+        assert!(!reg.register(&id).load(Ordering::Relaxed), "Fresh flag after finish");
+        reg.finish(&id);
+
+        reg.register_call(&serde_json::json!({"id": 8, "method": "tools/list"}));
+        assert!(!reg.cancel(&serde_json::json!(8)), "Only tools/call is registered");
+        reg.register_call(&serde_json::json!({"id": 9, "method": "tools/call"}));
+        assert!(reg.cancel(&serde_json::json!(9)));
+    }
+
+    #[test]
+    fn test_progress_sink_reports_and_cancels() {
+        let sent: Arc<Mutex<Vec<serde_json::Value>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink_sent = sent.clone();
+        let notifier: Notifier = Arc::new(move |n: &JsonRpcNotification| {
+            sink_sent.lock().unwrap().push(n.params.clone().unwrap());
+        });
+        let flag = Arc::new(AtomicBool::new(false));
+        let sink = ProgressSink::new(Some(serde_json::json!("tok")), flag.clone(), notifier.clone());
+
+        sink.report(1, Some(4), "step");
+        assert!(sink.check_cancelled().is_ok());
+        flag.store(true, Ordering::Relaxed);
+        assert!(sink.check_cancelled().is_err());
+
+        // No token → silent, but cancellation still observed
+        let silent = ProgressSink::new(None, flag, notifier);
+        silent.report(2, None, "");
+        assert!(silent.is_cancelled());
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["progressToken"], "tok");
+        assert_eq!(sent[0]["total"], 4);
+
+        assert!(!ProgressSink::none().is_cancelled());
+        assert_eq!(
+            parse_cancelled(&serde_json::json!({"method": "notifications/cancelled", "params": {"requestId": 3}})),
+            Some(serde_json::json!(3))
+        );
+    }
+}
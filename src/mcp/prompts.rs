@@ -0,0 +1,440 @@
+//! MCP prompts — parameterised templates assembled from live memory.
+//!
+//! Built-in prompts:
+//!   handoff        — session summary from beat state + recently active threads
+//!   memory_review  — low-usage injected threads (InjectionStats) to rate/suspend
+//!   recall         — structured recall of a subject
+//!   delegate_task  — prepare a task delegation for another agent
+//!
+//! User prompts are TOML files in `{data_dir}/prompts/` (global) and
+//! `{project_dir}/prompts/` (per project, wins over global). A user prompt with
+//! the name of a built-in replaces it. File format:
+//!
+//! ```toml
+//! name = "standup"
+//! description = "Daily standup summary"
+//! template = "Summarise progress on {{topic}}.\n\n{{memory.handoff}}"
+//!
+//! [[arguments]]
+//! name = "topic"
+//! required = true
+//! ```
+//!
+//! Placeholders: `{{arg}}` for arguments, `{{agent_id}}`, `{{project_hash}}`,
+//! and `{{memory.handoff}}` / `{{memory.review}}` / `{{memory.focus}}` for the
+//! live sections used by the built-ins.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use ai_smartness::storage::beat::BeatState;
+use ai_smartness::storage::path_utils;
+use ai_smartness::storage::threads::ThreadStorage;
+use ai_smartness::thread::{Thread, ThreadStatus};
+use ai_smartness::{AiError, AiResult};
+use serde::Deserialize;
+
+use super::tools::ToolContext;
+
+/// Recently active threads listed in the handoff section.
+const HANDOFF_THREAD_LIMIT: usize = 8;
+/// Default minimum injections before a thread is reviewed.
+const REVIEW_MIN_INJECTIONS: u32 = 5;
+/// Usage ratio below which an injected thread is considered noise.
+const REVIEW_MAX_USAGE: f64 = 0.2;
+const REVIEW_THREAD_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+    pub template: String,
+}
+
+impl PromptTemplate {
+    fn definition(&self) -> serde_json::Value {
+        let args: Vec<serde_json::Value> = self
+            .arguments
+            .iter()
+            .map(|a| serde_json::json!({
+                "name": a.name,
+                "description": a.description,
+                "required": a.required,
+            }))
+            .collect();
+        serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "arguments": args,
+        })
+    }
+}
+
+fn arg(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument { name: name.into(), description: description.into(), required }
+}
+
+fn builtins() -> Vec<PromptTemplate> {
+    vec![
+        PromptTemplate {
+            name: "handoff".into(),
+            description: "Summarise the session for the next agent or after compaction".into(),
+            arguments: vec![arg("focus", "Optional subject to emphasise", false)],
+            template: "Write a session handoff for agent {{agent_id}}{{focus_suffix}}.\n\
+                       Cover: what was done, current state, open questions and next steps.\n\
+                       Save durable decisions with ai_thread_create before ending.\n\n\
+                       {{memory.handoff}}"
+                .into(),
+        },
+        PromptTemplate {
+            name: "memory_review".into(),
+            description: "Review injected threads that are rarely used".into(),
+            arguments: vec![arg(
+                "min_injections",
+                "Only review threads injected at least this many times (default 5)",
+                false,
+            )],
+            template: "Review the threads below: they are injected into context but rarely used.\n\
+                       For each, either rate it (ai_rate_importance), merge/rename it, or suspend it \
+                       (ai_thread_suspend). Keep threads that hold decisions or rules.\n\n\
+                       {{memory.review}}"
+                .into(),
+        },
+        PromptTemplate {
+            name: "recall".into(),
+            description: "Recall everything known about a subject".into(),
+            arguments: vec![arg("query", "Subject to recall", true)],
+            template: "Call ai_recall with query \"{{query}}\", then follow the strongest bridges \
+                       (ai_bridges) and summarise what memory says about {{query}}: facts, decisions, \
+                       open conflicts and stale information.\n\n{{memory.focus}}"
+                .into(),
+        },
+        PromptTemplate {
+            name: "delegate_task".into(),
+            description: "Prepare a task delegation for another agent".into(),
+            arguments: vec![
+                arg("agent", "Assignee agent ID", true),
+                arg("task", "What to delegate", true),
+            ],
+            template: "Prepare a delegation of \"{{task}}\" to agent {{agent}}.\n\
+                       1. Recall relevant context (ai_recall) and share the key threads (ai_share).\n\
+                       2. Write a self-contained brief: goal, constraints, acceptance criteria.\n\
+                       3. Send it with task_delegate (to: {{agent}}) and note the task id.\n\n\
+                       {{memory.handoff}}"
+                .into(),
+        },
+    ]
+}
+
+/// Load prompt files from a directory. Invalid files are logged and skipped.
+pub fn load_dir(dir: &Path) -> Vec<PromptTemplate> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return vec![] };
+    let mut out = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+        match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| toml::from_str::<PromptTemplate>(&s).map_err(|e| e.to_string()))
+        {
+            Ok(t) => out.push(t),
+            Err(e) => tracing::warn!(path = %path.display(), "Invalid prompt file: {}", e),
+        }
+    }
+    out
+}
+
+/// All prompts by name: built-ins, then global files, then project files.
+pub fn catalog(project_hash: &str) -> BTreeMap<String, PromptTemplate> {
+    merge_layers([
+        builtins(),
+        load_dir(&path_utils::global_prompts_dir()),
+        load_dir(&path_utils::project_prompts_dir(project_hash)),
+    ])
+}
+
+/// Index prompt layers by name; a later layer replaces same-named prompts.
+fn merge_layers<const N: usize>(layers: [Vec<PromptTemplate>; N]) -> BTreeMap<String, PromptTemplate> {
+    let mut map = BTreeMap::new();
+    for layer in layers {
+        for t in layer {
+            map.insert(t.name.clone(), t);
+        }
+    }
+    map
+}
+
+/// `prompts/list`.
+pub fn list(project_hash: &str) -> Vec<serde_json::Value> {
+    catalog(project_hash).values().map(|t| t.definition()).collect()
+}
+
+/// `prompts/get`: render a prompt as a single user message.
+pub fn get(
+    name: &str,
+    arguments: &serde_json::Value,
+    ctx: &ToolContext,
+) -> AiResult<serde_json::Value> {
+    let catalog = catalog(ctx.project_hash);
+    let template = catalog
+        .get(name)
+        .ok_or_else(|| AiError::InvalidInput(format!("Unknown prompt: {}", name)))?;
+    let text = render(template, arguments, ctx)?;
+    Ok(serde_json::json!({
+        "description": template.description,
+        "messages": [{
+            "role": "user",
+            "content": {"type": "text", "text": text}
+        }]
+    }))
+}
+
+fn render(
+    template: &PromptTemplate,
+    arguments: &serde_json::Value,
+    ctx: &ToolContext,
+) -> AiResult<String> {
+    let mut vars: BTreeMap<String, String> = BTreeMap::new();
+    for a in &template.arguments {
+        let value = match arguments.get(&a.name) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        if a.required && value.trim().is_empty() {
+            return Err(AiError::InvalidInput(format!(
+                "Missing required prompt argument: {}",
+                a.name
+            )));
+        }
+        vars.insert(a.name.clone(), value);
+    }
+    vars.insert("agent_id".into(), ctx.agent_id.to_string());
+    vars.insert("project_hash".into(), ctx.project_hash.to_string());
+    let focus = vars.get("focus").cloned().unwrap_or_default();
+    let focus_suffix = if focus.is_empty() { String::new() } else { format!(", focusing on {}", focus) };
+    vars.insert("focus_suffix".into(), focus_suffix);
+
+    // Live sections are only computed when the template references them.
+    let t = &template.template;
+    if t.contains("{{memory.handoff}}") {
+        vars.insert("memory.handoff".into(), handoff_section(ctx)?);
+    }
+    if t.contains("{{memory.review}}") {
+        let min = vars
+            .get("min_injections")
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(REVIEW_MIN_INJECTIONS);
+        vars.insert("memory.review".into(), review_section(ctx, min)?);
+    }
+    if t.contains("{{memory.focus}}") {
+        vars.insert("memory.focus".into(), focus_section(ctx)?);
+    }
+
+    Ok(substitute(t, &vars))
+}
+
+/// Replace `{{key}}` placeholders in a single pass; unknown placeholders are
+/// left as-is and substituted values are never expanded again.
+fn substitute(template: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}").and_then(|end| vars.get(&after[..end]).map(|v| (end, v))) {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn handoff_section(ctx: &ToolContext) -> AiResult<String> {
+    let beat = BeatState::load(&path_utils::agent_data_dir(ctx.project_hash, ctx.agent_id));
+    let mut lines = vec!["## Session state".to_string()];
+    if let Some(ref branch) = beat.git_branch {
+        let dirty = if beat.git_dirty { " (uncommitted changes)" } else { "" };
+        lines.push(format!("- git branch: {}{}", branch, dirty));
+    }
+    lines.push(format!("- session started: {}", beat.started_at));
+    for task in &beat.pending_tasks {
+        let title = task.get("title").and_then(|v| v.as_str()).unwrap_or("?");
+        let status = task.get("status").and_then(|v| v.as_str()).unwrap_or("?");
+        lines.push(format!("- pending task: {} ({})", title, status));
+    }
+    if !beat.last_actions.is_empty() {
+        lines.push("- last actions:".to_string());
+        for a in &beat.last_actions {
+            match a.target.as_deref() {
+                Some(t) if !t.is_empty() => lines.push(format!("  - {} → {}", a.tool, t)),
+                _ => lines.push(format!("  - {}", a.tool)),
+            }
+        }
+    }
+
+    let mut threads = ThreadStorage::list_by_status(ctx.agent_conn, &ThreadStatus::Active)?;
+    threads.sort_by(|a, b| b.last_active.cmp(&a.last_active));
+    threads.truncate(HANDOFF_THREAD_LIMIT);
+    if !threads.is_empty() {
+        lines.push(String::new());
+        lines.push("## Recently active threads".to_string());
+        for t in &threads {
+            lines.push(thread_line(t));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+fn review_section(ctx: &ToolContext, min_injections: u32) -> AiResult<String> {
+    let mut candidates: Vec<(Thread, u32, f64)> = ThreadStorage::list_by_status(ctx.agent_conn, &ThreadStatus::Active)?
+        .into_iter()
+        .filter_map(|t| {
+            let stats = t.injection_stats.clone()?;
+            let ratio = stats.usage_ratio();
+            (stats.injection_count >= min_injections && ratio < REVIEW_MAX_USAGE)
+                .then_some((t, stats.injection_count, ratio))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal).then(b.1.cmp(&a.1)));
+    candidates.truncate(REVIEW_THREAD_LIMIT);
+
+    if candidates.is_empty() {
+        return Ok(format!(
+            "No active thread injected ≥{} times with usage < {:.0}%.",
+            min_injections,
+            REVIEW_MAX_USAGE * 100.0
+        ));
+    }
+    let mut lines = vec!["## Low-usage threads".to_string()];
+    for (t, injected, ratio) in &candidates {
+        lines.push(format!(
+            "{} — injected {}×, used {:.0}%",
+            thread_line(t),
+            injected,
+            ratio * 100.0
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn focus_section(ctx: &ToolContext) -> AiResult<String> {
+    let focus: Vec<String> = ThreadStorage::list_by_status(ctx.agent_conn, &ThreadStatus::Active)?
+        .into_iter()
+        .filter(|t| t.tags.iter().any(|tag| tag == "__focus__"))
+        .flat_map(|t| t.topics)
+        .collect();
+    if focus.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("Current focus: {}", focus.join(", ")))
+}
+
+fn thread_line(t: &Thread) -> String {
+    let mut line = format!("- `{}` {} (weight {:.2})", t.id, t.title, t.weight);
+    if !t.topics.is_empty() {
+        line.push_str(&format!(" [{}]", t.topics.join(", ")));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_and_unknown_placeholders() {
+        let mut vars = BTreeMap::new();
+        vars.insert("query".to_string(), "sqlite".to_string());
+        assert_eq!(
+            substitute("recall {{query}} and {{other}}", &vars),
+            "recall sqlite and {{other}}"
+        );
+
+        // Values are inserted verbatim, never expanded in turn
+        vars.insert("agent_id".to_string(), "agent-a".to_string());
+        vars.insert("query".to_string(), "{{agent_id}}".to_string());
+        assert_eq!(
+            substitute("{{agent_id}}: {{query}} {{{query}}}", &vars),
+            "agent-a: {{agent_id}} {{{agent_id}}}"
+        );
+    }
+
+    #[test]
+    fn test_load_dir_and_override() {
+        let dir = std::env::temp_dir().join(format!("ais-prompts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("recall.toml"),
+            "name = \"recall\"\ndescription = \"custom\"\ntemplate = \"Find {{query}}\"\n\
+             [[arguments]]\nname = \"query\"\nrequired = true\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("broken.toml"), "name = ").unwrap();
+        std::fs::write(dir.join("notes.md"), "ignored").unwrap();
+
+        let loaded = load_dir(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.len(), 1, "Broken and non-TOML files are skipped");
+        assert_eq!(loaded[0].name, "recall");
+        assert!(loaded[0].arguments[0].required);
+
+        let catalog = merge_layers([builtins(), loaded]);
+        let recall = &catalog["recall"];
+        assert_eq!(recall.description, "custom", "User file replaces the built-in");
+        assert_eq!(recall.template, "Find {{query}}");
+        assert!(catalog.contains_key("memory_review"), "Other built-ins are kept");
+    }
+
+    #[test]
+    fn test_render_requires_arguments() {
+        use rusqlite::Connection;
+        let open = |migrate: fn(&Connection) -> AiResult<()>| {
+            let conn = Connection::open(":memory:").unwrap();
+            migrate(&conn).unwrap();
+            conn
+        };
+        let agent = open(ai_smartness::storage::migrations::migrate_agent_db);
+        let registry = open(ai_smartness::storage::migrations::migrate_registry_db);
+        let shared = open(ai_smartness::storage::migrations::migrate_shared_db);
+        let ctx = ToolContext {
+            agent_conn: &agent,
+            registry_conn: &registry,
+            shared_conn: &shared,
+            project_hash: "test-prompts",
+            agent_id: "prompt_agent",
+            progress: crate::mcp::tools::ProgressSink::none(),
+        };
+        let recall = builtins().into_iter().find(|t| t.name == "recall").unwrap();
+        assert!(render(&recall, &serde_json::json!({}), &ctx).is_err());
+
+        let text = render(&recall, &serde_json::json!({"query": "wal mode"}), &ctx).unwrap();
+        assert!(text.contains("query \"wal mode\""));
+
+        let review = builtins().into_iter().find(|t| t.name == "memory_review").unwrap();
+        let text = render(&review, &serde_json::json!({}), &ctx).unwrap();
+        assert!(text.contains("No active thread injected"));
+    }
+}
@@ -0,0 +1,377 @@
+//! MCP resources — read-only `memory://` views over the agent's memory.
+//!
+//! Static resources:
+//!   memory://focus    — focus threads (JSON)
+//!   memory://inbox    — pending MCP messages (JSON)
+//!   memory://handoff  — session handoff snapshot (markdown)
+//! Templates:
+//!   memory://thread/{id}          — one thread with its recent messages (markdown)
+//!   memory://bridges/{thread_id}  — bridges touching a thread (JSON)
+//!
+//! Subscriptions are tracked by the server: each subscribed URI keeps a
+//! fingerprint of its last rendering, and `poll` reports the URIs whose
+//! content changed (daemon writes land in the same SQLite files).
+
+use std::collections::HashMap;
+
+use ai_smartness::storage::beat::BeatState;
+use ai_smartness::storage::bridges::BridgeStorage;
+use ai_smartness::storage::mcp_messages::McpMessages;
+use ai_smartness::storage::path_utils;
+use ai_smartness::storage::threads::ThreadStorage;
+use ai_smartness::thread::{Thread, ThreadStatus};
+use ai_smartness::{AiError, AiResult};
+
+use super::tools::ToolContext;
+
+const SCHEME: &str = "memory://";
+/// Threads listed by `resources/list` (the template covers the rest).
+const LIST_THREAD_LIMIT: usize = 20;
+/// Messages rendered in a thread resource.
+const THREAD_MESSAGE_LIMIT: usize = 10;
+
+const MIME_MARKDOWN: &str = "text/markdown";
+const MIME_JSON: &str = "application/json";
+
+/// Parsed `memory://` URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryUri {
+    Thread(String),
+    Bridges(String),
+    Focus,
+    Inbox,
+    Handoff,
+}
+
+impl MemoryUri {
+    pub fn parse(uri: &str) -> AiResult<Self> {
+        let rest = uri
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| AiError::InvalidInput(format!("Unsupported resource URI: {}", uri)))?;
+        let (kind, arg) = match rest.split_once('/') {
+            Some((k, a)) => (k, Some(a)),
+            None => (rest, None),
+        };
+        match (kind, arg) {
+            ("thread", Some(id)) if !id.is_empty() => Ok(Self::Thread(id.to_string())),
+            ("bridges", Some(id)) if !id.is_empty() => Ok(Self::Bridges(id.to_string())),
+            ("focus", None) => Ok(Self::Focus),
+            ("inbox", None) => Ok(Self::Inbox),
+            ("handoff", None) => Ok(Self::Handoff),
+            _ => Err(AiError::InvalidInput(format!("Unknown resource URI: {}", uri))),
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Thread(_) | Self::Handoff => MIME_MARKDOWN,
+            Self::Bridges(_) | Self::Focus | Self::Inbox => MIME_JSON,
+        }
+    }
+}
+
+/// `resources/list`: static resources + the most recent active threads.
+pub fn list(ctx: &ToolContext) -> AiResult<Vec<serde_json::Value>> {
+    let mut out = vec![
+        resource("memory://focus", "focus", "Focus threads of the current agent", MIME_JSON),
+        resource("memory://inbox", "inbox", "Pending messages for the current agent", MIME_JSON),
+        resource("memory://handoff", "handoff", "Session handoff snapshot", MIME_MARKDOWN),
+    ];
+
+    let mut threads = ThreadStorage::list_active(ctx.agent_conn)?;
+    threads.sort_by(|a, b| b.last_active.cmp(&a.last_active));
+    for t in threads.iter().take(LIST_THREAD_LIMIT) {
+        out.push(resource(
+            &format!("memory://thread/{}", t.id),
+            &t.title,
+            &format!("Thread ({}), weight {:.2}", t.status.as_str(), t.weight),
+            MIME_MARKDOWN,
+        ));
+    }
+    Ok(out)
+}
+
+/// `resources/templates/list`.
+pub fn templates() -> Vec<serde_json::Value> {
+    vec![
+        serde_json::json!({
+            "uriTemplate": "memory://thread/{id}",
+            "name": "thread",
+            "description": "A memory thread with summary, topics and recent messages",
+            "mimeType": MIME_MARKDOWN,
+        }),
+        serde_json::json!({
+            "uriTemplate": "memory://bridges/{thread_id}",
+            "name": "bridges",
+            "description": "Bridges connected to a thread",
+            "mimeType": MIME_JSON,
+        }),
+    ]
+}
+
+/// `resources/read`: render one resource as a `contents` entry.
+pub fn read(uri: &str, ctx: &ToolContext) -> AiResult<serde_json::Value> {
+    let parsed = MemoryUri::parse(uri)?;
+    let text = render(&parsed, ctx)?;
+    Ok(serde_json::json!({
+        "uri": uri,
+        "mimeType": parsed.mime_type(),
+        "text": text,
+    }))
+}
+
+fn render(uri: &MemoryUri, ctx: &ToolContext) -> AiResult<String> {
+    match uri {
+        MemoryUri::Thread(id) => {
+            let thread = ThreadStorage::get(ctx.agent_conn, id)?
+                .ok_or_else(|| AiError::ThreadNotFound(id.clone()))?;
+            let messages = ThreadStorage::get_messages(ctx.agent_conn, id)?;
+            Ok(render_thread(&thread, &messages))
+        }
+        MemoryUri::Bridges(id) => {
+            if ThreadStorage::get(ctx.agent_conn, id)?.is_none() {
+                return Err(AiError::ThreadNotFound(id.clone()));
+            }
+            let bridges: Vec<serde_json::Value> = BridgeStorage::list_for_thread(ctx.agent_conn, id)?
+                .iter()
+                .map(|b| serde_json::json!({
+                    "id": b.id,
+                    "source_id": b.source_id,
+                    "target_id": b.target_id,
+                    "relation": b.relation_type.as_str(),
+                    "status": b.status.as_str(),
+                    "weight": b.weight,
+                    "confidence": b.confidence,
+                    "reason": b.reason,
+                    "shared_concepts": b.shared_concepts,
+                }))
+                .collect();
+            to_json(&serde_json::json!({"thread_id": id, "bridges": bridges}))
+        }
+        MemoryUri::Focus => {
+            let focus: Vec<serde_json::Value> = ThreadStorage::list_all(ctx.agent_conn)?
+                .iter()
+                .filter(|t| t.tags.iter().any(|tag| tag == "__focus__"))
+                .map(|t| serde_json::json!({
+                    "id": t.id,
+                    "topics": t.topics,
+                    "weight": t.weight,
+                    "last_active": t.last_active.to_rfc3339(),
+                }))
+                .collect();
+            to_json(&serde_json::json!({"focus": focus}))
+        }
+        MemoryUri::Inbox => {
+            let messages: Vec<serde_json::Value> = McpMessages::inbox(ctx.shared_conn, ctx.agent_id)?
+                .iter()
+                .map(|m| serde_json::json!({
+                    "id": m.id,
+                    "from": m.from_agent,
+                    "subject": m.subject,
+                    "content": m.content,
+                    "priority": m.priority.as_str(),
+                    "created_at": m.created_at.to_rfc3339(),
+                }))
+                .collect();
+            to_json(&serde_json::json!({"agent_id": ctx.agent_id, "messages": messages}))
+        }
+        MemoryUri::Handoff => {
+            let beat = BeatState::load(&path_utils::agent_data_dir(ctx.project_hash, ctx.agent_id));
+            let mut threads = ThreadStorage::list_by_status(ctx.agent_conn, &ThreadStatus::Active)?;
+            threads.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal));
+            threads.truncate(5);
+            Ok(render_handoff(ctx.agent_id, &beat, &threads))
+        }
+    }
+}
+
+fn render_thread(thread: &Thread, messages: &[ai_smartness::thread::ThreadMessage]) -> String {
+    let mut lines = vec![
+        format!("# {}", thread.title),
+        String::new(),
+        format!("- id: `{}`", thread.id),
+        format!("- status: {}", thread.status.as_str()),
+        format!("- weight: {:.2} | importance: {:.2}", thread.weight, thread.importance),
+        format!("- last active: {}", thread.last_active.to_rfc3339()),
+    ];
+    if !thread.topics.is_empty() {
+        lines.push(format!("- topics: {}", thread.topics.join(", ")));
+    }
+    if !thread.labels.is_empty() {
+        lines.push(format!("- labels: {}", thread.labels.join(", ")));
+    }
+    if let Some(ref summary) = thread.summary {
+        lines.push(String::new());
+        lines.push("## Summary".to_string());
+        lines.push(summary.clone());
+    }
+    if !messages.is_empty() {
+        lines.push(String::new());
+        lines.push("## Recent messages".to_string());
+        let skip = messages.len().saturating_sub(THREAD_MESSAGE_LIMIT);
+        for m in &messages[skip..] {
+            lines.push(format!("- [{}] {}: {}", m.timestamp.to_rfc3339(), m.source, m.content));
+        }
+    }
+    lines.join("\n")
+}
+
+fn render_handoff(agent_id: &str, beat: &BeatState, threads: &[Thread]) -> String {
+    let mut lines = vec![format!("# Session handoff — {}", agent_id), String::new()];
+    if let Some(ref branch) = beat.git_branch {
+        let dirty = if beat.git_dirty { " (dirty)" } else { "" };
+        lines.push(format!("- git: {}{}", branch, dirty));
+    }
+    lines.push(format!("- pending tasks: {}", beat.pending_tasks.len()));
+
+    if !beat.last_actions.is_empty() {
+        lines.push(String::new());
+        lines.push("## Last actions".to_string());
+        for a in &beat.last_actions {
+            match a.target.as_deref() {
+                Some(t) if !t.is_empty() => lines.push(format!("- {} → {}", a.tool, t)),
+                _ => lines.push(format!("- {}", a.tool)),
+            }
+        }
+    }
+    if !threads.is_empty() {
+        lines.push(String::new());
+        lines.push("## Active threads".to_string());
+        for t in threads {
+            lines.push(format!("- `{}` {} (weight {:.2})", t.id, t.title, t.weight));
+        }
+    }
+    lines.join("\n")
+}
+
+fn resource(uri: &str, name: &str, description: &str, mime: &str) -> serde_json::Value {
+    serde_json::json!({
+        "uri": uri,
+        "name": name,
+        "description": description,
+        "mimeType": mime,
+    })
+}
+
+fn to_json(v: &serde_json::Value) -> AiResult<String> {
+    serde_json::to_string_pretty(v).map_err(|e| AiError::Storage(e.to_string()))
+}
+
+// ─── Subscriptions ───
+
+/// Subscribed URIs with the fingerprint of their last rendering.
+#[derive(Default)]
+pub struct Subscriptions {
+    entries: HashMap<String, u64>,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&mut self, uri: &str, ctx: &ToolContext) -> AiResult<()> {
+        let parsed = MemoryUri::parse(uri)?;
+        let fp = fingerprint(&parsed, ctx);
+        self.entries.insert(uri.to_string(), fp);
+        Ok(())
+    }
+
+    pub fn unsubscribe(&mut self, uri: &str) -> bool {
+        self.entries.remove(uri).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Re-render every subscribed URI and return those whose content changed.
+    pub fn poll(&mut self, ctx: &ToolContext) -> Vec<String> {
+        let mut changed = Vec::new();
+        for (uri, last) in self.entries.iter_mut() {
+            let Ok(parsed) = MemoryUri::parse(uri) else { continue };
+            let fp = fingerprint(&parsed, ctx);
+            if fp != *last {
+                *last = fp;
+                changed.push(uri.clone());
+            }
+        }
+        changed.sort();
+        changed
+    }
+
+    /// Re-baseline all subscriptions (after an agent swap the old content is meaningless).
+    pub fn reset(&mut self, ctx: &ToolContext) {
+        for (uri, fp) in self.entries.iter_mut() {
+            if let Ok(parsed) = MemoryUri::parse(uri) {
+                *fp = fingerprint(&parsed, ctx);
+            }
+        }
+    }
+}
+
+/// Hash of the rendered resource; a missing thread hashes as the empty string
+/// so deletion is reported as an update too.
+fn fingerprint(uri: &MemoryUri, ctx: &ToolContext) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let text = render(uri, ctx).unwrap_or_default();
+    let mut h = std::collections::hash_map::DefaultHasher::new();
+    text.hash(&mut h);
+    h.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_db(migrate: fn(&Connection) -> AiResult<()>) -> Connection {
+        let conn = Connection::open(":memory:").unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        migrate(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_uris() {
+        assert_eq!(MemoryUri::parse("memory://thread/t1").unwrap(), MemoryUri::Thread("t1".into()));
+        assert_eq!(MemoryUri::parse("memory://bridges/t1").unwrap(), MemoryUri::Bridges("t1".into()));
+        assert_eq!(MemoryUri::parse("memory://focus").unwrap(), MemoryUri::Focus);
+        assert_eq!(MemoryUri::parse("memory://inbox").unwrap(), MemoryUri::Inbox);
+        assert_eq!(MemoryUri::parse("memory://handoff").unwrap(), MemoryUri::Handoff);
+        assert!(MemoryUri::parse("memory://thread/").is_err());
+        assert!(MemoryUri::parse("memory://unknown").is_err());
+        assert!(MemoryUri::parse("file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_read_thread_and_subscription_change() {
+        use ai_smartness::storage::migrations;
+        let agent = setup_db(migrations::migrate_agent_db);
+        let registry = setup_db(migrations::migrate_registry_db);
+        let shared = setup_db(migrations::migrate_shared_db);
+        let ctx = ToolContext {
+            agent_conn: &agent,
+            registry_conn: &registry,
+            shared_conn: &shared,
+            project_hash: "test-resources",
+            agent_id: "res_agent",
+            progress: crate::mcp::tools::ProgressSink::none(),
+        };
+
+        super::super::tools::focus::handle_focus(&serde_json::json!({"topic": "rust"}), &ctx).unwrap();
+
+        let content = read("memory://thread/focus_rust", &ctx).unwrap();
+        assert_eq!(content["mimeType"], MIME_MARKDOWN);
+        assert!(content["text"].as_str().unwrap().contains("# Focus: rust"));
+
+        let focus = read("memory://focus", &ctx).unwrap();
+        assert!(focus["text"].as_str().unwrap().contains("focus_rust"));
+
+        let mut subs = Subscriptions::default();
+        subs.subscribe("memory://thread/focus_rust", &ctx).unwrap();
+        assert!(subs.poll(&ctx).is_empty(), "No change yet");
+
+        ThreadStorage::update_weight(&agent, "focus_rust", 0.1).unwrap();
+        assert_eq!(subs.poll(&ctx), vec!["memory://thread/focus_rust".to_string()]);
+        assert!(subs.poll(&ctx).is_empty(), "Fingerprint re-baselined after notify");
+
+        assert!(matches!(read("memory://thread/missing", &ctx), Err(AiError::ThreadNotFound(_))));
+    }
+}
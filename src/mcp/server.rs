@@ -13,9 +13,13 @@ use std::io::{self, BufRead, Write};
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
-use super::jsonrpc::{self, JsonRpcResponse};
+use super::jsonrpc::{self, JsonRpcNotification, JsonRpcResponse};
+use super::progress::{self, CancelRegistry, Notifier, ProgressSink};
+use super::prompts;
+use super::resources::{self, Subscriptions};
 use super::tools::{self, ToolContext};
 
 pub struct McpServer {
@@ -26,8 +30,18 @@ pub struct McpServer {
     agent_conn: Connection,
     registry_conn: Connection,
     shared_conn: Connection,
+    /// Resource URIs the client subscribed to (resources/subscribe).
+    subscriptions: Subscriptions,
+    last_resource_poll: Instant,
+    /// Cancellation flags of in-flight requests (shared with the request reader).
+    cancels: CancelRegistry,
+    /// Pushes server notifications (progress) to the client; stdout by default.
+    notifier: Notifier,
 }
 
+/// How often subscribed resources are re-checked for changes.
+const RESOURCE_POLL_SECS: u64 = 2;
+
 impl McpServer {
     pub fn new(project_hash: String, agent_id: String) -> AiResult<Self> {
         let agent_db = path_utils::agent_db_path(&project_hash, &agent_id);
@@ -42,15 +56,34 @@ impl McpServer {
         migrations::migrate_registry_db(&registry_conn)?;
         migrations::migrate_shared_db(&shared_conn)?;
 
+        Ok(Self::from_connections(project_hash, agent_id, agent_conn, registry_conn, shared_conn))
+    }
+
+    /// Server over already opened and migrated connections.
+    pub fn from_connections(
+        project_hash: String,
+        agent_id: String,
+        agent_conn: Connection,
+        registry_conn: Connection,
+        shared_conn: Connection,
+    ) -> Self {
         let shared_agent_id = Arc::new(RwLock::new(agent_id.clone()));
-        Ok(Self {
+        Self {
             project_hash,
             agent_id,
             shared_agent_id,
             agent_conn,
             registry_conn,
             shared_conn,
-        })
+            subscriptions: Subscriptions::default(),
+            last_resource_poll: Instant::now(),
+            cancels: CancelRegistry::default(),
+            notifier: Arc::new(|note: &JsonRpcNotification| {
+                let mut stdout = io::stdout().lock();
+                let _ = writeln!(stdout, "{}", jsonrpc::format_notification(note));
+                let _ = stdout.flush();
+            }),
+        }
     }
 
     /// Hot-swap the active agent: update agent_id and reopen agent_conn.
@@ -67,6 +100,10 @@ impl McpServer {
 
         self.agent_id = new_agent_id.clone();
         self.agent_conn = new_conn;
+        // Subscriptions are taken out so the context can borrow `self` whole
+        let mut subscriptions = std::mem::take(&mut self.subscriptions);
+        subscriptions.reset(&self.tool_context());
+        self.subscriptions = subscriptions;
         // Notify heartbeat thread of the agent swap
         if let Ok(mut shared) = self.shared_agent_id.write() {
             *shared = new_agent_id;
@@ -74,33 +111,79 @@ impl McpServer {
         Ok(())
     }
 
-    pub fn run(&mut self) -> AiResult<()> {
-        // Start background heartbeat thread (PID tracking + self-wake + cognitive proactive)
+    /// Handle for the request reader to register and cancel in-flight requests.
+    pub fn cancel_registry(&self) -> CancelRegistry {
+        self.cancels.clone()
+    }
+
+    /// Replace the notification channel (the HTTP transport routes to the session's SSE stream).
+    pub fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = notifier;
+    }
+
+    /// Start the background heartbeat thread (PID tracking + self-wake + cognitive proactive).
+    /// Store `false` in the returned flag to stop it.
+    pub fn spawn_heartbeat(&self) -> (Arc<AtomicBool>, std::thread::JoinHandle<()>) {
         let bg_project_hash = self.project_hash.clone();
         let bg_shared_agent = self.shared_agent_id.clone();
         let bg_running = Arc::new(AtomicBool::new(true));
         let bg_running_clone = bg_running.clone();
 
-        let heartbeat_handle = std::thread::spawn(move || {
+        let handle = std::thread::spawn(move || {
             heartbeat_loop(&bg_project_hash, bg_shared_agent, bg_running_clone);
         });
+        (bg_running, handle)
+    }
+
+    pub fn run(&mut self) -> AiResult<()> {
+        let (bg_running, heartbeat_handle) = self.spawn_heartbeat();
+
+        // stdin is read on its own thread so tool calls run off the read loop:
+        // the main loop can push resource notifications while the client is
+        // idle, and `notifications/cancelled` reaches a running tool at once.
+        let (line_tx, line_rx) = mpsc::channel::<String>();
+        let cancels = self.cancel_registry();
+        std::thread::spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                let Ok(line) = line else { break };
+                if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
+                    if let Some(request_id) = progress::parse_cancelled(&msg) {
+                        if cancels.cancel(&request_id) {
+                            tracing::info!(request = %request_id, "MCP request cancelled by client");
+                        }
+                        continue;
+                    }
+                    cancels.register_call(&msg);
+                }
+                if line_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
 
-        let stdin = io::stdin();
         let mut stdout = io::stdout();
 
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(_) => break,
-            };
-
-            if line.trim().is_empty() {
-                continue;
+        loop {
+            match line_rx.recv_timeout(Duration::from_secs(RESOURCE_POLL_SECS)) {
+                Ok(line) => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if let Some(resp) = self.handle_message(&line) {
+                        let out = jsonrpc::format_response(&resp);
+                        let _ = writeln!(stdout, "{}", out);
+                        let _ = stdout.flush();
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
             }
-
-            if let Some(resp) = self.handle_message(&line) {
-                let out = jsonrpc::format_response(&resp);
-                let _ = writeln!(stdout, "{}", out);
+            let notes = self.poll_resource_updates();
+            for note in &notes {
+                let _ = writeln!(stdout, "{}", jsonrpc::format_notification(note));
+            }
+            if !notes.is_empty() {
                 let _ = stdout.flush();
             }
         }
@@ -112,7 +195,7 @@ impl McpServer {
         Ok(())
     }
 
-    fn handle_message(&mut self, input: &str) -> Option<JsonRpcResponse> {
+    pub fn handle_message(&mut self, input: &str) -> Option<JsonRpcResponse> {
         tracing::debug!(input_len = input.len(), "MCP request received");
         let request = match jsonrpc::parse_request(input) {
             Ok(r) => r,
@@ -127,6 +210,11 @@ impl McpServer {
 
         // Notifications (no id) don't get responses
         if request.id.is_none() {
+            if request.method == "notifications/cancelled" {
+                if let Some(request_id) = request.params.as_ref().and_then(|p| p.get("requestId")) {
+                    self.cancels.cancel(request_id);
+                }
+            }
             return None;
         }
 
@@ -135,7 +223,20 @@ impl McpServer {
         match request.method.as_str() {
             "initialize" => Some(self.handle_initialize(id)),
             "tools/list" => Some(self.handle_tools_list(id)),
-            "tools/call" => Some(self.handle_tools_call(id, &request.params)),
+            "tools/call" => self.handle_tools_call(id, &request.params),
+            "prompts/list" => Some(JsonRpcResponse::success(
+                id,
+                serde_json::json!({"prompts": prompts::list(&self.project_hash)}),
+            )),
+            "prompts/get" => Some(self.handle_prompts_get(id, &request.params)),
+            "resources/list" => Some(self.handle_resources_list(id)),
+            "resources/templates/list" => Some(JsonRpcResponse::success(
+                id,
+                serde_json::json!({"resourceTemplates": resources::templates()}),
+            )),
+            "resources/read" => Some(self.handle_resources_read(id, &request.params)),
+            "resources/subscribe" => Some(self.handle_resources_subscribe(id, &request.params, true)),
+            "resources/unsubscribe" => Some(self.handle_resources_subscribe(id, &request.params, false)),
             _ => Some(JsonRpcResponse::error(
                 id,
                 -32601,
@@ -150,7 +251,9 @@ impl McpServer {
             serde_json::json!({
                 "protocolVersion": "2024-11-05",
                 "capabilities": {
-                    "tools": {}
+                    "tools": {},
+                    "resources": {"subscribe": true, "listChanged": false},
+                    "prompts": {"listChanged": false}
                 },
                 "serverInfo": {
                     "name": "ai-smartness",
@@ -160,19 +263,143 @@ impl McpServer {
         )
     }
 
+    fn tool_context(&self) -> ToolContext<'_> {
+        ToolContext::new(&self.agent_conn, &self.registry_conn, &self.shared_conn, &self.project_hash, &self.agent_id)
+    }
+
+    fn handle_prompts_get(
+        &self,
+        id: Option<serde_json::Value>,
+        params: &Option<serde_json::Value>,
+    ) -> JsonRpcResponse {
+        let Some(name) = params.as_ref().and_then(|p| p.get("name")).and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing prompt name".into());
+        };
+        let arguments = params
+            .as_ref()
+            .and_then(|p| p.get("arguments"))
+            .cloned()
+            .unwrap_or(serde_json::json!({}));
+        match prompts::get(name, &arguments, &self.tool_context()) {
+            Ok(result) => JsonRpcResponse::success(id, result),
+            Err(ai_smartness::AiError::InvalidInput(msg)) => JsonRpcResponse::error(id, -32602, msg),
+            Err(e) => JsonRpcResponse::error(id, -32603, e.to_string()),
+        }
+    }
+
+    fn handle_resources_list(&self, id: Option<serde_json::Value>) -> JsonRpcResponse {
+        match resources::list(&self.tool_context()) {
+            Ok(list) => JsonRpcResponse::success(id, serde_json::json!({"resources": list})),
+            Err(e) => JsonRpcResponse::error(id, -32603, e.to_string()),
+        }
+    }
+
+    fn handle_resources_read(
+        &self,
+        id: Option<serde_json::Value>,
+        params: &Option<serde_json::Value>,
+    ) -> JsonRpcResponse {
+        let Some(uri) = params.as_ref().and_then(|p| p.get("uri")).and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing resource uri".into());
+        };
+        match resources::read(uri, &self.tool_context()) {
+            Ok(content) => JsonRpcResponse::success(id, serde_json::json!({"contents": [content]})),
+            Err(e) => resource_error(id, e),
+        }
+    }
+
+    fn handle_resources_subscribe(
+        &mut self,
+        id: Option<serde_json::Value>,
+        params: &Option<serde_json::Value>,
+        subscribe: bool,
+    ) -> JsonRpcResponse {
+        let Some(uri) = params.as_ref().and_then(|p| p.get("uri")).and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing resource uri".into());
+        };
+        if !subscribe {
+            self.subscriptions.unsubscribe(uri);
+            return JsonRpcResponse::success(id, serde_json::json!({}));
+        }
+        let mut subscriptions = std::mem::take(&mut self.subscriptions);
+        let subscribed = subscriptions.subscribe(uri, &self.tool_context());
+        self.subscriptions = subscriptions;
+        match subscribed {
+            Ok(()) => {
+                tracing::debug!(uri, "Resource subscribed");
+                JsonRpcResponse::success(id, serde_json::json!({}))
+            }
+            Err(e) => resource_error(id, e),
+        }
+    }
+
+    /// Build `notifications/resources/updated` for subscribed URIs that changed
+    /// since the last poll (daemon captures, decay, new messages, ...).
+    pub fn poll_resource_updates(&mut self) -> Vec<JsonRpcNotification> {
+        if self.subscriptions.is_empty()
+            || self.last_resource_poll.elapsed() < Duration::from_secs(RESOURCE_POLL_SECS)
+        {
+            return Vec::new();
+        }
+        self.last_resource_poll = Instant::now();
+        let mut subscriptions = std::mem::take(&mut self.subscriptions);
+        let changed = subscriptions.poll(&self.tool_context());
+        self.subscriptions = subscriptions;
+        changed
+            .into_iter()
+            .map(|uri| {
+                JsonRpcNotification::new(
+                    "notifications/resources/updated",
+                    serde_json::json!({"uri": uri}),
+                )
+            })
+            .collect()
+    }
+
     fn handle_tools_list(&self, id: Option<serde_json::Value>) -> JsonRpcResponse {
         JsonRpcResponse::success(
             id,
             serde_json::json!({
-                "tools": tool_definitions()
+                "tools": tools::registry::mcp_definitions()
             }),
         )
     }
 
+    /// tools/call with progress + cancellation. A cancelled request gets no
+    /// response (MCP: the receiver should not answer a cancelled request).
     fn handle_tools_call(
         &mut self,
         id: Option<serde_json::Value>,
         params: &Option<serde_json::Value>,
+    ) -> Option<JsonRpcResponse> {
+        let request_id = id.clone().unwrap_or(serde_json::Value::Null);
+        let cancelled = self.cancels.register(&request_id);
+        if cancelled.load(Ordering::Relaxed) {
+            self.cancels.finish(&request_id);
+            tracing::info!(request = %request_id, "Skipping request cancelled before it started");
+            return None;
+        }
+        let token = params
+            .as_ref()
+            .and_then(|p| p.get("_meta"))
+            .and_then(|m| m.get("progressToken"))
+            .cloned();
+        let sink = ProgressSink::new(token, cancelled.clone(), self.notifier.clone());
+
+        let response = self.execute_tool_call(id, params, sink);
+        self.cancels.finish(&request_id);
+        if cancelled.load(Ordering::Relaxed) {
+            tracing::info!(request = %request_id, "Dropping response of cancelled request");
+            return None;
+        }
+        Some(response)
+    }
+
+    fn execute_tool_call(
+        &mut self,
+        id: Option<serde_json::Value>,
+        params: &Option<serde_json::Value>,
+        progress: ProgressSink,
     ) -> JsonRpcResponse {
         let params = match params {
             Some(p) => p,
@@ -195,13 +422,7 @@ impl McpServer {
 
         // Phase 1: execute the tool (ToolContext borrows are scoped here)
         let tool_result = {
-            let ctx = ToolContext {
-                agent_conn: &self.agent_conn,
-                registry_conn: &self.registry_conn,
-                shared_conn: &self.shared_conn,
-                project_hash: &self.project_hash,
-                agent_id: &self.agent_id,
-            };
+            let ctx = ToolContext { progress, ..self.tool_context() };
             tracing::debug!(tool = %tool_name, "MCP tools/call dispatching");
             let tool_name_owned = tool_name.to_string();
             let arguments_owned = arguments.clone();
@@ -256,6 +477,23 @@ impl McpServer {
                     }),
                 )
             }
+            Err(ai_smartness::AiError::PolicyDenied { effect, rule, reason }) => {
+                let denial = serde_json::json!({
+                    "error": "policy_denied",
+                    "tool": tool_name,
+                    "effect": effect,
+                    "rule": rule,
+                    "reason": reason,
+                });
+                JsonRpcResponse::success(
+                    id,
+                    serde_json::json!({
+                        "content": [{"type": "text", "text": denial.to_string()}],
+                        "structuredContent": denial,
+                        "isError": true
+                    }),
+                )
+            }
             Err(e) => JsonRpcResponse::success(
                 id,
                 serde_json::json!({
@@ -267,6 +505,17 @@ impl McpServer {
     }
 }
 
+/// Map resource errors to MCP codes: unknown thread → -32002 (resource not found).
+fn resource_error(id: Option<serde_json::Value>, e: ai_smartness::AiError) -> JsonRpcResponse {
+    match e {
+        ai_smartness::AiError::ThreadNotFound(_) => {
+            JsonRpcResponse::error(id, -32002, format!("Resource not found: {}", e))
+        }
+        ai_smartness::AiError::InvalidInput(_) => JsonRpcResponse::error(id, -32602, e.to_string()),
+        _ => JsonRpcResponse::error(id, -32603, e.to_string()),
+    }
+}
+
 // ─── Background Heartbeat ───
 
 /// Get the parent process PID (Claude CLI) — cross-platform.
@@ -515,87 +764,6 @@ fn check_cognitive_proactive(project_hash: &str, agent_id: &str) {
     super::tools::messaging::emit_wake_signal(agent_id, "cognitive-inbox", "Pending cognitive messages", "cognitive", false);
 }
 
-fn tool_definitions() -> Vec<serde_json::Value> {
-    vec![
-        tool_def("ai_recall", "Search semantic memory for relevant threads", &["query"], &["label", "include_bridges", "depth"]),
-        tool_def("ai_thread_create", "Create a new thread manually", &["title", "content"], &["topics", "importance", "tags"]),
-        tool_def("ai_thread_rm", "Delete a thread by ID", &["thread_id"], &[]),
-        tool_def("ai_thread_rm_batch", "Delete multiple threads", &["thread_ids"], &[]),
-        tool_def("ai_thread_list", "List threads with filters", &[], &["status", "sort_by", "limit", "offset"]),
-        tool_def("ai_thread_search", "Search threads across all states", &["query"], &["scope", "states"]),
-        tool_def("ai_thread_activate", "Reactivate threads", &["thread_ids"], &["confirm"]),
-        tool_def("ai_thread_suspend", "Suspend active threads", &["thread_ids"], &["reason", "confirm"]),
-        tool_def("ai_thread_purge", "Bulk delete all threads by status (suspended/archived). Cannot purge active.", &["status"], &["confirm"]),
-        tool_def("ai_reactivate", "Reactivate a thread by ID", &["thread_id"], &[]),
-        tool_def("ai_annotate", "Add a lightweight note to a thread (no LLM, no extraction)", &["thread_id", "note"], &[]),
-        tool_def("ai_split", "Split a thread", &["thread_id"], &["confirm", "message_groups", "titles", "lock_mode"]),
-        tool_def("ai_split_unlock", "Remove split lock", &["thread_id"], &[]),
-        tool_def("ai_label", "Manage labels", &["thread_id"], &["labels", "mode"]),
-        tool_def("ai_labels_suggest", "Show existing labels", &["label"], &[]),
-        tool_def("ai_concepts", "Manage semantic concepts", &["thread_id"], &["concepts", "mode"]),
-        tool_def("ai_backfill_concepts", "Generate concepts for threads missing them", &[], &["limit", "dry_run"]),
-        tool_def("ai_rename", "Rename a thread", &["thread_id", "new_title"], &[]),
-        tool_def("ai_rename_batch", "Rename multiple threads", &["operations"], &[]),
-        tool_def("ai_rate_importance", "Set importance score", &["thread_id", "score"], &["reason"]),
-        tool_def("ai_rate_context", "Rate context usefulness", &["thread_id", "useful"], &["reason"]),
-        tool_def("ai_mark_used", "Mark thread as used after injection", &["thread_id"], &[]),
-        tool_def("ai_continuity_edges", "Manage continuity edges (reasoning chain between threads)", &[], &["action", "thread_id", "parent_id", "coherence"]),
-        tool_def("ai_bridges", "List bridges", &[], &["thread_id", "relation_type", "status"]),
-        tool_def("ai_bridge_analysis", "Bridge network analytics", &[], &[]),
-        tool_def("ai_bridge_scan_orphans", "Scan orphan bridges", &[], &["confirm"]),
-        tool_def("ai_bridge_purge", "Bulk delete all bridges by status (invalid/weak)", &["status"], &["confirm"]),
-        tool_def("ai_bridge_kill", "Delete a bridge", &["bridge_id"], &[]),
-        tool_def("ai_bridge_kill_batch", "Delete multiple bridges", &["bridge_ids"], &[]),
-        tool_def("ai_focus", "Focus on a topic", &["topic"], &["weight"]),
-        tool_def("ai_unfocus", "Remove focus", &[], &["topic"]),
-        tool_def("ai_pin", "Pin important content", &["content"], &["title", "topics", "weight_boost"]),
-        tool_def("ai_msg_focus", "Write cognitive message", &["target_agent_id", "from_agent", "subject", "content"], &["priority", "ttl_minutes", "attachments", "reply_to"]),
-        tool_def("ai_msg_ack", "Acknowledge message", &[], &["thread_id", "msg_ref"]),
-        tool_def("ai_share", "Share a thread", &["thread_id"], &["visibility", "allowed_agents"]),
-        tool_def("ai_unshare", "Unshare a thread", &["shared_id"], &[]),
-        tool_def("ai_publish", "Update shared snapshot", &["shared_id"], &[]),
-        tool_def("ai_discover", "Discover shared threads", &[], &["topics", "agent_id", "limit"]),
-        tool_def("ai_subscribe", "Subscribe to shared thread", &["shared_id"], &[]),
-        tool_def("ai_unsubscribe", "Unsubscribe", &["shared_id"], &[]),
-        tool_def("ai_sync", "Sync subscriptions", &[], &["shared_id"]),
-        tool_def("ai_status", "Memory status", &[], &[]),
-        tool_def("ai_sysinfo", "System info", &[], &[]),
-        tool_def("ai_help", "Documentation — call with topic for detailed help (memory, threads, bridges, messaging, sharing, agents, tasks, maintenance, autonomy)", &[], &["topic"]),
-        tool_def("ai_suggestions", "Proactive suggestions", &[], &["context"]),
-        tool_def("ai_shared_status", "Shared cognition status", &[], &[]),
-        tool_def("ai_profile", "User profile — view, set, set_rule, remove_rule, list, clear_rules", &["action"], &["key", "value"]),
-        tool_def("ai_cleanup", "Fix thread titles", &[], &["mode", "dry_run"]),
-        tool_def("ai_lock", "Lock memory", &[], &["reason", "duration_minutes"]),
-        tool_def("ai_unlock", "Unlock memory", &[], &[]),
-        tool_def("ai_lock_status", "Lock state", &[], &[]),
-        tool_def("ai_backup", "Backup/restore", &["action"], &["interval_hours"]),
-        tool_def("ai_recommend", "Subscription recommendations", &[], &["limit"]),
-        tool_def("ai_topics", "Topic discovery", &[], &["agent_id"]),
-        tool_def("msg_send", "Send message", &["to", "subject"], &["payload", "priority", "agent_id", "attachments"]),
-        tool_def("msg_broadcast", "Broadcast message", &["subject"], &["payload", "priority", "attachments"]),
-        tool_def("msg_inbox", "Get pending messages", &[], &["limit", "agent_id"]),
-        tool_def("msg_reply", "Reply to message", &["message_id"], &["payload", "agent_id"]),
-        tool_def("ai_agent_select", "Switch to a different agent for this session. Writes the session file so subsequent prompts use the new agent identity. Pass session_id from your context for multi-panel isolation.", &["agent_id"], &["session_id"]),
-        tool_def("agent_list", "List agents", &[], &[]),
-        tool_def("agent_query", "Find agents by capability", &["capability"], &[]),
-        tool_def("agent_status", "Agent status", &["agent_id"], &[]),
-        tool_def("agent_context", "Inspect another agent's runtime state (beat, actions, tasks, context %)", &[], &["agent_id"]),
-        tool_def("agent_cleanup", "Clean up agents", &[], &["remove_agent", "remove_orphans"]),
-        tool_def("agent_configure", "Configure agent", &["agent_id", "project_hash"], &["role", "supervisor_id"]),
-        tool_def("agent_tasks", "Manage tasks", &["action"], &[]),
-        tool_def("task_delegate", "Delegate task", &["to", "task"], &["context", "priority", "context_path"]),
-        tool_def("task_status", "Task status", &["task_id"], &[]),
-        tool_def("task_complete", "Mark a delegated task as completed and auto-notify the delegator", &["task_id"], &["result"]),
-        tool_def("metrics_cross_agent", "Cross-agent metrics", &[], &["agent_id", "period"]),
-        tool_def("health_check", "Health check", &[], &[]),
-        tool_def("topics_network", "Trending topics", &[], &["agent_id", "limit"]),
-        tool_def("test_sampling", "Test sampling", &[], &["attempt_sampling"]),
-        tool_def("beat_wake", "Schedule self-wake after N beats (~5 min each). The heartbeat system will wake you automatically.", &["after"], &["reason"]),
-        tool_def("nanobeat_schedule", "Schedule a sub-beat self-wake with recall context. Use this to chain tasks autonomously: when finishing work, schedule a nanobeat so you wake up and continue with the next task.", &["delay_seconds", "reason"], &["recall_query", "recall_thread_id"]),
-        tool_def("ai_windows", "Open a new VSCode window on the current project", &[], &[]),
-    ]
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -653,23 +821,3 @@ mod tests {
         assert!(beat_dirty.git_dirty, "Should be dirty with untracked file");
     }
 }
-
-fn tool_def(name: &str, desc: &str, required: &[&str], optional: &[&str]) -> serde_json::Value {
-    let mut props = serde_json::Map::new();
-    for &r in required.iter().chain(optional.iter()) {
-        props.insert(
-            r.to_string(),
-            serde_json::json!({"type": "string", "description": r}),
-        );
-    }
-    let req: Vec<&str> = required.to_vec();
-    serde_json::json!({
-        "name": name,
-        "description": desc,
-        "inputSchema": {
-            "type": "object",
-            "properties": props,
-            "required": req
-        }
-    })
-}
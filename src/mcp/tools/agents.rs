@@ -6,16 +6,28 @@ use ai_smartness::{id_gen, time_utils};
 use ai_smartness::agent::{AgentTask, TaskPriority, TaskStatus};
 use ai_smartness::constants::{truncate_safe, MAX_MESSAGE_SIZE_BYTES};
 use ai_smartness::message::{Message, MessagePriority, MessageStatus};
+use ai_smartness::processing::daemon_ipc_client;
 use ai_smartness::storage::mcp_messages::McpMessages;
 use ai_smartness::AiResult;
 
+use super::confirm::Preview;
+use super::paging::{ListSpec, Page};
 use super::{optional_str, required_str, ToolContext, ToolOutput};
 use super::messaging::emit_wake_signal;
 
+pub const AGENTS: ListSpec = ListSpec {
+    items_key: "agents",
+    id_field: "id",
+    sort_keys: &["name", "id", "role", "status"],
+    desc_by_default: &[],
+    default_limit: 50,
+};
+
 pub fn handle_agent_list(
-    _params: &serde_json::Value,
+    params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
+    let page = Page::parse(params, &AGENTS)?;
     let agents = AgentRegistry::list(ctx.registry_conn, Some(ctx.project_hash), None, None)?;
 
     let hb_config = HeartbeatConfig::default();
@@ -40,7 +52,7 @@ pub fn handle_agent_list(
         })
         .collect();
 
-    Ok(serde_json::json!({"agents": results, "count": results.len()}))
+    Ok(page.apply(results))
 }
 
 pub fn handle_agent_query(
@@ -122,20 +134,8 @@ pub fn handle_agent_cleanup(
     }
 
     // Remove orphan agents whose project no longer exists
-    if optional_str(params, "remove_orphans").is_some() {
-        let orphans: Vec<(String, String)> = ctx.registry_conn
-            .prepare(
-                "SELECT a.id, a.project_hash FROM agents a \
-                 LEFT JOIN projects p ON a.project_hash = p.hash \
-                 WHERE p.hash IS NULL",
-            )
-            .and_then(|mut stmt| {
-                stmt.query_map([], |row| {
-                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-                })
-                .map(|rows| rows.flatten().collect())
-            })
-            .unwrap_or_default();
+    if super::optional_bool(params, "remove_orphans").unwrap_or(false) {
+        let orphans = orphan_agents(ctx.registry_conn);
 
         let mut removed = Vec::new();
         for (agent_id, project_hash) in &orphans {
@@ -157,6 +157,64 @@ pub fn handle_agent_cleanup(
     Ok(serde_json::json!({"action": "cleanup", "status": "ok"}))
 }
 
+/// Agents whose project no longer exists: (agent_id, project_hash).
+fn orphan_agents(conn: &rusqlite::Connection) -> Vec<(String, String)> {
+    conn.prepare(
+            "SELECT a.id, a.project_hash FROM agents a \
+             LEFT JOIN projects p ON a.project_hash = p.hash \
+             WHERE p.hash IS NULL",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map(|rows| rows.flatten().collect())
+        })
+        .unwrap_or_default()
+}
+
+/// Two-phase preview of agent removal (stale marking runs directly).
+pub fn preview_agent_cleanup(
+    params: &serde_json::Value,
+    ctx: &ToolContext,
+) -> AiResult<Option<Preview>> {
+    if let Some(agent_id) = optional_str(params, "remove_agent") {
+        let agent = AgentRegistry::get(ctx.registry_conn, &agent_id, ctx.project_hash)?;
+        let pending_tasks = AgentTaskStorage::list_tasks_for_agent(ctx.registry_conn, &agent_id, ctx.project_hash)
+            .map(|tasks| tasks.iter().filter(|t| !matches!(t.status, TaskStatus::Completed)).count())
+            .unwrap_or(0);
+        let message = match agent {
+            Some(ref a) => format!("Would remove agent '{}' ({}) from this project", a.id, a.role),
+            None => format!("Agent {} is not registered in this project — nothing to remove", agent_id),
+        };
+        let ids: Vec<String> = agent.map(|a| vec![a.id]).unwrap_or_default();
+        let preview = Preview::new(ids.clone(), message)
+            .counts(serde_json::json!({"agents": ids.len()}))
+            .side_effects(serde_json::json!({
+                "open_tasks_left_assigned": pending_tasks,
+                "claude_md_refreshed": true,
+            }));
+        return Ok(Some(preview));
+    }
+
+    if super::optional_bool(params, "remove_orphans").unwrap_or(false) {
+        let orphans = orphan_agents(ctx.registry_conn);
+        let items = orphans
+            .iter()
+            .map(|(id, ph)| serde_json::json!({"agent_id": id, "project_hash": ph}))
+            .collect();
+        let preview = Preview::new(
+            orphans.iter().map(|(id, _)| id.clone()).collect(),
+            format!("Would remove {} orphan agent(s) whose project no longer exists", orphans.len()),
+        )
+        .counts(serde_json::json!({"agents": orphans.len()}))
+        .items(items);
+        return Ok(Some(preview));
+    }
+
+    Ok(None)
+}
+
 pub fn handle_agent_configure(
     params: &serde_json::Value,
     ctx: &ToolContext,
@@ -255,6 +313,7 @@ pub fn handle_agent_tasks(
                 new_status,
                 result.as_deref(),
             )?;
+            publish_task_event(ctx, &task_id);
             Ok(serde_json::json!({"updated": task_id}))
         }
         "delete" => {
@@ -399,6 +458,24 @@ pub fn handle_task_status(
     }
 }
 
+/// Tell daemon event subscribers a task changed status (scoped to its assignee).
+fn publish_task_event(ctx: &ToolContext, task_id: &str) {
+    let Ok(Some(task)) = AgentTaskStorage::get_task(ctx.registry_conn, task_id, ctx.project_hash) else { return };
+    daemon_ipc_client::publish_event(
+        "task_status_changed",
+        ctx.project_hash,
+        Some(&task.assigned_to),
+        serde_json::json!({
+            "task_id": task.id,
+            "title": task.title,
+            "status": task.status.as_str(),
+            "assigned_by": task.assigned_by,
+            "updated_by": ctx.agent_id,
+            "result": task.result,
+        }),
+    );
+}
+
 pub fn handle_task_complete(
     params: &serde_json::Value,
     ctx: &ToolContext,
@@ -412,6 +489,7 @@ pub fn handle_task_complete(
         TaskStatus::Completed,
         result.as_deref(),
     )?;
+    publish_task_event(ctx, &task_id);
 
     if let Some(task) = AgentTaskStorage::get_task(ctx.registry_conn, &task_id, ctx.project_hash)? {
         let subject = format!("Task completed: {}", task.title);
@@ -532,6 +610,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({
@@ -568,6 +647,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({
@@ -606,6 +686,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({"to": target, "task": "Wake test task"});
@@ -683,6 +764,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH_B,
             agent_id: "tester-b",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let params = serde_json::json!({"capability": "coding"});
         let result = handle_agent_query(&params, &ctx_b).unwrap();
@@ -695,6 +777,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH_A,
             agent_id: "coder-a",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let result_a = handle_agent_query(&params, &ctx_a).unwrap();
         assert_eq!(result_a["count"], 1, "Project A should find its own agent");
@@ -716,6 +799,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH_A,
             agent_id: "agent-cfg",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         // Pass a DIFFERENT project_hash in params — it must be ignored
@@ -749,6 +833,7 @@ mod tests {
         let ctx_del = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: TC_DEL,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let del = handle_task_delegate(
             &serde_json::json!({"to": TC_WRK, "task": "Status update task"}),
@@ -759,6 +844,7 @@ mod tests {
         let ctx_wrk = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: TC_WRK,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let result = handle_task_complete(
             &serde_json::json!({"task_id": task_id}),
@@ -790,6 +876,7 @@ mod tests {
         let ctx_del = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: TC_DEL,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let del = handle_task_delegate(
             &serde_json::json!({"to": TC_WRK, "task": "Notify test task"}),
@@ -800,6 +887,7 @@ mod tests {
         let ctx_wrk = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: TC_WRK,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let result = handle_task_complete(
             &serde_json::json!({"task_id": task_id}),
@@ -838,6 +926,7 @@ mod tests {
         let ctx_del = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: TC_DEL,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let del = handle_task_delegate(
             &serde_json::json!({"to": TC_WRK, "task": "Result task"}),
@@ -848,6 +937,7 @@ mod tests {
         let ctx_wrk = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: TC_WRK,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         handle_task_complete(
             &serde_json::json!({"task_id": task_id, "result": "Build passed: 122 tests"}),
@@ -873,6 +963,7 @@ mod tests {
         let ctx = ToolContext {
             agent_conn: &agent_conn, registry_conn: &registry_conn,
             shared_conn: &shared_conn, project_hash: PH, agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let result = handle_task_complete(
             &serde_json::json!({"task_id": "nonexistent-task-id-xyz"}),
@@ -900,6 +991,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH_A,
             agent_id: "worker-a",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let del_params = serde_json::json!({"to": "worker-a", "task": "Isolated task"});
         let del_result = handle_task_delegate(&del_params, &ctx_a).unwrap();
@@ -912,6 +1004,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH_B,
             agent_id: "worker-b",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let status_params = serde_json::json!({"task_id": task_id});
         let result = handle_task_status(&status_params, &ctx_b);
@@ -0,0 +1,273 @@
+//! ai_batch — run an ordered list of memory edits atomically.
+//!
+//! All steps run inside one savepoint on agent_conn and one on shared_conn:
+//! the first failing step rolls everything back. `dry_run` runs the steps the
+//! same way and always rolls back, so the report shows exactly what would change.
+//!
+//! Step arguments may reference earlier results: a string `"$N.path"` is
+//! replaced by the value at `path` in the result of step N (1-based), e.g.
+//! `"$1.thread_id"` or `"$2.new_threads.0.thread_id"`. `"$N"` alone is the
+//! whole result.
+//!
+//! Only tools whose writes stay in those two databases are accepted.
+//!
+//! A batch containing a two-phase tool (confirm.rs) is itself two-phase: the
+//! preview is a dry run of the whole batch, and the token covers every step.
+
+use ai_smartness::config::GuardianConfig;
+use ai_smartness::storage::audit;
+use ai_smartness::storage::database::NestedTx;
+use ai_smartness::{AiError, AiResult};
+use serde_json::Value;
+
+use super::confirm::Preview;
+use super::{check_policy, optional_bool, registry, ToolContext, ToolOutput};
+
+/// Max steps per batch.
+pub const MAX_BATCH_STEPS: usize = 50;
+
+/// Tools allowed in a batch (DB-only side effects, no agent switch).
+const BATCHABLE: &[&str] = &[
+    // reads (useful as reference sources)
+    "ai_thread_list", "ai_thread_search", "ai_bridges", "ai_labels_suggest",
+    // threads
+    "ai_thread_create", "ai_thread_rm", "ai_thread_rm_batch", "ai_thread_activate",
+    "ai_thread_suspend", "ai_reactivate", "ai_annotate", "ai_split", "ai_split_unlock",
+    "ai_label", "ai_concepts", "ai_topic_alias", "ai_rename", "ai_rename_batch",
+    "ai_rate_importance", "ai_continuity_edges",
+    // bridges
+    "ai_bridge_kill", "ai_bridge_kill_batch", "ai_resolve_conflict",
+    // shared cognition
+    "ai_share", "ai_unshare",
+];
+
+struct Step {
+    tool: String,
+    args: Value,
+}
+
+pub fn handle_batch(params: &Value, ctx: &ToolContext) -> AiResult<Value> {
+    let steps = parse_steps(params)?;
+    let dry_run = optional_bool(params, "dry_run").unwrap_or(false);
+    let guardian = super::guardian_config();
+
+    let agent_tx = NestedTx::begin(ctx.agent_conn)?;
+    let shared_tx = NestedTx::begin(ctx.shared_conn)?;
+
+    let total = steps.len() as u64;
+    let mut results: Vec<Value> = Vec::with_capacity(steps.len());
+    let mut report: Vec<Value> = Vec::with_capacity(steps.len());
+    let mut affected: Vec<String> = Vec::new();
+
+    for (i, step) in steps.iter().enumerate() {
+        let n = i + 1;
+        // Dropping the savepoints on `?` rolls back every previous step
+        let outcome = ctx
+            .progress
+            .check_cancelled()
+            .and_then(|_| resolve_refs(&step.args, &results, n))
+            .and_then(|args| run_step(step, &args, ctx, &guardian).map(|r| (args, r)));
+        let (args, result) = match outcome {
+            Ok(ok) => ok,
+            Err(e) => {
+                drop(shared_tx);
+                drop(agent_tx);
+                return Err(AiError::InvalidState(format!(
+                    "ai_batch rolled back: step {} ({}) failed: {}",
+                    n, step.tool, e
+                )));
+            }
+        };
+        affected.extend(audit::affected_ids(&args, Some(&result)));
+        report.push(serde_json::json!({"step": n, "tool": step.tool, "result": result}));
+        results.push(result);
+        ctx.progress.report(n as u64, Some(total), &step.tool);
+    }
+
+    if dry_run {
+        shared_tx.rollback()?;
+        agent_tx.rollback()?;
+    } else {
+        agent_tx.commit()?;
+        shared_tx.commit()?;
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    affected.retain(|id| seen.insert(id.clone()));
+    Ok(serde_json::json!({
+        "dry_run": dry_run,
+        "committed": !dry_run,
+        "steps": report,
+        "affected_ids": affected,
+    }))
+}
+
+/// Two-phase when a step is a two-phase tool or needs policy confirmation.
+/// Bound to the ids named literally in the steps (created ids change per run).
+pub fn preview_batch(params: &Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let steps = parse_steps(params)?;
+    let guardian = super::guardian_config();
+    let mut gated = Vec::new();
+    for step in &steps {
+        let spec = registry::find(&step.tool)
+            .ok_or_else(|| AiError::InvalidInput(format!("Unknown tool: {}", step.tool)))?;
+        if spec.preview.is_some() || check_policy(spec, &step.args, ctx, &guardian.tool_policy)?.is_some() {
+            gated.push(step.tool.clone());
+        }
+    }
+    if gated.is_empty() {
+        return Ok(None);
+    }
+
+    let mut dry = params.clone();
+    dry["dry_run"] = Value::Bool(true);
+    let report = handle_batch(&dry, ctx)?;
+    let mut ids: Vec<String> = steps
+        .iter()
+        .flat_map(|s| audit::affected_ids(&s.args, None))
+        .filter(|id| !id.starts_with('$'))
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+
+    let preview = Preview::new(ids, format!("Would run {} step(s) atomically", steps.len()))
+        .counts(serde_json::json!({"steps": steps.len(), "gated_steps": gated}))
+        .side_effects(serde_json::json!({"dry_run_affected_ids": report["affected_ids"]}))
+        .items(report["steps"].as_array().cloned().unwrap_or_default());
+    Ok(Some(preview))
+}
+
+fn parse_steps(params: &Value) -> AiResult<Vec<Step>> {
+    let raw = match params.get("steps") {
+        Some(Value::Array(a)) => a.clone(),
+        Some(Value::String(s)) => match serde_json::from_str::<Value>(s.trim()) {
+            Ok(Value::Array(a)) => a,
+            _ => return Err(AiError::InvalidInput("steps must be an array of {tool, args}".into())),
+        },
+        _ => return Err(AiError::InvalidInput("Missing required array: steps".into())),
+    };
+    if raw.is_empty() {
+        return Err(AiError::InvalidInput("steps is empty".into()));
+    }
+    if raw.len() > MAX_BATCH_STEPS {
+        return Err(AiError::InvalidInput(format!(
+            "Too many steps: {} (max {})", raw.len(), MAX_BATCH_STEPS
+        )));
+    }
+    raw.iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let tool = s.get("tool").and_then(|t| t.as_str()).ok_or_else(|| {
+                AiError::InvalidInput(format!("step {}: missing 'tool'", i + 1))
+            })?;
+            if !BATCHABLE.contains(&tool) {
+                return Err(AiError::InvalidInput(format!(
+                    "step {}: '{}' cannot run in a batch (allowed: {})",
+                    i + 1, tool, BATCHABLE.join(", ")
+                )));
+            }
+            Ok(Step {
+                tool: tool.to_string(),
+                args: s.get("args").cloned().unwrap_or_else(|| serde_json::json!({})),
+            })
+        })
+        .collect()
+}
+
+fn run_step(step: &Step, args: &Value, ctx: &ToolContext, guardian: &GuardianConfig) -> AiResult<Value> {
+    let spec = registry::find(&step.tool)
+        .ok_or_else(|| AiError::InvalidInput(format!("Unknown tool: {}", step.tool)))?;
+    // Deny fails the step; Confirm was covered by the batch's own confirmation
+    check_policy(spec, args, ctx, &guardian.tool_policy)?;
+    match spec.call(args, ctx)? {
+        ToolOutput::Plain(v) => Ok(v),
+        ToolOutput::AgentSwitch { result, .. } => Ok(result),
+    }
+}
+
+/// Replace `"$N.path"` strings by values from earlier step results.
+fn resolve_refs(value: &Value, results: &[Value], current: usize) -> AiResult<Value> {
+    match value {
+        Value::String(s) => match parse_ref(s) {
+            Some((step, path)) => {
+                if step == 0 || step >= current {
+                    return Err(AiError::InvalidInput(format!(
+                        "'{}': can only reference an earlier step (1..{})", s, current - 1
+                    )));
+                }
+                lookup(&results[step - 1], path).cloned().ok_or_else(|| {
+                    AiError::InvalidInput(format!("'{}': no such field in step {} result", s, step))
+                })
+            }
+            None => Ok(value.clone()),
+        },
+        Value::Array(items) => items
+            .iter()
+            .map(|v| resolve_refs(v, results, current))
+            .collect::<AiResult<Vec<_>>>()
+            .map(Value::Array),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| resolve_refs(v, results, current).map(|r| (k.clone(), r)))
+            .collect::<AiResult<serde_json::Map<_, _>>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+/// `"$3.new_threads.0.thread_id"` → (3, "new_threads.0.thread_id").
+fn parse_ref(s: &str) -> Option<(usize, &str)> {
+    let rest = s.strip_prefix('$')?;
+    let (num, path) = match rest.split_once('.') {
+        Some((n, p)) => (n, p),
+        None => (rest, ""),
+    };
+    if num.is_empty() || !num.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((num.parse().ok()?, path))
+}
+
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.').try_fold(value, |v, seg| match v {
+        Value::Array(items) => seg.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => v.get(seg),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_refs() {
+        let results = vec![
+            serde_json::json!({"thread_id": "t-new"}),
+            serde_json::json!({"new_threads": [{"thread_id": "t-a"}, {"thread_id": "t-b"}]}),
+        ];
+        let args = serde_json::json!({
+            "thread_id": "$1.thread_id",
+            "ids": ["$2.new_threads.1.thread_id", "plain"],
+            "price": "$5 is not a ref",
+        });
+        let out = resolve_refs(&args, &results, 3).unwrap();
+        assert_eq!(out["thread_id"], "t-new");
+        assert_eq!(out["ids"], serde_json::json!(["t-b", "plain"]));
+        assert_eq!(out["price"], "$5 is not a ref");
+
+        assert!(resolve_refs(&serde_json::json!("$3.x"), &results, 3).is_err(), "Forward reference");
+        assert!(resolve_refs(&serde_json::json!("$1.missing"), &results, 3).is_err());
+    }
+
+    #[test]
+    fn test_parse_steps_rejects_unbatchable() {
+        let ok = serde_json::json!({"steps": [{"tool": "ai_rename", "args": {"thread_id": "t", "new_title": "x"}}]});
+        assert_eq!(parse_steps(&ok).unwrap().len(), 1);
+        let bad = serde_json::json!({"steps": [{"tool": "ai_backup", "args": {"action": "create"}}]});
+        assert!(parse_steps(&bad).is_err());
+        assert!(parse_steps(&serde_json::json!({"steps": []})).is_err());
+    }
+}
@@ -1,13 +1,30 @@
-use ai_smartness::AiResult;
+use ai_smartness::{id_gen, time_utils, AiError, AiResult};
+use ai_smartness::bridge::{BridgeStatus, BridgeType, ThinkBridge};
 use ai_smartness::storage::bridges::BridgeStorage;
+use ai_smartness::storage::database::NestedTx;
+use ai_smartness::storage::threads::ThreadStorage;
+use ai_smartness::thread::ThreadStatus;
 
-use super::{optional_bool, optional_str, required_array, required_str, ToolContext};
+use super::confirm::Preview;
+use super::paging::{ListSpec, Page};
+use super::{optional_bool, optional_str, required_array, required_str, ToolContext, PROGRESS_EVERY};
+
+pub const BRIDGES: ListSpec = ListSpec {
+    items_key: "bridges",
+    id_field: "id",
+    sort_keys: &["weight", "created_at", "use_count"],
+    desc_by_default: &["weight", "created_at", "use_count"],
+    default_limit: 50,
+};
 
 pub fn handle_bridges(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let thread_id = optional_str(params, "thread_id");
+    let relation = optional_str(params, "relation_type");
+    let status = optional_str(params, "status");
+    let page = Page::parse(params, &BRIDGES)?;
 
     let bridges = if let Some(ref tid) = thread_id {
         BridgeStorage::list_for_thread(ctx.agent_conn, tid)?
@@ -17,6 +34,8 @@ pub fn handle_bridges(
 
     let results: Vec<serde_json::Value> = bridges
         .iter()
+        .filter(|b| relation.as_deref().is_none_or(|r| b.relation_type.as_str() == r))
+        .filter(|b| status.as_deref().is_none_or(|s| b.status.as_str() == s))
         .map(|b| {
             serde_json::json!({
                 "id": b.id,
@@ -31,7 +50,7 @@ pub fn handle_bridges(
         })
         .collect();
 
-    Ok(serde_json::json!({"bridges": results, "count": results.len()}))
+    Ok(page.apply(results))
 }
 
 pub fn handle_bridge_analysis(
@@ -49,10 +68,15 @@ pub fn handle_bridge_analysis(
     };
 
     let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
-    for b in &all {
+    for (i, b) in all.iter().enumerate() {
+        if i % PROGRESS_EVERY == 0 {
+            ctx.progress.check_cancelled()?;
+            ctx.progress.report(i as u64, Some(total as u64), "counting bridge endpoints");
+        }
         *counts.entry(b.source_id.clone()).or_insert(0) += 1;
         *counts.entry(b.target_id.clone()).or_insert(0) += 1;
     }
+    ctx.progress.report(total as u64, Some(total as u64), "done");
     let mut top: Vec<(String, usize)> = counts.into_iter().collect();
     top.sort_by(|a, b| b.1.cmp(&a.1));
     top.truncate(5);
@@ -67,20 +91,10 @@ pub fn handle_bridge_analysis(
 }
 
 pub fn handle_bridge_scan_orphans(
-    params: &serde_json::Value,
+    _params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let confirm = optional_bool(params, "confirm").unwrap_or(false);
     let orphans = BridgeStorage::scan_orphans(ctx.agent_conn)?;
-
-    if !confirm {
-        let preview: Vec<serde_json::Value> = orphans
-            .iter()
-            .map(|b| serde_json::json!({"id": b.id, "source_id": b.source_id, "target_id": b.target_id}))
-            .collect();
-        return Ok(serde_json::json!({"dry_run": true, "orphans": preview, "count": preview.len()}));
-    }
-
     let ids: Vec<String> = orphans.iter().map(|b| b.id.clone()).collect();
     let deleted = BridgeStorage::delete_batch(ctx.agent_conn, &ids)?;
     Ok(serde_json::json!({"deleted": deleted}))
@@ -90,23 +104,7 @@ pub fn handle_bridge_purge(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let status_str = required_str(params, "status")?;
-    let confirm = optional_bool(params, "confirm").unwrap_or(false);
-    let status: ai_smartness::bridge::BridgeStatus = status_str
-        .parse()
-        .map_err(|e: String| ai_smartness::AiError::InvalidInput(e))?;
-
-    let count = BridgeStorage::count_by_status(ctx.agent_conn, &status)?;
-
-    if !confirm {
-        return Ok(serde_json::json!({
-            "dry_run": true,
-            "status": status.as_str(),
-            "count": count,
-            "message": format!("Would delete {} {} bridge(s). Pass confirm=true to execute.", count, status.as_str())
-        }));
-    }
-
+    let status = purge_status(params)?;
     let deleted = BridgeStorage::delete_by_status(ctx.agent_conn, &status)?;
     Ok(serde_json::json!({
         "purged": deleted,
@@ -131,3 +129,145 @@ pub fn handle_bridge_kill_batch(
     let deleted = BridgeStorage::delete_batch(ctx.agent_conn, &ids)?;
     Ok(serde_json::json!({"deleted": deleted}))
 }
+
+fn purge_status(params: &serde_json::Value) -> AiResult<BridgeStatus> {
+    required_str(params, "status")?
+        .parse()
+        .map_err(|e: String| AiError::InvalidInput(e))
+}
+
+// ── Two-phase previews (see confirm.rs) ──
+
+/// Preview deleting the given bridges (unknown ids reported as missing).
+fn preview_bridges(bridges: Vec<ThinkBridge>, missing: Vec<String>, message: String) -> Preview {
+    let mut threads: Vec<&str> = bridges
+        .iter()
+        .flat_map(|b| [b.source_id.as_str(), b.target_id.as_str()])
+        .collect();
+    threads.sort_unstable();
+    threads.dedup();
+    let side_effects = serde_json::json!({"threads_losing_bridges": threads.len()});
+    let items = bridges
+        .iter()
+        .map(|b| serde_json::json!({"id": b.id, "source_id": b.source_id, "target_id": b.target_id, "status": b.status.as_str()}))
+        .collect();
+    Preview::new(bridges.iter().map(|b| b.id.clone()).collect(), message)
+        .counts(serde_json::json!({"bridges": bridges.len(), "missing": missing}))
+        .side_effects(side_effects)
+        .items(items)
+}
+
+fn preview_bridge_ids(ids: &[String], ctx: &ToolContext) -> AiResult<Preview> {
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    for id in ids {
+        match BridgeStorage::get(ctx.agent_conn, id)? {
+            Some(b) => found.push(b),
+            None => missing.push(id.clone()),
+        }
+    }
+    let message = format!("Would delete {} of {} bridge(s)", found.len(), ids.len());
+    Ok(preview_bridges(found, missing, message))
+}
+
+pub fn preview_bridge_kill(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let id = required_str(params, "bridge_id")?;
+    preview_bridge_ids(&[id], ctx).map(Some)
+}
+
+pub fn preview_bridge_kill_batch(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let ids = required_array(params, "bridge_ids")?;
+    preview_bridge_ids(&ids, ctx).map(Some)
+}
+
+pub fn preview_bridge_purge(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let status = purge_status(params)?;
+    let bridges = BridgeStorage::list_by_status(ctx.agent_conn, status.clone())?;
+    let message = format!("Would delete {} {} bridge(s)", bridges.len(), status.as_str());
+    Ok(Some(preview_bridges(bridges, Vec::new(), message)))
+}
+
+pub fn preview_bridge_scan_orphans(_params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let orphans = BridgeStorage::scan_orphans(ctx.agent_conn)?;
+    let message = format!("Would delete {} orphan bridge(s)", orphans.len());
+    Ok(Some(preview_bridges(orphans, Vec::new(), message)))
+}
+
+/// Resolve a contradiction: drop the `contradicts` bridges between the pair and
+/// record a single `replaces` bridge (keep → replaced). Optionally suspends the
+/// replaced thread. All writes share one transaction.
+pub fn handle_resolve_conflict(
+    params: &serde_json::Value,
+    ctx: &ToolContext,
+) -> AiResult<serde_json::Value> {
+    let keep_id = required_str(params, "keep_id")?;
+    let replaced_id = required_str(params, "replaced_id")?;
+    let reason = optional_str(params, "reason");
+    let suspend = optional_bool(params, "suspend").unwrap_or(false);
+
+    if keep_id == replaced_id {
+        return Err(AiError::InvalidInput("keep_id and replaced_id must differ".into()));
+    }
+    for id in [&keep_id, &replaced_id] {
+        if ThreadStorage::get(ctx.agent_conn, id)?.is_none() {
+            return Err(AiError::ThreadNotFound(id.clone()));
+        }
+    }
+
+    let contradictions: Vec<ThinkBridge> = BridgeStorage::list_between(ctx.agent_conn, &keep_id, &replaced_id)?
+        .into_iter()
+        .filter(|b| b.relation_type == BridgeType::Contradicts)
+        .collect();
+    if contradictions.is_empty() {
+        return Err(AiError::InvalidInput(format!(
+            "No contradicts bridge between {} and {}", keep_id, replaced_id
+        )));
+    }
+
+    let ids: Vec<String> = contradictions.iter().map(|b| b.id.clone()).collect();
+    let weight = contradictions.iter().map(|b| b.weight).fold(0.0_f64, f64::max);
+    let shared_concepts: Vec<String> = {
+        let mut all: Vec<String> = contradictions.iter()
+            .flat_map(|b| b.shared_concepts.iter().cloned())
+            .collect();
+        all.sort();
+        all.dedup();
+        all
+    };
+    let tx = NestedTx::begin(ctx.agent_conn)?;
+    let removed = BridgeStorage::delete_batch(&tx, &ids)?;
+
+    let bridge = ThinkBridge {
+        id: id_gen::bridge_id(),
+        source_id: keep_id.clone(),
+        target_id: replaced_id.clone(),
+        relation_type: BridgeType::Replaces,
+        reason: format!("conflict resolved: {}", reason.as_deref().unwrap_or("kept newer decision")),
+        shared_concepts,
+        weight,
+        confidence: 1.0,
+        status: BridgeStatus::Active,
+        propagated_from: None,
+        propagation_depth: 0,
+        created_by: "conflict_resolution".to_string(),
+        use_count: 0,
+        created_at: time_utils::now(),
+        last_reinforced: None,
+        shard_concept: None,
+    };
+    BridgeStorage::insert(&tx, &bridge)?;
+
+    if suspend {
+        ThreadStorage::update_status(&tx, &replaced_id, ThreadStatus::Suspended)?;
+    }
+    tx.commit()?;
+
+    Ok(serde_json::json!({
+        "resolved": true,
+        "kept": keep_id,
+        "replaced": replaced_id,
+        "removed_bridges": removed,
+        "replaces_bridge_id": bridge.id,
+        "suspended": suspend,
+    }))
+}
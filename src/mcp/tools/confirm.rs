@@ -0,0 +1,255 @@
+//! Two-phase protocol for destructive tools: preview, then execute with a token.
+//!
+//! A tool opts in with `ToolSpec::two_phase(preview_fn)`. A call without
+//! `confirm_token` (or with `dry_run=true`) only computes the preview — affected
+//! ids, counts, side effects on bridges/continuity/shared — and returns a
+//! short-lived, single-use token. Re-calling with the same arguments plus the
+//! token executes, provided the preview still resolves to the same id set.
+//!
+//! Tools without a preview of their own go through the same gate when the tool
+//! policy answers `confirm` (generic preview built from the call arguments).
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use ai_smartness::id_gen;
+use ai_smartness::registry::policy::PolicyDecision;
+use ai_smartness::storage::audit;
+use ai_smartness::{AiError, AiResult};
+use serde_json::Value;
+
+use super::registry::ToolSpec;
+use super::{optional_bool, optional_str, ToolContext, ToolOutput};
+
+/// Lifetime of a confirmation token.
+pub const TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// Ids and items shown in a preview; larger sets are reported as a count plus this sample.
+pub const PREVIEW_SAMPLE: usize = 20;
+
+/// First `PREVIEW_SAMPLE` entries of a list.
+pub fn sample<T>(list: &[T]) -> &[T] {
+    &list[..list.len().min(PREVIEW_SAMPLE)]
+}
+
+/// Protocol arguments: never part of the arguments a token is bound to.
+const PROTOCOL_ARGS: &[&str] = &["confirm", "confirm_token", "dry_run"];
+
+/// What a destructive call would do.
+pub struct Preview {
+    /// Ids the call acts on. The token is bound to this set.
+    pub affected_ids: Vec<String>,
+    pub counts: Value,
+    pub side_effects: Value,
+    /// Per-item details (title, status…), informative only.
+    pub items: Vec<Value>,
+    pub message: String,
+}
+
+impl Preview {
+    pub fn new(affected_ids: Vec<String>, message: impl Into<String>) -> Self {
+        Self {
+            affected_ids,
+            counts: serde_json::json!({}),
+            side_effects: serde_json::json!({}),
+            items: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    pub fn counts(mut self, counts: Value) -> Self {
+        self.counts = counts;
+        self
+    }
+
+    pub fn side_effects(mut self, side_effects: Value) -> Self {
+        self.side_effects = side_effects;
+        self
+    }
+
+    pub fn items(mut self, items: Vec<Value>) -> Self {
+        self.items = items;
+        self
+    }
+}
+
+struct Pending {
+    tool: String,
+    project_hash: String,
+    agent_id: String,
+    fingerprint: String,
+    ids: Vec<String>,
+    expires: Instant,
+}
+
+fn pending() -> &'static Mutex<HashMap<String, Pending>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, Pending>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Run a tool through the gate. `policy` is the tool-policy decision when it
+/// asked for confirmation.
+pub fn gated_call(
+    spec: &ToolSpec,
+    params: &Value,
+    ctx: &ToolContext,
+    policy: Option<&PolicyDecision>,
+) -> AiResult<ToolOutput> {
+    let mut args = spec.validate(params)?;
+    // `confirm: true` no longer bypasses anything
+    if let Some(map) = args.as_object_mut() {
+        map.remove("confirm");
+    }
+
+    let preview = match spec.preview {
+        Some(preview_fn) => preview_fn(&args, ctx)?,
+        None => None,
+    };
+    let preview = match (preview, policy) {
+        (Some(p), _) => p,
+        (None, Some(decision)) => generic_preview(spec, &args, decision),
+        (None, None) => return spec.call(&args, ctx),
+    };
+
+    let dry_run = optional_bool(&args, "dry_run").unwrap_or(false);
+    match optional_str(&args, "confirm_token") {
+        Some(token) if !dry_run => {
+            redeem(&token, spec.name, ctx, &args, &preview)?;
+            if let Some(map) = args.as_object_mut() {
+                map.remove("confirm_token");
+            }
+            spec.call(&args, ctx)
+        }
+        _ => Ok(ToolOutput::Plain(preview_response(spec, ctx, &args, preview, policy, dry_run))),
+    }
+}
+
+/// Preview for tools without one: ids named in the arguments.
+fn generic_preview(spec: &ToolSpec, args: &Value, decision: &PolicyDecision) -> Preview {
+    let mut ids = audit::affected_ids(args, None);
+    if let Some(target) = spec.target_param.and_then(|p| args.get(p)).and_then(|v| v.as_str()) {
+        ids.push(target.to_string());
+    }
+    Preview::new(ids, decision.reason.clone())
+}
+
+fn preview_response(
+    spec: &ToolSpec,
+    ctx: &ToolContext,
+    args: &Value,
+    preview: Preview,
+    policy: Option<&PolicyDecision>,
+    dry_run: bool,
+) -> Value {
+    let mut out = serde_json::json!({
+        "confirmation_required": true,
+        "dry_run": dry_run,
+        "tool": spec.name,
+        "affected_count": preview.affected_ids.len(),
+        "affected_ids": sample(&preview.affected_ids),
+        "counts": preview.counts,
+        "side_effects": preview.side_effects,
+        "message": preview.message,
+    });
+    if !preview.items.is_empty() {
+        let mut items = preview.items;
+        items.truncate(PREVIEW_SAMPLE);
+        out["items"] = Value::Array(items);
+    }
+    if let Some(decision) = policy {
+        out["policy"] = serde_json::json!({"rule": decision.rule, "reason": decision.reason});
+    }
+    if !dry_run {
+        let token = issue(spec.name, ctx, args, &preview.affected_ids);
+        out["next"] = Value::String(format!(
+            "Re-call {} with the same arguments plus confirm_token=\"{}\" to execute", spec.name, token
+        ));
+        out["confirm_token"] = Value::String(token);
+        out["expires_in_secs"] = TOKEN_TTL.as_secs().into();
+    }
+    out
+}
+
+fn issue(tool: &str, ctx: &ToolContext, args: &Value, ids: &[String]) -> String {
+    let token = id_gen::confirm_token();
+    let now = Instant::now();
+    let mut map = pending().lock().unwrap_or_else(|e| e.into_inner());
+    map.retain(|_, p| p.expires > now);
+    map.insert(token.clone(), Pending {
+        tool: tool.to_string(),
+        project_hash: ctx.project_hash.to_string(),
+        agent_id: ctx.agent_id.to_string(),
+        fingerprint: fingerprint(args),
+        ids: id_set(ids),
+        expires: now + TOKEN_TTL,
+    });
+    token
+}
+
+/// Consume the token and check it matches this call and the current preview.
+fn redeem(token: &str, tool: &str, ctx: &ToolContext, args: &Value, preview: &Preview) -> AiResult<()> {
+    let p = pending()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(token)
+        .ok_or_else(|| AiError::InvalidState(
+            "Unknown or already used confirm_token — call again without it for a new preview".into(),
+        ))?;
+    if p.expires <= Instant::now() {
+        return Err(AiError::InvalidState("confirm_token expired — call again without it for a new preview".into()));
+    }
+    if p.tool != tool || p.agent_id != ctx.agent_id || p.project_hash != ctx.project_hash {
+        return Err(AiError::InvalidState(format!(
+            "confirm_token was issued for {} by {}, not this call", p.tool, p.agent_id
+        )));
+    }
+    if p.fingerprint != fingerprint(args) {
+        return Err(AiError::InvalidState(
+            "Arguments differ from the previewed call — re-preview without confirm_token".into(),
+        ));
+    }
+    let current = id_set(&preview.affected_ids);
+    if p.ids != current {
+        return Err(AiError::InvalidState(format!(
+            "Affected set changed since the preview ({} → {} ids) — re-preview without confirm_token",
+            p.ids.len(), current.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Canonical arguments minus protocol keys (serde_json maps are sorted).
+fn fingerprint(args: &Value) -> String {
+    match args {
+        Value::Object(map) => {
+            let mut map = map.clone();
+            for k in PROTOCOL_ARGS {
+                map.remove(*k);
+            }
+            Value::Object(map).to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+fn id_set(ids: &[String]) -> Vec<String> {
+    let mut ids = ids.to_vec();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_protocol_args() {
+        let a = serde_json::json!({"thread_ids": ["a", "b"], "confirm_token": "ct_x", "dry_run": false});
+        let b = serde_json::json!({"dry_run": true, "thread_ids": ["a", "b"]});
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_ne!(fingerprint(&a), fingerprint(&serde_json::json!({"thread_ids": ["a"]})));
+        assert_eq!(id_set(&["b".into(), "a".into(), "b".into()]), vec!["a", "b"]);
+    }
+}
@@ -3,12 +3,22 @@ use ai_smartness::shared::Subscription;
 use ai_smartness::AiResult;
 use ai_smartness::storage::shared_storage::SharedStorage;
 
+use super::paging::{ListSpec, Page};
 use super::{optional_array, optional_str, optional_usize, required_str, ToolContext};
 
+pub const SHARED: ListSpec = ListSpec {
+    items_key: "shared",
+    id_field: "shared_id",
+    sort_keys: &["published_at", "title", "owner_agent"],
+    desc_by_default: &["published_at"],
+    default_limit: 20,
+};
+
 pub fn handle_discover(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
+    let page = Page::parse(params, &SHARED)?;
     let topics = optional_array(params, "topics").unwrap_or_default();
     let agent_filter = optional_str(params, "agent_id");
 
@@ -28,7 +38,7 @@ pub fn handle_discover(
         })
         .collect();
 
-    Ok(serde_json::json!({"shared": results, "count": results.len()}))
+    Ok(page.apply(results))
 }
 
 pub fn handle_subscribe(
@@ -148,6 +158,7 @@ mod tests {
             shared_conn: shared,
             project_hash: "test-proj",
             agent_id: "test-agent",
+            progress: crate::mcp::tools::ProgressSink::none(),
         }
     }
 
@@ -243,6 +254,7 @@ mod tests {
             shared_conn: &shared,
             project_hash: "test-proj",
             agent_id: "agent-b",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
         let result_b = handle_subscribe(&serde_json::json!({"shared_id": "s1"}), &ctx_b).unwrap();
         assert_eq!(result_b["subscribed"], "s1");
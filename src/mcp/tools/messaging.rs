@@ -9,8 +9,10 @@ use ai_smartness::storage::database::{self, ConnectionRole};
 use ai_smartness::storage::mcp_messages::McpMessages;
 use ai_smartness::storage::migrations;
 use ai_smartness::storage::path_utils;
+use ai_smartness::processing::daemon_ipc_client;
 use ai_smartness::registry::registry::AgentRegistry;
 
+use super::paging::{ListSpec, Page};
 use super::{optional_array, optional_str, optional_usize, required_str, ToolContext};
 
 /// Write a wake signal file so the VSCode extension / CLI controller can wake the target agent.
@@ -54,6 +56,26 @@ pub(crate) fn emit_wake_signal(target_agent: &str, from_agent: &str, subject: &s
     let _ = std::fs::write(&signal_path, signal.to_string());
 }
 
+/// Tell daemon event subscribers a message arrived (`to_agent` "*" = broadcast,
+/// published without an agent so every subscriber of the project gets it).
+fn publish_message_event(project_hash: &str, msg: &Message, channel: &str) {
+    let recipient = (msg.to_agent != "*").then_some(msg.to_agent.as_str());
+    daemon_ipc_client::publish_event(
+        "message_received",
+        project_hash,
+        recipient,
+        serde_json::json!({
+            "message_id": msg.id,
+            "from": msg.from_agent,
+            "to": msg.to_agent,
+            "subject": msg.subject,
+            "priority": msg.priority,
+            "channel": channel,
+            "reply_to_id": msg.reply_to_id,
+        }),
+    );
+}
+
 /// Resolve file paths into inlined attachments.
 /// Graceful: skips files that fail (not found, binary, too large) with warnings.
 /// Returns (valid_attachments, warning_lines).
@@ -179,6 +201,7 @@ pub fn handle_msg_focus(
     CognitiveInbox::send(&target_conn, &msg)?;
 
     emit_wake_signal(&target, &msg.from_agent, &msg.subject, "cognitive", false);
+    publish_message_event(ctx.project_hash, &msg, "cognitive");
     Ok(serde_json::json!({"sent": true, "message_id": msg.id, "target": target, "attachments": att_count}))
 }
 
@@ -265,6 +288,7 @@ pub fn handle_msg_send(
     McpMessages::send(ctx.shared_conn, &msg)?;
     let interrupt = msg.priority == MessagePriority::Urgent;
     emit_wake_signal(&to, &msg.from_agent, &msg.subject, "inbox", interrupt);
+    publish_message_event(ctx.project_hash, &msg, "inbox");
     Ok(serde_json::json!({"sent": true, "message_id": msg.id, "attachments": att_count}))
 }
 
@@ -309,6 +333,7 @@ pub fn handle_msg_broadcast(
     };
 
     McpMessages::broadcast(ctx.shared_conn, &msg)?;
+    publish_message_event(ctx.project_hash, &msg, "inbox");
 
     // Wake all agents in the project (except sender)
     if let Ok(agents) = AgentRegistry::list(ctx.registry_conn, Some(ctx.project_hash), None, None) {
@@ -322,11 +347,20 @@ pub fn handle_msg_broadcast(
     Ok(serde_json::json!({"broadcast": true, "message_id": msg.id}))
 }
 
+/// Inbox pages, oldest first.
+pub const MESSAGES: ListSpec = ListSpec {
+    items_key: "messages",
+    id_field: "id",
+    sort_keys: &["created_at"],
+    desc_by_default: &[],
+    default_limit: 10,
+};
+
 pub fn handle_msg_inbox(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let _limit = optional_usize(params, "limit").unwrap_or(10);
+    let page = Page::parse(params, &MESSAGES)?;
     let effective_agent = optional_str(params, "agent_id")
         .unwrap_or_else(|| ctx.agent_id.to_string());
     let messages = McpMessages::inbox(ctx.shared_conn, &effective_agent)?;
@@ -363,9 +397,13 @@ pub fn handle_msg_inbox(
         })
         .collect();
 
-    // Mark all fetched messages as read so they don't accumulate
-    for m in &messages {
-        McpMessages::ack(ctx.shared_conn, &m.id).ok();
+    // Mark the returned page as read so it doesn't accumulate; the rest stays pending
+    let (out, page_ids) = page.apply_with_ids(results);
+    if params.get("peek").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return Ok(out);
+    }
+    for id in &page_ids {
+        McpMessages::ack(ctx.shared_conn, id).ok();
     }
 
     // Ghost wake fix: if inbox is now drained, remove the wake signal — but only
@@ -381,7 +419,7 @@ pub fn handle_msg_inbox(
         }
     }
 
-    Ok(serde_json::json!({"messages": results, "count": results.len()}))
+    Ok(out)
 }
 
 pub fn handle_msg_reply(
@@ -436,6 +474,7 @@ pub fn handle_msg_reply(
     McpMessages::reply(ctx.shared_conn, &message_id, &reply)?;
 
     emit_wake_signal(&original_sender, &effective_agent, &reply.subject, "inbox", false);
+    publish_message_event(ctx.project_hash, &reply, "inbox");
 
     Ok(serde_json::json!({"replied": true, "reply_id": reply.id}))
 }
@@ -524,6 +563,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: "test-ph",
             agent_id: "sender",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         // Urgent → interrupt=true
@@ -585,6 +625,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: "test-ph",
             agent_id: agent,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         // Send a message so inbox is non-empty, then emit inbox wake signal
@@ -654,6 +695,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: "test-ph",
             agent_id: agent,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         // Emit a cognitive signal (inbox is empty — no mcp_messages)
@@ -681,6 +723,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: "test-ph",
             agent_id,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let future = ai_smartness::time_utils::to_sqlite(
@@ -721,6 +764,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: "test-ph",
             agent_id,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let future = ai_smartness::time_utils::to_sqlite(
@@ -814,6 +858,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: SENDER,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         handle_msg_broadcast(
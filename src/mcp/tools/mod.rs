@@ -1,22 +1,39 @@
 pub mod agents;
+pub mod batch;
 pub mod bridges;
+pub mod confirm;
 pub mod discover;
 pub mod focus;
 pub mod messaging;
+pub mod paging;
 pub mod recall;
+pub mod registry;
 pub mod share;
 pub mod split;
 pub mod status;
 pub mod threads;
 pub mod windows;
 
-use ai_smartness::AiResult;
+use ai_smartness::config::GuardianConfig;
+use ai_smartness::processing::ipc_auth::{self, Scope};
 use ai_smartness::registry::heartbeat::Heartbeat;
+use ai_smartness::registry::policy::{
+    self, PolicyDecision, PolicyEffect, PolicyEngine, PolicyRequest, ToolPolicyConfig,
+};
 use ai_smartness::registry::registry::AgentRegistry;
+use ai_smartness::storage::audit::{self, AuditEntry, AuditLog};
 use ai_smartness::storage::beat::BeatState;
 use ai_smartness::storage::path_utils;
 use ai_smartness::storage::threads::ThreadStorage;
+use ai_smartness::{AiError, AiResult};
 use rusqlite::Connection;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+pub use super::progress::ProgressSink;
+
+/// Item interval between progress reports / cancellation checks in bulk loops.
+pub const PROGRESS_EVERY: usize = 500;
 
 /// Shared context passed to every tool handler.
 pub struct ToolContext<'a> {
@@ -25,6 +42,21 @@ pub struct ToolContext<'a> {
     pub shared_conn: &'a Connection,
     pub project_hash: &'a str,
     pub agent_id: &'a str,
+    /// Progress/cancellation handle (no-op outside MCP tools/call).
+    pub progress: ProgressSink,
+}
+
+impl<'a> ToolContext<'a> {
+    /// Context of a call outside an MCP tools/call (no progress reporting).
+    pub fn new(
+        agent_conn: &'a Connection,
+        registry_conn: &'a Connection,
+        shared_conn: &'a Connection,
+        project_hash: &'a str,
+        agent_id: &'a str,
+    ) -> Self {
+        Self { agent_conn, registry_conn, shared_conn, project_hash, agent_id, progress: ProgressSink::none() }
+    }
 }
 
 /// Result of a tool invocation, optionally carrying a side-effect
@@ -52,11 +84,23 @@ pub fn route_tool(
         tracing::debug!(error = %e, "Activity update failed (non-critical)");
     }
 
-    // Tools that produce side-effects
-    let result = match name {
-        "ai_agent_select" => agents::handle_agent_select(params, ctx),
-        _ => route_plain_tool(name, params, ctx).map(ToolOutput::Plain),
-    };
+    let guardian = guardian_config();
+    let started = std::time::Instant::now();
+
+    // Registry: validation + coercion of arguments, policy check on the coerced
+    // values, two-phase confirmation gate, then dispatch. Daemon calls made by the tool authenticate
+    // as the calling agent.
+    let scope = Scope::Agent { project_hash: ctx.project_hash.to_string(), agent_id: ctx.agent_id.to_string() };
+    let result = ipc_auth::with_client_scope(scope, || match registry::find(name) {
+        Some(spec) => spec
+            .validate(params)
+            .and_then(|args| check_policy(spec, &args, ctx, &guardian.tool_policy))
+            .and_then(|decision| confirm::gated_call(spec, params, ctx, decision.as_ref())),
+        None => Err(ai_smartness::AiError::InvalidInput(format!(
+            "Unknown tool: {}",
+            name
+        ))),
+    });
 
     // E5: Update beat.json with error tracking (tool call count moved to pretool nanobeat)
     if let Err(ref e) = result {
@@ -66,6 +110,10 @@ pub fn route_tool(
         beat.save(&data_dir);
     }
 
+    if guardian.audit.enabled && !guardian.audit.exclude_tools.iter().any(|t| t == name) {
+        record_audit(name, params, &result, started.elapsed(), ctx);
+    }
+
     match &result {
         Ok(_) => tracing::debug!(tool = %name, "MCP tool success"),
         Err(e) => tracing::warn!(tool = %name, error = %e, "MCP tool error"),
@@ -73,126 +121,114 @@ pub fn route_tool(
     result
 }
 
-/// Route all plain tools (no side-effects). Handlers remain unchanged.
-fn route_plain_tool(
+/// config.json as loaded by this process, re-read only when the file's
+/// modification time changes (a file that does not parse keeps the last one).
+pub fn guardian_config() -> Arc<GuardianConfig> {
+    static LOADED: Mutex<Option<(Option<SystemTime>, Arc<GuardianConfig>)>> = Mutex::new(None);
+    let path = path_utils::data_dir().join("config.json");
+    let mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    match loaded.as_ref() {
+        Some((seen, cfg)) if *seen == mtime => cfg.clone(),
+        previous => {
+            let parsed = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<GuardianConfig>(&s).ok());
+            let cfg = match (parsed, previous) {
+                (Some(cfg), _) => Arc::new(cfg),
+                (None, Some((_, last))) if mtime.is_some() => last.clone(),
+                (None, _) => Arc::new(GuardianConfig::default()),
+            };
+            *loaded = Some((mtime, cfg.clone()));
+            cfg
+        }
+    }
+}
+
+/// Audit a memory mutation on its own entry, with every id it touched
+/// (the call entry only sees what the tool returns).
+pub fn record_mutation(ctx: &ToolContext, source: &str, params: &serde_json::Value, ids: &[String]) {
+    if !guardian_config().audit.enabled {
+        return;
+    }
+    let mut entry = AuditEntry::new(ctx.project_hash, ctx.agent_id, source, params);
+    entry.affected_ids = ids.to_vec();
+    if let Err(e) = AuditLog::record(ctx.registry_conn, &entry) {
+        tracing::debug!(error = %e, source, "Mutation audit failed (non-critical)");
+    }
+}
+
+/// Append the call to the registry audit log (non-critical).
+fn record_audit(
     name: &str,
     params: &serde_json::Value,
+    result: &AiResult<ToolOutput>,
+    elapsed: std::time::Duration,
     ctx: &ToolContext,
-) -> AiResult<serde_json::Value> {
-    match name {
-        // -- Memory & Search --
-        "ai_recall" => recall::handle_recall(params, ctx),
-
-        // -- Thread lifecycle --
-        "ai_thread_create" => threads::handle_thread_create(params, ctx),
-        "ai_thread_rm" => threads::handle_thread_rm(params, ctx),
-        "ai_thread_rm_batch" => threads::handle_thread_rm_batch(params, ctx),
-        "ai_thread_list" => threads::handle_thread_list(params, ctx),
-        "ai_thread_search" => threads::handle_thread_search(params, ctx),
-        "ai_continuity_edges" => threads::handle_continuity_edges(params, ctx),
-        "ai_thread_activate" => threads::handle_thread_activate(params, ctx),
-        "ai_thread_suspend" => threads::handle_thread_suspend(params, ctx),
-        "ai_thread_purge" => threads::handle_thread_purge(params, ctx),
-        "ai_reactivate" => threads::handle_reactivate(params, ctx),
-
-        // -- Thread annotation --
-        "ai_annotate" => threads::handle_annotate(params, ctx),
-
-        // -- Thread operations --
-        "ai_split" => split::handle_split(params, ctx),
-        "ai_split_unlock" => split::handle_split_unlock(params, ctx),
-
-        // -- Thread metadata --
-        "ai_label" => threads::handle_label(params, ctx),
-        "ai_labels_suggest" => threads::handle_labels_suggest(params, ctx),
-        "ai_concepts" => threads::handle_concepts(params, ctx),
-        "ai_backfill_concepts" => threads::handle_backfill_concepts(params, ctx),
-        "ai_rename" => threads::handle_rename(params, ctx),
-        "ai_rename_batch" => threads::handle_rename_batch(params, ctx),
-        "ai_rate_importance" => threads::handle_rate_importance(params, ctx),
-        "ai_rate_context" => threads::handle_rate_context(params, ctx),
-        "ai_mark_used" => threads::handle_mark_used(params, ctx),
-
-        // -- Bridges --
-        "ai_bridges" => bridges::handle_bridges(params, ctx),
-        "ai_bridge_analysis" => bridges::handle_bridge_analysis(params, ctx),
-        "ai_bridge_scan_orphans" => bridges::handle_bridge_scan_orphans(params, ctx),
-        "ai_bridge_purge" => bridges::handle_bridge_purge(params, ctx),
-        "ai_bridge_kill" => bridges::handle_bridge_kill(params, ctx),
-        "ai_bridge_kill_batch" => bridges::handle_bridge_kill_batch(params, ctx),
-
-        // -- Focus & Pins --
-        "ai_focus" => focus::handle_focus(params, ctx),
-        "ai_unfocus" => focus::handle_unfocus(params, ctx),
-        "ai_pin" => focus::handle_pin(params, ctx),
-
-        // -- Cognitive Messaging --
-        "ai_msg_focus" => messaging::handle_msg_focus(params, ctx),
-        "ai_msg_ack" => messaging::handle_msg_ack(params, ctx),
-
-        // -- Shared Cognition --
-        "ai_share" => share::handle_share(params, ctx),
-        "ai_unshare" => share::handle_unshare(params, ctx),
-        "ai_publish" => share::handle_publish(params, ctx),
-        "ai_discover" => discover::handle_discover(params, ctx),
-        "ai_subscribe" => discover::handle_subscribe(params, ctx),
-        "ai_unsubscribe" => discover::handle_unsubscribe(params, ctx),
-        "ai_sync" => discover::handle_sync(params, ctx),
-
-        // -- System & Status --
-        "ai_status" => status::handle_status(params, ctx),
-        "ai_sysinfo" => status::handle_sysinfo(params, ctx),
-        "ai_help" => status::handle_help(params, ctx),
-        "ai_suggestions" => status::handle_suggestions(params, ctx),
-        "ai_shared_status" => status::handle_shared_status(params, ctx),
-        "ai_profile" => status::handle_profile(params, ctx),
-
-        // -- Maintenance --
-        "ai_cleanup" => status::handle_cleanup(params, ctx),
-        "ai_lock" | "ai_unlock" | "ai_lock_status" => status::handle_lock(params, ctx, name),
-        "ai_backup" => status::handle_backup(params, ctx),
-
-        // -- mcp-smartness-com: Messaging --
-        "msg_send" => messaging::handle_msg_send(params, ctx),
-        "msg_broadcast" => messaging::handle_msg_broadcast(params, ctx),
-        "msg_inbox" => messaging::handle_msg_inbox(params, ctx),
-        "msg_reply" => messaging::handle_msg_reply(params, ctx),
-
-        // -- mcp-smartness-com: Agents --
-        "agent_list" => agents::handle_agent_list(params, ctx),
-        "agent_query" => agents::handle_agent_query(params, ctx),
-        "agent_status" => agents::handle_agent_status(params, ctx),
-        "agent_context" => status::handle_agent_context(params, ctx),
-        "agent_cleanup" => agents::handle_agent_cleanup(params, ctx),
-        "agent_configure" => agents::handle_agent_configure(params, ctx),
-        "agent_tasks" => agents::handle_agent_tasks(params, ctx),
-
-        // -- mcp-smartness-com: Tasks --
-        "task_delegate" => agents::handle_task_delegate(params, ctx),
-        "task_status" => agents::handle_task_status(params, ctx),
-        "task_complete" => agents::handle_task_complete(params, ctx),
-
-        // -- mcp-smartness-com: Metrics & Health --
-        "metrics_cross_agent" => status::handle_metrics(params, ctx),
-        "health_check" => status::handle_health_check(params, ctx),
-        "topics_network" => status::handle_topics_network(params, ctx),
-        "test_sampling" => status::handle_test_sampling(params, ctx),
-
-        // -- Beat / Self-wake --
-        "beat_wake" => status::handle_beat_wake(params, ctx),
-        "nanobeat_schedule" => status::handle_nanobeat_schedule(params, ctx),
-
-        // -- Windows --
-        "ai_windows" => windows::handle_windows(params, ctx),
-
-        // -- ai-smartness aliases (defined in tool_definitions) --
-        "ai_recommend" => discover::handle_recommend(params, ctx),
-        "ai_topics" => status::handle_topics_network(params, ctx),
-
-        _ => Err(ai_smartness::AiError::InvalidInput(format!(
-            "Unknown tool: {}",
-            name
-        ))),
+) {
+    let mut entry = AuditEntry::new(ctx.project_hash, ctx.agent_id, name, params);
+    entry.duration_ms = elapsed.as_millis() as u64;
+    let output = match result {
+        Ok(ToolOutput::Plain(v)) | Ok(ToolOutput::AgentSwitch { result: v, .. }) => {
+            if v.get("confirmation_required").and_then(|c| c.as_bool()).unwrap_or(false) {
+                entry.outcome = audit::OUTCOME_PREVIEW.to_string();
+            }
+            Some(v)
+        }
+        Err(e) => {
+            entry.outcome = match e {
+                AiError::PolicyDenied { .. } => audit::OUTCOME_DENIED,
+                _ => audit::OUTCOME_ERROR,
+            }
+            .to_string();
+            entry.error = Some(e.to_string());
+            None
+        }
+    };
+    entry.affected_ids = audit::affected_ids(params, output);
+    if let Err(e) = AuditLog::record(ctx.registry_conn, &entry) {
+        tracing::debug!(error = %e, "Audit record failed (non-critical)");
+    }
+}
+
+// ── Tool policy ──
+
+/// Evaluate the tool policy for this call, on the arguments as coerced by the
+/// registry. Deny → PolicyDenied; Confirm is returned so the call goes through
+/// the two-phase gate (confirm.rs).
+fn check_policy(
+    spec: &registry::ToolSpec,
+    params: &serde_json::Value,
+    ctx: &ToolContext,
+    policy_cfg: &ToolPolicyConfig,
+) -> AiResult<Option<PolicyDecision>> {
+    if !policy_cfg.enabled {
+        return Ok(None);
+    }
+
+    let caller = AgentRegistry::get(ctx.registry_conn, ctx.agent_id, ctx.project_hash).ok().flatten();
+    let target = match (spec.target_param, caller.as_ref()) {
+        (Some(param), Some(caller)) => params.get(param).and_then(|v| v.as_str()).map(|target_id| {
+            let target_agent = AgentRegistry::get(ctx.registry_conn, target_id, ctx.project_hash).ok().flatten();
+            policy::target_scope(caller, target_agent.as_ref(), target_id)
+        }),
+        _ => None,
+    };
+
+    let decision = PolicyEngine::new(policy_cfg).evaluate(&PolicyRequest {
+        tool: spec.name,
+        category: spec.category,
+        caller: caller.as_ref(),
+        target,
+    });
+    match decision.effect {
+        PolicyEffect::Allow => Ok(None),
+        PolicyEffect::Confirm => Ok(Some(decision)),
+        PolicyEffect::Deny => Err(AiError::PolicyDenied {
+            effect: decision.effect.as_str().to_string(),
+            rule: decision.rule,
+            reason: decision.reason,
+        }),
     }
 }
 
@@ -276,8 +312,8 @@ pub fn optional_array(params: &serde_json::Value, key: &str) -> Option<Vec<Strin
 }
 
 /// Parse a value that may be a JSON array or a string containing a JSON array
-/// or comma-separated values. MCP tool schemas declare all params as "string",
-/// so array values arrive as strings that need parsing.
+/// or comma-separated values. Older clients send arrays as strings; the tool
+/// registry uses this to coerce them before dispatch.
 pub fn parse_string_or_array(v: &serde_json::Value) -> Option<Vec<String>> {
     // Case 1: native JSON array
     if let Some(arr) = v.as_array() {
@@ -461,6 +497,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let (active, quota) = check_thread_quota(&ctx).unwrap();
@@ -484,6 +521,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         // Call route_tool with ai_thread_list (the tool itself may error, but
@@ -497,4 +535,166 @@ mod tests {
         ).unwrap();
         assert_eq!(activity, "tool:ai_thread_list");
     }
+
+    /// Tool policy: role defaults gate destructive tools and admin of other agents.
+    #[test]
+    fn test_route_tool_policy_denial() {
+        let agent_conn = setup_agent_db();
+        let registry_conn = setup_registry_db();
+        let shared_conn = setup_shared_db();
+
+        insert_project(&registry_conn);
+        register_agent(&registry_conn, ThreadMode::Normal);
+
+        let ctx = ToolContext {
+            agent_conn: &agent_conn,
+            registry_conn: &registry_conn,
+            shared_conn: &shared_conn,
+            project_hash: PH,
+            agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
+        };
+
+        // programmer → destructive tools need confirmation; `confirm: true` no longer bypasses
+        let preview = match route_tool("ai_thread_rm", &serde_json::json!({"thread_id": "t-x", "confirm": true}), &ctx) {
+            Ok(ToolOutput::Plain(v)) => v,
+            other => panic!("Expected preview, got {:?}", other.err()),
+        };
+        assert_eq!(preview["confirmation_required"], true);
+        assert_eq!(preview["policy"]["rule"], "role_default");
+        let token = preview["confirm_token"].as_str().unwrap();
+        let confirmed = route_tool("ai_thread_rm", &serde_json::json!({"thread_id": "t-x", "confirm_token": token}), &ctx);
+        assert!(confirmed.is_ok());
+
+        // Configuring an agent that is not a subordinate is denied
+        let args = serde_json::json!({"agent_id": "someone-else", "project_hash": PH, "role": "reviewer"});
+        match route_tool("agent_configure", &args, &ctx) {
+            Err(AiError::PolicyDenied { effect, rule, .. }) => {
+                assert_eq!(effect, "deny");
+                assert_eq!(rule, "hierarchy");
+            }
+            other => panic!("Expected denial, got {:?}", other.err()),
+        }
+        // The policy sees the coerced target (a number becomes the id "42")
+        let args = serde_json::json!({"agent_id": 42, "project_hash": PH, "role": "reviewer"});
+        assert!(matches!(route_tool("agent_configure", &args, &ctx), Err(AiError::PolicyDenied { .. })));
+
+        // Every call is in the audit log, denials included
+        let denied = AuditLog::query(&registry_conn, &audit::AuditFilter {
+            outcome: Some(audit::OUTCOME_DENIED.into()),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(denied.len(), 2);
+        assert_eq!(denied[0].tool, "agent_configure");
+        let rm = AuditLog::query(&registry_conn, &audit::AuditFilter {
+            affected_id: Some("t-x".into()),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(rm.len(), 2);
+        assert_eq!(rm[1].outcome, audit::OUTCOME_PREVIEW);
+        assert_eq!(rm[0].outcome, audit::OUTCOME_OK);
+    }
+
+    /// Two-phase tools: preview + token, token bound to arguments and id set, single use.
+    #[test]
+    fn test_two_phase_token_binding() {
+        let agent_conn = setup_agent_db();
+        let registry_conn = setup_registry_db();
+        let shared_conn = setup_shared_db();
+
+        insert_project(&registry_conn);
+        register_agent(&registry_conn, ThreadMode::Heavy);
+        insert_active_threads(&agent_conn, 3);
+
+        let ctx = ToolContext {
+            agent_conn: &agent_conn,
+            registry_conn: &registry_conn,
+            shared_conn: &shared_conn,
+            project_hash: PH,
+            agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
+        };
+        let call = |tool: &str, args: serde_json::Value| match route_tool(tool, &args, &ctx) {
+            Ok(ToolOutput::Plain(v)) => Ok(v),
+            Ok(ToolOutput::AgentSwitch { result, .. }) => Ok(result),
+            Err(e) => Err(e),
+        };
+        let ids = serde_json::json!(["t-mod-0", "t-mod-1", "missing"]);
+
+        // Preview executes nothing
+        let preview = call("ai_thread_suspend", serde_json::json!({"thread_ids": ids})).unwrap();
+        assert_eq!(preview["affected_ids"], serde_json::json!(["t-mod-0", "t-mod-1"]));
+        assert_eq!(preview["affected_count"], 2);
+        assert_eq!(preview["counts"]["missing"], 1);
+        assert_eq!(preview["counts"]["missing_sample"], serde_json::json!(["missing"]));
+        assert_eq!(ThreadStorage::count_by_status(&agent_conn, &ThreadStatus::Suspended).unwrap(), 0);
+        let token = preview["confirm_token"].as_str().unwrap().to_string();
+
+        // Other arguments → rejected, and the token is spent
+        let other = serde_json::json!({"thread_ids": ["t-mod-2"], "confirm_token": token});
+        assert!(call("ai_thread_suspend", other).is_err());
+        let again = serde_json::json!({"thread_ids": ids, "confirm_token": token});
+        assert!(call("ai_thread_suspend", again).is_err(), "Tokens are single use");
+
+        // Matching call executes
+        let token = call("ai_thread_suspend", serde_json::json!({"thread_ids": ids})).unwrap()["confirm_token"]
+            .as_str().unwrap().to_string();
+        call("ai_thread_suspend", serde_json::json!({"thread_ids": ids, "confirm_token": token})).unwrap();
+        assert_eq!(ThreadStorage::count_by_status(&agent_conn, &ThreadStatus::Suspended).unwrap(), 2);
+
+        // Id set drifted between preview and execution → re-preview
+        let purge = call("ai_thread_purge", serde_json::json!({"status": "suspended"})).unwrap();
+        assert_eq!(purge["counts"]["threads"], 2);
+        ThreadStorage::update_status(&agent_conn, "t-mod-2", ThreadStatus::Suspended).unwrap();
+        let token = purge["confirm_token"].as_str().unwrap();
+        let err = call("ai_thread_purge", serde_json::json!({"status": "suspended", "confirm_token": token})).unwrap_err();
+        assert!(err.to_string().contains("re-preview"));
+        assert_eq!(ThreadStorage::count(&agent_conn).unwrap(), 3);
+    }
+
+    /// ai_batch: references between steps, rollback on failure, dry-run.
+    #[test]
+    fn test_batch_atomic_with_refs() {
+        let agent_conn = setup_agent_db();
+        let registry_conn = setup_registry_db();
+        let shared_conn = setup_shared_db();
+
+        insert_project(&registry_conn);
+        register_agent(&registry_conn, ThreadMode::Normal);
+
+        let ctx = ToolContext {
+            agent_conn: &agent_conn,
+            registry_conn: &registry_conn,
+            shared_conn: &shared_conn,
+            project_hash: PH,
+            agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
+        };
+        let create_then = |last: serde_json::Value, dry_run: bool| serde_json::json!({
+            "dry_run": dry_run,
+            "steps": [
+                {"tool": "ai_thread_create", "args": {"title": "draft", "content": "batch body"}},
+                {"tool": "ai_rename", "args": {"thread_id": "$1.thread_id", "new_title": "final"}},
+                last,
+            ],
+        });
+        let count = || ThreadStorage::count(&agent_conn).unwrap();
+
+        // Step 3 fails → steps 1-2 rolled back
+        let failing = create_then(serde_json::json!({"tool": "ai_rename", "args": {"thread_id": "missing", "new_title": "x"}}), false);
+        assert!(batch::handle_batch(&failing, &ctx).is_err());
+        assert_eq!(count(), 0);
+
+        // Dry run reports but keeps nothing
+        let ok_step = serde_json::json!({"tool": "ai_label", "args": {"thread_id": "$1.thread_id", "labels": ["x"], "mode": "set"}});
+        let out = batch::handle_batch(&create_then(ok_step.clone(), true), &ctx).unwrap();
+        assert_eq!(out["steps"][1]["result"]["new_title"], "final");
+        assert_eq!(count(), 0);
+
+        let out = batch::handle_batch(&create_then(ok_step, false), &ctx).unwrap();
+        assert_eq!(out["committed"], true);
+        let id = out["steps"][0]["result"]["thread_id"].as_str().unwrap();
+        assert_eq!(ThreadStorage::get(&agent_conn, id).unwrap().unwrap().title, "final");
+        assert!(agent_conn.is_autocommit(), "Savepoints released");
+    }
 }
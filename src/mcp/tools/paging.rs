@@ -0,0 +1,273 @@
+//! Shared pagination for list tools: opaque cursors, sort keys, `fields`
+//! projection and `total_count` / `has_more` / `next_cursor` in every response.
+//!
+//! Items are ordered by (sort key, id): the id makes the order total. A cursor
+//! records the sort key value and id of the last item returned, and the next
+//! page starts strictly after that position (keyset, not offset), so rows
+//! inserted or removed meanwhile never shift or duplicate the page boundary.
+
+use std::cmp::Ordering;
+
+use ai_smartness::{AiError, AiResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{optional_array, optional_str, optional_usize};
+
+/// Hard cap on a page.
+pub const MAX_LIMIT: usize = 500;
+
+static NULL: Value = Value::Null;
+
+/// Static description of a list tool's items.
+pub struct ListSpec {
+    /// Response key holding the page ("threads", "bridges"…).
+    pub items_key: &'static str,
+    /// Field with the stable unique id of an item (tie-breaker).
+    pub id_field: &'static str,
+    /// Allowed sort keys — item fields. The first one is the default.
+    pub sort_keys: &'static [&'static str],
+    /// Sort keys whose default order is descending.
+    pub desc_by_default: &'static [&'static str],
+    pub default_limit: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+    /// items_key of the issuing tool
+    t: String,
+    s: String,
+    d: bool,
+    k: Value,
+    id: String,
+}
+
+/// Parsed page request.
+pub struct Page {
+    spec: &'static ListSpec,
+    sort: String,
+    desc: bool,
+    after: Option<(Value, String)>,
+    offset: usize,
+    pub limit: usize,
+    fields: Option<Vec<String>>,
+}
+
+impl Page {
+    pub fn parse(params: &Value, spec: &'static ListSpec) -> AiResult<Self> {
+        let cursor = optional_str(params, "cursor").map(|c| decode(&c)).transpose()?;
+        if let Some(ref c) = cursor {
+            if c.t != spec.items_key {
+                return Err(AiError::InvalidInput(format!(
+                    "cursor was issued by another list ({}), not {}", c.t, spec.items_key
+                )));
+            }
+        }
+
+        let sort = match (optional_str(params, "sort_by"), cursor.as_ref()) {
+            (Some(s), Some(c)) if s != c.s => {
+                return Err(AiError::InvalidInput(format!(
+                    "cursor was issued for sort_by={}, not {}", c.s, s
+                )))
+            }
+            (Some(s), _) => s,
+            (None, Some(c)) => c.s.clone(),
+            (None, None) => spec.sort_keys[0].to_string(),
+        };
+        if !spec.sort_keys.contains(&sort.as_str()) {
+            return Err(AiError::InvalidInput(format!(
+                "Unknown sort_by '{}' (allowed: {})", sort, spec.sort_keys.join(", ")
+            )));
+        }
+        let desc = match optional_str(params, "order").as_deref() {
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => {
+                return Err(AiError::InvalidInput(format!("Unknown order '{}' (asc|desc)", other)))
+            }
+            None => match cursor.as_ref() {
+                Some(c) => c.d,
+                None => spec.desc_by_default.contains(&sort.as_str()),
+            },
+        };
+        if cursor.as_ref().is_some_and(|c| c.d != desc) {
+            return Err(AiError::InvalidInput("cursor was issued for the other sort order".into()));
+        }
+
+        Ok(Self {
+            spec,
+            sort,
+            desc,
+            after: cursor.map(|c| (c.k, c.id)),
+            offset: optional_usize(params, "offset").unwrap_or(0),
+            limit: optional_usize(params, "limit").unwrap_or(spec.default_limit).clamp(1, MAX_LIMIT),
+            fields: optional_array(params, "fields").filter(|f| !f.is_empty()),
+        })
+    }
+
+    /// Sort, cut the page and build the list response.
+    pub fn apply(self, items: Vec<Value>) -> Value {
+        self.apply_with_ids(items).0
+    }
+
+    /// Same as `apply`, also returning the ids of the page items (before the
+    /// `fields` projection may drop them).
+    pub fn apply_with_ids(self, mut items: Vec<Value>) -> (Value, Vec<String>) {
+        let total_count = items.len();
+        items.sort_by(|a, b| self.cmp_pos(self.key(a), self.id(a), self.key(b), self.id(b)));
+
+        let start = match self.after {
+            Some((ref k, ref id)) => items.partition_point(|it| {
+                self.cmp_pos(self.key(it), self.id(it), k, id) != Ordering::Greater
+            }),
+            None => 0,
+        };
+        let rest = items.len().saturating_sub(start + self.offset);
+        let page: Vec<Value> = items.into_iter().skip(start + self.offset).take(self.limit).collect();
+        let has_more = rest > page.len();
+
+        let next_cursor = match page.last() {
+            Some(last) if has_more => Some(encode(&Cursor {
+                t: self.spec.items_key.to_string(),
+                s: self.sort.clone(),
+                d: self.desc,
+                k: self.key(last).clone(),
+                id: self.id(last).to_string(),
+            })),
+            _ => None,
+        };
+        let ids: Vec<String> = page.iter().map(|it| self.id(it).to_string()).collect();
+        let page: Vec<Value> = match self.fields {
+            Some(ref fields) => page.into_iter().map(|it| project(it, fields)).collect(),
+            None => page,
+        };
+
+        let mut out = serde_json::json!({
+            "count": page.len(),
+            "total_count": total_count,
+            "has_more": has_more,
+            "next_cursor": next_cursor,
+            "sort_by": self.sort,
+            "order": if self.desc { "desc" } else { "asc" },
+        });
+        out[self.spec.items_key] = Value::Array(page);
+        (out, ids)
+    }
+
+    fn key<'v>(&self, item: &'v Value) -> &'v Value {
+        item.get(&self.sort).unwrap_or(&NULL)
+    }
+
+    fn id<'v>(&self, item: &'v Value) -> &'v str {
+        item.get(self.spec.id_field).and_then(|v| v.as_str()).unwrap_or("")
+    }
+
+    /// Sort key in the requested direction, then id ascending.
+    fn cmp_pos(&self, ka: &Value, ida: &str, kb: &Value, idb: &str) -> Ordering {
+        let by_key = cmp_values(ka, kb);
+        let by_key = if self.desc { by_key.reverse() } else { by_key };
+        by_key.then_with(|| ida.cmp(idb))
+    }
+}
+
+/// Null < bool < number < string; mixed kinds fall back to their JSON text.
+fn cmp_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+fn project(item: Value, fields: &[String]) -> Value {
+    match item {
+        Value::Object(mut map) => Value::Object(
+            fields.iter().filter_map(|f| map.remove(f).map(|v| (f.clone(), v))).collect(),
+        ),
+        other => other,
+    }
+}
+
+fn encode(cursor: &Cursor) -> String {
+    serde_json::to_vec(cursor)
+        .unwrap_or_default()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn decode(s: &str) -> AiResult<Cursor> {
+    let invalid = || AiError::InvalidInput("Invalid cursor — pass next_cursor from a previous page unchanged".into());
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: ListSpec = ListSpec {
+        items_key: "items",
+        id_field: "id",
+        sort_keys: &["weight", "title"],
+        desc_by_default: &["weight"],
+        default_limit: 2,
+    };
+
+    fn item(id: &str, weight: f64) -> Value {
+        serde_json::json!({"id": id, "title": format!("T {}", id), "weight": weight})
+    }
+
+    fn ids(page: &Value) -> Vec<String> {
+        page["items"].as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn test_cursor_stable_under_inserts() {
+        let mut items = vec![item("a", 0.9), item("b", 0.5), item("c", 0.5), item("d", 0.1)];
+        let first = Page::parse(&serde_json::json!({}), &ITEMS).unwrap().apply(items.clone());
+        assert_eq!(ids(&first), vec!["a", "b"]);
+        assert_eq!(first["total_count"], 4);
+        assert_eq!(first["has_more"], true);
+
+        // New rows before and after the boundary: no duplicate, no skip
+        items.push(item("z", 0.95));
+        items.push(item("bb", 0.5));
+        let cursor = first["next_cursor"].as_str().unwrap();
+        let second = Page::parse(&serde_json::json!({"cursor": cursor}), &ITEMS).unwrap().apply(items.clone());
+        assert_eq!(ids(&second), vec!["bb", "c"]);
+        let cursor = second["next_cursor"].as_str().unwrap();
+        let last = Page::parse(&serde_json::json!({"cursor": cursor}), &ITEMS).unwrap().apply(items);
+        assert_eq!(ids(&last), vec!["d"]);
+        assert_eq!(last["has_more"], false);
+        assert!(last["next_cursor"].is_null());
+    }
+
+    #[test]
+    fn test_projection_and_validation() {
+        let items = vec![item("a", 0.9), item("b", 0.5)];
+        let params = serde_json::json!({"fields": ["id", "weight"], "sort_by": "title", "limit": 10});
+        let page = Page::parse(&params, &ITEMS).unwrap().apply(items);
+        assert_eq!(page["items"][0], serde_json::json!({"id": "a", "weight": 0.9}));
+        assert_eq!(page["order"], "asc");
+
+        assert!(Page::parse(&serde_json::json!({"sort_by": "nope"}), &ITEMS).is_err());
+        assert!(Page::parse(&serde_json::json!({"cursor": "zz"}), &ITEMS).is_err());
+        let c = encode(&Cursor { t: "items".into(), s: "weight".into(), d: true, k: serde_json::json!(0.5), id: "b".into() });
+        assert!(Page::parse(&serde_json::json!({"cursor": c, "sort_by": "title"}), &ITEMS).is_err());
+    }
+}
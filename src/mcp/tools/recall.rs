@@ -1,14 +1,16 @@
 use ai_smartness::AiResult;
 use ai_smartness::config::EngramConfig;
 use ai_smartness::intelligence::engram_retriever::EngramRetriever;
+use ai_smartness::intelligence::spreading_activation::SpreadingActivation;
 use ai_smartness::storage::beat::BeatState;
 use ai_smartness::storage::bridges::BridgeStorage;
+use ai_smartness::storage::cross_bridges::CrossBridgeStorage;
 use ai_smartness::storage::path_utils;
 use ai_smartness::storage::threads::ThreadStorage;
 use ai_smartness::thread::Thread;
 use chrono::Utc;
 
-use super::{optional_str, required_str, ToolContext};
+use super::{optional_bool, optional_str, optional_usize, required_str, ToolContext};
 
 pub fn handle_recall(
     params: &serde_json::Value,
@@ -16,12 +18,12 @@ pub fn handle_recall(
 ) -> AiResult<serde_json::Value> {
     let query = required_str(params, "query")?;
     let label_filter = optional_str(params, "label");
-    let include_bridges = optional_str(params, "include_bridges")
-        .map(|s| s == "true")
-        .unwrap_or(false);
+    let include_bridges = optional_bool(params, "include_bridges").unwrap_or(false);
     let deep = optional_str(params, "depth")
         .map(|s| s == "deep")
         .unwrap_or(false);
+    // Spreading activation: follow bridges + continuity edges N hops from direct hits
+    let hops = optional_usize(params, "hops").unwrap_or(0) as u32;
 
     let engram = EngramRetriever::new(ctx.agent_conn, EngramConfig::default())?;
     let mut threads = engram.search(ctx.agent_conn, &query, 10)?;
@@ -118,6 +120,14 @@ pub fn handle_recall(
         })
         .collect();
 
+    let related_out = related_in_other_projects(ctx, &threads);
+
+    let indirect_out = if hops > 0 && !threads.is_empty() {
+        spread_from_hits(ctx, &threads, hops)
+    } else {
+        Vec::new()
+    };
+
     // Update last_recall_beat in beat state
     let agent_data = path_utils::agent_data_dir(ctx.project_hash, ctx.agent_id);
     let mut beat_state = BeatState::load(&agent_data);
@@ -127,10 +137,83 @@ pub fn handle_recall(
     Ok(serde_json::json!({
         "threads": threads_json,
         "bridges": bridges_out,
+        "indirect": indirect_out,
+        "related_projects": related_out,
         "count": threads_json.len(),
     }))
 }
 
+/// Cross-project bridges of the direct hits (cross-project gossip), strongest
+/// first, at most 3 per hit: "related memory in project X".
+fn related_in_other_projects(ctx: &ToolContext, threads: &[Thread]) -> Vec<serde_json::Value> {
+    let mut labels: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut out = Vec::new();
+    for t in threads {
+        let Ok(bridges) = CrossBridgeStorage::list_for_thread(ctx.registry_conn, ctx.project_hash, ctx.agent_id, &t.id) else {
+            continue;
+        };
+        for b in bridges.iter().take(3) {
+            let Some(other) = b.other_side(ctx.project_hash, ctx.agent_id, &t.id) else { continue };
+            let project = labels
+                .entry(other.project_hash.clone())
+                .or_insert_with(|| CrossBridgeStorage::project_label(ctx.registry_conn, &other.project_hash))
+                .clone();
+            out.push(serde_json::json!({
+                "thread_id": t.id,
+                "note": format!("related memory in project {}", project),
+                "project": project,
+                "project_hash": other.project_hash,
+                "agent_id": other.agent_id,
+                "related_thread_id": other.thread_id,
+                "related_title": other.title,
+                "weight": (b.weight * 1000.0).round() / 1000.0,
+                "shared_concepts": b.shared_concepts,
+            }));
+        }
+    }
+    out
+}
+
+/// Spreading activation from direct hits. Seeds are weighted by rank
+/// (1.0 for the top hit, -0.05 per rank); since every direct hit is a seed,
+/// `spread` already leaves them out of the indirect list.
+fn spread_from_hits(ctx: &ToolContext, threads: &[Thread], hops: u32) -> Vec<serde_json::Value> {
+    let cfg = super::guardian_config().engram.spreading.clone();
+    let hops = hops.min(cfg.max_hops);
+
+    let seeds: Vec<(String, f64)> = threads
+        .iter()
+        .enumerate()
+        .map(|(rank, t)| (t.id.clone(), (1.0 - rank as f64 * 0.05).max(0.5)))
+        .collect();
+
+    let activated = match SpreadingActivation::spread(ctx.agent_conn, &seeds, hops, &cfg) {
+        Ok(a) => a,
+        Err(e) => {
+            tracing::warn!(error = %e, "Spreading activation failed");
+            return Vec::new();
+        }
+    };
+
+    activated
+        .into_iter()
+        .filter_map(|a| {
+            let t = ThreadStorage::get(ctx.agent_conn, &a.thread_id).ok().flatten()?;
+            Some(serde_json::json!({
+                "id": t.id,
+                "title": t.title,
+                "status": t.status.as_str(),
+                "weight": t.weight,
+                "summary": t.summary,
+                "activation": (a.activation * 1000.0).round() / 1000.0,
+                "hops": a.hops,
+                "seed_id": a.seed_id,
+                "path": a.path,
+            }))
+        })
+        .collect()
+}
+
 /// Freshness Score (#7): compute how "fresh" a thread's information is.
 ///
 /// Score: 1.0 = just updated, 0.0 = very stale (30+ days).
@@ -0,0 +1,725 @@
+//! Tool registry — single declarative source for every MCP tool.
+//!
+//! Each tool declares its typed parameters once. The registry generates:
+//!   - JSON Schemas for `tools/list` (MCP) and the runtime (Anthropic tool_use)
+//!   - argument validation + coercion before dispatch (route_tool)
+//!   - dispatch to the handler
+//!
+//! Coercion is lenient for legacy clients that were taught "everything is a string":
+//! `"5"` → 5 for integers, `"true"` → true for booleans, `"a,b"` / `"[\"a\"]"` → arrays.
+//! Values that cannot be coerced are rejected with a precise InvalidInput error,
+//! and so are undeclared arguments (a typo like `limt` must not silently fall
+//! back to the default).
+
+use std::sync::OnceLock;
+
+use ai_smartness::registry::policy::{
+    CATEGORY_AGENT_ADMIN, CATEGORY_DESTRUCTIVE, CATEGORY_GENERAL, CATEGORY_MESSAGING, CATEGORY_WAKE,
+};
+use ai_smartness::{AiError, AiResult};
+use serde_json::{json, Value};
+
+use super::confirm::Preview;
+use super::paging::{ListSpec, MAX_LIMIT};
+use super::{
+    agents, batch, bridges, discover, focus, messaging, parse_object_array, parse_string_or_array, recall,
+    share, split, status, threads, windows, ToolContext, ToolOutput,
+};
+
+/// Accepted on every tool although undeclared: `confirm` is the legacy
+/// bypass flag, dropped by the confirmation gate (confirm.rs).
+const LEGACY_ARGS: &[&str] = &["confirm"];
+
+type PlainHandler = fn(&Value, &ToolContext) -> AiResult<Value>;
+type OutputHandler = fn(&Value, &ToolContext) -> AiResult<ToolOutput>;
+/// Preview of a two-phase tool call (None = nothing to confirm, run directly).
+pub type PreviewFn = fn(&Value, &ToolContext) -> AiResult<Option<Preview>>;
+
+/// JSON type of a tool parameter.
+#[derive(Debug, Clone, Copy)]
+pub enum ParamKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// Array of strings.
+    StringArray,
+    /// Array of objects / nested arrays.
+    Array,
+    /// Any JSON value (passed through untouched).
+    Any,
+    /// String restricted to the given values.
+    Enum(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub required: bool,
+    pub description: &'static str,
+    /// Documented default (the handler applies it when the param is absent).
+    pub default: Option<Value>,
+}
+
+enum Dispatch {
+    Plain(PlainHandler),
+    Output(OutputHandler),
+}
+
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: Vec<ParamSpec>,
+    /// Also exposed to the runtime (Anthropic tool_use), not only MCP.
+    pub runtime: bool,
+    /// Tool policy category (registry::policy::CATEGORY_*).
+    pub category: &'static str,
+    /// Parameter naming the agent this tool acts on (tool policy target).
+    pub target_param: Option<&'static str>,
+    /// Two-phase tool: preview + confirmation token before executing (see confirm.rs).
+    pub preview: Option<PreviewFn>,
+    dispatch: Dispatch,
+}
+
+impl ToolSpec {
+    fn new(name: &'static str, description: &'static str, handler: PlainHandler) -> Self {
+        Self {
+            name, description, params: Vec::new(), runtime: true,
+            category: CATEGORY_GENERAL, target_param: None, preview: None, dispatch: Dispatch::Plain(handler),
+        }
+    }
+
+    fn with_output(name: &'static str, description: &'static str, handler: OutputHandler) -> Self {
+        Self {
+            name, description, params: Vec::new(), runtime: true,
+            category: CATEGORY_GENERAL, target_param: None, preview: None, dispatch: Dispatch::Output(handler),
+        }
+    }
+
+    fn param(mut self, name: &'static str, kind: ParamKind, required: bool, description: &'static str, default: Option<Value>) -> Self {
+        self.params.push(ParamSpec { name, kind, required, description, default });
+        self
+    }
+
+    fn req(self, name: &'static str, kind: ParamKind, description: &'static str) -> Self {
+        self.param(name, kind, true, description, None)
+    }
+
+    fn opt(self, name: &'static str, kind: ParamKind, description: &'static str) -> Self {
+        self.param(name, kind, false, description, None)
+    }
+
+    fn opt_default(self, name: &'static str, kind: ParamKind, description: &'static str, default: Value) -> Self {
+        self.param(name, kind, false, description, Some(default))
+    }
+
+    /// MCP-only tool (not offered to the runtime).
+    fn mcp_only(mut self) -> Self {
+        self.runtime = false;
+        self
+    }
+
+    fn category(mut self, category: &'static str) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Declare which parameter names the target agent (hierarchy checks).
+    fn targets(mut self, param: &'static str) -> Self {
+        self.target_param = Some(param);
+        self
+    }
+
+    /// Paginated list tool (see paging.rs).
+    fn paged(self, list: &'static ListSpec) -> Self {
+        self.opt("cursor", ParamKind::String, "next_cursor of the previous page")
+            .opt_default("limit", ParamKind::Integer, "Page size (max 500)", json!(list.default_limit.min(MAX_LIMIT)))
+            .opt_default("sort_by", ParamKind::Enum(list.sort_keys), "Sort key (ties broken by id)", json!(list.sort_keys[0]))
+            .opt("order", ParamKind::Enum(&["asc", "desc"]), "Sort order (default depends on sort_by)")
+            .opt("fields", ParamKind::StringArray, "Only return these fields per item")
+    }
+
+    /// Two-phase tool: the first call returns a preview and a confirmation
+    /// token, the second call with the token executes.
+    fn two_phase(mut self, preview: PreviewFn) -> Self {
+        self.preview = Some(preview);
+        let spec = self.opt("confirm_token", ParamKind::String, "Token from the preview call (executes)");
+        if spec.params.iter().any(|p| p.name == "dry_run") {
+            return spec;
+        }
+        spec.opt_default("dry_run", ParamKind::Boolean, "Preview only, no token", json!(false))
+    }
+
+    /// JSON Schema of the tool arguments.
+    pub fn input_schema(&self) -> Value {
+        let mut props = serde_json::Map::new();
+        for p in &self.params {
+            let mut schema = match p.kind {
+                ParamKind::String => json!({"type": "string"}),
+                ParamKind::Integer => json!({"type": "integer"}),
+                ParamKind::Number => json!({"type": "number"}),
+                ParamKind::Boolean => json!({"type": "boolean"}),
+                ParamKind::StringArray => json!({"type": "array", "items": {"type": "string"}}),
+                ParamKind::Array => json!({"type": "array"}),
+                ParamKind::Any => json!({}),
+                ParamKind::Enum(values) => json!({"type": "string", "enum": values}),
+            };
+            schema["description"] = json!(p.description);
+            if let Some(ref d) = p.default {
+                schema["default"] = d.clone();
+            }
+            props.insert(p.name.to_string(), schema);
+        }
+        let required: Vec<&str> = self.params.iter().filter(|p| p.required).map(|p| p.name).collect();
+        json!({"type": "object", "properties": props, "required": required})
+    }
+
+    /// MCP tool definition (`inputSchema` key).
+    pub fn definition(&self) -> Value {
+        json!({"name": self.name, "description": self.description, "inputSchema": self.input_schema()})
+    }
+
+    /// Validate and coerce arguments against the declared params.
+    /// Undeclared arguments are rejected.
+    pub fn validate(&self, args: &Value) -> AiResult<Value> {
+        let mut out = match args {
+            Value::Object(map) => map.clone(),
+            Value::Null => serde_json::Map::new(),
+            _ => {
+                return Err(AiError::InvalidInput(format!(
+                    "{}: arguments must be a JSON object", self.name
+                )))
+            }
+        };
+        if let Some(unknown) = out
+            .keys()
+            .find(|k| !LEGACY_ARGS.contains(&k.as_str()) && !self.params.iter().any(|p| p.name == k.as_str()))
+        {
+            let declared: Vec<&str> = self.params.iter().map(|p| p.name).collect();
+            return Err(AiError::InvalidInput(format!(
+                "{}: unknown parameter '{}' (accepted: {})",
+                self.name,
+                unknown,
+                if declared.is_empty() { "none".to_string() } else { declared.join(", ") }
+            )));
+        }
+        for p in &self.params {
+            match out.get(p.name).cloned() {
+                None | Some(Value::Null) => {
+                    if p.required {
+                        return Err(AiError::InvalidInput(format!(
+                            "{}: missing required parameter '{}' ({})", self.name, p.name, p.description
+                        )));
+                    }
+                    out.remove(p.name);
+                }
+                Some(v) => {
+                    let coerced = coerce(p, &v).map_err(|expected| {
+                        AiError::InvalidInput(format!(
+                            "{}: parameter '{}' must be {} (got {})", self.name, p.name, expected, v
+                        ))
+                    })?;
+                    out.insert(p.name.to_string(), coerced);
+                }
+            }
+        }
+        Ok(Value::Object(out))
+    }
+
+    /// Validate then dispatch to the handler.
+    pub fn call(&self, args: &Value, ctx: &ToolContext) -> AiResult<ToolOutput> {
+        let args = self.validate(args)?;
+        match self.dispatch {
+            Dispatch::Plain(h) => h(&args, ctx).map(ToolOutput::Plain),
+            Dispatch::Output(h) => h(&args, ctx),
+        }
+    }
+}
+
+/// Coerce one value to the declared kind. Err carries the expected type.
+fn coerce(p: &ParamSpec, v: &Value) -> Result<Value, String> {
+    match p.kind {
+        ParamKind::Any => Ok(v.clone()),
+        ParamKind::String => match v {
+            Value::String(_) => Ok(v.clone()),
+            Value::Number(_) | Value::Bool(_) => Ok(Value::String(v.to_string())),
+            _ => Err("a string".into()),
+        },
+        ParamKind::Enum(values) => {
+            let s = match v {
+                Value::String(s) => s.clone(),
+                Value::Number(_) | Value::Bool(_) => v.to_string(),
+                _ => return Err(format!("one of {:?}", values)),
+            };
+            if values.contains(&s.as_str()) {
+                Ok(Value::String(s))
+            } else {
+                Err(format!("one of {:?}", values))
+            }
+        }
+        ParamKind::Integer => match v {
+            Value::Number(n) if n.is_i64() || n.is_u64() => Ok(v.clone()),
+            Value::Number(n) => n.as_f64()
+                .filter(|f| f.fract() == 0.0)
+                .map(|f| json!(f as i64))
+                .ok_or_else(|| "an integer".into()),
+            Value::String(s) => s.trim().parse::<i64>().map(|i| json!(i)).map_err(|_| "an integer".into()),
+            _ => Err("an integer".into()),
+        },
+        ParamKind::Number => match v {
+            Value::Number(_) => Ok(v.clone()),
+            Value::String(s) => s.trim().parse::<f64>().map(|f| json!(f)).map_err(|_| "a number".into()),
+            _ => Err("a number".into()),
+        },
+        ParamKind::Boolean => match v {
+            Value::Bool(_) => Ok(v.clone()),
+            Value::String(s) => match s.trim() {
+                "true" | "1" | "yes" => Ok(json!(true)),
+                "false" | "0" | "no" => Ok(json!(false)),
+                _ => Err("a boolean".into()),
+            },
+            Value::Number(n) => Ok(json!(n.as_f64().unwrap_or(0.0) != 0.0)),
+            _ => Err("a boolean".into()),
+        },
+        ParamKind::StringArray => {
+            if let Some(arr) = v.as_array() {
+                arr.iter()
+                    .map(|item| match item {
+                        Value::String(_) => Ok(item.clone()),
+                        Value::Number(_) | Value::Bool(_) => Ok(Value::String(item.to_string())),
+                        _ => Err("an array of strings".to_string()),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Value::Array)
+            } else {
+                parse_string_or_array(v).map(|a| json!(a)).ok_or_else(|| "an array of strings".into())
+            }
+        }
+        ParamKind::Array => match v {
+            Value::Array(_) => Ok(v.clone()),
+            Value::String(s) => serde_json::from_str::<Value>(s.trim())
+                .ok()
+                .filter(|parsed| parsed.is_array())
+                .or_else(|| parse_object_array(v).map(Value::Array))
+                .ok_or_else(|| "an array".into()),
+            _ => Err("an array".into()),
+        },
+    }
+}
+
+/// All registered tools, in `tools/list` order.
+pub fn registry() -> &'static [ToolSpec] {
+    static REGISTRY: OnceLock<Vec<ToolSpec>> = OnceLock::new();
+    REGISTRY.get_or_init(build)
+}
+
+pub fn find(name: &str) -> Option<&'static ToolSpec> {
+    registry().iter().find(|t| t.name == name)
+}
+
+/// Definitions for MCP `tools/list`.
+pub fn mcp_definitions() -> Vec<Value> {
+    registry().iter().map(ToolSpec::definition).collect()
+}
+
+/// Definitions offered to the runtime (MCP format; converted by the tool executor).
+pub fn runtime_definitions() -> Vec<Value> {
+    registry().iter().filter(|t| t.runtime).map(ToolSpec::definition).collect()
+}
+
+// ── Declarations ──
+
+use ParamKind::{Any, Array, Boolean, Enum, Integer, Number, String as Str, StringArray};
+
+const THREAD_STATUSES: &[&str] = &["active", "suspended", "archived"];
+const EDIT_MODES: &[&str] = &["list", "set", "add", "remove"];
+const PRIORITIES: &[&str] = &["low", "normal", "high", "urgent"];
+
+fn build() -> Vec<ToolSpec> {
+    vec![
+        // -- Memory & Search --
+        ToolSpec::new("ai_recall", "Search semantic memory for relevant threads", recall::handle_recall)
+            .req("query", Str, "Search query (keywords or natural language)")
+            .opt("label", Str, "Only return threads carrying this label")
+            .opt_default("include_bridges", Boolean, "Include bridges of the returned threads", json!(false))
+            .opt("depth", Enum(&["shallow", "deep"]), "deep includes the first messages of each thread")
+            .opt_default("hops", Integer, "Spreading activation hops along bridges (0-5)", json!(0)),
+
+        // -- Thread lifecycle --
+        ToolSpec::new("ai_thread_create", "Create a new thread manually", threads::handle_thread_create)
+            .req("title", Str, "Thread title")
+            .req("content", Str, "Initial message content")
+            .opt("topics", StringArray, "Topics")
+            .opt_default("importance", Number, "Importance 0.0-1.0", json!(0.5))
+            .opt("tags", StringArray, "Tags (system tags: __pin__, __focus__, __mind__, __shared__)"),
+        ToolSpec::new("ai_thread_rm", "Delete a thread by ID", threads::handle_thread_rm)
+            .req("thread_id", Str, "Thread ID")
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(threads::preview_thread_rm),
+        ToolSpec::new("ai_thread_rm_batch", "Delete multiple threads", threads::handle_thread_rm_batch)
+            .req("thread_ids", StringArray, "Thread IDs")
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(threads::preview_thread_rm_batch),
+        ToolSpec::new("ai_thread_list", "List threads with filters", threads::handle_thread_list)
+            .opt_default("status", Enum(THREAD_STATUSES), "Thread status", json!("active"))
+            .opt_default("offset", Integer, "Items to skip (prefer cursor)", json!(0))
+            .paged(&threads::THREADS),
+        ToolSpec::new("ai_thread_search", "Search threads across all states", threads::handle_thread_search)
+            .req("query", Str, "Search query")
+            .opt("scope", Str, "Search scope")
+            .opt("states", StringArray, "Thread states to include")
+            .paged(&threads::THREADS),
+        ToolSpec::new("ai_continuity_edges", "Manage continuity edges (reasoning chain between threads)", threads::handle_continuity_edges)
+            .opt_default("action", Enum(&["list", "set", "unset", "scan_orphans", "repair"]), "Operation", json!("list"))
+            .opt("thread_id", Str, "Child thread ID")
+            .opt("parent_id", Str, "Parent thread ID (set)")
+            .opt("coherence", Number, "Subject coherence 0.0-1.0 (set)"),
+        ToolSpec::new("ai_thread_activate", "Reactivate threads", threads::handle_thread_activate)
+            .req("thread_ids", StringArray, "Thread IDs")
+            .two_phase(threads::preview_thread_activate),
+        ToolSpec::new("ai_thread_suspend", "Suspend active threads", threads::handle_thread_suspend)
+            .req("thread_ids", StringArray, "Thread IDs")
+            .opt("reason", Str, "Reason")
+            .two_phase(threads::preview_thread_suspend),
+        ToolSpec::new("ai_thread_purge", "Bulk delete all threads by status (suspended/archived). Cannot purge active.", threads::handle_thread_purge)
+            .req("status", Enum(&["suspended", "archived"]), "Status to purge")
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(threads::preview_thread_purge),
+        ToolSpec::new("ai_reactivate", "Reactivate a thread by ID", threads::handle_reactivate)
+            .req("thread_id", Str, "Thread ID"),
+
+        // -- Thread annotation & operations --
+        ToolSpec::new("ai_annotate", "Add a lightweight note to a thread (no LLM, no extraction)", threads::handle_annotate)
+            .req("thread_id", Str, "Thread ID")
+            .req("note", Str, "Note text"),
+        ToolSpec::new("ai_split", "Split a thread", split::handle_split)
+            .req("thread_id", Str, "Thread ID")
+            .opt("message_groups", Array, "Message ID groups, one array per new thread")
+            .opt("titles", StringArray, "Titles of the new threads (same length as message_groups)")
+            .opt("lock_mode", Str, "Split lock mode")
+            .two_phase(split::preview_split),
+        ToolSpec::new("ai_split_unlock", "Remove split lock", split::handle_split_unlock)
+            .req("thread_id", Str, "Thread ID"),
+
+        // -- Thread metadata --
+        ToolSpec::new("ai_label", "Manage labels", threads::handle_label)
+            .req("thread_id", Str, "Thread ID")
+            .opt("labels", StringArray, "Labels (set/add/remove)")
+            .opt_default("mode", Enum(EDIT_MODES), "Operation", json!("list")),
+        ToolSpec::new("ai_labels_suggest", "Show existing labels", threads::handle_labels_suggest)
+            .req("label", Str, "Proposed label"),
+        ToolSpec::new("ai_concepts", "Manage semantic concepts", threads::handle_concepts)
+            .req("thread_id", Str, "Thread ID")
+            .opt("concepts", StringArray, "Concepts (set/add/remove)")
+            .opt_default("mode", Enum(EDIT_MODES), "Operation", json!("list")),
+        ToolSpec::new("ai_topic_alias", "Manage topic aliases (list, suggest, confirm, reject, add, remove)", threads::handle_topic_alias)
+            .opt_default("action", Enum(&["list", "suggest", "confirm", "reject", "add", "remove"]), "Operation", json!("list"))
+            .opt("alias", Str, "Alias topic (confirm/reject/add/remove)")
+            .opt("canonical", Str, "Canonical topic (add)")
+            .opt("status", Enum(&["confirmed", "suggested", "rejected"]), "Filter for list")
+            .opt_default("limit", Integer, "Max suggestions", json!(20)),
+        ToolSpec::new("ai_backfill_concepts", "Generate concepts for threads missing them", threads::handle_backfill_concepts)
+            .opt_default("limit", Integer, "Max threads to enrich", json!(10))
+            .two_phase(threads::preview_backfill_concepts),
+        ToolSpec::new("ai_rename", "Rename a thread", threads::handle_rename)
+            .req("thread_id", Str, "Thread ID")
+            .req("new_title", Str, "New title"),
+        ToolSpec::new("ai_rename_batch", "Rename multiple threads", threads::handle_rename_batch)
+            .req("operations", Array, "Array of {thread_id, new_title}"),
+        ToolSpec::new("ai_rate_importance", "Set importance score", threads::handle_rate_importance)
+            .req("thread_id", Str, "Thread ID")
+            .req("score", Number, "Importance 0.0-1.0")
+            .opt("reason", Str, "Reason"),
+        ToolSpec::new("ai_rate_context", "Rate context usefulness", threads::handle_rate_context)
+            .req("thread_id", Str, "Thread ID")
+            .req("useful", Boolean, "Was the injected context useful")
+            .opt("reason", Str, "Reason"),
+        ToolSpec::new("ai_mark_used", "Mark thread as used after injection", threads::handle_mark_used)
+            .req("thread_id", Str, "Thread ID"),
+
+        ToolSpec::new("ai_batch", "Run an ordered list of memory edits atomically (one transaction, rollback on first error). Step args may reference earlier results: \"$1.thread_id\"", batch::handle_batch)
+            .req("steps", Array, "Array of {tool, args}, max 50")
+            .opt_default("dry_run", Boolean, "Run then roll back, reporting what would change", json!(false))
+            .two_phase(batch::preview_batch),
+
+        // -- Bridges --
+        ToolSpec::new("ai_bridges", "List bridges", bridges::handle_bridges)
+            .opt("thread_id", Str, "Only bridges touching this thread")
+            .opt("relation_type", Str, "Relation type filter")
+            .opt("status", Str, "Bridge status filter")
+            .paged(&bridges::BRIDGES),
+        ToolSpec::new("ai_bridge_analysis", "Bridge network analytics", bridges::handle_bridge_analysis),
+        ToolSpec::new("ai_bridge_scan_orphans", "Scan orphan bridges", bridges::handle_bridge_scan_orphans)
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(bridges::preview_bridge_scan_orphans),
+        ToolSpec::new("ai_bridge_purge", "Bulk delete all bridges by status (invalid/weak)", bridges::handle_bridge_purge)
+            .req("status", Str, "Bridge status to purge (invalid, weak)")
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(bridges::preview_bridge_purge),
+        ToolSpec::new("ai_bridge_kill", "Delete a bridge", bridges::handle_bridge_kill)
+            .req("bridge_id", Str, "Bridge ID")
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(bridges::preview_bridge_kill),
+        ToolSpec::new("ai_bridge_kill_batch", "Delete multiple bridges", bridges::handle_bridge_kill_batch)
+            .req("bridge_ids", StringArray, "Bridge IDs")
+            .category(CATEGORY_DESTRUCTIVE)
+            .two_phase(bridges::preview_bridge_kill_batch),
+        ToolSpec::new("ai_resolve_conflict", "Resolve a contradiction: keep one thread, mark the other as replaced", bridges::handle_resolve_conflict)
+            .req("keep_id", Str, "Thread to keep")
+            .req("replaced_id", Str, "Thread being replaced")
+            .opt("reason", Str, "Resolution reason")
+            .opt_default("suspend", Boolean, "Suspend the replaced thread", json!(false)),
+
+        // -- Focus & Pins --
+        ToolSpec::new("ai_focus", "Focus on a topic", focus::handle_focus)
+            .req("topic", Str, "Topic to focus on")
+            .opt("weight", Number, "Focus weight 0.0-1.0"),
+        ToolSpec::new("ai_unfocus", "Remove focus", focus::handle_unfocus)
+            .opt("topic", Str, "Topic (all when omitted)"),
+        ToolSpec::new("ai_pin", "Pin important content", focus::handle_pin)
+            .req("content", Str, "Content to pin")
+            .opt("title", Str, "Title")
+            .opt("topics", StringArray, "Topics")
+            .opt("weight_boost", Number, "Extra weight"),
+
+        // -- Cognitive Messaging --
+        ToolSpec::new("ai_msg_focus", "Write cognitive message", messaging::handle_msg_focus)
+            .req("target_agent_id", Str, "Recipient agent")
+            .req("from_agent", Str, "Sender agent")
+            .req("subject", Str, "Subject")
+            .req("content", Str, "Message body")
+            .opt_default("priority", Enum(PRIORITIES), "Priority", json!("normal"))
+            .opt("ttl_minutes", Integer, "Expiry in minutes")
+            .opt("attachments", StringArray, "Attachment paths")
+            .opt("reply_to", Str, "Message being answered"),
+        ToolSpec::new("ai_msg_ack", "Acknowledge message", messaging::handle_msg_ack)
+            .opt("thread_id", Str, "Message thread ID")
+            .opt("msg_ref", Str, "Message reference"),
+
+        // -- Shared Cognition --
+        ToolSpec::new("ai_share", "Share a thread", share::handle_share)
+            .req("thread_id", Str, "Thread ID")
+            .opt_default("visibility", Enum(&["network", "restricted"]), "Visibility", json!("network"))
+            .opt("allowed_agents", StringArray, "Agents allowed (restricted)"),
+        ToolSpec::new("ai_unshare", "Unshare a thread", share::handle_unshare)
+            .req("shared_id", Str, "Shared thread ID"),
+        ToolSpec::new("ai_publish", "Update shared snapshot", share::handle_publish)
+            .req("shared_id", Str, "Shared thread ID"),
+        ToolSpec::new("ai_discover", "Discover shared threads", discover::handle_discover)
+            .opt("topics", StringArray, "Topics filter")
+            .opt("agent_id", Str, "Owner agent filter")
+            .paged(&discover::SHARED),
+        ToolSpec::new("ai_subscribe", "Subscribe to shared thread", discover::handle_subscribe)
+            .req("shared_id", Str, "Shared thread ID"),
+        ToolSpec::new("ai_unsubscribe", "Unsubscribe", discover::handle_unsubscribe)
+            .req("shared_id", Str, "Shared thread ID"),
+        ToolSpec::new("ai_sync", "Sync subscriptions", discover::handle_sync)
+            .opt("shared_id", Str, "Only this subscription"),
+
+        // -- System & Status --
+        ToolSpec::new("ai_status", "Memory status", status::handle_status),
+        ToolSpec::new("ai_sysinfo", "System info", status::handle_sysinfo),
+        ToolSpec::new("ai_help", "Documentation — call with topic for detailed help (memory, threads, bridges, messaging, sharing, agents, tasks, maintenance, autonomy)", status::handle_help)
+            .opt("topic", Str, "Help topic"),
+        ToolSpec::new("ai_suggestions", "Proactive suggestions", status::handle_suggestions)
+            .opt("context", Str, "Current context"),
+        ToolSpec::new("ai_shared_status", "Shared cognition status", status::handle_shared_status),
+        ToolSpec::new("ai_profile", "User profile — view, set, set_rule, remove_rule, list, clear_rules", status::handle_profile)
+            .req("action", Enum(&["view", "set", "set_rule", "remove_rule", "list", "clear_rules"]), "Operation")
+            .opt("key", Str, "Profile key (set) or rule index (remove_rule)")
+            .opt("value", Str, "Value (set) or rule text (set_rule)"),
+
+        // -- Maintenance --
+        ToolSpec::new("ai_cleanup", "Fix thread titles", status::handle_cleanup)
+            .opt("mode", Str, "Cleanup mode")
+            .opt_default("dry_run", Boolean, "Preview only", json!(false)),
+        ToolSpec::new("ai_lock", "Lock memory", |p, c| status::handle_lock(p, c, "ai_lock"))
+            .opt("reason", Str, "Reason")
+            .opt("duration_minutes", Integer, "Lock duration"),
+        ToolSpec::new("ai_unlock", "Unlock memory", |p, c| status::handle_lock(p, c, "ai_unlock")),
+        ToolSpec::new("ai_lock_status", "Lock state", |p, c| status::handle_lock(p, c, "ai_lock_status")),
+        ToolSpec::new("ai_backup", "Backup/restore", status::handle_backup)
+            .req("action", Enum(&["create", "restore", "status"]), "Operation")
+            .opt("interval_hours", Integer, "Auto-backup interval")
+            .category(CATEGORY_DESTRUCTIVE),
+        ToolSpec::new("ai_recommend", "Subscription recommendations", discover::handle_recommend)
+            .opt("limit", Integer, "Max results"),
+        ToolSpec::new("ai_topics", "Topic discovery", status::handle_topics_network)
+            .opt("agent_id", Str, "Agent filter")
+            .paged(&status::TOPICS),
+
+        // -- mcp-smartness-com: Messaging --
+        ToolSpec::new("msg_send", "Send message", messaging::handle_msg_send)
+            .req("to", Str, "Recipient agent")
+            .req("subject", Str, "Subject")
+            .opt("payload", Str, "Message body")
+            .opt_default("priority", Enum(PRIORITIES), "Priority", json!("normal"))
+            .opt("agent_id", Str, "Sender override")
+            .opt("attachments", StringArray, "Attachment paths")
+            .category(CATEGORY_MESSAGING)
+            .targets("to"),
+        ToolSpec::new("msg_broadcast", "Broadcast message", messaging::handle_msg_broadcast)
+            .req("subject", Str, "Subject")
+            .opt("payload", Str, "Message body")
+            .opt_default("priority", Enum(PRIORITIES), "Priority", json!("normal"))
+            .opt("attachments", StringArray, "Attachment paths")
+            .category(CATEGORY_MESSAGING),
+        ToolSpec::new("msg_inbox", "Get pending messages (the returned page is marked read)", messaging::handle_msg_inbox)
+            .opt("agent_id", Str, "Inbox owner override")
+            .opt_default("peek", Boolean, "Leave the returned page pending", json!(false))
+            .paged(&messaging::MESSAGES),
+        ToolSpec::new("msg_reply", "Reply to message", messaging::handle_msg_reply)
+            .req("message_id", Str, "Message being answered")
+            .opt("payload", Any, "Reply body (string or JSON)")
+            .opt("agent_id", Str, "Sender override")
+            .opt("attachments", StringArray, "Attachment paths"),
+
+        // -- mcp-smartness-com: Agents --
+        ToolSpec::with_output("ai_agent_select", "Switch to a different agent for this session. Writes the session file so subsequent prompts use the new agent identity. Pass session_id from your context for multi-panel isolation.", agents::handle_agent_select)
+            .req("agent_id", Str, "Agent to switch to")
+            .opt("session_id", Str, "Session ID for multi-panel isolation"),
+        ToolSpec::new("agent_list", "List agents", agents::handle_agent_list)
+            .paged(&agents::AGENTS),
+        ToolSpec::new("agent_query", "Find agents by capability", agents::handle_agent_query)
+            .req("capability", Str, "Capability"),
+        ToolSpec::new("agent_status", "Agent status", agents::handle_agent_status)
+            .req("agent_id", Str, "Agent ID"),
+        ToolSpec::new("agent_context", "Inspect another agent's runtime state (beat, actions, tasks, context %)", status::handle_agent_context)
+            .opt("agent_id", Str, "Agent ID (self when omitted)"),
+        ToolSpec::new("agent_cleanup", "Clean up agents", agents::handle_agent_cleanup)
+            .opt("remove_agent", Str, "Agent to remove")
+            .opt_default("remove_orphans", Boolean, "Remove orphan agents", json!(false))
+            .category(CATEGORY_AGENT_ADMIN)
+            .targets("remove_agent")
+            .two_phase(agents::preview_agent_cleanup),
+        ToolSpec::new("agent_configure", "Configure agent", agents::handle_agent_configure)
+            .req("agent_id", Str, "Agent ID")
+            .req("project_hash", Str, "Project hash (ignored — current project is used)")
+            .opt("role", Str, "Role")
+            .opt("supervisor_id", Str, "Supervisor agent")
+            .opt("name", Str, "Display name")
+            .opt("team", Str, "Team")
+            .opt("description", Str, "Description")
+            .opt("custom_role", Str, "Custom role")
+            .opt("coordination_mode", Str, "Coordination mode")
+            .opt("report_to", Str, "Reporting agent")
+            .opt("thread_mode", Str, "Thread mode")
+            .opt("workspace_path", Str, "Workspace path")
+            .opt("expected_model", Str, "Expected model")
+            .opt("full_permissions", Boolean, "Grant full permissions")
+            .category(CATEGORY_AGENT_ADMIN)
+            .targets("agent_id"),
+        ToolSpec::new("agent_tasks", "Manage tasks", agents::handle_agent_tasks)
+            .req("action", Enum(&["list", "create", "update_status", "complete", "delete"]), "Operation")
+            .opt("task_id", Str, "Task ID")
+            .opt("title", Str, "Task title (create)")
+            .opt("assigned_to", Str, "Assignee (create)")
+            .opt("description", Str, "Description (create)")
+            .opt("priority", Str, "Priority (create)")
+            .opt("status", Str, "New status (update_status)")
+            .opt("result", Str, "Result (complete)"),
+
+        // -- mcp-smartness-com: Tasks --
+        ToolSpec::new("task_delegate", "Delegate task", agents::handle_task_delegate)
+            .req("to", Str, "Assignee agent")
+            .req("task", Str, "Task description")
+            .opt("context", Str, "Context")
+            .opt("priority", Str, "Priority")
+            .opt("context_path", Str, "Context file path")
+            .category(CATEGORY_MESSAGING)
+            .targets("to"),
+        ToolSpec::new("task_status", "Task status", agents::handle_task_status)
+            .req("task_id", Str, "Task ID"),
+        ToolSpec::new("task_complete", "Mark a delegated task as completed and auto-notify the delegator", agents::handle_task_complete)
+            .req("task_id", Str, "Task ID")
+            .opt("result", Str, "Result summary"),
+
+        // -- mcp-smartness-com: Metrics & Health --
+        ToolSpec::new("metrics_cross_agent", "Cross-agent metrics", status::handle_metrics)
+            .opt("agent_id", Str, "Agent filter")
+            .opt("period", Str, "Period"),
+        ToolSpec::new("ai_audit", "Query the audit log of tool calls (who called what, outcome, affected ids)", status::handle_audit)
+            .opt("agent_id", Str, "Only calls by this agent")
+            .opt("tool", Str, "Tool name (trailing * = prefix, e.g. ai_thread_*)")
+            .opt("outcome", Enum(&["ok", "error", "denied", "preview"]), "Outcome filter")
+            .opt("since_hours", Integer, "Only the last N hours")
+            .opt("affected_id", Str, "Only calls that touched this thread/bridge/agent id")
+            .opt_default("limit", Integer, "Max entries (<= 500)", json!(50)),
+        ToolSpec::new("health_check", "Health check", status::handle_health_check),
+        ToolSpec::new("topics_network", "Trending topics", status::handle_topics_network)
+            .opt("agent_id", Str, "Agent filter")
+            .opt("limit", Integer, "Max topics"),
+        ToolSpec::new("test_sampling", "Test sampling", status::handle_test_sampling)
+            .opt("attempt_sampling", Boolean, "Attempt a sampling request")
+            .mcp_only(),
+
+        // -- Beat / Self-wake --
+        ToolSpec::new("beat_wake", "Schedule self-wake after N beats (~5 min each). The heartbeat system will wake you automatically.", status::handle_beat_wake)
+            .req("after", Integer, "Beats to wait (>= 1)")
+            .opt("reason", Str, "Wake reason")
+            .category(CATEGORY_WAKE),
+        ToolSpec::new("nanobeat_schedule", "Schedule a sub-beat self-wake with recall context. Use this to chain tasks autonomously: when finishing work, schedule a nanobeat so you wake up and continue with the next task.", status::handle_nanobeat_schedule)
+            .req("delay_seconds", Integer, "Delay in seconds")
+            .req("reason", Str, "Wake reason")
+            .opt("recall_query", Str, "Recall query to pre-load on wake")
+            .opt("recall_thread_id", Str, "Thread to focus on wake")
+            .category(CATEGORY_WAKE),
+
+        // -- Windows --
+        ToolSpec::new("ai_windows", "Open a new VSCode window on the current project", windows::handle_windows)
+            .mcp_only(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_names_unique() {
+        let mut seen = std::collections::HashSet::new();
+        for t in registry() {
+            assert!(seen.insert(t.name), "duplicate tool {}", t.name);
+        }
+    }
+
+    #[test]
+    fn test_schema_is_typed() {
+        let schema = find("ai_recall").unwrap().input_schema();
+        assert_eq!(schema["properties"]["hops"]["type"], "integer");
+        assert_eq!(schema["properties"]["include_bridges"]["type"], "boolean");
+        assert_eq!(schema["properties"]["depth"]["enum"][1], "deep");
+        assert_eq!(schema["required"][0], "query");
+    }
+
+    #[test]
+    fn test_validate_coerces_legacy_strings() {
+        let spec = find("ai_thread_list").unwrap();
+        let out = spec.validate(&json!({"limit": "5", "status": "suspended"})).unwrap();
+        assert_eq!(out["limit"], 5);
+
+        let spec = find("ai_thread_rm_batch").unwrap();
+        let out = spec.validate(&json!({"thread_ids": "a, b"})).unwrap();
+        assert_eq!(out["thread_ids"], json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_input() {
+        let spec = find("ai_thread_list").unwrap();
+        assert!(spec.validate(&json!({"limit": "many"})).is_err());
+        assert!(spec.validate(&json!({"status": "deleted"})).is_err());
+        assert!(find("ai_rename").unwrap().validate(&json!({"thread_id": "t"})).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_undeclared_args() {
+        let spec = find("ai_thread_list").unwrap();
+        let err = spec.validate(&json!({"limt": 5})).unwrap_err().to_string();
+        assert!(err.contains("unknown parameter 'limt'"), "{}", err);
+        assert!(spec.validate(&json!({"limit": 5, "confirm": true})).is_ok(), "Legacy confirm flag accepted");
+        assert!(spec.validate(&json!({"offset": 10})).is_ok());
+        let search = find("ai_thread_search").unwrap();
+        assert!(search.validate(&json!({"query": "q", "offset": 10})).is_err(), "offset not declared here");
+    }
+}
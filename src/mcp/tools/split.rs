@@ -5,19 +5,20 @@ use ai_smartness::storage::threads::ThreadStorage;
 
 use ai_smartness::constants::truncate_safe;
 
-use super::{optional_bool, required_str, ToolContext};
+use super::confirm::Preview;
+use super::{required_str, ToolContext};
 
 pub fn handle_split(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let thread_id = required_str(params, "thread_id")?;
-    let confirm = optional_bool(params, "confirm").unwrap_or(false);
 
     let thread = ThreadStorage::get(ctx.agent_conn, &thread_id)?
         .ok_or_else(|| ai_smartness::AiError::ThreadNotFound(thread_id.clone()))?;
 
-    if !confirm {
+    // Without groups: list the messages to pick from
+    if params.get("message_groups").is_none() {
         let messages = ThreadStorage::get_messages(ctx.agent_conn, &thread_id)?;
         let msg_list: Vec<serde_json::Value> = messages
             .iter()
@@ -39,7 +40,7 @@ pub fn handle_split(
             "thread_id": thread_id,
             "title": thread.title,
             "messages": msg_list,
-            "instruction": "Provide message_groups and titles, call with confirm=true",
+            "instruction": "Provide message_groups and titles to preview the split",
         }));
     }
 
@@ -129,6 +130,45 @@ pub fn handle_split(
     }))
 }
 
+/// Two-phase preview of the split itself (the message listing runs directly).
+pub fn preview_split(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let Some(groups) = params.get("message_groups").and_then(|v| v.as_array()) else {
+        return Ok(None);
+    };
+    let thread_id = required_str(params, "thread_id")?;
+    let thread = ThreadStorage::get(ctx.agent_conn, &thread_id)?
+        .ok_or_else(|| ai_smartness::AiError::ThreadNotFound(thread_id.clone()))?;
+    let known: std::collections::HashSet<String> = ThreadStorage::get_messages(ctx.agent_conn, &thread_id)?
+        .into_iter()
+        .map(|m| m.msg_id)
+        .collect();
+
+    let mut ids = vec![thread_id.clone()];
+    let mut missing = Vec::new();
+    for mid in groups.iter().filter_map(|g| g.as_array()).flatten().filter_map(|m| m.as_str()) {
+        if known.contains(mid) {
+            ids.push(mid.to_string());
+        } else {
+            missing.push(mid.to_string());
+        }
+    }
+    let copied = ids.len() - 1;
+    let preview = Preview::new(
+        ids,
+        format!("Would split '{}' into {} new thread(s) and lock it", thread.title, groups.len()),
+    )
+    .counts(serde_json::json!({
+        "new_threads": groups.len(),
+        "messages_copied": copied,
+        "missing_messages": missing,
+    }))
+    .side_effects(serde_json::json!({
+        "original_split_locked": true,
+        "continuity_parent_of_new_threads": thread_id,
+    }));
+    Ok(Some(preview))
+}
+
 pub fn handle_split_unlock(
     params: &serde_json::Value,
     ctx: &ToolContext,
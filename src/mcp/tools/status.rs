@@ -1,5 +1,7 @@
 use ai_smartness::agent::TaskStatus;
+use ai_smartness::intelligence::retention::RetentionReport;
 use ai_smartness::registry::tasks::AgentTaskStorage;
+use ai_smartness::storage::audit::{AuditFilter, AuditLog};
 use ai_smartness::storage::backup::BackupManager;
 use ai_smartness::storage::beat::BeatState;
 use ai_smartness::storage::bridges::BridgeStorage;
@@ -12,7 +14,8 @@ use ai_smartness::thread::ThreadStatus;
 use ai_smartness::user_profile::UserProfile;
 use ai_smartness::AiResult;
 
-use super::{optional_str, required_str, ToolContext};
+use super::paging::{ListSpec, Page};
+use super::{optional_str, optional_usize, required_str, ToolContext, PROGRESS_EVERY};
 
 pub fn handle_status(
     _params: &serde_json::Value,
@@ -89,6 +92,32 @@ pub fn handle_status(
         })
         .collect();
 
+    // Unresolved contradictions (LLM-classified), one entry per thread pair
+    let mut seen_pairs = std::collections::HashSet::new();
+    let conflicts: Vec<serde_json::Value> = BridgeStorage::list_conflicts(ctx.agent_conn)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|b| {
+            let key = if b.source_id < b.target_id {
+                (b.source_id.clone(), b.target_id.clone())
+            } else {
+                (b.target_id.clone(), b.source_id.clone())
+            };
+            seen_pairs.insert(key)
+        })
+        .take(10)
+        .map(|b| {
+            let title_of = |id: &str| all_threads.iter().find(|t| t.id == id).map(|t| t.title.clone());
+            serde_json::json!({
+                "bridge_id": b.id,
+                "thread_a": {"id": b.source_id, "title": title_of(&b.source_id)},
+                "thread_b": {"id": b.target_id, "title": title_of(&b.target_id)},
+                "reason": b.reason,
+                "confidence": b.confidence,
+            })
+        })
+        .collect();
+
     // Pending tasks
     let pending_tasks = AgentTaskStorage::list_tasks_for_agent(
         ctx.registry_conn,
@@ -132,6 +161,8 @@ pub fn handle_status(
         "pins": pins,
         "focus": focus,
         "top_threads": top_threads,
+        "conflicts": conflicts,
+        "retention": RetentionReport::load(&data_dir),
         "pending_tasks": pending_tasks,
         "pending_messages": pending_messages,
     }))
@@ -199,26 +230,28 @@ fn help_overview() -> serde_json::Value {
     serde_json::json!({
         "name": "AI Smartness",
         "version": env!("CARGO_PKG_VERSION"),
-        "tool_count": 69,
+        "tool_count": super::registry::registry().len(),
         "usage": "ai_help(topic=\"memory\") for detailed help per category",
         "categories": {
             "memory": "Memory & Search — ai_recall, ai_focus, ai_unfocus, ai_pin",
             "threads": "Thread Lifecycle & Operations — create, list, search, split, annotate, label, rename, rate",
-            "bridges": "Bridges — ai_bridges, ai_bridge_analysis, ai_bridge_scan_orphans, ai_bridge_kill",
+            "bridges": "Bridges — ai_bridges, ai_bridge_analysis, ai_bridge_scan_orphans, ai_bridge_kill, ai_resolve_conflict",
             "messaging": "Messaging — msg_send, msg_broadcast, msg_inbox, msg_reply, ai_msg_focus, ai_msg_ack",
             "sharing": "Shared Cognition — ai_share, ai_publish, ai_discover, ai_subscribe, ai_sync",
             "agents": "Agent Management — ai_agent_select, agent_list, agent_query, agent_status, agent_context, agent_configure",
             "tasks": "Task Delegation — task_delegate, task_status, task_complete, agent_tasks",
-            "maintenance": "Maintenance & System — ai_cleanup, ai_backup, ai_lock, ai_sysinfo, health_check",
+            "maintenance": "Maintenance & System — ai_cleanup, ai_backup, ai_lock, ai_sysinfo, ai_audit, health_check",
             "autonomy": "Autonomous Task Chaining — nanobeat_schedule, beat_wake",
         },
         "quick_ref": [
-            "ai_status → full context snapshot (beat, threads, pins, focus, profile)",
-            "ai_recall(query) → semantic search [depth=deep for inline messages, freshness score]",
+            "ai_status → full context snapshot (beat, threads, pins, focus, conflicts, retention, profile)",
+            "ai_recall(query) → semantic search [depth=deep for inline messages, hops=N for multi-hop, freshness score]",
             "ai_help(topic) → detailed help per category",
             "ai_profile(action=set, key, value) → edit identity/preferences",
             "ai_profile(action=set_rule, value) → add persistent rule",
             "nanobeat_schedule(delay_seconds, reason) → self-wake for task chaining",
+            "List tools (ai_thread_list, ai_thread_search, ai_bridges, msg_inbox, agent_list, ai_discover, ai_topics) page: limit, sort_by, order, fields=[\"id\",\"title\"]; pass next_cursor back as cursor while has_more",
+            "Destructive tools are two-phase: first call → preview (affected_ids, side_effects) + confirm_token; re-call with the same args + confirm_token to execute (token valid 5 min, single use)",
         ],
     })
 }
@@ -230,8 +263,8 @@ fn help_memory() -> serde_json::Value {
             "ai_recall": {
                 "description": "Semantic search across all threads",
                 "required": ["query"],
-                "optional": ["label", "include_bridges", "depth"],
-                "notes": "depth=deep includes first 3 messages (500 char cap). Every result includes a freshness score (1.0=fresh, 0.0=stale).",
+                "optional": ["label", "include_bridges", "depth", "hops"],
+                "notes": "depth=deep includes first 3 messages (500 char cap). Every result includes a freshness score (1.0=fresh, 0.0=stale). hops=N (1-5) spreads activation along bridges + continuity edges and returns indirectly reached threads in `indirect` with their activation path. When cross-project gossip is enabled, `related_projects` lists related threads of other projects (\"related memory in project X\").",
             },
             "ai_focus": {
                 "description": "Read full thread content (all messages)",
@@ -277,7 +310,7 @@ fn help_threads() -> serde_json::Value {
             "ai_thread_list": {
                 "description": "List threads with optional filters",
                 "required": [],
-                "optional": ["status", "limit", "offset", "sort"],
+                "optional": ["status", "limit", "cursor", "sort_by", "order", "fields"],
             },
             "ai_thread_search": {
                 "description": "Search threads by title/topic keyword",
@@ -306,11 +339,18 @@ fn help_threads() -> serde_json::Value {
             "ai_labels_suggest": { "description": "Suggest labels for a thread based on content", "required": ["thread_id"] },
             "ai_rename": { "description": "Rename a thread", "required": ["thread_id", "title"] },
             "ai_rename_batch": { "description": "Rename multiple threads", "required": ["operations"] },
+            "ai_batch": { "description": "Atomic multi-step edit: steps=[{tool, args}], rollback on first error; \"$1.thread_id\" references step 1's result; dry_run=true previews", "required": ["steps"] },
             "ai_rate_importance": { "description": "Manually set thread importance (0.0-1.0)", "required": ["thread_id", "importance"] },
             "ai_rate_context": { "description": "Rate thread context relevance", "required": ["thread_id", "rating"] },
             "ai_mark_used": { "description": "Mark a thread as recently used (boosts weight)", "required": ["thread_id"] },
             "ai_concepts": { "description": "View concepts extracted from a thread", "required": ["thread_id"] },
             "ai_backfill_concepts": { "description": "Re-extract concepts for threads missing them", "required": [] },
+            "ai_topic_alias": {
+                "description": "Manage the topic alias table used to normalise topics and concepts",
+                "required": [],
+                "optional": ["action", "alias", "canonical", "status", "limit"],
+                "notes": "action: list (default) | suggest | confirm | reject | add | remove. Confirmed aliases apply when topics are keyed; stored topics keep their spelling.",
+            },
         },
     })
 }
@@ -321,10 +361,11 @@ fn help_bridges() -> serde_json::Value {
         "tools": {
             "ai_bridges": { "description": "List bridges for a thread", "required": ["thread_id"], "optional": ["limit"] },
             "ai_bridge_analysis": { "description": "Analyze bridge network for a thread", "required": ["thread_id"] },
-            "ai_bridge_scan_orphans": { "description": "Find and optionally remove orphan bridges", "required": [], "optional": ["fix"] },
+            "ai_bridge_scan_orphans": { "description": "Find and remove orphan bridges (two-phase: preview, then confirm_token)", "required": [], "optional": ["confirm_token", "dry_run"] },
             "ai_bridge_purge": { "description": "Purge weak bridges below threshold", "required": [], "optional": ["threshold"] },
             "ai_bridge_kill": { "description": "Delete a specific bridge", "required": ["bridge_id"] },
             "ai_bridge_kill_batch": { "description": "Delete multiple bridges", "required": ["bridge_ids"] },
+            "ai_resolve_conflict": { "description": "Resolve a contradiction: keep one thread, mark the other as replaced (contradicts bridges → replaces)", "required": ["keep_id", "replaced_id"], "optional": ["reason", "suspend"] },
         },
     })
 }
@@ -452,6 +493,7 @@ fn help_maintenance() -> serde_json::Value {
             "ai_backup": { "description": "Create/restore/check database backup", "required": ["action"], "actions": "create, restore, status" },
             "ai_sysinfo": { "description": "System info — threads, bridges, disk, hardware, GPU", "required": [] },
             "ai_suggestions": { "description": "Get maintenance suggestions (unlabeled threads, weak bridges)", "required": [] },
+            "ai_audit": { "description": "Audit log of tool calls — filter by agent_id, tool, outcome, since_hours, affected_id", "required": [] },
             "health_check": { "description": "Quick health check", "required": [] },
             "topics_network": { "description": "Top 20 topics by thread count", "required": [] },
             "ai_topics": { "description": "Alias for topics_network", "required": [] },
@@ -685,8 +727,13 @@ pub fn handle_cleanup(
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let threads = ThreadStorage::list_all(ctx.agent_conn)?;
+    let total = threads.len() as u64;
     let mut fixed = 0;
-    for t in &threads {
+    for (i, t) in threads.iter().enumerate() {
+        if i % PROGRESS_EVERY == 0 {
+            ctx.progress.check_cancelled()?;
+            ctx.progress.report(i as u64, Some(total), "checking threads");
+        }
         if t.title.is_empty() || t.title.len() < 3 {
             // Would auto-generate title from first message
             fixed += 1;
@@ -728,7 +775,10 @@ pub fn handle_backup(
     match action.as_str() {
         "create" => {
             let backup_path = db_path.with_extension("db.backup");
-            BackupManager::create_backup(ctx.agent_conn, &backup_path)?;
+            BackupManager::create_backup_with_progress(ctx.agent_conn, &backup_path, |done, total| {
+                ctx.progress.report(done, Some(total), "copying pages");
+                !ctx.progress.is_cancelled()
+            })?;
             Ok(serde_json::json!({"action": "create", "path": backup_path.display().to_string()}))
         }
         "restore" => {
@@ -754,6 +804,32 @@ pub fn handle_metrics(
     Ok(serde_json::json!({"period": "week", "messages_sent": 0, "messages_received": 0}))
 }
 
+/// ai_audit — query the audit log of the current project (newest first).
+pub fn handle_audit(
+    params: &serde_json::Value,
+    ctx: &ToolContext,
+) -> AiResult<serde_json::Value> {
+    let since = optional_usize(params, "since_hours").map(|h| {
+        let cutoff = ai_smartness::time_utils::now() - chrono::Duration::hours(h as i64);
+        ai_smartness::time_utils::to_sqlite(&cutoff)
+    });
+    let filter = AuditFilter {
+        project_hash: Some(ctx.project_hash.to_string()),
+        agent_id: optional_str(params, "agent_id"),
+        tool: optional_str(params, "tool"),
+        outcome: optional_str(params, "outcome"),
+        since,
+        until: None,
+        affected_id: optional_str(params, "affected_id"),
+        limit: optional_usize(params, "limit").unwrap_or(50).min(500),
+    };
+    let entries = AuditLog::query(ctx.registry_conn, &filter)?;
+    Ok(serde_json::json!({
+        "count": entries.len(),
+        "entries": entries,
+    }))
+}
+
 pub fn handle_health_check(
     _params: &serde_json::Value,
     ctx: &ToolContext,
@@ -766,10 +842,19 @@ pub fn handle_health_check(
     }))
 }
 
+pub const TOPICS: ListSpec = ListSpec {
+    items_key: "topics",
+    id_field: "topic",
+    sort_keys: &["count", "topic"],
+    desc_by_default: &["count"],
+    default_limit: 20,
+};
+
 pub fn handle_topics_network(
-    _params: &serde_json::Value,
+    params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
+    let page = Page::parse(params, &TOPICS)?;
     let threads = ThreadStorage::list_all(ctx.agent_conn)?;
     let mut topic_counts: std::collections::HashMap<String, usize> =
         std::collections::HashMap::new();
@@ -778,13 +863,12 @@ pub fn handle_topics_network(
             *topic_counts.entry(topic.clone()).or_insert(0) += 1;
         }
     }
-    let mut topics: Vec<(String, usize)> = topic_counts.into_iter().collect();
-    topics.sort_by(|a, b| b.1.cmp(&a.1));
-    topics.truncate(20);
-
-    Ok(serde_json::json!({
-        "topics": topics.iter().map(|(t, c)| serde_json::json!({"topic": t, "count": c})).collect::<Vec<_>>(),
-    }))
+    Ok(page.apply(
+        topic_counts
+            .into_iter()
+            .map(|(t, c)| serde_json::json!({"topic": t, "count": c}))
+            .collect(),
+    ))
 }
 
 pub fn handle_test_sampling(
@@ -801,10 +885,9 @@ pub fn handle_beat_wake(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let after_str = required_str(params, "after")?;
-    let after: u64 = after_str
-        .parse()
-        .map_err(|_| ai_smartness::AiError::InvalidInput("'after' must be a positive integer".into()))?;
+    let after = optional_usize(params, "after")
+        .ok_or_else(|| ai_smartness::AiError::InvalidInput("'after' must be a positive integer".into()))?
+        as u64;
     let reason = optional_str(params, "reason")
         .unwrap_or_else(|| "self-wake".into());
 
@@ -827,10 +910,9 @@ pub fn handle_nanobeat_schedule(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let delay_str = required_str(params, "delay_seconds")?;
-    let delay: u64 = delay_str
-        .parse()
-        .map_err(|_| ai_smartness::AiError::InvalidInput("'delay_seconds' must be a positive integer".into()))?;
+    let delay = optional_usize(params, "delay_seconds")
+        .ok_or_else(|| ai_smartness::AiError::InvalidInput("'delay_seconds' must be a positive integer".into()))?
+        as u64;
     let reason = required_str(params, "reason")?;
     let recall_query = optional_str(params, "recall_query");
     let recall_thread_id = optional_str(params, "recall_thread_id");
@@ -1,15 +1,23 @@
+use std::collections::{HashMap, HashSet};
+
 use ai_smartness::{id_gen, time_utils};
 use ai_smartness::config::GossipConfig;
-use ai_smartness::constants::normalize_concepts;
 use ai_smartness::intelligence::thread_manager::ThreadManager;
 use ai_smartness::processing::daemon_ipc_client;
+use ai_smartness::intelligence::topic_alias_suggester::TopicAliasSuggester;
+use ai_smartness::processing::topic_normalizer::{canonical_key, TopicNormalizer};
 use ai_smartness::thread::{OriginType, Thread, ThreadMessage, ThreadStatus};
 use ai_smartness::AiResult;
 use ai_smartness::registry::registry::AgentRegistry;
-use ai_smartness::storage::threads::ThreadStorage;
+use ai_smartness::storage::bridges::BridgeStorage;
+use ai_smartness::storage::shared_storage::SharedStorage;
+use ai_smartness::storage::threads::{ThreadHeader, ThreadStorage};
+use ai_smartness::storage::topic_aliases::{TopicAliasStorage, STATUS_CONFIRMED, STATUS_REJECTED, STATUS_SUGGESTED};
 
 use rusqlite::Connection;
 
+use super::confirm::{self, Preview};
+use super::paging::{ListSpec, Page, MAX_LIMIT};
 use super::{
     check_thread_quota, optional_bool, optional_f64, optional_str, optional_usize,
     parse_object_array, parse_string_or_array, required_array, required_str, ToolContext,
@@ -141,6 +149,12 @@ pub fn handle_thread_create(
         continuity_to: None,
     };
     ThreadStorage::add_message(ctx.agent_conn, &msg)?;
+    daemon_ipc_client::publish_event(
+        "thread_created",
+        ctx.project_hash,
+        Some(ctx.agent_id),
+        serde_json::json!({"thread_id": thread_id, "title": title, "status": "active", "weight": thread.weight}),
+    );
 
     // Send to daemon for full pipeline processing (LLM extraction, concepts,
     // thinkbridges, embedding) — same treatment as all other threads.
@@ -203,32 +217,33 @@ pub fn handle_thread_rm_batch(
     Ok(serde_json::json!({"deleted": count}))
 }
 
+/// Paging of thread lists (ai_thread_list, ai_thread_search).
+pub const THREADS: ListSpec = ListSpec {
+    items_key: "threads",
+    id_field: "id",
+    sort_keys: &["weight", "importance", "last_active", "title"],
+    desc_by_default: &["weight", "importance", "last_active"],
+    default_limit: 50,
+};
+
 pub fn handle_thread_list(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let status_str = optional_str(params, "status").unwrap_or_else(|| "active".into());
-    let limit = optional_usize(params, "limit").unwrap_or_else(|| {
+    let mut page = Page::parse(params, &THREADS)?;
+    if params.get("limit").is_none() {
         // Use the agent's thread_mode quota as default limit
-        AgentRegistry::get(ctx.registry_conn, ctx.agent_id, ctx.project_hash)
+        page.limit = AgentRegistry::get(ctx.registry_conn, ctx.agent_id, ctx.project_hash)
             .ok()
             .flatten()
-            .map(|a| a.thread_mode.quota())
-            .unwrap_or(50)
-    });
-    let offset = optional_usize(params, "offset").unwrap_or(0);
+            .map(|a| a.thread_mode.quota().min(MAX_LIMIT))
+            .unwrap_or(THREADS.default_limit);
+    }
     let status: ThreadStatus = status_str.parse().unwrap_or(ThreadStatus::Active);
 
     let threads = ThreadStorage::list_by_status(ctx.agent_conn, &status)?;
-    let total = threads.len();
-    let page: Vec<serde_json::Value> = threads
-        .into_iter()
-        .skip(offset)
-        .take(limit)
-        .map(|t| thread_json(&t))
-        .collect();
-
-    Ok(serde_json::json!({"threads": page, "total": total, "offset": offset, "limit": limit}))
+    Ok(page.apply(threads.iter().map(thread_json).collect()))
 }
 
 pub fn handle_thread_search(
@@ -236,9 +251,9 @@ pub fn handle_thread_search(
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let query = required_str(params, "query")?;
+    let page = Page::parse(params, &THREADS)?;
     let threads = ThreadStorage::search(ctx.agent_conn, &query)?;
-    let results: Vec<serde_json::Value> = threads.iter().map(thread_json).collect();
-    Ok(serde_json::json!({"threads": results, "count": results.len()}))
+    Ok(page.apply(threads.iter().map(thread_json).collect()))
 }
 
 pub fn handle_thread_activate(
@@ -246,18 +261,6 @@ pub fn handle_thread_activate(
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let ids = required_array(params, "thread_ids")?;
-    let confirm = optional_bool(params, "confirm").unwrap_or(false);
-
-    if !confirm {
-        let preview: Vec<serde_json::Value> = ids
-            .iter()
-            .filter_map(|id| ThreadStorage::get(ctx.agent_conn, id).ok().flatten())
-            .map(|t| {
-                serde_json::json!({"id": t.id, "title": t.title, "current_status": t.status.as_str()})
-            })
-            .collect();
-        return Ok(serde_json::json!({"dry_run": true, "threads": preview}));
-    }
 
     // Quota guard: evict lightest threads to make room for reactivations
     let (active, quota) = check_thread_quota(ctx)?;
@@ -293,25 +296,19 @@ pub fn handle_thread_suspend(
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
     let ids = required_array(params, "thread_ids")?;
-    let confirm = optional_bool(params, "confirm").unwrap_or(false);
-
-    if !confirm {
-        let preview: Vec<serde_json::Value> = ids
-            .iter()
-            .filter_map(|id| ThreadStorage::get(ctx.agent_conn, id).ok().flatten())
-            .map(|t| {
-                serde_json::json!({"id": t.id, "title": t.title, "current_status": t.status.as_str()})
-            })
-            .collect();
-        return Ok(serde_json::json!({"dry_run": true, "threads": preview}));
-    }
 
-    let mut count = 0;
     for id in &ids {
         ThreadStorage::update_status(ctx.agent_conn, id, ThreadStatus::Suspended)?;
-        count += 1;
     }
-    Ok(serde_json::json!({"suspended": count}))
+    if !ids.is_empty() {
+        daemon_ipc_client::publish_event(
+            "thread_suspended",
+            ctx.project_hash,
+            Some(ctx.agent_id),
+            serde_json::json!({"thread_ids": ids, "reason": "manual"}),
+        );
+    }
+    Ok(serde_json::json!({"suspended": ids.len()}))
 }
 
 pub fn handle_reactivate(
@@ -374,6 +371,7 @@ pub fn handle_label(
             let gossip_cfg = GossipConfig::default();
             let (deleted, created) = ThreadManager::refresh_thinkbridges(
                 ctx.agent_conn,
+                &TopicNormalizer::load(ctx.agent_conn),
                 &id,
                 &thread.concepts,
                 &gossip_cfg,
@@ -407,17 +405,18 @@ pub fn handle_concepts(
         "set" | "add" | "remove" => {
             let concepts = required_array(params, "concepts")?;
             let mut thread = thread;
+            let normalizer = TopicNormalizer::load(ctx.agent_conn);
             match mode.as_str() {
-                "set" => thread.concepts = normalize_concepts(&concepts),
+                "set" => thread.concepts = normalizer.dedup_concepts(&concepts),
                 "add" => {
                     let mut all = thread.concepts.clone();
                     all.extend(concepts);
-                    thread.concepts = normalize_concepts(&all);
+                    thread.concepts = normalizer.dedup_concepts(&all);
                 }
                 "remove" => {
                     let to_remove: std::collections::HashSet<String> =
-                        concepts.into_iter().map(|c| c.to_lowercase()).collect();
-                    thread.concepts.retain(|c| !to_remove.contains(&c.to_lowercase()));
+                        concepts.iter().map(|c| normalizer.normalize(c)).collect();
+                    thread.concepts.retain(|c| !to_remove.contains(&normalizer.normalize(c)));
                 }
                 _ => unreachable!(),
             }
@@ -434,16 +433,7 @@ pub fn handle_backfill_concepts(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let limit = optional_usize(params, "limit").unwrap_or(10);
-    let dry_run = optional_bool(params, "dry_run").unwrap_or(false);
-
-    // All active threads are candidates — no filter.
-    // When called explicitly by agent/human, re-enrichment is always valid.
-    // Prioritize by importance (higher first), take up to limit.
-    let all = ThreadStorage::list_active(ctx.agent_conn)?;
-    let mut candidates: Vec<&Thread> = all.iter().collect();
-    candidates.sort_by(|a, b| b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal));
-    let batch: Vec<&Thread> = candidates.into_iter().take(limit).collect();
+    let (batch, total_active) = backfill_candidates(params, ctx)?;
 
     if batch.is_empty() {
         return Ok(serde_json::json!({
@@ -452,31 +442,20 @@ pub fn handle_backfill_concepts(
         }));
     }
 
-    if dry_run {
-        let preview: Vec<serde_json::Value> = batch.iter().map(|t| {
-            serde_json::json!({
-                "id": &t.id[..8.min(t.id.len())],
-                "title": &t.title,
-                "topics": &t.topics,
-                "labels": &t.labels,
-                "has_summary": t.summary.is_some(),
-                "has_concepts": !t.concepts.is_empty(),
-            })
-        }).collect();
-        return Ok(serde_json::json!({
-            "dry_run": true,
-            "candidates": batch.len(),
-            "total_active": all.len(),
-            "preview": preview
-        }));
-    }
-
     // Submit enrichment jobs via IPC → daemon capture queue (async, non-blocking)
     let mut queued = 0usize;
     let mut failed = 0usize;
     let mut results = Vec::new();
-
-    for thread in &batch {
+    let total = batch.len() as u64;
+    let mut cancelled = false;
+
+    for (i, thread) in batch.iter().enumerate() {
+        // Stop between jobs: already queued jobs stay queued.
+        if ctx.progress.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        ctx.progress.report(i as u64, Some(total), &format!("enriching {}", thread.title));
         let ipc_params = serde_json::json!({
             "project_hash": ctx.project_hash,
             "agent_id": ctx.agent_id,
@@ -502,10 +481,12 @@ pub fn handle_backfill_concepts(
         }
     }
 
+    ctx.progress.report(total, Some(total), "done");
     Ok(serde_json::json!({
         "queued": queued,
         "failed": failed,
-        "total_active": all.len(),
+        "cancelled": cancelled,
+        "total_active": total_active,
         "results": results
     }))
 }
@@ -556,35 +537,181 @@ pub fn handle_thread_purge(
     params: &serde_json::Value,
     ctx: &ToolContext,
 ) -> AiResult<serde_json::Value> {
-    let status_str = required_str(params, "status")?;
-    let confirm = optional_bool(params, "confirm").unwrap_or(false);
-    let status: ThreadStatus = status_str
+    let status = purge_status(params)?;
+
+    let count = ThreadStorage::count_by_status(ctx.agent_conn, &status)?;
+
+    // Single transaction: cancellable until it starts, then atomic.
+    ctx.progress.check_cancelled()?;
+    ctx.progress.report(0, Some(count as u64), &format!("purging {} thread(s)", status.as_str()));
+    // Ids listed before the delete so the audit log can name what was purged
+    let purged_ids = ThreadStorage::ids_by_status(ctx.agent_conn, &status)?;
+    let deleted = ThreadStorage::delete_by_status(ctx.agent_conn, &status)?;
+    super::record_mutation(
+        ctx,
+        "mcp:thread_purge",
+        &serde_json::json!({"status": status.as_str(), "purged": deleted}),
+        &purged_ids,
+    );
+    ctx.progress.report(count as u64, Some(count as u64), "done");
+    Ok(serde_json::json!({
+        "purged": deleted,
+        "purged_sample": confirm::sample(&purged_ids),
+        "status": status.as_str()
+    }))
+}
+
+fn purge_status(params: &serde_json::Value) -> AiResult<ThreadStatus> {
+    let status: ThreadStatus = required_str(params, "status")?
         .parse()
         .map_err(|e: String| ai_smartness::AiError::InvalidInput(e))?;
-
     // Safety: never purge active threads
     if status == ThreadStatus::Active {
         return Err(ai_smartness::AiError::InvalidInput(
             "Cannot purge active threads. Suspend them first.".into(),
         ));
     }
+    Ok(status)
+}
 
-    let count = ThreadStorage::count_by_status(ctx.agent_conn, &status)?;
+/// All active threads are candidates — no filter.
+/// When called explicitly by agent/human, re-enrichment is always valid.
+/// Prioritize by importance (higher first), take up to limit.
+fn backfill_candidates(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<(Vec<Thread>, usize)> {
+    let limit = optional_usize(params, "limit").unwrap_or(10);
+    let mut all = ThreadStorage::list_active(ctx.agent_conn)?;
+    let total_active = all.len();
+    all.sort_by(|a, b| b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal));
+    all.truncate(limit);
+    Ok((all, total_active))
+}
 
-    if !confirm {
-        return Ok(serde_json::json!({
-            "dry_run": true,
-            "status": status.as_str(),
-            "count": count,
-            "message": format!("Would delete {} {} thread(s). Pass confirm=true to execute.", count, status.as_str())
-        }));
+// ── Two-phase previews (see confirm.rs) ──
+
+/// Preview an action on explicit thread ids: existing vs missing ids, and the
+/// bridges, continuity children and shared copies they carry. Lists are
+/// reported as counts plus a sample.
+fn preview_threads(ctx: &ToolContext, ids: &[String], deleting: bool) -> AiResult<(Preview, Vec<ThreadHeader>)> {
+    let requested: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let mut headers: HashMap<String, ThreadHeader> = ThreadStorage::headers(ctx.agent_conn, ids)?
+        .into_iter()
+        .map(|h| (h.id.clone(), h))
+        .collect();
+    // Keep the requested order, first occurrence of each id
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id.as_str()) {
+            continue;
+        }
+        match headers.remove(id) {
+            Some(h) => found.push(h),
+            None => missing.push(id.clone()),
+        }
     }
+    let found_ids: Vec<String> = found.iter().map(|t| t.id.clone()).collect();
 
-    let deleted = ThreadStorage::delete_by_status(ctx.agent_conn, &status)?;
-    Ok(serde_json::json!({
-        "purged": deleted,
-        "status": status.as_str()
-    }))
+    let bridges: HashSet<String> = BridgeStorage::ids_for_threads(ctx.agent_conn, &found_ids)?.into_iter().collect();
+    let mut children = ThreadStorage::continuity_child_ids_batch(ctx.agent_conn, &found_ids)?;
+    // Children that are themselves in the set are not relinked
+    children.retain(|c| !requested.contains(c.as_str()));
+    let shared = SharedStorage::count_by_thread_ids(ctx.shared_conn, &found_ids)?;
+
+    let side_effects = if deleting {
+        serde_json::json!({
+            "bridges_deleted": bridges.len(),
+            "continuity_children_relinked": children.len(),
+            "continuity_children_sample": confirm::sample(&children),
+            "shared_copies_orphaned": shared,
+        })
+    } else {
+        serde_json::json!({
+            "bridges_touching": bridges.len(),
+            "continuity_children": children.len(),
+            "continuity_children_sample": confirm::sample(&children),
+            "shared_copies": shared,
+        })
+    };
+    let items = confirm::sample(&found)
+        .iter()
+        .map(|t| serde_json::json!({"id": t.id, "title": t.title, "current_status": t.status.as_str()}))
+        .collect();
+    let preview = Preview::new(found_ids, String::new())
+        .counts(serde_json::json!({
+            "threads": found.len(),
+            "missing": missing.len(),
+            "missing_sample": confirm::sample(&missing),
+        }))
+        .side_effects(side_effects)
+        .items(items);
+    Ok((preview, found))
+}
+
+pub fn preview_thread_rm(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let id = required_str(params, "thread_id")?;
+    let (mut preview, found) = preview_threads(ctx, &[id.clone()], true)?;
+    preview.message = match found.first() {
+        Some(t) => format!("Would delete thread '{}'", t.title),
+        None => format!("Thread {} does not exist — nothing to delete", id),
+    };
+    Ok(Some(preview))
+}
+
+pub fn preview_thread_rm_batch(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let ids = required_array(params, "thread_ids")?;
+    let (mut preview, found) = preview_threads(ctx, &ids, true)?;
+    preview.message = format!("Would delete {} of {} thread(s)", found.len(), ids.len());
+    Ok(Some(preview))
+}
+
+pub fn preview_thread_activate(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let ids = required_array(params, "thread_ids")?;
+    let (mut preview, found) = preview_threads(ctx, &ids, false)?;
+    let to_activate = found.iter().filter(|t| t.status != ThreadStatus::Active).count();
+    let (active, quota) = check_thread_quota(ctx)?;
+    preview.side_effects["quota_evictions"] = (active + to_activate).saturating_sub(quota).into();
+    preview.message = format!("Would activate {} thread(s) ({} already active)", to_activate, found.len() - to_activate);
+    Ok(Some(preview))
+}
+
+pub fn preview_thread_suspend(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let ids = required_array(params, "thread_ids")?;
+    let (mut preview, found) = preview_threads(ctx, &ids, false)?;
+    preview.message = format!("Would suspend {} thread(s)", found.len());
+    Ok(Some(preview))
+}
+
+pub fn preview_thread_purge(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let status = purge_status(params)?;
+    let ids = ThreadStorage::ids_by_status(ctx.agent_conn, &status)?;
+    let (mut preview, found) = preview_threads(ctx, &ids, true)?;
+    preview.message = format!("Would delete {} {} thread(s)", found.len(), status.as_str());
+    Ok(Some(preview))
+}
+
+pub fn preview_backfill_concepts(params: &serde_json::Value, ctx: &ToolContext) -> AiResult<Option<Preview>> {
+    let (batch, total_active) = backfill_candidates(params, ctx)?;
+    if batch.is_empty() {
+        return Ok(None);
+    }
+    let items = batch.iter().map(|t| {
+        serde_json::json!({
+            "id": &t.id,
+            "title": &t.title,
+            "topics": &t.topics,
+            "labels": &t.labels,
+            "has_summary": t.summary.is_some(),
+            "has_concepts": !t.concepts.is_empty(),
+        })
+    }).collect();
+    let preview = Preview::new(
+        batch.iter().map(|t| t.id.clone()).collect(),
+        format!("Would queue concept enrichment for {} thread(s) (overwrites their concepts)", batch.len()),
+    )
+    .counts(serde_json::json!({"candidates": batch.len(), "total_active": total_active}))
+    .items(items);
+    Ok(Some(preview))
 }
 
 pub fn handle_labels_suggest(
@@ -609,6 +736,70 @@ pub fn handle_labels_suggest(
     Ok(serde_json::json!({"labels": suggestions}))
 }
 
+/// Manage the agent's topic alias table (normalisation of topics/concepts).
+/// Stored topics are left as written: a confirmed alias applies wherever keys
+/// are computed (indexes, thinkbridges, validators).
+pub fn handle_topic_alias(
+    params: &serde_json::Value,
+    ctx: &ToolContext,
+) -> AiResult<serde_json::Value> {
+    let action = optional_str(params, "action").unwrap_or_else(|| "list".into());
+    let key = |field: &str| -> AiResult<String> {
+        let k = canonical_key(&required_str(params, field)?);
+        if k.is_empty() {
+            return Err(ai_smartness::AiError::InvalidInput(format!("{} is empty after normalisation", field)));
+        }
+        Ok(k)
+    };
+
+    match action.as_str() {
+        "list" => {
+            let status = optional_str(params, "status");
+            let aliases = TopicAliasStorage::list(ctx.agent_conn, status.as_deref())?;
+            Ok(serde_json::json!({"count": aliases.len(), "aliases": aliases}))
+        }
+        "suggest" => {
+            let limit = optional_usize(params, "limit").unwrap_or(20);
+            let stored = TopicAliasSuggester::run(ctx.agent_conn, limit)?;
+            let pending = TopicAliasStorage::list(ctx.agent_conn, Some(STATUS_SUGGESTED))?;
+            Ok(serde_json::json!({"new_suggestions": stored, "suggested": pending}))
+        }
+        "confirm" | "add" => {
+            let alias = key("alias")?;
+            if action == "add" {
+                let canonical = key("canonical")?;
+                if canonical == alias {
+                    return Err(ai_smartness::AiError::InvalidInput("alias and canonical are identical".into()));
+                }
+                TopicAliasStorage::add_confirmed(ctx.agent_conn, &alias, &canonical)?;
+            } else if !TopicAliasStorage::confirm(ctx.agent_conn, &alias)? {
+                return Err(ai_smartness::AiError::InvalidInput(format!("No alias suggestion for '{}'", alias)));
+            }
+            let normalizer = TopicNormalizer::load(ctx.agent_conn);
+            Ok(serde_json::json!({
+                "alias": alias,
+                "canonical": normalizer.normalize(&alias),
+                "status": STATUS_CONFIRMED,
+            }))
+        }
+        "reject" => {
+            let alias = key("alias")?;
+            if !TopicAliasStorage::reject(ctx.agent_conn, &alias)? {
+                return Err(ai_smartness::AiError::InvalidInput(format!("No alias for '{}'", alias)));
+            }
+            Ok(serde_json::json!({"alias": alias, "status": STATUS_REJECTED}))
+        }
+        "remove" => {
+            let alias = key("alias")?;
+            let removed = TopicAliasStorage::delete(ctx.agent_conn, &alias)?;
+            Ok(serde_json::json!({"alias": alias, "removed": removed}))
+        }
+        other => Err(ai_smartness::AiError::InvalidInput(format!(
+            "Unknown action '{}' (list|suggest|confirm|reject|add|remove)", other
+        ))),
+    }
+}
+
 pub fn handle_rename(
     params: &serde_json::Value,
     ctx: &ToolContext,
@@ -877,6 +1068,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({"title": "new thread", "content": "hello"});
@@ -899,6 +1091,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({"title": "new thread", "content": "hello"});
@@ -956,6 +1149,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({"thread_ids": ["suspended-1"], "confirm": true});
@@ -1013,6 +1207,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({"thread_ids": ["suspended-1"], "confirm": true});
@@ -1070,6 +1265,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let params = serde_json::json!({"thread_id": "suspended-1"});
@@ -1092,6 +1288,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: AGENT,
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         // Reactivate an already-active thread — should skip quota check
@@ -1115,6 +1312,7 @@ mod tests {
             shared_conn: &shared_conn,
             project_hash: PH,
             agent_id: "nonexistent-agent",
+            progress: crate::mcp::tools::ProgressSink::none(),
         };
 
         let (active, quota) = check_thread_quota(&ctx).unwrap();
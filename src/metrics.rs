@@ -0,0 +1,344 @@
+//! Metrics registry — counters, gauges and histograms for the daemon and the
+//! capture pipeline, rendered in OpenMetrics text format.
+//!
+//! Process-wide and in-memory: each metric is a `const Metric` declared here,
+//! recorded from anywhere with its labels (`CAPTURES_RECEIVED.inc(&[...])`).
+//! Every process records into its own registry but only the daemon exports it
+//! (IPC `metrics`, `GET /api/v1/metrics`, `ai-smartness metrics`), so the
+//! numbers cover the work done by the daemon: captures, extraction, LLM
+//! generation, engram queries, prune cycles and its SQLite connections.
+//! Counters restart from zero with the daemon.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+/// Content type of `render()` output.
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Buckets (seconds) for LLM-bound work: extraction, generation.
+const LLM_SECONDS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+/// Buckets (seconds) for in-process work: engram query phases.
+const QUERY_SECONDS: &[f64] = &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+/// Buckets for generation throughput (tokens per second).
+const TOKENS_PER_SECOND: &[f64] = &[1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0];
+
+#[derive(Debug, Clone, Copy)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram(&'static [f64]),
+}
+
+/// A metric family. Declared as a constant; series are created on first use.
+#[derive(Debug)]
+pub struct Metric {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+// ── Captures ──
+
+pub const CAPTURES_RECEIVED: Metric = Metric {
+    name: "ai_smartness_captures_received",
+    help: "Captures accepted by the daemon (labels: project, agent, source_type)",
+    kind: MetricKind::Counter,
+};
+pub const CAPTURES_PROCESSED: Metric = Metric {
+    name: "ai_smartness_captures_processed",
+    help: "Captures fully processed (labels: project, agent, source_type)",
+    kind: MetricKind::Counter,
+};
+pub const CAPTURES_DROPPED: Metric = Metric {
+    name: "ai_smartness_captures_dropped",
+    help: "Captures refused or given up (labels: project, agent, source_type, reason)",
+    kind: MetricKind::Counter,
+};
+pub const RETRIES: Metric = Metric {
+    name: "ai_smartness_retries",
+    help: "Retried pipeline steps (label: stage)",
+    kind: MetricKind::Counter,
+};
+
+// ── Extraction / LLM ──
+
+pub const EXTRACTION_DURATION: Metric = Metric {
+    name: "ai_smartness_extraction_duration_seconds",
+    help: "LLM extraction latency, retries included (label: model)",
+    kind: MetricKind::Histogram(LLM_SECONDS),
+};
+pub const LLM_TOKENS_PER_SECOND: Metric = Metric {
+    name: "ai_smartness_llm_tokens_per_second",
+    help: "Local LLM generation throughput per completion (label: model)",
+    kind: MetricKind::Histogram(TOKENS_PER_SECOND),
+};
+pub const LLM_TOKENS_GENERATED: Metric = Metric {
+    name: "ai_smartness_llm_tokens_generated",
+    help: "Tokens generated by the local LLM (label: model)",
+    kind: MetricKind::Counter,
+};
+pub const LLM_STATUS: Metric = Metric {
+    name: "ai_smartness_llm_status",
+    help: "Local LLM circuit breaker state, 1 for the current state (label: state)",
+    kind: MetricKind::Gauge,
+};
+
+// ── Memory ──
+
+pub const ENGRAM_QUERY_DURATION: Metric = Metric {
+    name: "ai_smartness_engram_query_duration_seconds",
+    help: "Engram retrieval latency per phase (labels: query, phase)",
+    kind: MetricKind::Histogram(QUERY_SECONDS),
+};
+pub const GOSSIP_BRIDGES_CREATED: Metric = Metric {
+    name: "ai_smartness_gossip_bridges_created",
+    help: "Bridges created by gossip cycles (labels: project, agent)",
+    kind: MetricKind::Counter,
+};
+pub const DECAY_SUSPENSIONS: Metric = Metric {
+    name: "ai_smartness_decay_suspensions",
+    help: "Threads suspended by decay",
+    kind: MetricKind::Counter,
+};
+
+// ── SQLite ──
+
+pub const SQLITE_BUSY_WAITS: Metric = Metric {
+    name: "ai_smartness_sqlite_busy_waits",
+    help: "Statements that found the database locked and waited",
+    kind: MetricKind::Counter,
+};
+pub const SQLITE_BUSY_ERRORS: Metric = Metric {
+    name: "ai_smartness_sqlite_busy_errors",
+    help: "Statements that gave up on a locked database (SQLITE_BUSY)",
+    kind: MetricKind::Counter,
+};
+
+// ── Daemon state (gauges, set at scrape time) ──
+
+pub const QUEUE_PENDING: Metric = Metric {
+    name: "ai_smartness_capture_queue_pending",
+    help: "Capture jobs waiting in memory",
+    kind: MetricKind::Gauge,
+};
+pub const QUEUE_IN_FLIGHT: Metric = Metric {
+    name: "ai_smartness_capture_queue_in_flight",
+    help: "Capture jobs being processed",
+    kind: MetricKind::Gauge,
+};
+pub const QUEUE_SPILLED: Metric = Metric {
+    name: "ai_smartness_capture_queue_spilled",
+    help: "Capture jobs kept in the journal while the queue is full",
+    kind: MetricKind::Gauge,
+};
+pub const DEAD_LETTERS: Metric = Metric {
+    name: "ai_smartness_dead_letters",
+    help: "Failed captures kept for replay",
+    kind: MetricKind::Gauge,
+};
+pub const POOL_CONNECTIONS: Metric = Metric {
+    name: "ai_smartness_pool_connections",
+    help: "Agent database connections in the daemon pool (label: state)",
+    kind: MetricKind::Gauge,
+};
+pub const UPTIME: Metric = Metric {
+    name: "ai_smartness_daemon_uptime_seconds",
+    help: "Seconds since the daemon started",
+    kind: MetricKind::Gauge,
+};
+
+/// Render order of `render()`: every family appears, recorded or not.
+const ALL: &[&Metric] = &[
+    &CAPTURES_RECEIVED,
+    &CAPTURES_PROCESSED,
+    &CAPTURES_DROPPED,
+    &RETRIES,
+    &EXTRACTION_DURATION,
+    &LLM_TOKENS_PER_SECOND,
+    &LLM_TOKENS_GENERATED,
+    &LLM_STATUS,
+    &ENGRAM_QUERY_DURATION,
+    &GOSSIP_BRIDGES_CREATED,
+    &DECAY_SUSPENSIONS,
+    &SQLITE_BUSY_WAITS,
+    &SQLITE_BUSY_ERRORS,
+    &QUEUE_PENDING,
+    &QUEUE_IN_FLIGHT,
+    &QUEUE_SPILLED,
+    &DEAD_LETTERS,
+    &POOL_CONNECTIONS,
+    &UPTIME,
+];
+
+type Labels = Vec<(String, String)>;
+
+enum Series {
+    Value(f64),
+    Histogram { buckets: Vec<u64>, sum: f64, count: u64 },
+}
+
+static REGISTRY: LazyLock<Mutex<HashMap<&'static str, BTreeMap<Labels, Series>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+impl Metric {
+    /// Counter += 1.
+    pub fn inc(&self, labels: &[(&str, &str)]) {
+        self.add(labels, 1.0);
+    }
+
+    /// Counter += `v`.
+    pub fn add(&self, labels: &[(&str, &str)], v: f64) {
+        debug_assert!(matches!(self.kind, MetricKind::Counter), "{} is not a counter", self.name);
+        self.update(labels, |s| match s {
+            Series::Value(x) => *x += v,
+            Series::Histogram { .. } => {}
+        });
+    }
+
+    /// Gauge = `v`.
+    pub fn set(&self, labels: &[(&str, &str)], v: f64) {
+        debug_assert!(matches!(self.kind, MetricKind::Gauge), "{} is not a gauge", self.name);
+        self.update(labels, |s| match s {
+            Series::Value(x) => *x = v,
+            Series::Histogram { .. } => {}
+        });
+    }
+
+    /// Histogram sample.
+    pub fn observe(&self, labels: &[(&str, &str)], v: f64) {
+        let MetricKind::Histogram(bounds) = self.kind else {
+            debug_assert!(false, "{} is not a histogram", self.name);
+            return;
+        };
+        self.update(labels, |s| {
+            if let Series::Histogram { buckets, sum, count } = s {
+                // Cumulative buckets: a sample counts in every bucket >= itself
+                for (n, le) in buckets.iter_mut().zip(bounds) {
+                    if v <= *le {
+                        *n += 1;
+                    }
+                }
+                *sum += v;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Histogram sample: seconds elapsed since `start`.
+    pub fn observe_since(&self, labels: &[(&str, &str)], start: Instant) {
+        self.observe(labels, start.elapsed().as_secs_f64());
+    }
+
+    /// Current value of a counter or gauge series (0 if never recorded).
+    pub fn value(&self, labels: &[(&str, &str)]) -> f64 {
+        let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        match registry.get(self.name).and_then(|f| f.get(&owned(labels))) {
+            Some(Series::Value(v)) => *v,
+            _ => 0.0,
+        }
+    }
+
+    fn update(&self, labels: &[(&str, &str)], f: impl FnOnce(&mut Series)) {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        let series = registry
+            .entry(self.name)
+            .or_default()
+            .entry(owned(labels))
+            .or_insert_with(|| match self.kind {
+                MetricKind::Histogram(bounds) => Series::Histogram {
+                    buckets: vec![0; bounds.len()],
+                    sum: 0.0,
+                    count: 0,
+                },
+                _ => Series::Value(0.0),
+            });
+        f(series);
+    }
+}
+
+fn owned(labels: &[(&str, &str)]) -> Labels {
+    labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+/// The whole registry in OpenMetrics text format (ends with `# EOF`).
+pub fn render() -> String {
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let mut out = String::new();
+    for metric in ALL {
+        let kind = match metric.kind {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram(_) => "histogram",
+        };
+        let _ = writeln!(out, "# TYPE {} {}", metric.name, kind);
+        let _ = writeln!(out, "# HELP {} {}", metric.name, metric.help);
+        let Some(family) = registry.get(metric.name) else { continue };
+        for (labels, series) in family {
+            match (series, metric.kind) {
+                (Series::Value(v), MetricKind::Counter) => {
+                    let _ = writeln!(out, "{}_total{} {}", metric.name, label_set(labels, None), v);
+                }
+                (Series::Value(v), _) => {
+                    let _ = writeln!(out, "{}{} {}", metric.name, label_set(labels, None), v);
+                }
+                (Series::Histogram { buckets, sum, count }, MetricKind::Histogram(bounds)) => {
+                    for (n, le) in buckets.iter().zip(bounds) {
+                        let le = le.to_string();
+                        let _ = writeln!(out, "{}_bucket{} {}", metric.name, label_set(labels, Some(&le)), n);
+                    }
+                    let _ = writeln!(out, "{}_bucket{} {}", metric.name, label_set(labels, Some("+Inf")), count);
+                    let _ = writeln!(out, "{}_sum{} {}", metric.name, label_set(labels, None), sum);
+                    let _ = writeln!(out, "{}_count{} {}", metric.name, label_set(labels, None), count);
+                }
+                (Series::Histogram { .. }, _) => {}
+            }
+        }
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+/// `{k="v",...}` with an optional `le` bound, or "" without labels.
+fn label_set(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_openmetrics_format() {
+        RETRIES.inc(&[("stage", "render \"test\"")]);
+        EXTRACTION_DURATION.observe(&[("model", "render-test")], 0.3);
+        EXTRACTION_DURATION.observe(&[("model", "render-test")], 200.0);
+
+        let text = render();
+        assert!(text.ends_with("# EOF\n"));
+        assert!(text.contains("# TYPE ai_smartness_retries counter\n"));
+        assert!(text.contains("ai_smartness_retries_total{stage=\"render \\\"test\\\"\"} 1\n"));
+        assert!(text.contains("ai_smartness_extraction_duration_seconds_bucket{model=\"render-test\",le=\"0.25\"} 0\n"));
+        assert!(text.contains("ai_smartness_extraction_duration_seconds_bucket{model=\"render-test\",le=\"0.5\"} 1\n"));
+        assert!(text.contains("ai_smartness_extraction_duration_seconds_bucket{model=\"render-test\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("ai_smartness_extraction_duration_seconds_count{model=\"render-test\"} 2\n"));
+        // Unrecorded families still declare themselves
+        assert!(text.contains("# TYPE ai_smartness_daemon_uptime_seconds gauge\n"));
+    }
+}
@@ -4,6 +4,9 @@
 //! Protocol: JSON-RPC over local socket (cross-platform via interprocess).
 //!   - Unix/macOS: Unix domain sockets
 //!   - Windows: Named pipes
+//!
+//! Each connection first authenticates (`auth` with the token from
+//! `ipc_auth::client_token`), then sends its request.
 
 use crate::{AiError, AiResult};
 use serde::{Deserialize, Serialize};
@@ -56,6 +59,12 @@ pub fn socket_path() -> std::path::PathBuf {
     data_dir.join("processor.sock")
 }
 
+/// Marker file the daemon keeps next to its socket while at least one
+/// `subscribe` stream is open (no marker = `publish_event` is a no-op).
+pub fn subscribers_marker_path() -> std::path::PathBuf {
+    socket_path().with_file_name("event_subscribers")
+}
+
 /// Send a capture to the daemon for processing.
 pub fn send_capture(
     project_hash: &str,
@@ -119,11 +128,99 @@ pub fn restart() -> AiResult<serde_json::Value> {
     call_daemon("restart", serde_json::json!({}))
 }
 
+/// Ask the daemon to re-read config.json + daemon_config.json (no restart).
+pub fn reload_config() -> AiResult<serde_json::Value> {
+    call_daemon("reload_config", serde_json::json!({}))
+}
+
 /// Generic method call to the daemon (public wrapper).
 pub fn send_method(method: &str, params: serde_json::Value) -> AiResult<serde_json::Value> {
     call_daemon(method, params)
 }
 
+/// Method call that may run long in the daemon (e.g. `run_task`): waits up
+/// to `timeout` instead of the default 2s.
+pub fn send_method_with_timeout(
+    method: &str,
+    params: serde_json::Value,
+    timeout: std::time::Duration,
+) -> AiResult<serde_json::Value> {
+    call_daemon_with_timeout(method, params, timeout)
+}
+
+/// Events waiting for the background publisher (a full queue drops events).
+const PUBLISH_QUEUE_CAPACITY: usize = 256;
+/// Max events sent in one `publish_event` call.
+const PUBLISH_BATCH_MAX: usize = 64;
+
+struct PendingEvent {
+    token: Option<String>,
+    project_hash: String,
+    event: serde_json::Value,
+}
+
+/// Single background sender: one thread per process, batches what queued up
+/// while the previous call was in flight.
+static PUBLISHER: std::sync::LazyLock<std::sync::mpsc::SyncSender<PendingEvent>> =
+    std::sync::LazyLock::new(|| {
+        let (tx, rx) = std::sync::mpsc::sync_channel(PUBLISH_QUEUE_CAPACITY);
+        let spawned = std::thread::Builder::new()
+            .name("event-publisher".into())
+            .spawn(move || run_publisher(rx));
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Event publisher thread not started");
+        }
+        tx
+    });
+
+fn run_publisher(rx: std::sync::mpsc::Receiver<PendingEvent>) {
+    while let Ok(first) = rx.recv() {
+        let mut pending: Vec<PendingEvent> = std::iter::once(first)
+            .chain(rx.try_iter().take(PUBLISH_BATCH_MAX - 1))
+            .collect();
+        // One call per (token, project): the daemon authorizes the whole batch
+        while let Some(head) = pending.first() {
+            let token = head.token.clone();
+            let project_hash = head.project_hash.clone();
+            let (batch, rest): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|p| p.token == token && p.project_hash == project_hash);
+            pending = rest;
+            let params = serde_json::json!({
+                "project_hash": project_hash,
+                "events": batch.into_iter().map(|p| p.event).collect::<Vec<_>>(),
+            });
+            let _ = call_daemon_as(token, "publish_event", params, std::time::Duration::from_secs(2));
+        }
+    }
+}
+
+/// Report a change made outside the daemon (message, task, thread tool) to
+/// `subscribe` clients. Fire-and-forget: skipped when nobody is subscribed,
+/// queued for the background publisher otherwise; errors (daemon down,
+/// unknown type, queue full) are ignored.
+pub fn publish_event(
+    event_type: &str,
+    project_hash: &str,
+    agent_id: Option<&str>,
+    data: serde_json::Value,
+) {
+    if !subscribers_marker_path().exists() {
+        return;
+    }
+    let pending = PendingEvent {
+        // The token is resolved on the caller's thread (scope of the tool call)
+        token: super::ipc_auth::client_token(),
+        project_hash: project_hash.to_string(),
+        event: serde_json::json!({
+            "type": event_type,
+            "agent_id": agent_id,
+            "data": data,
+        }),
+    };
+    let _ = PUBLISHER.try_send(pending);
+}
+
 /// Engram query result — lightweight struct for thinking injection.
 #[derive(Debug, Clone)]
 pub struct EngramResult {
@@ -172,17 +269,47 @@ pub fn engram_query(
 }
 
 /// Inner IPC call — connect, write, read, parse. Runs in a dedicated thread.
-fn do_ipc_call(sock_path: std::path::PathBuf, request_json: String) -> AiResult<serde_json::Value> {
+fn do_ipc_call(
+    sock_path: std::path::PathBuf,
+    token: Option<String>,
+    request_json: String,
+) -> AiResult<serde_json::Value> {
     use interprocess::local_socket::{prelude::*, GenericFilePath};
 
     let name = sock_path
         .to_fs_name::<GenericFilePath>()
         .map_err(|e| AiError::Provider(format!("Invalid socket name: {}", e)))?;
 
-    let mut stream = interprocess::local_socket::Stream::connect(name)
+    let stream = interprocess::local_socket::Stream::connect(name)
         .map_err(|e| AiError::Provider(format!("Failed to connect to daemon: {}", e)))?;
+    let mut reader = BufReader::new(stream);
+
+    // Handshake: the daemon closes unauthenticated connections
+    let token = token.ok_or_else(|| {
+        AiError::Provider(format!(
+            "No IPC credentials: set {} or start the daemon (issues agent tokens)",
+            super::ipc_auth::TOKEN_ENV
+        ))
+    })?;
+    let auth = IpcRequest {
+        jsonrpc: "2.0",
+        method: "auth",
+        params: serde_json::json!({"token": token}),
+        id: 0,
+    };
+    let auth_json = serde_json::to_string(&auth).map_err(AiError::Serialization)?;
+    exchange(&mut reader, &auth_json)
+        .map_err(|e| AiError::Provider(format!("Daemon authentication failed: {}", e)))?;
+
+    exchange(&mut reader, &request_json)
+}
 
-    // Send request
+/// Write one request line and read its response.
+fn exchange(
+    reader: &mut BufReader<interprocess::local_socket::Stream>,
+    request_json: &str,
+) -> AiResult<serde_json::Value> {
+    let stream = reader.get_mut();
     stream
         .write_all(request_json.as_bytes())
         .map_err(|e| AiError::Provider(format!("Failed to write to daemon: {}", e)))?;
@@ -194,7 +321,6 @@ fn do_ipc_call(sock_path: std::path::PathBuf, request_json: String) -> AiResult<
         .map_err(|e| AiError::Provider(format!("Failed to flush: {}", e)))?;
 
     // Read response
-    let mut reader = BufReader::new(&mut stream);
     let mut response_line = String::new();
     reader
         .read_line(&mut response_line)
@@ -212,10 +338,26 @@ fn do_ipc_call(sock_path: std::path::PathBuf, request_json: String) -> AiResult<
         .unwrap_or(serde_json::Value::Object(Default::default())))
 }
 
-/// Generic IPC call to the daemon — guarded by a 5s timeout via thread + channel.
+/// Generic IPC call to the daemon — guarded by a 2s timeout via thread + channel.
 fn call_daemon(method: &str, params: serde_json::Value) -> AiResult<serde_json::Value> {
-    use std::time::Duration;
+    call_daemon_with_timeout(method, params, std::time::Duration::from_secs(2))
+}
+
+fn call_daemon_with_timeout(
+    method: &str,
+    params: serde_json::Value,
+    timeout: std::time::Duration,
+) -> AiResult<serde_json::Value> {
+    call_daemon_as(super::ipc_auth::client_token(), method, params, timeout)
+}
 
+/// IPC call authenticated with `token`.
+fn call_daemon_as(
+    token: Option<String>,
+    method: &str,
+    params: serde_json::Value,
+    timeout: std::time::Duration,
+) -> AiResult<serde_json::Value> {
     let sock_path = socket_path();
 
     // On Unix, check if socket file exists; on Windows named pipes don't create files
@@ -237,9 +379,9 @@ fn call_daemon(method: &str, params: serde_json::Value) -> AiResult<serde_json::
 
     let (tx, rx) = std::sync::mpsc::channel();
     std::thread::spawn(move || {
-        tx.send(do_ipc_call(sock_path, request_json)).ok();
+        tx.send(do_ipc_call(sock_path, token, request_json)).ok();
     });
 
-    rx.recv_timeout(Duration::from_secs(2))
-        .map_err(|_| AiError::Provider("Daemon IPC timeout after 2s".into()))?
+    rx.recv_timeout(timeout)
+        .map_err(|_| AiError::Provider(format!("Daemon IPC timeout after {}s", timeout.as_secs())))?
 }
@@ -7,7 +7,7 @@
 use crate::config::{ExtractionConfig, ImportanceRatingConfig, LabelSuggestionConfig, LocalModelSize};
 use crate::constants::truncate_safe;
 use crate::processing::prompt_loader::{self, PromptName};
-use crate::AiResult;
+use crate::{metrics, AiResult};
 use serde::{Deserialize, Serialize};
 
 /// How a thread's content was processed by the LLM.
@@ -124,7 +124,8 @@ impl ExtractionSource {
 }
 
 /// Extract structured data from content using LLM.
-/// Returns None if LLM decides to skip or if extraction fails.
+/// Returns None if LLM decides to skip; Err if every attempt failed (LLM error
+/// or degenerate output), so the caller can dead-letter the capture.
 /// No heuristic fallback — quality over quantity.
 ///
 /// `agent_context` — optional recent context from the agent's activity.
@@ -155,6 +156,7 @@ pub fn extract(
                 // Gate: detect degenerate extraction (LLM returned placeholders like "...").
                 if is_degenerate_extraction(&extraction) {
                     if attempt < 2 {
+                        metrics::RETRIES.inc(&[("stage", "extraction")]);
                         tracing::warn!(
                             attempt = attempt + 1,
                             title = %extraction.title,
@@ -166,9 +168,9 @@ pub fn extract(
                     tracing::warn!(
                         title = %extraction.title,
                         summary = %extraction.summary,
-                        "Extraction: degenerate output after 3 attempts — dropping"
+                        "Extraction: degenerate output after 3 attempts"
                     );
-                    return Ok(None);
+                    return Err(crate::AiError::Provider("degenerate extraction output after 3 attempts".into()));
                 }
                 // Prompt and Response: use verbatim fallback ONLY if LLM didn't produce a summary.
                 if matches!(source, ExtractionSource::Prompt | ExtractionSource::Response)
@@ -181,6 +183,7 @@ pub fn extract(
             }
             Err(e) => {
                 if attempt < 2 {
+                    metrics::RETRIES.inc(&[("stage", "extraction")]);
                     tracing::warn!(
                         attempt = attempt + 1,
                         error = %e,
@@ -188,8 +191,8 @@ pub fn extract(
                     );
                     continue;
                 }
-                tracing::warn!("Extraction failed after 3 attempts: {} — dropping capture", e);
-                return Ok(None);
+                tracing::warn!("Extraction failed after 3 attempts: {}", e);
+                return Err(e);
             }
         }
     }
@@ -222,6 +225,8 @@ fn extract_via_llm(
     for attempt in 0..3u8 {
         match super::llm_subprocess::call_llm(&prompt) {
             Ok(response) => {
+                let model = super::llm_subprocess::model_label();
+                metrics::EXTRACTION_DURATION.observe_since(&[("model", &model)], start);
                 tracing::info!(
                     response_len = response.len(),
                     attempt = attempt + 1,
@@ -255,6 +260,7 @@ fn extract_via_llm(
             }
             Err(e) => {
                 if attempt < 2 {
+                    metrics::RETRIES.inc(&[("stage", "llm_call")]);
                     tracing::warn!(
                         attempt = attempt + 1,
                         error = %e,
@@ -0,0 +1,400 @@
+//! IPC authentication — per-user secret and scoped session tokens.
+//!
+//! The daemon creates `{data_dir}/ipc_secret` (owner-only) on startup. Every
+//! IPC connection opens with an `auth` handshake carrying a token:
+//!
+//!   ais1.<scopes>.<hex HMAC-SHA256(secret, scopes)>
+//!
+//! `<scopes>` is a comma-separated list of `admin`, `<project_hash>/*` (every
+//! agent of a project) or `<project_hash>/<agent_id>`. Tokens are stateless:
+//! rotating the secret revokes all of them.
+//!
+//! Clients use the token in `AI_SMARTNESS_IPC_TOKEN` when set (e.g. an agent
+//! launched with a token from `ai-smartness daemon token --agent ...`).
+//! Otherwise the token depends on who the process acts for (`set_client_scope`,
+//! `with_client_scope`): hooks, MCP servers and tool calls use the token the
+//! daemon issued to their agent (`{agent_data_dir}/ipc_token`); only the CLI
+//! and GUI (daemon control) read the secret and authenticate as admin.
+//!
+//! The daemon keeps token files in line with the registry: written for every
+//! registered agent, deleted once the agent is unregistered. An agent-scoped
+//! token is only accepted while its agent's file exists (`check_issued`), so
+//! unregistering an agent revokes its token without rotating the secret.
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::Path;
+use std::sync::RwLock;
+
+use sha2::{Digest, Sha256};
+
+use crate::storage::path_utils;
+use crate::{AiError, AiResult};
+
+/// Environment variable holding the token of a scoped client.
+pub const TOKEN_ENV: &str = "AI_SMARTNESS_IPC_TOKEN";
+
+const TOKEN_PREFIX: &str = "ais1.";
+
+/// Who this process authenticates as when no token is set in the environment.
+static CLIENT_SCOPE: RwLock<Option<Scope>> = RwLock::new(None);
+
+thread_local! {
+    /// Per-thread override (the agent a tool call runs for).
+    static ACTING_SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+/// What a session may act on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope {
+    /// Everything, including daemon control (shutdown, restart, pool_flush...).
+    Admin,
+    /// Every agent of a project.
+    Project(String),
+    /// One agent.
+    Agent { project_hash: String, agent_id: String },
+}
+
+impl Scope {
+    pub fn parse(s: &str) -> Option<Self> {
+        if s == "admin" {
+            return Some(Self::Admin);
+        }
+        let (project_hash, agent_id) = s.split_once('/')?;
+        if project_hash.is_empty() || agent_id.is_empty() || agent_id.contains(',') {
+            return None;
+        }
+        Some(match agent_id {
+            "*" => Self::Project(project_hash.to_string()),
+            _ => Self::Agent { project_hash: project_hash.to_string(), agent_id: agent_id.to_string() },
+        })
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Admin => write!(f, "admin"),
+            Self::Project(project_hash) => write!(f, "{}/*", project_hash),
+            Self::Agent { project_hash, agent_id } => write!(f, "{}/{}", project_hash, agent_id),
+        }
+    }
+}
+
+/// Scopes granted to an authenticated session.
+#[derive(Debug, Clone)]
+pub struct Grant {
+    pub scopes: Vec<Scope>,
+}
+
+impl Grant {
+    pub fn is_admin(&self) -> bool {
+        self.scopes.contains(&Scope::Admin)
+    }
+
+    /// May act on `agent_id` of the project (None = the whole project).
+    pub fn covers(&self, project_hash: &str, agent_id: Option<&str>) -> bool {
+        self.scopes.iter().any(|s| match s {
+            Scope::Admin => true,
+            Scope::Project(p) => p == project_hash,
+            Scope::Agent { project_hash: p, agent_id: a } => p == project_hash && agent_id == Some(a.as_str()),
+        })
+    }
+}
+
+/// Read the IPC secret, creating it (owner-only) if missing. Daemon side.
+pub fn load_or_create_secret() -> AiResult<String> {
+    let path = path_utils::ipc_secret_path();
+    if let Ok(secret) = read_secret() {
+        restrict_to_owner(&path)?;
+        return Ok(secret);
+    }
+    let secret = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    write_owner_only(&path, &secret)?;
+    tracing::info!(path = %path.display(), "IPC secret created");
+    Ok(secret)
+}
+
+/// Read the IPC secret (Err if missing or empty).
+pub fn read_secret() -> AiResult<String> {
+    let path = path_utils::ipc_secret_path();
+    let secret = std::fs::read_to_string(&path)
+        .map_err(|e| AiError::Storage(format!("Cannot read {}: {}", path.display(), e)))?;
+    let secret = secret.trim().to_string();
+    if secret.is_empty() {
+        return Err(AiError::Storage(format!("Empty IPC secret: {}", path.display())));
+    }
+    Ok(secret)
+}
+
+/// Token granting `scopes`.
+pub fn mint(secret: &str, scopes: &[Scope]) -> String {
+    let scopes = scopes.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(",");
+    format!("{}{}.{}", TOKEN_PREFIX, scopes, to_hex(&hmac_sha256(secret.as_bytes(), scopes.as_bytes())))
+}
+
+/// Check a token against the secret and return its grant.
+pub fn verify(secret: &str, token: &str) -> Result<Grant, String> {
+    let (scopes, mac) = token
+        .strip_prefix(TOKEN_PREFIX)
+        .and_then(|t| t.rsplit_once('.'))
+        .ok_or("Malformed IPC token")?;
+    let expected = to_hex(&hmac_sha256(secret.as_bytes(), scopes.as_bytes()));
+    // Constant-time comparison (no early exit on the first differing byte)
+    let matches = mac.len() == expected.len()
+        && mac.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+    if !matches {
+        return Err("Invalid IPC token".to_string());
+    }
+    let scopes = scopes
+        .split(',')
+        .map(|s| Scope::parse(s).ok_or_else(|| format!("Invalid IPC token scope '{}'", s)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Grant { scopes })
+}
+
+/// Write the agent's token file (owner-only) unless it already holds the
+/// current token. Daemon side. Returns true when the file was (re)written.
+pub fn issue_agent_token(secret: &str, project_hash: &str, agent_id: &str) -> AiResult<bool> {
+    let path = path_utils::agent_ipc_token_path(project_hash, agent_id);
+    let token = mint(
+        secret,
+        &[Scope::Agent { project_hash: project_hash.to_string(), agent_id: agent_id.to_string() }],
+    );
+    if std::fs::read_to_string(&path).is_ok_and(|current| current.trim() == token) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_owner_only(&path, &token)?;
+    Ok(true)
+}
+
+/// Agents entitled to a token (registered, not offline), as sorted
+/// (project_hash, agent_id) pairs.
+pub fn token_holders(conn: &rusqlite::Connection) -> AiResult<Vec<(String, String)>> {
+    let mut holders: Vec<(String, String)> = crate::registry::registry::AgentRegistry::list(conn, None, None, None)?
+        .into_iter()
+        .map(|a| (a.project_hash, a.id))
+        .collect();
+    holders.sort();
+    Ok(holders)
+}
+
+/// Write the token of every holder (new agents, rotated secret) and delete
+/// the token files of everyone else (unregistered agents). Daemon side.
+/// Returns the number of token files written or deleted.
+pub fn sync_agent_tokens(secret: &str, holders: &[(String, String)]) -> AiResult<usize> {
+    let mut changed = 0;
+    for (project_hash, agent_id) in holders {
+        if issue_agent_token(secret, project_hash, agent_id)? {
+            changed += 1;
+        }
+    }
+    for (project_hash, agent_id) in issued_token_files() {
+        if holders.iter().any(|(p, a)| *p == project_hash && *a == agent_id) {
+            continue;
+        }
+        let path = path_utils::agent_ipc_token_path(&project_hash, &agent_id);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                tracing::info!(project = %project_hash, agent = %agent_id, "Agent IPC token revoked (unregistered)");
+                changed += 1;
+            }
+            Err(e) => tracing::warn!(path = %path.display(), error = %e, "Agent IPC token revocation failed"),
+        }
+    }
+    Ok(changed)
+}
+
+/// Sync the token files with the registry (see `sync_agent_tokens`).
+pub fn issue_registered_agent_tokens() -> AiResult<usize> {
+    let secret = read_secret()?;
+    let conn = crate::storage::database::open_connection(
+        &path_utils::registry_db_path(),
+        crate::storage::database::ConnectionRole::Daemon,
+    )?;
+    sync_agent_tokens(&secret, &token_holders(&conn)?)
+}
+
+/// (project_hash, agent_id) of every agent token file on disk.
+fn issued_token_files() -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let Ok(projects) = std::fs::read_dir(path_utils::projects_dir()) else { return found };
+    for project in projects.flatten() {
+        let project_hash = project.file_name().to_string_lossy().into_owned();
+        let Ok(agents) = std::fs::read_dir(project.path().join("agents")) else { continue };
+        for agent in agents.flatten() {
+            let agent_id = agent.file_name().to_string_lossy().into_owned();
+            if path_utils::agent_ipc_token_path(&project_hash, &agent_id).is_file() {
+                found.push((project_hash.clone(), agent_id));
+            }
+        }
+    }
+    found
+}
+
+/// Err when an agent scope of `grant` belongs to an agent whose token was
+/// revoked (no token file: unregistered). Daemon side, after `verify`.
+pub fn check_issued(grant: &Grant) -> Result<(), String> {
+    for scope in &grant.scopes {
+        if let Scope::Agent { project_hash, agent_id } = scope {
+            if !path_utils::agent_ipc_token_path(project_hash, agent_id).is_file() {
+                return Err(format!("IPC token revoked: agent {}/{} is not registered", project_hash, agent_id));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Declare who this process acts for (entry points: `Scope::Admin` for the
+/// CLI/GUI, the agent for hooks and MCP servers).
+pub fn set_client_scope(scope: Scope) {
+    *CLIENT_SCOPE.write().unwrap_or_else(|e| e.into_inner()) = Some(scope);
+}
+
+/// Run `f` with this thread's IPC calls authenticated as `scope`.
+pub fn with_client_scope<T>(scope: Scope, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Scope>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            ACTING_SCOPE.with(|s| *s.borrow_mut() = previous);
+        }
+    }
+    let _restore = Restore(ACTING_SCOPE.with(|s| s.borrow_mut().replace(scope)));
+    f()
+}
+
+/// Token a client authenticates with: `AI_SMARTNESS_IPC_TOKEN`, else the one
+/// matching its scope — admin from the secret file, or the token the daemon
+/// issued to the agent. None when no scope is set or the file is missing.
+pub fn client_token() -> Option<String> {
+    if let Ok(token) = std::env::var(TOKEN_ENV) {
+        if !token.trim().is_empty() {
+            return Some(token.trim().to_string());
+        }
+    }
+    let scope = ACTING_SCOPE
+        .with(|s| s.borrow().clone())
+        .or_else(|| CLIENT_SCOPE.read().unwrap_or_else(|e| e.into_inner()).clone())?;
+    match scope {
+        Scope::Admin => read_secret().ok().map(|secret| mint(&secret, &[Scope::Admin])),
+        Scope::Agent { project_hash, agent_id } => {
+            std::fs::read_to_string(path_utils::agent_ipc_token_path(&project_hash, &agent_id))
+                .ok()
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty())
+        }
+        Scope::Project(_) => None,
+    }
+}
+
+/// Remove group/other access from an existing file (mode 0600). Unix only.
+pub fn restrict_to_owner(path: &Path) -> AiResult<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| AiError::Storage(format!("Cannot restrict {}: {}", path.display(), e)))?;
+            tracing::warn!(path = %path.display(), mode = %format!("{:o}", mode & 0o777), "Permissions tightened to 0600");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Create or replace `path` with `content`, readable by the owner only.
+pub fn write_owner_only(path: &Path, content: &str) -> AiResult<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| AiError::Storage(format!("Cannot create {}: {}", path.display(), e)))?;
+    // The mode above only applies to new files
+    restrict_to_owner(path)?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| AiError::Storage(format!("Cannot write {}: {}", path.display(), e)))
+}
+
+/// HMAC-SHA256 (RFC 2104).
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.iter().map(|b| b ^ byte).collect::<Vec<u8>>();
+    let inner = Sha256::new().chain_update(pad(0x36)).chain_update(message).finalize();
+    Sha256::new().chain_update(pad(0x5c)).chain_update(inner).finalize().into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_vector() {
+        let mac = hmac_sha256(&[0x0b; 20], b"Hi There");
+        assert_eq!(to_hex(&mac), "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_existing_file_restricted_to_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_owner_only(&path, "new").unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_token_roundtrip_and_scopes() {
+        let agent = Scope::Agent { project_hash: "ph1".into(), agent_id: "dev.backend".into() };
+        let token = mint("secret", &[agent.clone(), Scope::Project("ph2".into())]);
+        let grant = verify("secret", &token).unwrap();
+        assert_eq!(grant.scopes, vec![agent, Scope::Project("ph2".into())]);
+        assert!(!grant.is_admin());
+        assert!(grant.covers("ph1", Some("dev.backend")));
+        assert!(!grant.covers("ph1", Some("other")));
+        assert!(!grant.covers("ph1", None), "Agent scope does not cover the whole project");
+        assert!(grant.covers("ph2", Some("anyone")) && grant.covers("ph2", None));
+        assert!(!grant.covers("ph3", None));
+
+        assert!(verify("other-secret", &token).is_err());
+        let widened = token.replacen("ph1/dev.backend", "admin", 1);
+        assert!(verify("secret", &widened).is_err(), "Scopes are covered by the MAC");
+        assert!(verify("secret", &mint("secret", &[Scope::Admin])).unwrap().is_admin());
+    }
+
+    #[test]
+    fn test_with_client_scope_nests_and_restores() {
+        let agent = |id: &str| Scope::Agent { project_hash: "ph".into(), agent_id: id.into() };
+        let acting = || ACTING_SCOPE.with(|s| s.borrow().clone());
+        with_client_scope(agent("outer"), || {
+            with_client_scope(agent("inner"), || assert_eq!(acting(), Some(agent("inner"))));
+            assert_eq!(acting(), Some(agent("outer")));
+        });
+        assert_eq!(acting(), None);
+    }
+}
@@ -6,21 +6,27 @@
 
 use crate::config::{GuardianConfig, LlmBackend};
 use crate::{AiError, AiResult};
-use std::sync::OnceLock;
+use std::sync::{Arc, LazyLock, RwLock};
 
 /// Cached guardian config for backend routing.
-static GUARDIAN_CFG: OnceLock<GuardianConfig> = OnceLock::new();
+static GUARDIAN_CFG: LazyLock<RwLock<Arc<GuardianConfig>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(GuardianConfig::default())));
 
-/// Initialize routing config (called by daemon init).
+/// Set routing config (called by daemon init and config reload).
+/// Calls already running keep the config they started with.
 pub fn init_routing(cfg: &GuardianConfig) {
-    let _ = GUARDIAN_CFG.set(cfg.clone());
+    *GUARDIAN_CFG.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(cfg.clone());
+}
+
+fn routing() -> Arc<GuardianConfig> {
+    GUARDIAN_CFG.read().unwrap_or_else(|e| e.into_inner()).clone()
 }
 
 /// Call LLM with a prompt and return the response text.
 /// Routes to local or remote based on configured backend.
 pub fn call_llm(prompt: &str) -> AiResult<String> {
     let start = std::time::Instant::now();
-    let cfg = GUARDIAN_CFG.get_or_init(GuardianConfig::default);
+    let cfg = routing();
 
     tracing::info!(
         prompt_len = prompt.len(),
@@ -30,7 +36,7 @@ pub fn call_llm(prompt: &str) -> AiResult<String> {
 
     let result = match cfg.llm_backend {
         LlmBackend::Local => call_local(prompt),
-        LlmBackend::Remote => call_remote(prompt, cfg),
+        LlmBackend::Remote => call_remote(prompt, &cfg),
         LlmBackend::Auto => {
             match call_local(prompt) {
                 Ok(r) => Ok(r),
@@ -39,7 +45,7 @@ pub fn call_llm(prompt: &str) -> AiResult<String> {
                         error = %local_err,
                         "Local LLM failed, falling back to remote"
                     );
-                    call_remote(prompt, cfg).map_err(|remote_err| {
+                    call_remote(prompt, &cfg).map_err(|remote_err| {
                         AiError::Provider(format!(
                             "Both local and remote failed. Local: {}. Remote: {}",
                             local_err, remote_err
@@ -59,6 +65,27 @@ pub fn call_llm(prompt: &str) -> AiResult<String> {
     result
 }
 
+/// Model answering `call_llm` (metrics label): local model file stem or
+/// remote model name. Auto reports the local model while it is available.
+pub fn model_label() -> String {
+    let cfg = routing();
+    let local = || {
+        super::local_llm::LocalLlm::loaded()
+            .filter(|llm| llm.is_available())
+            .map(|llm| {
+                llm.model_path()
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            })
+    };
+    match cfg.llm_backend {
+        LlmBackend::Local => local().unwrap_or_else(|| "unavailable".to_string()),
+        LlmBackend::Remote => cfg.remote_llm.model.clone(),
+        LlmBackend::Auto => local().unwrap_or_else(|| cfg.remote_llm.model.clone()),
+    }
+}
+
 fn call_local(prompt: &str) -> AiResult<String> {
     let local = super::local_llm::LocalLlm::global();
     if !local.is_available() {
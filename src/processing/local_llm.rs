@@ -4,12 +4,13 @@
 //! Hardware-agnostic: adapts GPU layers and context size to available VRAM.
 //!
 //! Model: GGUF format, auto-downloaded to {data_dir}/models/ on first use.
-//! Singleton pattern (OnceLock) — same as EmbeddingManager.
+//! Singleton pattern, swappable: `reload` replaces the model when its
+//! parameters change (daemon config reload).
 
 use crate::config::{DeviceSelection, LocalLlmConfig, LocalModelSize};
 use crate::{AiError, AiResult};
 use std::path::PathBuf;
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 use llama_cpp_2::context::LlamaContext;
 use llama_cpp_2::context::params::LlamaContextParams;
@@ -141,11 +142,27 @@ impl LlmCircuitBreaker {
 // LocalLlm
 // ============================================================================
 
-static GLOBAL: OnceLock<LocalLlm> = OnceLock::new();
+static GLOBAL: RwLock<Option<Arc<LocalLlm>>> = RwLock::new(None);
+
+/// llama.cpp backend, initialized once per process and shared by every model
+/// (a reload loads the new model while the old one is still serving).
+static BACKEND: OnceLock<Option<LlamaBackend>> = OnceLock::new();
+
+fn shared_backend() -> Option<&'static LlamaBackend> {
+    BACKEND
+        .get_or_init(|| match LlamaBackend::init() {
+            Ok(b) => Some(b),
+            Err(e) => {
+                tracing::warn!("Failed to init llama backend: {:?}, local LLM unavailable", e);
+                None
+            }
+        })
+        .as_ref()
+}
 
 /// Local LLM engine — wraps llama.cpp for in-process inference.
 pub struct LocalLlm {
-    /// Backend + model held together. None = unavailable.
+    /// Loaded model. None = unavailable.
     inner: Option<LlmInner>,
     model_path: PathBuf,
     /// Which model variant is loaded (needed for chat template wrapping).
@@ -160,22 +177,28 @@ pub struct LocalLlm {
 }
 
 struct LlmInner {
-    backend: LlamaBackend,
     model: LlamaModel,
+    backend: &'static LlamaBackend,
 }
 
 // Safety: LlamaBackend and LlamaModel are thread-safe for read operations.
 // LlamaContext is guarded by Mutex (exclusive access during generate()).
-// The 'static lifetime on LlamaContext is sound: model lives in 'static OnceLock.
+// The 'static lifetime on LlamaContext is sound: the context never leaves
+// the LocalLlm owning the model, and Drop frees it before the model.
 unsafe impl Send for LocalLlm {}
 unsafe impl Sync for LocalLlm {}
 unsafe impl Send for LlmInner {}
 unsafe impl Sync for LlmInner {}
 
 impl LocalLlm {
-    /// Global singleton (initialized once on first access).
-    pub fn global() -> &'static Self {
-        GLOBAL.get_or_init(|| Self::new(None, LocalLlmConfig::default(), DeviceSelection::Auto))
+    /// Global singleton (initialized on first access).
+    pub fn global() -> Arc<Self> {
+        Self::get_or_init(|| Self::new(None, LocalLlmConfig::default(), DeviceSelection::Auto))
+    }
+
+    /// Singleton if already initialized — never triggers a model load.
+    pub fn loaded() -> Option<Arc<Self>> {
+        GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone()
     }
 
     /// Initialize with explicit model size, config, and device selection. Called by daemon.
@@ -183,8 +206,52 @@ impl LocalLlm {
         size: &LocalModelSize,
         config: &LocalLlmConfig,
         runtime_device: &DeviceSelection,
-    ) -> &'static Self {
-        GLOBAL.get_or_init(|| Self::new(Some(size.clone()), config.clone(), runtime_device.clone()))
+    ) -> Arc<Self> {
+        Self::get_or_init(|| Self::new(Some(size.clone()), config.clone(), runtime_device.clone()))
+    }
+
+    /// Replace the singleton with a model loaded from new parameters, unless
+    /// `still_wanted` (checked under the singleton lock, after the load) says
+    /// a newer reload superseded this one: the new model is then dropped and
+    /// None returned.
+    ///
+    /// The new model is loaded without holding the singleton lock, so callers
+    /// of `global()` keep using the old one meanwhile; the swap itself is a
+    /// short write. The old model is freed when its last in-flight generation
+    /// drops its handle. Peak memory: old and new model are both resident
+    /// during the load, so the VRAM probe may see too little free memory and
+    /// place the new model (partly) on CPU.
+    pub fn reload(
+        size: &LocalModelSize,
+        config: &LocalLlmConfig,
+        runtime_device: &DeviceSelection,
+        still_wanted: impl FnOnce() -> bool,
+    ) -> Option<Arc<Self>> {
+        let llm = Arc::new(Self::new(Some(size.clone()), config.clone(), runtime_device.clone()));
+        let old = {
+            let mut slot = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+            if !still_wanted() {
+                return None;
+            }
+            slot.replace(llm.clone())
+        };
+        if let Some(old) = old {
+            tracing::info!(
+                old = %old.model_path.display(),
+                new = %llm.model_path.display(),
+                in_flight = Arc::strong_count(&old) - 1,
+                "Local LLM swapped"
+            );
+        }
+        Some(llm)
+    }
+
+    fn get_or_init(init: impl FnOnce() -> Self) -> Arc<Self> {
+        if let Some(llm) = Self::loaded() {
+            return llm;
+        }
+        let mut slot = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+        slot.get_or_insert_with(|| Arc::new(init())).clone()
     }
 
     /// Initialize: probe VRAM, find/download model, load with adaptive GPU layers.
@@ -216,13 +283,8 @@ impl LocalLlm {
             circuit_breaker: Mutex::new(LlmCircuitBreaker::new()),
         };
 
-        // Initialize llama.cpp backend
-        let backend = match LlamaBackend::init() {
-            Ok(b) => b,
-            Err(e) => {
-                tracing::warn!("Failed to init llama backend: {:?}, local LLM unavailable", e);
-                return unavailable(model_path, size, profile);
-            }
+        let Some(backend) = shared_backend() else {
+            return unavailable(model_path, size, profile);
         };
 
         // Try to find model file (download via model_download module if missing)
@@ -236,7 +298,7 @@ impl LocalLlm {
 
         // Load model with adaptive GPU layers — cascade: full → half → CPU-only
         let target_layers = profile.gpu_layers;
-        let (model, actual_layers) = match Self::load_model_cascade(&backend, &model_path, target_layers, &runtime_device) {
+        let (model, actual_layers) = match Self::load_model_cascade(backend, &model_path, target_layers, &runtime_device) {
             Some(result) => result,
             None => {
                 tracing::warn!("All model load attempts failed, local LLM unavailable");
@@ -506,7 +568,7 @@ impl LocalLlm {
 
             tracing::info!(ctx_size, attempt = i + 1, "Attempting context creation");
 
-            match inner.model.new_context(&inner.backend, params) {
+            match inner.model.new_context(inner.backend, params) {
                 Ok(ctx) => {
                     if ctx_size < initial_ctx_size {
                         tracing::warn!(
@@ -624,8 +686,8 @@ impl LocalLlm {
         // Lazy-create persistent context on first call, reuse on subsequent calls.
         if ctx_guard.is_none() {
             let ctx = self.try_create_context_cascade(inner, ctx_size, threads)?;
-            // Safety: model lives in 'static OnceLock (GLOBAL) — it is never dropped,
-            // so the context's borrow of model is valid for 'static lifetime.
+            // Safety: the context is stored in self.persistent_ctx and Drop frees
+            // it before self.inner, so its borrow of the model never dangles.
             let ctx: LlamaContext<'static> = unsafe { std::mem::transmute(ctx) };
             *ctx_guard = Some(ctx);
         } else {
@@ -753,6 +815,7 @@ impl LocalLlm {
                                     } else { 0 },
                                     "Local LLM generation complete (JSON early-stop)"
                                 );
+                                self.record_generation(total_generated, sample_start.elapsed());
                                 let preview_end = safe_preview_end(&output, 500);
                                 tracing::info!(output_preview = %&output[..preview_end], "LLM raw output");
                                 return Ok(output);
@@ -804,12 +867,30 @@ impl LocalLlm {
             } else { 0 },
             "Local LLM generation complete"
         );
+        self.record_generation(total_generated, sample_start.elapsed());
         let preview_end = safe_preview_end(&output, 500);
         tracing::info!(output_preview = %&output[..preview_end], "LLM raw output");
 
         Ok(output)
     }
 
+    /// Token count and throughput of one completion, labelled by model file.
+    fn record_generation(&self, tokens: usize, sampling: std::time::Duration) {
+        let model = self.model_path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        let labels = [("model", &*model)];
+        crate::metrics::LLM_TOKENS_GENERATED.add(&labels, tokens as f64);
+        if !sampling.is_zero() {
+            crate::metrics::LLM_TOKENS_PER_SECOND.observe(&labels, tokens as f64 / sampling.as_secs_f64());
+        }
+    }
+}
+
+impl Drop for LocalLlm {
+    fn drop(&mut self) {
+        // The context borrows the model: free it first (fields drop after this)
+        let ctx = self.persistent_ctx.get_mut().unwrap_or_else(|e| e.into_inner());
+        *ctx = None;
+    }
 }
 
 /// Safe char-boundary truncation for preview (avoid panic on multi-byte UTF-8).
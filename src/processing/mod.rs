@@ -4,10 +4,13 @@ pub mod coherence;
 pub mod daemon_ipc_client;
 pub mod embeddings;
 pub mod extractor;
+pub mod ipc_auth;
 pub mod llm_subprocess;
 pub mod local_llm;
 pub mod model_download;
 pub mod prompt_loader;
+pub mod relation_classifier;
 pub mod remote_llm;
 pub mod toolextractor;
+pub mod topic_normalizer;
 pub mod vram_probe;
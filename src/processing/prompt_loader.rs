@@ -48,6 +48,7 @@ pub enum PromptName {
     Extractor,
     ToolExtractor,
     Coherence,
+    Relation,
 }
 
 impl PromptName {
@@ -56,6 +57,7 @@ impl PromptName {
             Self::Extractor => "extractor.toml",
             Self::ToolExtractor => "toolextractor.toml",
             Self::Coherence => "coherence.toml",
+            Self::Relation => "relation.toml",
         }
     }
 }
@@ -210,6 +212,7 @@ mod tests {
     fn test_prompt_filenames() {
         assert_eq!(PromptName::Extractor.filename(), "extractor.toml");
         assert_eq!(PromptName::ToolExtractor.filename(), "toolextractor.toml");
+        assert_eq!(PromptName::Relation.filename(), "relation.toml");
     }
 
     #[test]
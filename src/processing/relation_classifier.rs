@@ -0,0 +1,124 @@
+//! Relation classifier — LLM-based bridge relation typing.
+//!
+//! Gossip infers `BridgeType` from structural heuristics only. For high-weight
+//! candidate pairs this module asks the LLM how thread B relates to thread A
+//! and returns the relation, a short reason and a confidence.
+//!
+//! This is the only source of "real" `Contradicts` bridges (two threads
+//! asserting incompatible decisions), surfaced to the agent as conflicts.
+
+use crate::bridge::BridgeType;
+use crate::config::LocalModelSize;
+use crate::processing::prompt_loader::{self, PromptName};
+use crate::thread::Thread;
+use crate::{AiError, AiResult};
+use serde::Deserialize;
+
+/// LLM verdict for a thread pair.
+#[derive(Debug, Clone)]
+pub struct RelationVerdict {
+    pub relation: BridgeType,
+    pub reason: String,
+    pub confidence: f64,
+}
+
+#[derive(Deserialize)]
+struct RawVerdict {
+    relation: String,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    confidence: f64,
+}
+
+/// Ask the LLM how `target` relates to `source`.
+pub fn classify(
+    source: &Thread,
+    target: &Thread,
+    model: &LocalModelSize,
+) -> AiResult<RelationVerdict> {
+    let template = prompt_loader::get_template(model, PromptName::Relation)?;
+    let max_chars = prompt_loader::get_max_context_chars(model, PromptName::Relation)
+        .unwrap_or(1000) / 2;
+
+    let prompt = template
+        .replace("{source_title}", &source.title)
+        .replace("{source_summary}", &truncate(source.summary.as_deref().unwrap_or(""), max_chars))
+        .replace("{source_concepts}", &format!("{:?}", source.concepts))
+        .replace("{target_title}", &target.title)
+        .replace("{target_summary}", &truncate(target.summary.as_deref().unwrap_or(""), max_chars))
+        .replace("{target_concepts}", &format!("{:?}", target.concepts));
+
+    let response = super::llm_subprocess::call_llm(&prompt)?;
+    let verdict = parse_verdict(&response)?;
+
+    tracing::debug!(
+        source = %source.id,
+        target = %target.id,
+        relation = %verdict.relation,
+        confidence = verdict.confidence,
+        "Relation classified (LLM)"
+    );
+    Ok(verdict)
+}
+
+/// Parse the JSON verdict out of a raw LLM response.
+pub fn parse_verdict(response: &str) -> AiResult<RelationVerdict> {
+    let json_str = match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if end > start => &response[start..=end],
+        _ => response,
+    };
+
+    let raw: RawVerdict = serde_json::from_str(json_str).map_err(|e| {
+        AiError::InvalidInput(format!("Failed to parse relation verdict: {}", e))
+    })?;
+
+    let relation: BridgeType = raw.relation.trim().to_lowercase().parse()
+        .map_err(AiError::InvalidInput)?;
+
+    Ok(RelationVerdict {
+        relation,
+        reason: raw.reason.trim().to_string(),
+        confidence: raw.confidence.clamp(0.0, 1.0),
+    })
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(max_chars).collect();
+    format!("{}...", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verdict_contradicts() {
+        let v = parse_verdict(
+            r#"Sure: {"relation":"contradicts","reason":"A picks SQLite, B picks Postgres","confidence":0.9}"#,
+        ).unwrap();
+        assert_eq!(v.relation, BridgeType::Contradicts);
+        assert_eq!(v.reason, "A picks SQLite, B picks Postgres");
+        assert!((v.confidence - 0.9).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_verdict_clamps_confidence() {
+        let v = parse_verdict(r#"{"relation":"Extends","reason":"","confidence":3.0}"#).unwrap();
+        assert_eq!(v.relation, BridgeType::Extends);
+        assert_eq!(v.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_parse_verdict_unknown_relation() {
+        assert!(parse_verdict(r#"{"relation":"opposes","reason":"x","confidence":0.5}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_verdict_garbage() {
+        assert!(parse_verdict("no json here").is_err());
+    }
+}
@@ -97,9 +97,9 @@ pub fn summarize_tool_output(
                     tracing::warn!(
                         title = %ext.title,
                         summary = %ext.summary,
-                        "Tool extraction: degenerate output after 3 attempts — dropping"
+                        "Tool extraction: degenerate output after 3 attempts"
                     );
-                    return Ok(None);
+                    return Err(crate::AiError::Provider("degenerate tool extraction output after 3 attempts".into()));
                 }
 
                 // Gate 2: detect truncated JSON (both fields at serde default 0.0).
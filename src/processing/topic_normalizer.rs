@@ -0,0 +1,208 @@
+//! Topic normalizer — canonical keys for topics and concepts.
+//!
+//! Pipeline: casefold → separator folding (`_`, `-`, `/` → space) →
+//! punctuation strip → allow-listed plural folding →
+//! per-agent alias resolution (`topic_aliases` table, confirmed rows only).
+//!
+//! "Auth flow", "auth_flow" and "auth-flows" all map to `auth flow`;
+//! "authn" → "authentication" needs a confirmed alias.
+//!
+//! Threads keep the topics and concepts as written (deduplicated by key);
+//! keys are computed when reading. TopicIndex and ConceptIndex are built with
+//! `normalize` as their key function. Load one normalizer per job and pass it
+//! down: `load` queries the alias table.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::storage::topic_aliases::TopicAliasStorage;
+use rusqlite::Connection;
+
+/// Max alias hops (guards against cycles like a→b→a).
+const MAX_ALIAS_HOPS: usize = 4;
+
+/// Plural → singular pairs folded by `canonical_key`. Deliberately short: any
+/// other word is kept as written ("caches" and "cache" stay distinct) until an
+/// alias is confirmed.
+const PLURALS: &[(&str, &str)] = &[
+    ("agents", "agent"),
+    ("bridges", "bridge"),
+    ("bugs", "bug"),
+    ("branches", "branch"),
+    ("commits", "commit"),
+    ("concepts", "concept"),
+    ("dependencies", "dependency"),
+    ("endpoints", "endpoint"),
+    ("errors", "error"),
+    ("files", "file"),
+    ("flows", "flow"),
+    ("messages", "message"),
+    ("migrations", "migration"),
+    ("queries", "query"),
+    ("requests", "request"),
+    ("tests", "test"),
+    ("threads", "thread"),
+    ("tokens", "token"),
+    ("topics", "topic"),
+    ("users", "user"),
+];
+
+/// Casefold + fold separators + strip punctuation + fold allow-listed plurals.
+pub fn canonical_key(raw: &str) -> String {
+    let lower = raw.trim().to_lowercase();
+    let folded: String = lower
+        .chars()
+        .map(|c| match c {
+            '_' | '-' | '/' => ' ',
+            c if c.is_alphanumeric() || c == ' ' || c == '+' || c == '#' || c == '.' => c,
+            _ => ' ',
+        })
+        .collect();
+    folded
+        .split_whitespace()
+        .map(|w| singular(w.trim_matches('.')))
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Singular form from the `PLURALS` allow-list; other words are unchanged.
+fn singular(word: &str) -> &str {
+    PLURALS
+        .iter()
+        .find(|(plural, _)| *plural == word)
+        .map_or(word, |(_, one)| *one)
+}
+
+/// Canonicaliser with a per-agent alias table (alias key → canonical key).
+#[derive(Debug, Clone, Default)]
+pub struct TopicNormalizer {
+    aliases: HashMap<String, String>,
+}
+
+impl TopicNormalizer {
+    /// Build from raw alias pairs; both sides are canonicalised.
+    pub fn new(aliases: HashMap<String, String>) -> Self {
+        let aliases = aliases
+            .into_iter()
+            .map(|(a, c)| (canonical_key(&a), canonical_key(&c)))
+            .filter(|(a, c)| !a.is_empty() && !c.is_empty() && a != c)
+            .collect();
+        Self { aliases }
+    }
+
+    /// Load confirmed aliases from the agent DB.
+    /// Graceful: no aliases if the table doesn't exist yet.
+    pub fn load(conn: &Connection) -> Self {
+        Self::new(TopicAliasStorage::confirmed_map(conn).unwrap_or_default())
+    }
+
+    pub fn alias_count(&self) -> usize {
+        self.aliases.len()
+    }
+
+    /// Canonical key for one topic/concept (plural-folded + alias-resolved).
+    pub fn normalize(&self, raw: &str) -> String {
+        let mut key = canonical_key(raw);
+        for _ in 0..MAX_ALIAS_HOPS {
+            match self.aliases.get(&key) {
+                Some(target) if *target != key => key = target.clone(),
+                _ => break,
+            }
+        }
+        key
+    }
+
+    /// Dedup a topic list by canonical key, keeping the first spelling of each
+    /// (trimmed). Topics with an empty key are dropped.
+    pub fn dedup_topics(&self, raw: &[String]) -> Vec<String> {
+        let mut seen = HashSet::new();
+        raw.iter()
+            .map(|t| t.trim())
+            .filter(|t| {
+                let key = self.normalize(t);
+                !key.is_empty() && seen.insert(key)
+            })
+            .map(String::from)
+            .collect()
+    }
+
+    /// Existing single-word split/stopword pass, then dedup by canonical key.
+    pub fn dedup_concepts(&self, raw: &[String]) -> Vec<String> {
+        let mut seen = HashSet::new();
+        crate::constants::normalize_concepts(raw)
+            .into_iter()
+            .filter(|c| {
+                let key = self.normalize(c);
+                key.len() >= 3 && seen.insert(key)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_key_folds_case_separators_plurals() {
+        assert_eq!(canonical_key("Auth Flow"), "auth flow");
+        assert_eq!(canonical_key("auth_flows"), "auth flow");
+        assert_eq!(canonical_key("  Dependencies "), "dependency");
+        assert_eq!(canonical_key("branches"), "branch");
+        assert_eq!(canonical_key("Redis"), "redis");
+        assert_eq!(canonical_key("C++"), "c++");
+        assert_eq!(canonical_key("v2s"), "v2s");
+    }
+
+    #[test]
+    fn test_canonical_key_keeps_words_outside_the_plural_list() {
+        for word in ["caches", "nodejs", "bias", "atlas", "always", "movies", "kubernetes"] {
+            assert_eq!(canonical_key(word), word);
+        }
+        assert_ne!(canonical_key("caches"), canonical_key("cache"));
+    }
+
+    #[test]
+    fn test_alias_resolution_and_dedup() {
+        let mut aliases = HashMap::new();
+        aliases.insert("authn".to_string(), "authentication".to_string());
+        aliases.insert("Auth".to_string(), "authentication".to_string());
+        let n = TopicNormalizer::new(aliases);
+
+        assert_eq!(n.normalize("AuthN"), "authentication");
+        let topics = n.dedup_topics(&["auth".into(), "Authentication".into(), " authn".into(), "JWT".into()]);
+        assert_eq!(topics, vec!["auth".to_string(), "JWT".to_string()], "First spelling is kept");
+    }
+
+    #[test]
+    fn test_alias_cycle_terminates() {
+        let mut aliases = HashMap::new();
+        aliases.insert("a1".to_string(), "b1".to_string());
+        aliases.insert("b1".to_string(), "a1".to_string());
+        let n = TopicNormalizer::new(aliases);
+        let k = n.normalize("a1");
+        assert!(k == "a1" || k == "b1");
+    }
+
+    #[test]
+    fn test_load_reads_confirmed_aliases_only() {
+        use crate::storage::topic_aliases::{TopicAlias, STATUS_SUGGESTED};
+        use crate::test_helpers::*;
+
+        let conn = setup_agent_db();
+        TopicAliasStorage::add_confirmed(&conn, "authn", "authentication").unwrap();
+        TopicAliasStorage::upsert(&conn, &TopicAlias {
+            alias: "k8s".into(),
+            canonical: "kubernetes".into(),
+            status: STATUS_SUGGESTED.into(),
+            score: 0.9,
+            source: "embedding".into(),
+            created_at: crate::time_utils::now().to_rfc3339(),
+        }).unwrap();
+
+        let n = TopicNormalizer::load(&conn);
+        assert_eq!(n.alias_count(), 1);
+        assert_eq!(n.normalize("authn"), "authentication");
+        assert_eq!(n.normalize("k8s"), "k8s");
+    }
+}
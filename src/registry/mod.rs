@@ -1,4 +1,5 @@
 pub mod discovery;
 pub mod heartbeat;
+pub mod policy;
 pub mod registry;
 pub mod tasks;
@@ -0,0 +1,384 @@
+//! Tool policy -- per-agent allow/deny/confirm rules evaluated before dispatch.
+//!
+//! Evaluation order (first match wins):
+//!   1. configured rules (`guardian.tool_policy.rules`), in order
+//!   2. built-in hierarchy rule: agent administration of another agent is
+//!      reserved to its supervisors (coordinators get a confirm), and denied
+//!      to unregistered callers
+//!   3. role defaults from ROLE_TEMPLATES (denied / confirm categories)
+//!   4. allow
+//!
+//! `full_permissions` agents skip confirmations (never denials).
+
+use crate::agent::{role_template, Agent};
+use serde::{Deserialize, Serialize};
+
+/// Tool categories (declared per tool in the MCP registry).
+pub const CATEGORY_GENERAL: &str = "general";
+/// Deletes memory (threads, bridges) or overwrites the DB.
+pub const CATEGORY_DESTRUCTIVE: &str = "destructive";
+/// Changes or removes agents in the registry.
+pub const CATEGORY_AGENT_ADMIN: &str = "agent_admin";
+/// Schedules wakes of the agent.
+pub const CATEGORY_WAKE: &str = "wake";
+/// Sends messages / tasks to other agents.
+pub const CATEGORY_MESSAGING: &str = "messaging";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyEffect {
+    #[default]
+    Allow,
+    Deny,
+    /// Allowed only when the call carries `confirm: true`.
+    Confirm,
+}
+
+impl PolicyEffect {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Confirm => "confirm",
+        }
+    }
+}
+
+/// Relation between the calling agent and the agent a tool call targets.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetScope {
+    /// The caller itself.
+    #[serde(rename = "self")]
+    SelfAgent,
+    /// An agent whose supervisor is the caller.
+    Subordinate,
+    /// Any other agent.
+    Other,
+}
+
+impl TargetScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SelfAgent => "self",
+            Self::Subordinate => "subordinate",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Policy rule. Every non-empty matcher must match; empty = any.
+///
+/// Example: `{"name": "no-purge-for-reviewers", "effect": "deny", "categories": ["destructive"], "roles": ["reviewer"]}`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PolicyRule {
+    pub name: String,
+    pub effect: PolicyEffect,
+    /// Tool names; a trailing `*` matches a prefix (`"agent_*"`).
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Caller roles (role or custom_role).
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Caller coordination modes ("autonomous", "supervised", "coordinator").
+    #[serde(default)]
+    pub modes: Vec<String>,
+    /// Target relations; only matches calls that target an agent.
+    #[serde(default)]
+    pub targets: Vec<TargetScope>,
+}
+
+/// Tool policy configuration — `guardian.tool_policy` in config.json.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPolicyConfig {
+    /// Master switch. Default: true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Apply ROLE_TEMPLATES defaults after configured rules. Default: true
+    #[serde(default = "default_true")]
+    pub role_defaults: bool,
+    /// Configured rules, evaluated in order before the defaults.
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for ToolPolicyConfig {
+    fn default() -> Self {
+        Self { enabled: true, role_defaults: true, rules: Vec::new() }
+    }
+}
+
+/// One tool call to evaluate.
+pub struct PolicyRequest<'a> {
+    pub tool: &'a str,
+    pub category: &'a str,
+    /// Registry entry of the caller (None = unregistered caller: roles/modes never
+    /// match, agent administration is denied).
+    pub caller: Option<&'a Agent>,
+    pub target: Option<TargetScope>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyDecision {
+    pub effect: PolicyEffect,
+    /// Rule that decided ("default" when nothing matched).
+    pub rule: String,
+    pub reason: String,
+}
+
+impl PolicyDecision {
+    fn new(effect: PolicyEffect, rule: &str, reason: String) -> Self {
+        Self { effect, rule: rule.to_string(), reason }
+    }
+}
+
+/// Relation of `target_id` to `caller`.
+pub fn target_scope(caller: &Agent, target: Option<&Agent>, target_id: &str) -> TargetScope {
+    if target_id == caller.id {
+        TargetScope::SelfAgent
+    } else if target.and_then(|t| t.supervisor_id.as_deref()) == Some(caller.id.as_str()) {
+        TargetScope::Subordinate
+    } else {
+        TargetScope::Other
+    }
+}
+
+pub struct PolicyEngine<'a> {
+    config: &'a ToolPolicyConfig,
+}
+
+impl<'a> PolicyEngine<'a> {
+    pub fn new(config: &'a ToolPolicyConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn evaluate(&self, req: &PolicyRequest) -> PolicyDecision {
+        let decision = self.evaluate_rules(req);
+        let full = req.caller.map(|a| a.full_permissions).unwrap_or(false);
+        if decision.effect == PolicyEffect::Confirm && full {
+            return PolicyDecision::new(
+                PolicyEffect::Allow,
+                &decision.rule,
+                "full_permissions agent: confirmation skipped".into(),
+            );
+        }
+        decision
+    }
+
+    fn evaluate_rules(&self, req: &PolicyRequest) -> PolicyDecision {
+        if !self.config.enabled {
+            return PolicyDecision::new(PolicyEffect::Allow, "default", "tool policy disabled".into());
+        }
+
+        if let Some(rule) = self.config.rules.iter().find(|r| rule_matches(r, req)) {
+            return PolicyDecision::new(
+                rule.effect,
+                &rule.name,
+                format!("{} by configured rule '{}'", rule.effect.as_str(), rule.name),
+            );
+        }
+
+        if req.category == CATEGORY_AGENT_ADMIN {
+            let Some(caller) = req.caller else {
+                return PolicyDecision::new(
+                    PolicyEffect::Deny,
+                    "hierarchy",
+                    "unregistered callers may not administer agents".into(),
+                );
+            };
+            if req.target == Some(TargetScope::Other) {
+                let coordinator = caller.coordination_mode.as_str() == "coordinator";
+                let effect = if coordinator { PolicyEffect::Confirm } else { PolicyEffect::Deny };
+                return PolicyDecision::new(
+                    effect,
+                    "hierarchy",
+                    "only supervisors may administer other agents".into(),
+                );
+            }
+        }
+
+        if self.config.role_defaults {
+            if let Some(template) = req.caller.and_then(|a| role_template(&a.role)) {
+                if template.denied_categories.contains(&req.category) {
+                    return PolicyDecision::new(
+                        PolicyEffect::Deny,
+                        "role_default",
+                        format!("role '{}' may not use {} tools", template.role, req.category),
+                    );
+                }
+                if template.confirm_categories.contains(&req.category) {
+                    return PolicyDecision::new(
+                        PolicyEffect::Confirm,
+                        "role_default",
+                        format!("role '{}' must confirm {} tools", template.role, req.category),
+                    );
+                }
+            }
+        }
+
+        PolicyDecision::new(PolicyEffect::Allow, "default", "no rule matched".into())
+    }
+}
+
+fn rule_matches(rule: &PolicyRule, req: &PolicyRequest) -> bool {
+    if !rule.tools.is_empty() && !rule.tools.iter().any(|t| tool_matches(t, req.tool)) {
+        return false;
+    }
+    if !rule.categories.is_empty() && !rule.categories.iter().any(|c| c == req.category) {
+        return false;
+    }
+    if !rule.roles.is_empty() {
+        let Some(caller) = req.caller else { return false };
+        let custom = caller.custom_role.as_deref().unwrap_or("");
+        if !rule.roles.iter().any(|r| *r == caller.role || (!custom.is_empty() && r == custom)) {
+            return false;
+        }
+    }
+    if !rule.modes.is_empty() {
+        let Some(caller) = req.caller else { return false };
+        if !rule.modes.iter().any(|m| m == caller.coordination_mode.as_str()) {
+            return false;
+        }
+    }
+    if !rule.targets.is_empty() {
+        match req.target {
+            Some(t) if rule.targets.contains(&t) => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn tool_matches(pattern: &str, tool: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => tool.starts_with(prefix),
+        None => pattern == tool,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{AgentStatus, CoordinationMode, ThreadMode};
+
+    fn agent(id: &str, role: &str, mode: CoordinationMode) -> Agent {
+        let now = chrono::Utc::now();
+        Agent {
+            id: id.to_string(),
+            project_hash: "policy-test".to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            role: role.to_string(),
+            capabilities: vec![],
+            status: AgentStatus::Active,
+            last_seen: now,
+            registered_at: now,
+            supervisor_id: None,
+            coordination_mode: mode,
+            team: None,
+            specializations: vec![],
+            thread_mode: ThreadMode::Normal,
+            current_activity: String::new(),
+            report_to: None,
+            custom_role: None,
+            workspace_path: String::new(),
+            full_permissions: false,
+            expected_model: None,
+        }
+    }
+
+    fn req<'a>(tool: &'a str, category: &'a str, caller: &'a Agent, target: Option<TargetScope>) -> PolicyRequest<'a> {
+        PolicyRequest { tool, category, caller: Some(caller), target }
+    }
+
+    #[test]
+    fn test_role_defaults_and_full_permissions() {
+        let cfg = ToolPolicyConfig::default();
+        let engine = PolicyEngine::new(&cfg);
+        let reviewer = agent("rev", "reviewer", CoordinationMode::Supervised);
+        let d = engine.evaluate(&req("ai_thread_purge", CATEGORY_DESTRUCTIVE, &reviewer, None));
+        assert_eq!(d.effect, PolicyEffect::Deny);
+        assert_eq!(d.rule, "role_default");
+
+        let mut programmer = agent("dev", "programmer", CoordinationMode::Supervised);
+        let d = engine.evaluate(&req("ai_thread_purge", CATEGORY_DESTRUCTIVE, &programmer, None));
+        assert_eq!(d.effect, PolicyEffect::Confirm);
+        programmer.full_permissions = true;
+        let d = engine.evaluate(&req("ai_thread_purge", CATEGORY_DESTRUCTIVE, &programmer, None));
+        assert_eq!(d.effect, PolicyEffect::Allow, "full_permissions skips confirm");
+
+        let d = engine.evaluate(&req("ai_recall", CATEGORY_GENERAL, &reviewer, None));
+        assert_eq!(d.effect, PolicyEffect::Allow);
+    }
+
+    #[test]
+    fn test_hierarchy_rule_for_agent_admin() {
+        let cfg = ToolPolicyConfig::default();
+        let engine = PolicyEngine::new(&cfg);
+        let lead = agent("lead", "architect", CoordinationMode::Coordinator);
+        let dev = agent("dev", "programmer", CoordinationMode::Supervised);
+        let mut sub = agent("sub", "programmer", CoordinationMode::Supervised);
+        sub.supervisor_id = Some("lead".into());
+
+        assert_eq!(target_scope(&lead, Some(&sub), "sub"), TargetScope::Subordinate);
+        assert_eq!(target_scope(&dev, Some(&sub), "sub"), TargetScope::Other);
+        assert_eq!(target_scope(&dev, Some(&dev), "dev"), TargetScope::SelfAgent);
+
+        let d = engine.evaluate(&req("agent_configure", CATEGORY_AGENT_ADMIN, &dev, Some(TargetScope::Other)));
+        assert_eq!(d.effect, PolicyEffect::Deny);
+        let d = engine.evaluate(&req("agent_configure", CATEGORY_AGENT_ADMIN, &lead, Some(TargetScope::Other)));
+        assert_eq!(d.effect, PolicyEffect::Confirm);
+        let d = engine.evaluate(&req("agent_configure", CATEGORY_AGENT_ADMIN, &lead, Some(TargetScope::Subordinate)));
+        assert_eq!(d.effect, PolicyEffect::Allow);
+
+        // Unregistered caller: no hierarchy to check, denied
+        let unregistered = PolicyRequest { tool: "agent_cleanup", category: CATEGORY_AGENT_ADMIN, caller: None, target: None };
+        let d = engine.evaluate(&unregistered);
+        assert_eq!((d.effect, d.rule.as_str()), (PolicyEffect::Deny, "hierarchy"));
+    }
+
+    #[test]
+    fn test_configured_rules_first_match() {
+        let cfg = ToolPolicyConfig {
+            rules: vec![
+                PolicyRule {
+                    name: "no-wake".into(),
+                    effect: PolicyEffect::Deny,
+                    tools: vec!["beat_*".into()],
+                    modes: vec!["supervised".into()],
+                    ..Default::default()
+                },
+                PolicyRule {
+                    name: "reviewers-purge".into(),
+                    effect: PolicyEffect::Allow,
+                    categories: vec![CATEGORY_DESTRUCTIVE.into()],
+                    roles: vec!["reviewer".into()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let engine = PolicyEngine::new(&cfg);
+        let reviewer = agent("rev", "reviewer", CoordinationMode::Supervised);
+        let researcher = agent("res", "researcher", CoordinationMode::Autonomous);
+
+        assert_eq!(engine.evaluate(&req("beat_wake", CATEGORY_WAKE, &reviewer, None)).rule, "no-wake");
+        assert_eq!(engine.evaluate(&req("beat_wake", CATEGORY_WAKE, &researcher, None)).effect, PolicyEffect::Allow);
+        assert_eq!(
+            engine.evaluate(&req("ai_bridge_purge", CATEGORY_DESTRUCTIVE, &reviewer, None)).effect,
+            PolicyEffect::Allow,
+            "Configured rule overrides the role default"
+        );
+
+        let parsed: PolicyRule = serde_json::from_value(serde_json::json!({
+            "name": "x", "effect": "confirm", "targets": ["self", "other"]
+        }))
+        .unwrap();
+        assert_eq!(parsed.effect, PolicyEffect::Confirm);
+        assert_eq!(parsed.targets, vec![TargetScope::SelfAgent, TargetScope::Other]);
+    }
+}
@@ -15,6 +15,7 @@ pub mod conversation;
 pub mod tool_executor;
 
 use crate::mcp::tools::ToolContext;
+use ai_smartness::processing::ipc_auth;
 use ai_smartness::storage::database::{self, ConnectionRole};
 use ai_smartness::storage::migrations;
 use ai_smartness::storage::path_utils;
@@ -25,6 +26,10 @@ use std::io::{self, BufRead, Write};
 pub fn run(project_hash: &str, agent_id: &str) {
     ai_smartness::tracing_init::init_file_tracing(project_hash);
     tracing::info!(project = project_hash, agent = agent_id, "Runtime starting");
+    ipc_auth::set_client_scope(ipc_auth::Scope::Agent {
+        project_hash: project_hash.to_string(),
+        agent_id: agent_id.to_string(),
+    });
 
     // Resolve API key
     let api_key = match std::env::var("ANTHROPIC_API_KEY") {
@@ -57,6 +62,7 @@ pub fn run(project_hash: &str, agent_id: &str) {
         shared_conn: &shared_conn,
         project_hash,
         agent_id,
+        progress: crate::mcp::tools::ProgressSink::none(),
     };
 
     // Build system prompt from agent profile
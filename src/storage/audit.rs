@@ -0,0 +1,473 @@
+//! Audit log — who called which tool, with what, and what it touched.
+//!
+//! One row per MCP/runtime tool call (recorded by route_tool) plus one per
+//! bulk memory mutation with every id it touched (daemon retention, thread purge). Stored in the registry DB so a single query
+//! covers every agent of every project. Params are sanitised before storage:
+//! secret-looking keys are redacted and long strings truncated. The same key
+//! detection redacts free text (`redact_secrets`) for other stores.
+
+use crate::config::AuditConfig;
+use crate::{time_utils, AiError, AiResult};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+pub const OUTCOME_OK: &str = "ok";
+pub const OUTCOME_ERROR: &str = "error";
+/// Refused by the tool policy.
+pub const OUTCOME_DENIED: &str = "denied";
+/// Two-phase tool: preview returned, nothing executed yet.
+pub const OUTCOME_PREVIEW: &str = "preview";
+
+/// Strings longer than this are truncated in stored params.
+const MAX_PARAM_CHARS: usize = 256;
+/// Max affected ids kept per entry.
+const MAX_AFFECTED_IDS: usize = 500;
+
+const REDACTED: &str = "[redacted]";
+
+/// Words that mark a param key as a credential (`access_token`, `apiKey`,
+/// `X-Api-Key`). Matched on whole words, so `max_tokens` or `tokenizer` are kept.
+const SECRET_WORDS: &[&str] = &[
+    "token", "secret", "password", "passwd", "apikey", "authorization", "credential", "credentials", "bearer",
+];
+
+/// Keys (in params or results) holding ids of touched objects.
+const ID_KEYS: &[&str] = &[
+    "thread_id", "thread_ids", "bridge_id", "bridge_ids", "keep_id", "replaced_id",
+    "parent_id", "message_id", "task_id", "remove_agent", "new_thread_ids", "purged_sample",
+    "affected_ids",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(default)]
+    pub id: i64,
+    pub timestamp: String,
+    pub project_hash: String,
+    pub agent_id: String,
+    pub tool: String,
+    /// Sanitised arguments.
+    pub params: serde_json::Value,
+    /// "ok", "error" or "denied".
+    pub outcome: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub affected_ids: Vec<String>,
+}
+
+impl AuditEntry {
+    /// New entry stamped now (params are sanitised here).
+    pub fn new(project_hash: &str, agent_id: &str, tool: &str, params: &serde_json::Value) -> Self {
+        Self {
+            id: 0,
+            timestamp: time_utils::to_sqlite(&time_utils::now()),
+            project_hash: project_hash.to_string(),
+            agent_id: agent_id.to_string(),
+            tool: tool.to_string(),
+            params: sanitize_params(params),
+            outcome: OUTCOME_OK.to_string(),
+            error: None,
+            duration_ms: 0,
+            affected_ids: Vec::new(),
+        }
+    }
+}
+
+/// Query filters — every set field must match.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub project_hash: Option<String>,
+    pub agent_id: Option<String>,
+    /// Exact tool name, or prefix with a trailing `*` (`"ai_thread_*"`).
+    pub tool: Option<String>,
+    pub outcome: Option<String>,
+    /// RFC 3339 lower bound (inclusive).
+    pub since: Option<String>,
+    /// RFC 3339 upper bound (exclusive).
+    pub until: Option<String>,
+    /// Only entries that touched this id.
+    pub affected_id: Option<String>,
+    /// Max rows (newest first). 0 = default (100).
+    pub limit: usize,
+}
+
+pub struct AuditLog;
+
+impl AuditLog {
+    /// Insert an entry. Affected ids beyond `MAX_AFFECTED_IDS` are dropped.
+    pub fn record(conn: &Connection, entry: &AuditEntry) -> AiResult<i64> {
+        let ids = &entry.affected_ids[..entry.affected_ids.len().min(MAX_AFFECTED_IDS)];
+        conn.execute(
+            "INSERT INTO audit_log (timestamp, project_hash, agent_id, tool, params, outcome, error, duration_ms, affected_ids)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                entry.timestamp,
+                entry.project_hash,
+                entry.agent_id,
+                entry.tool,
+                entry.params.to_string(),
+                entry.outcome,
+                entry.error,
+                entry.duration_ms as i64,
+                serde_json::to_string(ids)?,
+            ],
+        )
+        .map_err(|e| AiError::Storage(format!("Audit insert failed: {}", e)))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Entries matching `filter`, newest first.
+    pub fn query(conn: &Connection, filter: &AuditFilter) -> AiResult<Vec<AuditEntry>> {
+        let (tool_exact, tool_prefix) = match filter.tool.as_deref() {
+            Some(t) => match t.strip_suffix('*') {
+                Some(prefix) => (None, Some(format!("{}%", prefix.replace('%', "\\%").replace('_', "\\_")))),
+                None => (Some(t.to_string()), None),
+            },
+            None => (None, None),
+        };
+        let affected = filter.affected_id.as_ref().map(|id| format!("\"{}\"", id));
+        let limit = if filter.limit == 0 { 100 } else { filter.limit };
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, timestamp, project_hash, agent_id, tool, params, outcome, error, duration_ms, affected_ids
+                 FROM audit_log
+                 WHERE (?1 IS NULL OR project_hash = ?1)
+                   AND (?2 IS NULL OR agent_id = ?2)
+                   AND (?3 IS NULL OR tool = ?3)
+                   AND (?4 IS NULL OR tool LIKE ?4 ESCAPE '\\')
+                   AND (?5 IS NULL OR outcome = ?5)
+                   AND (?6 IS NULL OR timestamp >= ?6)
+                   AND (?7 IS NULL OR timestamp < ?7)
+                   AND (?8 IS NULL OR instr(affected_ids, ?8) > 0)
+                 ORDER BY timestamp DESC, id DESC
+                 LIMIT ?9",
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    filter.project_hash,
+                    filter.agent_id,
+                    tool_exact,
+                    tool_prefix,
+                    filter.outcome,
+                    filter.since,
+                    filter.until,
+                    affected,
+                    limit as i64,
+                ],
+                Self::from_row,
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    pub fn count(conn: &Connection) -> AiResult<usize> {
+        conn.query_row("SELECT COUNT(*) FROM audit_log", [], |r| r.get::<_, i64>(0))
+            .map(|n| n as usize)
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    /// Apply retention: drop entries older than `retention_days`, then the
+    /// oldest beyond `max_entries`. Returns the number of rows removed.
+    pub fn prune(conn: &Connection, cfg: &AuditConfig) -> AiResult<usize> {
+        let mut removed = 0;
+        if cfg.retention_days > 0 {
+            let cutoff = time_utils::now() - chrono::Duration::days(cfg.retention_days as i64);
+            removed += conn
+                .execute(
+                    "DELETE FROM audit_log WHERE timestamp < ?1",
+                    params![time_utils::to_sqlite(&cutoff)],
+                )
+                .map_err(|e| AiError::Storage(format!("Audit prune failed: {}", e)))?;
+        }
+        if cfg.max_entries > 0 {
+            removed += conn
+                .execute(
+                    "DELETE FROM audit_log WHERE id NOT IN (
+                        SELECT id FROM audit_log ORDER BY timestamp DESC, id DESC LIMIT ?1
+                     )",
+                    params![cfg.max_entries as i64],
+                )
+                .map_err(|e| AiError::Storage(format!("Audit prune failed: {}", e)))?;
+        }
+        Ok(removed)
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+        let params_json: String = row.get(5)?;
+        let ids_json: String = row.get(9)?;
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            project_hash: row.get(2)?,
+            agent_id: row.get(3)?,
+            tool: row.get(4)?,
+            params: serde_json::from_str(&params_json).unwrap_or(serde_json::Value::Null),
+            outcome: row.get(6)?,
+            error: row.get(7)?,
+            duration_ms: row.get::<_, i64>(8)? as u64,
+            affected_ids: serde_json::from_str(&ids_json).unwrap_or_default(),
+        })
+    }
+}
+
+/// Copy of `params` safe to store: secret-looking keys redacted, long strings truncated.
+pub fn sanitize_params(params: &serde_json::Value) -> serde_json::Value {
+    match params {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    if is_secret_key(k) {
+                        (k.clone(), serde_json::json!(REDACTED))
+                    } else {
+                        (k.clone(), sanitize_params(v))
+                    }
+                })
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(sanitize_params).collect())
+        }
+        serde_json::Value::String(s) if s.chars().count() > MAX_PARAM_CHARS => {
+            let head: String = s.chars().take(MAX_PARAM_CHARS).collect();
+            serde_json::json!(format!("{}… ({} chars)", head, s.chars().count()))
+        }
+        other => other.clone(),
+    }
+}
+
+/// `text` with the value of every secret-looking `key: value` / `key=value`
+/// pair (bare, quoted or in escaped JSON) and every `Bearer` token replaced
+/// by `[redacted]`.
+pub fn redact_secrets(text: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    while let Some(off) = text[pos..].find(is_word) {
+        let start = pos + off;
+        let end = text[start..].find(|c: char| !is_word(c)).map_or(text.len(), |n| start + n);
+        out.push_str(&text[pos..end]);
+        pos = end;
+        let word = &text[start..end];
+        let value = if is_secret_key(word) { secret_value(text, end) } else { None }
+            .or_else(|| if word.eq_ignore_ascii_case("bearer") { credential(text, end, 8) } else { None });
+        if let Some((from, to)) = value {
+            out.push_str(&text[pos..from]);
+            out.push_str(REDACTED);
+            pos = to;
+        }
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+/// Byte span of the value following a key that ends at `at`: optional closing
+/// quote, `:` or `=`, then a quoted value or a bare word (with the credential
+/// after an auth scheme such as `Bearer`).
+fn secret_value(text: &str, at: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let quote = |i: &mut usize| -> Option<char> {
+        if bytes.get(*i) == Some(&b'\\') && matches!(bytes.get(*i + 1), Some(b'"' | b'\'')) {
+            *i += 1;
+        }
+        let q = *bytes.get(*i).filter(|b| matches!(b, b'"' | b'\''))?;
+        *i += 1;
+        Some(q as char)
+    };
+    let spaces = |i: &mut usize| {
+        while matches!(bytes.get(*i), Some(b' ' | b'\t')) {
+            *i += 1;
+        }
+    };
+    let mut i = at;
+    quote(&mut i);
+    spaces(&mut i);
+    if !matches!(bytes.get(i), Some(b':' | b'=')) {
+        return None;
+    }
+    i += 1;
+    spaces(&mut i);
+    let end = match quote(&mut i) {
+        Some(q) => text[i..].find([q, '\\']).map_or(text.len(), |n| i + n),
+        None if matches!(bytes.get(i), Some(b'{' | b'[')) => return None,
+        None => {
+            let end = text[i..]
+                .find(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '&' | '"' | '\'' | '\\' | '}' | ']'))
+                .map_or(text.len(), |n| i + n);
+            let scheme = ["bearer", "basic", "token"].iter().any(|s| text[i..end].eq_ignore_ascii_case(s));
+            match scheme.then(|| credential(text, end, 1)).flatten() {
+                Some((_, to)) => to,
+                None => end,
+            }
+        }
+    };
+    (end > i).then_some((i, end))
+}
+
+/// Byte span of the credential after an auth scheme ending at `at` (spaces,
+/// then at least `min_len` non-space chars).
+fn credential(text: &str, at: usize, min_len: usize) -> Option<(usize, usize)> {
+    let rest = &text[at..];
+    let trimmed = rest.trim_start_matches([' ', '\t']);
+    if trimmed.len() == rest.len() {
+        return None;
+    }
+    let start = at + rest.len() - trimmed.len();
+    let len = trimmed
+        .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ',' | ';'))
+        .unwrap_or(trimmed.len());
+    (len >= min_len).then_some((start, start + len))
+}
+
+/// Whether a param key names a credential (see `SECRET_WORDS`, plus `api_key`).
+fn is_secret_key(key: &str) -> bool {
+    let words = key_words(key);
+    words.iter().any(|w| SECRET_WORDS.contains(&w.as_str()))
+        || words.windows(2).any(|w| w[0] == "api" && w[1] == "key")
+}
+
+/// Lowercase words of a key split on separators and camelCase (`xApiKey` → x, api, key).
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Ids of objects touched by a call, from well-known keys of its params and result.
+pub fn affected_ids(params: &serde_json::Value, result: Option<&serde_json::Value>) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    let mut push = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) if !s.is_empty() => ids.push(s.clone()),
+        serde_json::Value::Array(items) => {
+            ids.extend(items.iter().filter_map(|i| i.as_str()).filter(|s| !s.is_empty()).map(String::from))
+        }
+        _ => {}
+    };
+    for source in std::iter::once(params).chain(result) {
+        for key in ID_KEYS {
+            if let Some(v) = source.get(*key) {
+                push(v);
+            }
+        }
+        // Batch operations: [{thread_id, ...}, ...]
+        if let Some(ops) = source.get("operations").and_then(|v| v.as_array()) {
+            for op in ops {
+                if let Some(v) = op.get("thread_id") {
+                    push(v);
+                }
+            }
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+    ids.truncate(MAX_AFFECTED_IDS);
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::setup_registry_db;
+
+    fn entry(agent: &str, tool: &str, ts: &str, ids: &[&str]) -> AuditEntry {
+        let mut e = AuditEntry::new("ph", agent, tool, &serde_json::json!({}));
+        e.timestamp = ts.to_string();
+        e.affected_ids = ids.iter().map(|s| s.to_string()).collect();
+        e
+    }
+
+    #[test]
+    fn test_record_query_filters() {
+        let conn = setup_registry_db();
+        AuditLog::record(&conn, &entry("a1", "ai_thread_rm", "2026-01-01T10:00:00+00:00", &["t1"])).unwrap();
+        AuditLog::record(&conn, &entry("a1", "ai_thread_purge", "2026-01-02T10:00:00+00:00", &["t2", "t3"])).unwrap();
+        let mut failed = entry("a2", "ai_recall", "2026-01-03T10:00:00+00:00", &[]);
+        failed.outcome = OUTCOME_ERROR.into();
+        AuditLog::record(&conn, &failed).unwrap();
+
+        let all = AuditLog::query(&conn, &AuditFilter::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].agent_id, "a2", "Newest first");
+
+        let f = AuditFilter { tool: Some("ai_thread_*".into()), ..Default::default() };
+        assert_eq!(AuditLog::query(&conn, &f).unwrap().len(), 2);
+        let f = AuditFilter { affected_id: Some("t3".into()), ..Default::default() };
+        assert_eq!(AuditLog::query(&conn, &f).unwrap()[0].tool, "ai_thread_purge");
+        let f = AuditFilter { since: Some("2026-01-02T00:00:00+00:00".into()), agent_id: Some("a1".into()), ..Default::default() };
+        assert_eq!(AuditLog::query(&conn, &f).unwrap().len(), 1);
+        let f = AuditFilter { outcome: Some(OUTCOME_ERROR.into()), ..Default::default() };
+        assert_eq!(AuditLog::query(&conn, &f).unwrap()[0].tool, "ai_recall");
+    }
+
+    #[test]
+    fn test_prune_by_age_and_cap() {
+        let conn = setup_registry_db();
+        AuditLog::record(&conn, &entry("a", "old", "2000-01-01T00:00:00+00:00", &[])).unwrap();
+        for i in 0..5 {
+            AuditLog::record(&conn, &AuditEntry::new("ph", "a", &format!("t{}", i), &serde_json::json!({}))).unwrap();
+        }
+        let cfg = AuditConfig { retention_days: 30, max_entries: 3, ..Default::default() };
+        assert_eq!(AuditLog::prune(&conn, &cfg).unwrap(), 3);
+        assert_eq!(AuditLog::count(&conn).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_sanitize_and_affected_ids() {
+        let params = serde_json::json!({
+            "thread_ids": ["t1", "t2"],
+            "api_key": "sk-123",
+            "accessToken": "abc",
+            "X-Api-Key": "k",
+            "max_tokens": 512,
+            "tokenizer": "bpe",
+            "content": "x".repeat(1000),
+            "operations": [{"thread_id": "t3", "new_title": "n"}],
+        });
+        let clean = sanitize_params(&params);
+        assert_eq!(clean["api_key"], "[redacted]");
+        assert_eq!(clean["accessToken"], "[redacted]");
+        assert_eq!(clean["X-Api-Key"], "[redacted]");
+        assert_eq!(clean["max_tokens"], 512);
+        assert_eq!(clean["tokenizer"], "bpe");
+        assert!(clean["content"].as_str().unwrap().ends_with("(1000 chars)"));
+
+        let result = serde_json::json!({"purged_sample": ["t2", "t4"]});
+        assert_eq!(affected_ids(&params, Some(&result)), vec!["t1", "t2", "t3", "t4"]);
+    }
+
+    #[test]
+    fn test_redact_secrets_in_text() {
+        let cases = [
+            ("GITHUB_TOKEN=ghp_abc123 make", "GITHUB_TOKEN=[redacted] make"),
+            (r#"{"api_key": "sk-123", "max_tokens": 512}"#, r#"{"api_key": "[redacted]", "max_tokens": 512}"#),
+            (r#"{\"password\":\"hunter2\"}"#, r#"{\"password\":\"[redacted]\"}"#),
+            ("Authorization: Bearer eyJhbGciOi.x.y\nnext", "Authorization: [redacted]\nnext"),
+            ("curl -H 'X-Api-Key: k1' url", "curl -H 'X-Api-Key: [redacted]' url"),
+            ("sent bearer abcdef0123456789", "sent bearer [redacted]"),
+            ("the bearer of news; tokenizer=bpe", "the bearer of news; tokenizer=bpe"),
+        ];
+        for (raw, clean) in cases {
+            assert_eq!(redact_secrets(raw), clean);
+        }
+    }
+}
@@ -118,21 +118,64 @@ impl BackupConfig {
 impl BackupManager {
     /// Backup a SQLite DB via the .backup API.
     pub fn create_backup(conn: &Connection, dest: &Path) -> AiResult<()> {
+        Self::create_backup_with_progress(conn, dest, |_, _| true)
+    }
+
+    /// Backup with a per-step callback `(pages_done, pages_total) -> continue`.
+    /// The copy goes to a temp file next to `dest`, renamed over it only once
+    /// complete: a cancelled or failed backup leaves the previous one intact.
+    pub fn create_backup_with_progress(
+        conn: &Connection,
+        dest: &Path,
+        on_step: impl FnMut(u64, u64) -> bool,
+    ) -> AiResult<()> {
         if let Some(parent) = dest.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
+        let mut tmp_name = dest.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = dest.with_file_name(tmp_name);
+        let _ = std::fs::remove_file(&tmp);
+
+        let result = Self::copy_to(conn, &tmp, on_step)
+            .and_then(|()| std::fs::rename(&tmp, dest).map_err(AiError::from));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        result
+    }
+
+    /// Run the SQLite backup of `conn` into `dest` (Err if cancelled).
+    fn copy_to(
+        conn: &Connection,
+        dest: &Path,
+        mut on_step: impl FnMut(u64, u64) -> bool,
+    ) -> AiResult<()> {
+        use rusqlite::backup::StepResult;
+
         let mut dst = Connection::open(dest)
             .map_err(|e| AiError::Storage(format!("Failed to open backup dest: {}", e)))?;
 
         let backup = rusqlite::backup::Backup::new(conn, &mut dst)
             .map_err(|e| AiError::Storage(format!("Failed to create backup: {}", e)))?;
-
-        backup
-            .run_to_completion(100, std::time::Duration::from_millis(50), None)
-            .map_err(|e| AiError::Storage(format!("Backup failed: {}", e)))?;
-
-        Ok(())
+        loop {
+            let step = backup
+                .step(100)
+                .map_err(|e| AiError::Storage(format!("Backup failed: {}", e)))?;
+            let p = backup.progress();
+            let total = p.pagecount.max(0) as u64;
+            let done = total.saturating_sub(p.remaining.max(0) as u64);
+            match step {
+                StepResult::Done => return Ok(()),
+                StepResult::More => {
+                    if !on_step(done, total) {
+                        return Err(AiError::InvalidState("Backup cancelled".into()));
+                    }
+                }
+                _ => std::thread::sleep(std::time::Duration::from_millis(50)),
+            }
+        }
     }
 
     /// Restore a DB from a backup.
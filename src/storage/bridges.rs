@@ -3,6 +3,9 @@ use crate::bridge::{BridgeStatus, BridgeType, ThinkBridge};
 use crate::{AiError, AiResult};
 use rusqlite::{params, Connection, Row};
 
+/// `created_by` marker for bridges whose relation was classified by the LLM.
+pub const LLM_RELATION_CREATOR: &str = "gossip_llm";
+
 pub struct BridgeStorage;
 
 // ── Row mapping ──
@@ -152,6 +155,34 @@ impl BridgeStorage {
         Ok(bridges)
     }
 
+    /// IDs of the bridges touching any of the given threads (may repeat across chunks).
+    pub fn ids_for_threads(conn: &Connection, thread_ids: &[String]) -> AiResult<Vec<String>> {
+        if thread_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if thread_ids.len() > 500 {
+            return thread_ids.chunks(500).try_fold(Vec::new(), |mut acc, chunk| {
+                acc.extend(Self::ids_for_threads(conn, chunk)?);
+                Ok(acc)
+            });
+        }
+
+        let placeholders = (1..=thread_ids.len()).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", ");
+        let sql = format!(
+            "SELECT id FROM bridges WHERE source_id IN ({0}) OR target_id IN ({0})",
+            placeholders
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let ids = stmt
+            .query_map(rusqlite::params_from_iter(thread_ids), |row| row.get::<_, String>(0))
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(ids)
+    }
+
     pub fn list_active(conn: &Connection) -> AiResult<Vec<ThinkBridge>> {
         let mut stmt = conn
             .prepare("SELECT * FROM bridges WHERE status = 'active' ORDER BY weight DESC")
@@ -194,6 +225,45 @@ impl BridgeStorage {
         Ok(bridges)
     }
 
+    /// LLM-classified `contradicts` bridges that are still live (one row per shard;
+    /// callers dedupe by thread pair).
+    pub fn list_conflicts(conn: &Connection) -> AiResult<Vec<ThinkBridge>> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT * FROM bridges
+                 WHERE relation_type = 'contradicts' AND created_by = ?1
+                   AND status != 'invalid'
+                 ORDER BY confidence DESC",
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+
+        let bridges = stmt
+            .query_map(params![LLM_RELATION_CREATOR], bridge_from_row)
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(bridges)
+    }
+
+    /// All bridges between two threads, in either direction.
+    pub fn list_between(conn: &Connection, a: &str, b: &str) -> AiResult<Vec<ThinkBridge>> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT * FROM bridges
+                 WHERE (source_id = ?1 AND target_id = ?2) OR (source_id = ?2 AND target_id = ?1)",
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+
+        let bridges = stmt
+            .query_map(params![a, b], bridge_from_row)
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(bridges)
+    }
+
     pub fn update_weight(conn: &Connection, id: &str, weight: f64) -> AiResult<()> {
         conn.execute(
             "UPDATE bridges SET weight = ?1 WHERE id = ?2",
@@ -548,4 +618,21 @@ mod tests {
         // Empty input returns 0
         assert_eq!(BridgeStorage::delete_batch(&conn, &[]).unwrap(), 0);
     }
+
+    #[test]
+    fn test_list_conflicts_only_llm_contradicts() {
+        let conn = setup_agent_db();
+        let (s, t) = setup_two_threads(&conn);
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("llm").source_id(&s).target_id(&t)
+            .relation_type(BridgeType::Contradicts).created_by(LLM_RELATION_CREATOR).build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("split").source_id(&s).target_id(&t)
+            .relation_type(BridgeType::Contradicts).created_by("gossip_v2").build()).unwrap();
+        BridgeStorage::insert(&conn, &BridgeBuilder::new().id("ext").source_id(&t).target_id(&s)
+            .relation_type(BridgeType::Extends).created_by(LLM_RELATION_CREATOR).build()).unwrap();
+
+        let conflicts = BridgeStorage::list_conflicts(&conn).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].id, "llm");
+        assert_eq!(BridgeStorage::list_between(&conn, &t, &s).unwrap().len(), 3);
+    }
 }
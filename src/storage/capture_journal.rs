@@ -0,0 +1,284 @@
+//! Capture journal — durable backing store of the daemon capture queue.
+//!
+//! Every submitted capture is appended here before it is queued in memory and
+//! deleted only once a worker has finished with it (at-least-once). On daemon
+//! start, rows left behind by a crash or restart are replayed. Jobs that did not
+//! fit in the in-memory queue stay journal-only (`spilled`) and are loaded as
+//! room frees up, instead of being dropped.
+
+use crate::storage::dead_letters::DeadLetterStore;
+use crate::{time_utils, AiError, AiResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// A journaled capture job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    #[serde(default)]
+    pub id: i64,
+    pub project_hash: String,
+    pub agent_id: String,
+    pub source_type: String,
+    pub content: String,
+    pub file_path: Option<String>,
+    pub is_prompt: bool,
+    pub session_id: Option<String>,
+    pub enrich_thread_id: Option<String>,
+    pub enrichment_retry: u8,
+    /// Processing attempts started since the job was last queued (including
+    /// ones interrupted by a crash; reset by `requeue`).
+    pub attempts: u32,
+    pub enqueued_at: String,
+    pub last_attempt_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl JournalEntry {
+    /// New entry stamped now.
+    pub fn new(project_hash: &str, agent_id: &str, source_type: &str, content: &str) -> Self {
+        Self {
+            id: 0,
+            project_hash: project_hash.to_string(),
+            agent_id: agent_id.to_string(),
+            source_type: source_type.to_string(),
+            content: content.to_string(),
+            file_path: None,
+            is_prompt: false,
+            session_id: None,
+            enrich_thread_id: None,
+            enrichment_retry: 0,
+            attempts: 0,
+            enqueued_at: time_utils::to_sqlite(&time_utils::now()),
+            last_attempt_at: None,
+            last_error: None,
+        }
+    }
+}
+
+/// Journal backlog summary (queue_status).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JournalBacklog {
+    /// Jobs not finished yet (queued, in flight or spilled).
+    pub pending: usize,
+    /// Jobs waiting in the journal only.
+    pub spilled: usize,
+    pub oldest_enqueued_at: Option<String>,
+    /// Age of the oldest unfinished job, in seconds.
+    pub backlog_age_secs: Option<i64>,
+    /// Highest attempt count among unfinished jobs.
+    pub max_attempts: u32,
+}
+
+pub struct CaptureJournal;
+
+impl CaptureJournal {
+    /// Append a job. `spilled` = not held by the in-memory queue.
+    pub fn append(conn: &Connection, entry: &JournalEntry, spilled: bool) -> AiResult<i64> {
+        conn.execute(
+            "INSERT INTO capture_jobs (project_hash, agent_id, source_type, content, file_path, is_prompt,
+                session_id, enrich_thread_id, enrichment_retry, attempts, spilled, enqueued_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                entry.project_hash,
+                entry.agent_id,
+                entry.source_type,
+                entry.content,
+                entry.file_path,
+                entry.is_prompt,
+                entry.session_id,
+                entry.enrich_thread_id,
+                entry.enrichment_retry,
+                entry.attempts,
+                spilled,
+                entry.enqueued_at,
+            ],
+        )
+        .map_err(|e| AiError::Storage(format!("Journal append failed: {}", e)))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Record the start of a processing attempt. Returns the attempt number.
+    pub fn begin_attempt(conn: &Connection, id: i64) -> AiResult<u32> {
+        conn.execute(
+            "UPDATE capture_jobs SET attempts = attempts + 1, last_attempt_at = ?2 WHERE id = ?1",
+            params![id, time_utils::to_sqlite(&time_utils::now())],
+        )
+        .map_err(|e| AiError::Storage(format!("Journal attempt update failed: {}", e)))?;
+        conn.query_row("SELECT attempts FROM capture_jobs WHERE id = ?1", params![id], |r| r.get::<_, u32>(0))
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    /// Keep a job for another attempt (retry), held in memory or spilled.
+    /// Its attempt count starts over: retries are limited by the caller.
+    pub fn requeue(conn: &Connection, id: i64, enrichment_retry: u8, spilled: bool) -> AiResult<()> {
+        conn.execute(
+            "UPDATE capture_jobs SET enrichment_retry = ?2, spilled = ?3, attempts = 0 WHERE id = ?1",
+            params![id, enrichment_retry, spilled],
+        )
+        .map_err(|e| AiError::Storage(format!("Journal requeue failed: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn record_error(conn: &Connection, id: i64, error: &str) -> AiResult<()> {
+        conn.execute("UPDATE capture_jobs SET last_error = ?2 WHERE id = ?1", params![id, error])
+            .map_err(|e| AiError::Storage(format!("Journal error update failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// Job finished (processed, or given up): remove it.
+    pub fn ack(conn: &Connection, id: i64) -> AiResult<()> {
+        conn.execute("DELETE FROM capture_jobs WHERE id = ?1", params![id])
+            .map_err(|e| AiError::Storage(format!("Journal ack failed: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn get(conn: &Connection, id: i64) -> AiResult<Option<JournalEntry>> {
+        conn.query_row("SELECT * FROM capture_jobs WHERE id = ?1", params![id], Self::from_row)
+            .optional()
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    /// Startup: nothing is in memory yet, mark every row spilled. Returns the row count.
+    pub fn spill_all(conn: &Connection) -> AiResult<usize> {
+        conn.execute("UPDATE capture_jobs SET spilled = 1", [])
+            .map_err(|e| AiError::Storage(format!("Journal replay failed: {}", e)))
+    }
+
+    /// Oldest `limit` spilled jobs, marked as held in memory again (one
+    /// transaction). Rows that cannot be decoded are moved to the dead letters
+    /// instead of staying spilled forever.
+    pub fn take_spilled(conn: &Connection, limit: usize) -> AiResult<Vec<JournalEntry>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let rows: Vec<(i64, rusqlite::Result<JournalEntry>)> = {
+            let mut stmt = tx
+                .prepare("SELECT * FROM capture_jobs WHERE spilled = 1 ORDER BY id ASC LIMIT ?1")
+                .map_err(|e| AiError::Storage(e.to_string()))?;
+            let rows = stmt
+                .query_map(params![limit as i64], |r| Ok((r.get::<_, i64>("id")?, Self::from_row(r))))
+                .map_err(|e| AiError::Storage(e.to_string()))?;
+            rows.collect::<rusqlite::Result<_>>()
+                .map_err(|e| AiError::Storage(e.to_string()))?
+        };
+        let Some(last_id) = rows.last().map(|(id, _)| *id) else { return Ok(Vec::new()) };
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for (id, decoded) in rows {
+            match decoded {
+                Ok(entry) => entries.push(entry),
+                Err(e) => {
+                    tracing::warn!(journal_id = id, error = %e, "Capture journal: undecodable job moved to the dead letters");
+                    DeadLetterStore::bury_undecodable(&tx, id, &format!("Undecodable journal row: {}", e))?;
+                }
+            }
+        }
+        tx.execute(
+            "UPDATE capture_jobs SET spilled = 0 WHERE spilled = 1 AND id <= ?1",
+            params![last_id],
+        )
+        .map_err(|e| AiError::Storage(format!("Journal reload failed: {}", e)))?;
+        tx.commit().map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(entries)
+    }
+
+    pub fn backlog(conn: &Connection) -> AiResult<JournalBacklog> {
+        let (pending, spilled, oldest, max_attempts) = conn
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(spilled), 0), MIN(enqueued_at), COALESCE(MAX(attempts), 0)
+                 FROM capture_jobs",
+                [],
+                |r| Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?, r.get::<_, Option<String>>(2)?, r.get::<_, u32>(3)?)),
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let backlog_age_secs = oldest
+            .as_deref()
+            .and_then(|ts| time_utils::from_sqlite(ts).ok())
+            .map(|t| (time_utils::now() - t).num_seconds().max(0));
+        Ok(JournalBacklog {
+            pending: pending as usize,
+            spilled: spilled as usize,
+            oldest_enqueued_at: oldest,
+            backlog_age_secs,
+            max_attempts,
+        })
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<JournalEntry> {
+        Ok(JournalEntry {
+            id: row.get("id")?,
+            project_hash: row.get("project_hash")?,
+            agent_id: row.get("agent_id")?,
+            source_type: row.get("source_type")?,
+            content: row.get("content")?,
+            file_path: row.get("file_path")?,
+            is_prompt: row.get("is_prompt")?,
+            session_id: row.get("session_id")?,
+            enrich_thread_id: row.get("enrich_thread_id")?,
+            enrichment_retry: row.get("enrichment_retry")?,
+            attempts: row.get("attempts")?,
+            enqueued_at: row.get("enqueued_at")?,
+            last_attempt_at: row.get("last_attempt_at")?,
+            last_error: row.get("last_error")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::migrations;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::migrate_journal_db(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_replay_after_restart() {
+        let conn = setup();
+        let a = CaptureJournal::append(&conn, &JournalEntry::new("ph", "dev", "prompt", "first"), false).unwrap();
+        let b = CaptureJournal::append(&conn, &JournalEntry::new("ph", "dev", "Read", "second"), true).unwrap();
+        assert_eq!(CaptureJournal::begin_attempt(&conn, a).unwrap(), 1);
+        // Crash: `a` was in flight, `b` spilled. Nothing was acked.
+
+        assert_eq!(CaptureJournal::spill_all(&conn).unwrap(), 2);
+        let replay = CaptureJournal::take_spilled(&conn, 10).unwrap();
+        assert_eq!(replay.iter().map(|e| e.id).collect::<Vec<_>>(), vec![a, b], "FIFO order");
+        assert_eq!(replay[0].attempts, 1);
+        assert_eq!(replay[1].content, "second");
+        assert!(CaptureJournal::take_spilled(&conn, 10).unwrap().is_empty());
+
+        assert_eq!(CaptureJournal::begin_attempt(&conn, a).unwrap(), 2);
+        CaptureJournal::ack(&conn, a).unwrap();
+        let backlog = CaptureJournal::backlog(&conn).unwrap();
+        assert_eq!((backlog.pending, backlog.spilled), (1, 0));
+        assert!(backlog.backlog_age_secs.is_some());
+
+        CaptureJournal::begin_attempt(&conn, b).unwrap();
+        CaptureJournal::requeue(&conn, b, 1, true).unwrap();
+        CaptureJournal::record_error(&conn, b, "LLM unavailable").unwrap();
+        let entry = CaptureJournal::get(&conn, b).unwrap().unwrap();
+        assert_eq!(entry.enrichment_retry, 1);
+        assert_eq!(entry.attempts, 0, "A retry starts a new attempt count");
+        assert_eq!(entry.last_error.as_deref(), Some("LLM unavailable"));
+        assert_eq!(CaptureJournal::backlog(&conn).unwrap().spilled, 1);
+    }
+    #[test]
+    fn test_undecodable_spilled_row_is_dead_lettered() {
+        let conn = setup();
+        let good = CaptureJournal::append(&conn, &JournalEntry::new("ph", "dev", "Read", "ok"), true).unwrap();
+        let bad = CaptureJournal::append(&conn, &JournalEntry::new("ph", "dev", "Read", "bad"), true).unwrap();
+        conn.execute("UPDATE capture_jobs SET attempts = -1 WHERE id = ?1", params![bad]).unwrap();
+
+        let taken = CaptureJournal::take_spilled(&conn, 10).unwrap();
+        assert_eq!(taken.iter().map(|e| e.id).collect::<Vec<_>>(), vec![good]);
+        assert!(CaptureJournal::get(&conn, bad).unwrap().is_none(), "Bad row leaves the journal");
+        assert_eq!(DeadLetterStore::count(&conn).unwrap(), 1);
+        assert_eq!(CaptureJournal::backlog(&conn).unwrap().spilled, 0);
+    }
+}
@@ -8,6 +8,9 @@
 //!   - Build: O(N × C) where N=threads, C=avg concepts per thread
 //!   - Lookup: O(K) where K=query concepts count
 //!   - find_overlaps: O(C × T_avg²) where C=concepts, T_avg=threads per concept
+//!
+//! Keys are whatever the caller's key function returns (the agent's topic
+//! normalizer); the index itself does no normalisation.
 
 use std::collections::{HashMap, HashSet};
 use crate::{AiError, AiResult};
@@ -16,14 +19,17 @@ use rusqlite::Connection;
 /// Find threads sharing concepts via direct DB query (no in-memory index needed).
 /// Returns Vec<(thread_id, shared_concepts, total_concepts_count)> sorted by shared_count DESC.
 /// Used by thinkbridges — works at thread creation time without building ConceptIndex.
+/// Concepts are compared by `key`.
 pub fn find_threads_sharing_concepts_db(
     conn: &Connection,
     query_concepts: &[String],
     exclude_thread_id: Option<&str>,
+    key: &dyn Fn(&str) -> String,
 ) -> AiResult<Vec<(String, Vec<String>, usize)>> {
     if query_concepts.is_empty() {
         return Ok(vec![]);
     }
+    let query_keys: Vec<(String, &String)> = query_concepts.iter().map(|c| (key(c), c)).collect();
 
     // Accumulate: thread_id → (shared_concepts, total_concepts_count)
     let mut hits: HashMap<String, (Vec<String>, usize)> = HashMap::new();
@@ -55,13 +61,13 @@ pub fn find_threads_sharing_concepts_db(
         }
 
         let total = thread_concepts.len();
-        let thread_set: HashSet<String> = thread_concepts.into_iter()
-            .map(|c| c.to_lowercase())
+        let thread_set: HashSet<String> = thread_concepts.iter()
+            .map(|c| key(c))
             .collect();
 
-        let shared: Vec<String> = query_concepts.iter()
-            .filter(|c| thread_set.contains(&c.to_lowercase()))
-            .cloned()
+        let shared: Vec<String> = query_keys.iter()
+            .filter(|(k, _)| thread_set.contains(k))
+            .map(|(_, c)| (*c).clone())
             .collect();
 
         if !shared.is_empty() {
@@ -83,16 +89,17 @@ pub fn find_threads_sharing_concepts_db(
 /// Inverted concept index for O(1) candidate lookup.
 #[derive(Debug, Default)]
 pub struct ConceptIndex {
-    /// concept (lowercase) → set of thread_ids
+    /// concept key → set of thread_ids
     index: HashMap<String, HashSet<String>>,
     /// thread_id → set of concepts (reverse lookup)
     thread_concepts: HashMap<String, HashSet<String>>,
 }
 
 impl ConceptIndex {
-    /// Build the index from all active/suspended threads in the database.
+    /// Build the index from all active/suspended threads in the database,
+    /// keying stored concepts with `key`.
     /// Graceful: returns empty index if the threads table doesn't exist yet.
-    pub fn build_from_db(conn: &Connection) -> AiResult<Self> {
+    pub fn build_from_db(conn: &Connection, key: &dyn Fn(&str) -> String) -> AiResult<Self> {
         let mut idx = Self::default();
 
         // Exclude Command (Bash) threads — they pollute the concept graph with build noise
@@ -116,7 +123,8 @@ impl ConceptIndex {
             if let Ok((id, concepts_json)) = row {
                 let concepts: Vec<String> = serde_json::from_str(&concepts_json).unwrap_or_default();
                 if !concepts.is_empty() {
-                    idx.insert(&id, &concepts);
+                    let keys: Vec<String> = concepts.iter().map(|c| key(c)).collect();
+                    idx.insert(&id, &keys);
                 }
             }
         }
@@ -126,13 +134,14 @@ impl ConceptIndex {
 
     /// Extract concepts from text that match existing indexed concepts.
     /// Used by Engram V9 to convert user message → query concepts.
-    /// Only returns words/phrases that exist in the index (known concepts).
-    pub fn extract_matching_concepts(&self, text: &str) -> Vec<String> {
+    /// Only returns words/phrases that exist in the index (known concepts);
+    /// text words are keyed with `key` before matching.
+    pub fn extract_matching_concepts(&self, text: &str, key: &dyn Fn(&str) -> String) -> Vec<String> {
         let text_lower = text.to_lowercase();
         let words: HashSet<String> = text_lower
             .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
             .filter(|w| w.len() >= 3)
-            .map(String::from)
+            .map(key)
             .collect();
 
         let mut matched = Vec::new();
@@ -150,12 +159,11 @@ impl ConceptIndex {
         matched
     }
 
-    /// Lookup candidate thread_ids that share at least one concept with the query.
+    /// Lookup candidate thread_ids that share at least one concept key with the query.
     pub fn lookup(&self, query_concepts: &[String]) -> HashSet<String> {
         let mut candidates = HashSet::new();
         for concept in query_concepts {
-            let key = concept.to_lowercase();
-            if let Some(thread_ids) = self.index.get(&key) {
+            if let Some(thread_ids) = self.index.get(concept) {
                 candidates.extend(thread_ids.iter().cloned());
             }
         }
@@ -213,13 +221,22 @@ impl ConceptIndex {
         (count, ratio, shared)
     }
 
-    /// Add a thread to the index.
+    /// Canonical concepts of one thread.
+    pub fn concepts_of(&self, thread_id: &str) -> Option<&HashSet<String>> {
+        self.thread_concepts.get(thread_id)
+    }
+
+    /// All indexed threads with their concept keys.
+    pub fn threads(&self) -> impl Iterator<Item = (&String, &HashSet<String>)> {
+        self.thread_concepts.iter()
+    }
+
+    /// Add a thread to the index (concepts already keyed).
     pub fn insert(&mut self, thread_id: &str, concepts: &[String]) {
         let mut concept_set = HashSet::new();
         for concept in concepts {
-            let key = concept.to_lowercase();
-            self.index.entry(key.clone()).or_default().insert(thread_id.to_string());
-            concept_set.insert(key);
+            self.index.entry(concept.clone()).or_default().insert(thread_id.to_string());
+            concept_set.insert(concept.clone());
         }
         self.thread_concepts.insert(thread_id.to_string(), concept_set);
     }
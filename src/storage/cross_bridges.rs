@@ -0,0 +1,259 @@
+//! Cross-project bridges — links between threads of different projects.
+//!
+//! Discovered by the cross-project gossip pass (DaemonConfig::gossip_cross_project)
+//! and stored in the registry DB, since no single agent DB holds both ends.
+//! Each endpoint carries its project hash, agent id and thread title, so recall
+//! can name the related memory without opening the other agent's DB.
+
+use crate::{id_gen, time_utils, AiError, AiResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// One side of a cross-project bridge.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CrossEndpoint {
+    pub project_hash: String,
+    pub agent_id: String,
+    pub thread_id: String,
+    pub title: String,
+}
+
+impl CrossEndpoint {
+    fn same_thread(&self, project_hash: &str, agent_id: &str, thread_id: &str) -> bool {
+        self.project_hash == project_hash && self.agent_id == agent_id && self.thread_id == thread_id
+    }
+
+    fn key(&self) -> (&str, &str, &str) {
+        (&self.project_hash, &self.agent_id, &self.thread_id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossBridge {
+    pub id: String,
+    pub source: CrossEndpoint,
+    pub target: CrossEndpoint,
+    pub weight: f64,
+    /// Cosine similarity of the two thread embeddings, when both had one.
+    pub embedding_similarity: Option<f64>,
+    pub shared_concepts: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl CrossBridge {
+    /// New bridge stamped now, endpoints put in canonical order.
+    pub fn new(a: CrossEndpoint, b: CrossEndpoint, weight: f64, shared_concepts: Vec<String>) -> Self {
+        let (source, target) = if a.key() <= b.key() { (a, b) } else { (b, a) };
+        let now = time_utils::to_sqlite(&time_utils::now());
+        Self {
+            id: id_gen::bridge_id(),
+            source,
+            target,
+            weight,
+            embedding_similarity: None,
+            shared_concepts,
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    /// The endpoint opposite to the given thread (None if it is not an endpoint).
+    pub fn other_side(&self, project_hash: &str, agent_id: &str, thread_id: &str) -> Option<&CrossEndpoint> {
+        if self.source.same_thread(project_hash, agent_id, thread_id) {
+            Some(&self.target)
+        } else if self.target.same_thread(project_hash, agent_id, thread_id) {
+            Some(&self.source)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct CrossBridgeStorage;
+
+impl CrossBridgeStorage {
+    /// Insert, or refresh weight/titles/concepts of the existing bridge
+    /// between the same two threads. Returns true if a new row was created.
+    pub fn upsert(conn: &Connection, bridge: &CrossBridge) -> AiResult<bool> {
+        let (s, t) = (&bridge.source, &bridge.target);
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT id FROM cross_bridges
+                 WHERE source_project = ?1 AND source_agent = ?2 AND source_thread = ?3
+                   AND target_project = ?4 AND target_agent = ?5 AND target_thread = ?6",
+                params![s.project_hash, s.agent_id, s.thread_id, t.project_hash, t.agent_id, t.thread_id],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let concepts = serde_json::to_string(&bridge.shared_concepts)?;
+
+        match existing {
+            Some(id) => {
+                conn.execute(
+                    "UPDATE cross_bridges SET source_title = ?2, target_title = ?3, weight = ?4,
+                        embedding_similarity = ?5, shared_concepts = ?6, updated_at = ?7
+                     WHERE id = ?1",
+                    params![id, s.title, t.title, bridge.weight, bridge.embedding_similarity, concepts, bridge.updated_at],
+                )
+                .map_err(|e| AiError::Storage(format!("Cross bridge update failed: {}", e)))?;
+                Ok(false)
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO cross_bridges (id, source_project, source_agent, source_thread, source_title,
+                        target_project, target_agent, target_thread, target_title,
+                        weight, embedding_similarity, shared_concepts, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                    params![
+                        bridge.id,
+                        s.project_hash, s.agent_id, s.thread_id, s.title,
+                        t.project_hash, t.agent_id, t.thread_id, t.title,
+                        bridge.weight,
+                        bridge.embedding_similarity,
+                        concepts,
+                        bridge.created_at,
+                        bridge.updated_at,
+                    ],
+                )
+                .map_err(|e| AiError::Storage(format!("Cross bridge insert failed: {}", e)))?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Bridges touching one thread, strongest first.
+    pub fn list_for_thread(
+        conn: &Connection,
+        project_hash: &str,
+        agent_id: &str,
+        thread_id: &str,
+    ) -> AiResult<Vec<CrossBridge>> {
+        Self::query(
+            conn,
+            "SELECT * FROM cross_bridges
+             WHERE (source_project = ?1 AND source_agent = ?2 AND source_thread = ?3)
+                OR (target_project = ?1 AND target_agent = ?2 AND target_thread = ?3)
+             ORDER BY weight DESC",
+            params![project_hash, agent_id, thread_id],
+        )
+    }
+
+    /// Bridges touching any thread of one agent of one project.
+    pub fn list_for_agent(conn: &Connection, project_hash: &str, agent_id: &str) -> AiResult<Vec<CrossBridge>> {
+        Self::query(
+            conn,
+            "SELECT * FROM cross_bridges
+             WHERE (source_project = ?1 AND source_agent = ?2)
+                OR (target_project = ?1 AND target_agent = ?2)
+             ORDER BY weight DESC",
+            params![project_hash, agent_id],
+        )
+    }
+
+    pub fn delete(conn: &Connection, id: &str) -> AiResult<()> {
+        conn.execute("DELETE FROM cross_bridges WHERE id = ?1", params![id])
+            .map_err(|e| AiError::Storage(format!("Cross bridge delete failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// Drop every bridge with an endpoint in a removed project.
+    pub fn delete_for_project(conn: &Connection, project_hash: &str) -> AiResult<usize> {
+        conn.execute(
+            "DELETE FROM cross_bridges WHERE source_project = ?1 OR target_project = ?1",
+            params![project_hash],
+        )
+        .map_err(|e| AiError::Storage(format!("Cross bridge delete failed: {}", e)))
+    }
+
+    pub fn count(conn: &Connection) -> AiResult<usize> {
+        conn.query_row("SELECT COUNT(*) FROM cross_bridges", [], |r| r.get::<_, i64>(0))
+            .map(|n| n as usize)
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    /// Display name of a project (name, else path, else the hash itself).
+    pub fn project_label(conn: &Connection, project_hash: &str) -> String {
+        conn.query_row(
+            "SELECT COALESCE(NULLIF(name, ''), path) FROM projects WHERE hash = ?1",
+            params![project_hash],
+            |r| r.get::<_, String>(0),
+        )
+        .unwrap_or_else(|_| project_hash.to_string())
+    }
+
+    fn query(conn: &Connection, sql: &str, args: impl rusqlite::Params) -> AiResult<Vec<CrossBridge>> {
+        let mut stmt = conn.prepare(sql).map_err(|e| AiError::Storage(e.to_string()))?;
+        let rows = stmt
+            .query_map(args, Self::from_row)
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<CrossBridge> {
+        let concepts_json: String = row.get("shared_concepts")?;
+        Ok(CrossBridge {
+            id: row.get("id")?,
+            source: CrossEndpoint {
+                project_hash: row.get("source_project")?,
+                agent_id: row.get("source_agent")?,
+                thread_id: row.get("source_thread")?,
+                title: row.get("source_title")?,
+            },
+            target: CrossEndpoint {
+                project_hash: row.get("target_project")?,
+                agent_id: row.get("target_agent")?,
+                thread_id: row.get("target_thread")?,
+                title: row.get("target_title")?,
+            },
+            weight: row.get("weight")?,
+            embedding_similarity: row.get("embedding_similarity")?,
+            shared_concepts: serde_json::from_str(&concepts_json).unwrap_or_default(),
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::setup_registry_db;
+
+    fn endpoint(project: &str, thread: &str) -> CrossEndpoint {
+        CrossEndpoint {
+            project_hash: project.into(),
+            agent_id: "dev".into(),
+            thread_id: thread.into(),
+            title: format!("Thread {}", thread),
+        }
+    }
+
+    #[test]
+    fn test_upsert_is_order_independent() {
+        let conn = setup_registry_db();
+        let b = CrossBridge::new(endpoint("p2", "t2"), endpoint("p1", "t1"), 0.4, vec!["sqlite".into()]);
+        assert_eq!(b.source.project_hash, "p1", "Endpoints are stored in canonical order");
+        assert!(CrossBridgeStorage::upsert(&conn, &b).unwrap());
+
+        let again = CrossBridge::new(endpoint("p1", "t1"), endpoint("p2", "t2"), 0.7, vec!["sqlite".into(), "wal".into()]);
+        assert!(!CrossBridgeStorage::upsert(&conn, &again).unwrap());
+        assert_eq!(CrossBridgeStorage::count(&conn).unwrap(), 1);
+
+        let found = CrossBridgeStorage::list_for_thread(&conn, "p2", "dev", "t2").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].weight, 0.7);
+        assert_eq!(found[0].shared_concepts.len(), 2);
+        let other = found[0].other_side("p2", "dev", "t2").unwrap();
+        assert_eq!((other.project_hash.as_str(), other.thread_id.as_str()), ("p1", "t1"));
+        assert!(found[0].other_side("p3", "dev", "t2").is_none());
+
+        assert_eq!(CrossBridgeStorage::list_for_agent(&conn, "p1", "dev").unwrap().len(), 1);
+        assert!(CrossBridgeStorage::list_for_agent(&conn, "p1", "other").unwrap().is_empty());
+        assert_eq!(CrossBridgeStorage::project_label(&conn, "p9"), "p9");
+
+        assert_eq!(CrossBridgeStorage::delete_for_project(&conn, "p2").unwrap(), 1);
+        assert_eq!(CrossBridgeStorage::count(&conn).unwrap(), 0);
+    }
+}
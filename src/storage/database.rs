@@ -1,7 +1,12 @@
 use crate::constants::SQLITE_BUSY_TIMEOUT_MS;
+use crate::metrics;
 use crate::{AiError, AiResult};
 use rusqlite::Connection;
 
+/// Attentes successives (ms) sur une base verrouillee — meme echelle que le
+/// handler par defaut de SQLite, dans la limite de SQLITE_BUSY_TIMEOUT_MS.
+const BUSY_DELAYS_MS: [u32; 12] = [1, 2, 5, 10, 15, 20, 25, 25, 25, 50, 50, 100];
+
 /// Configuration role: differencie les pragmas selon le binaire appelant
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionRole {
@@ -24,6 +29,8 @@ pub fn open_connection(path: &std::path::Path, role: ConnectionRole) -> AiResult
     tracing::debug!(path = %path.display(), role = ?role, "Database connection opened");
 
     configure_common(&conn)?;
+    conn.busy_handler(Some(busy_wait))
+        .map_err(|e| AiError::Storage(format!("Failed to install busy handler: {}", e)))?;
 
     match role {
         ConnectionRole::Daemon => configure_daemon(&conn)?,
@@ -37,7 +44,8 @@ pub fn open_connection(path: &std::path::Path, role: ConnectionRole) -> AiResult
 
 /// Pragmas communs a toutes les connexions:
 /// - journal_mode = WAL
-/// - busy_timeout = SQLITE_BUSY_TIMEOUT_MS (constants.rs)
+/// - busy_timeout = SQLITE_BUSY_TIMEOUT_MS (constants.rs), puis remplace par
+///   `busy_wait` (meme budget, attentes et abandons comptes dans les metriques)
 /// - synchronous = NORMAL
 /// - cache_size = -2000 (2 MB)
 /// - foreign_keys = ON
@@ -56,6 +64,37 @@ fn configure_common(conn: &Connection) -> AiResult<()> {
     Ok(())
 }
 
+/// Handler SQLITE_BUSY: attend selon BUSY_DELAYS_MS tant que le budget
+/// SQLITE_BUSY_TIMEOUT_MS n'est pas epuise. `count` = appels precedents
+/// pour la meme instruction.
+fn busy_wait(count: i32) -> bool {
+    let count = count.max(0) as usize;
+    let delay = busy_delay(count);
+    if delay == 0 {
+        metrics::SQLITE_BUSY_ERRORS.inc(&[]);
+        return false;
+    }
+    if count == 0 {
+        metrics::SQLITE_BUSY_WAITS.inc(&[]);
+    }
+    std::thread::sleep(std::time::Duration::from_millis(delay as u64));
+    true
+}
+
+/// Attente (ms) avant la tentative `count`, 0 = budget epuise (abandon).
+/// La somme des attentes vaut exactement SQLITE_BUSY_TIMEOUT_MS.
+fn busy_delay(count: usize) -> u32 {
+    let last = BUSY_DELAYS_MS[BUSY_DELAYS_MS.len() - 1];
+    let (delay, waited) = match BUSY_DELAYS_MS.get(count) {
+        Some(&d) => (d, BUSY_DELAYS_MS[..count].iter().sum::<u32>()),
+        None => {
+            let table: u32 = BUSY_DELAYS_MS.iter().sum();
+            (last, table + last * (count - BUSY_DELAYS_MS.len()) as u32)
+        }
+    };
+    delay.min(SQLITE_BUSY_TIMEOUT_MS.saturating_sub(waited))
+}
+
 /// Pragmas specifiques au daemon
 fn configure_daemon(conn: &Connection) -> AiResult<()> {
     conn.execute_batch(
@@ -87,6 +126,61 @@ pub fn checkpoint_passive(conn: &Connection) -> AiResult<()> {
     Ok(())
 }
 
+/// Transaction that nests: implemented as a SAVEPOINT, so it opens a real
+/// transaction in autocommit mode and a nested one inside an outer transaction
+/// (ai_batch wraps several storage calls that each open their own).
+/// Dropped without `commit()` → rolled back.
+pub struct NestedTx<'c> {
+    conn: &'c Connection,
+    name: String,
+    done: bool,
+}
+
+static SAVEPOINT_SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+impl<'c> NestedTx<'c> {
+    pub fn begin(conn: &'c Connection) -> AiResult<Self> {
+        let seq = SAVEPOINT_SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let name = format!("sp_{}", seq);
+        conn.execute_batch(&format!("SAVEPOINT {}", name))
+            .map_err(|e| AiError::Storage(format!("Begin transaction failed: {}", e)))?;
+        Ok(Self { conn, name, done: false })
+    }
+
+    pub fn commit(mut self) -> AiResult<()> {
+        self.done = true;
+        self.conn.execute_batch(&format!("RELEASE {}", self.name))
+            .map_err(|e| AiError::Storage(format!("Commit failed: {}", e)))
+    }
+
+    pub fn rollback(mut self) -> AiResult<()> {
+        self.done = true;
+        self.rollback_inner()
+    }
+
+    fn rollback_inner(&self) -> AiResult<()> {
+        self.conn.execute_batch(&format!("ROLLBACK TO {0}; RELEASE {0}", self.name))
+            .map_err(|e| AiError::Storage(format!("Rollback failed: {}", e)))
+    }
+}
+
+impl std::ops::Deref for NestedTx<'_> {
+    type Target = Connection;
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for NestedTx<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            if let Err(e) = self.rollback_inner() {
+                tracing::warn!(error = %e, "Savepoint rollback on drop failed");
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,13 +200,25 @@ mod tests {
     }
 
     #[test]
-    fn test_busy_timeout_set_correctly() {
+    fn test_busy_wait_budget_matches_timeout() {
+        // Somme des attentes de busy_wait = SQLITE_BUSY_TIMEOUT_MS
+        let waited: u32 = (0..).map(busy_delay).take_while(|&d| d > 0).sum();
+        assert_eq!(waited, SQLITE_BUSY_TIMEOUT_MS);
+        assert_eq!(busy_delay(0), BUSY_DELAYS_MS[0]);
+    }
+
+    #[test]
+    fn test_busy_wait_gives_up_when_locked() {
         let (_dir, path) = tmp_db_path();
-        let conn = open_connection(&path, ConnectionRole::Hook).unwrap();
-        let timeout: u32 = conn
-            .query_row("PRAGMA busy_timeout", [], |row| row.get(0))
-            .unwrap();
-        assert_eq!(timeout, SQLITE_BUSY_TIMEOUT_MS);
+        let holder = open_connection(&path, ConnectionRole::Hook).unwrap();
+        let waiter = open_connection(&path, ConnectionRole::Hook).unwrap();
+        holder.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+        let errors_before = metrics::SQLITE_BUSY_ERRORS.value(&[]);
+        let result = waiter.execute_batch("BEGIN IMMEDIATE");
+
+        assert!(result.is_err(), "locked database should end in SQLITE_BUSY");
+        assert!(metrics::SQLITE_BUSY_ERRORS.value(&[]) > errors_before);
     }
 
     #[test]
@@ -135,4 +241,29 @@ mod tests {
         assert_eq!(ckpt, HOOK_WAL_AUTOCHECKPOINT);
         assert_ne!(ckpt, DAEMON_WAL_AUTOCHECKPOINT);
     }
+
+    #[test]
+    fn test_nested_tx_rollback_and_commit() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (v INTEGER)").unwrap();
+
+        let outer = NestedTx::begin(&conn).unwrap();
+        outer.execute("INSERT INTO t VALUES (1)", []).unwrap();
+        {
+            let inner = NestedTx::begin(&conn).unwrap();
+            inner.execute("INSERT INTO t VALUES (2)", []).unwrap();
+            inner.commit().unwrap();
+        }
+        {
+            let dropped = NestedTx::begin(&conn).unwrap();
+            dropped.execute("INSERT INTO t VALUES (3)", []).unwrap();
+        }
+        let n: i64 = conn.query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)).unwrap();
+        assert_eq!(n, 2, "Dropped inner savepoint rolled back");
+
+        outer.rollback().unwrap();
+        let n: i64 = conn.query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)).unwrap();
+        assert_eq!(n, 0, "Outer rollback undoes the committed inner one");
+        assert!(conn.is_autocommit());
+    }
 }
@@ -0,0 +1,412 @@
+//! Dead letters — captures that failed for good, kept for inspection and replay.
+//!
+//! Stored next to the capture journal (`capture_journal.db`). A failed journal
+//! job is moved here with its last error instead of being deleted, so an LLM or
+//! VRAM outage leaves a replayable trail instead of a silent hole in memory.
+//! `restore` moves letters back into the journal as spilled jobs: a running
+//! daemon picks them up right away, otherwise they replay on its next start.
+//! Letters outlive the journal job by weeks, so content and errors are stored
+//! with secrets redacted (`audit::redact_secrets`).
+
+use crate::storage::audit::redact_secrets;
+use crate::storage::capture_journal::{CaptureJournal, JournalEntry};
+use crate::{time_utils, AiError, AiResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Pipeline stage where a capture failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureStage {
+    /// Extraction of a tool/response capture from the pool.
+    Capture,
+    /// Direct processing of a user prompt.
+    Prompt,
+    /// LLM enrichment of an existing thread.
+    Enrichment,
+    /// Write of a tool capture to the agent pool.
+    PoolWrite,
+    /// Attempts kept being interrupted (daemon killed mid-job).
+    Interrupted,
+}
+
+impl FailureStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Capture => "capture",
+            Self::Prompt => "prompt",
+            Self::Enrichment => "enrichment",
+            Self::PoolWrite => "pool_write",
+            Self::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "capture" => Some(Self::Capture),
+            "prompt" => Some(Self::Prompt),
+            "enrichment" => Some(Self::Enrichment),
+            "pool_write" => Some(Self::PoolWrite),
+            "interrupted" => Some(Self::Interrupted),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    #[serde(default)]
+    pub id: i64,
+    pub project_hash: String,
+    pub agent_id: String,
+    pub source_type: String,
+    /// Capture content, secrets redacted.
+    pub content: String,
+    pub file_path: Option<String>,
+    pub is_prompt: bool,
+    pub session_id: Option<String>,
+    pub enrich_thread_id: Option<String>,
+    pub stage: FailureStage,
+    pub error: String,
+    pub attempts: u32,
+    pub enqueued_at: String,
+    pub failed_at: String,
+}
+
+impl DeadLetter {
+    /// Dead letter for a journal job, stamped now (secrets redacted).
+    pub fn from_journal(entry: &JournalEntry, stage: FailureStage, error: &str) -> Self {
+        Self {
+            id: 0,
+            project_hash: entry.project_hash.clone(),
+            agent_id: entry.agent_id.clone(),
+            source_type: entry.source_type.clone(),
+            content: redact_secrets(&entry.content),
+            file_path: entry.file_path.clone(),
+            is_prompt: entry.is_prompt,
+            session_id: entry.session_id.clone(),
+            enrich_thread_id: entry.enrich_thread_id.clone(),
+            stage,
+            error: redact_secrets(error),
+            attempts: entry.attempts,
+            enqueued_at: entry.enqueued_at.clone(),
+            failed_at: time_utils::to_sqlite(&time_utils::now()),
+        }
+    }
+
+    /// Fresh journal job for a replay (attempts and retries reset).
+    pub fn to_journal(&self) -> JournalEntry {
+        let mut entry = JournalEntry::new(&self.project_hash, &self.agent_id, &self.source_type, &self.content);
+        entry.file_path = self.file_path.clone();
+        entry.is_prompt = self.is_prompt;
+        entry.session_id = self.session_id.clone();
+        entry.enrich_thread_id = self.enrich_thread_id.clone();
+        entry
+    }
+}
+
+/// Selection of dead letters (all fields optional, AND-ed).
+#[derive(Debug, Clone, Default)]
+pub struct DeadLetterFilter {
+    pub ids: Option<Vec<i64>>,
+    pub project_hash: Option<String>,
+    pub agent_id: Option<String>,
+    pub stage: Option<FailureStage>,
+    /// Only letters that failed before this timestamp (sqlite format).
+    pub failed_before: Option<String>,
+}
+
+impl DeadLetterFilter {
+    /// SQL `WHERE` clause (empty without criteria) and its parameters.
+    fn where_clause(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut conds = Vec::new();
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(ref ids) = self.ids {
+            let first = values.len() + 1;
+            values.extend(ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+            let marks: Vec<String> = (first..=values.len()).map(|i| format!("?{}", i)).collect();
+            conds.push(format!("id IN ({})", marks.join(", ")));
+        }
+        if let Some(ref project_hash) = self.project_hash {
+            values.push(Box::new(project_hash.clone()));
+            conds.push(format!("project_hash = ?{}", values.len()));
+        }
+        if let Some(ref agent_id) = self.agent_id {
+            values.push(Box::new(agent_id.clone()));
+            conds.push(format!("agent_id = ?{}", values.len()));
+        }
+        if let Some(stage) = self.stage {
+            values.push(Box::new(stage.as_str()));
+            conds.push(format!("stage = ?{}", values.len()));
+        }
+        if let Some(ref failed_before) = self.failed_before {
+            values.push(Box::new(failed_before.clone()));
+            conds.push(format!("failed_at < ?{}", values.len()));
+        }
+        if conds.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", conds.join(" AND ")), values)
+        }
+    }
+}
+
+pub struct DeadLetterStore;
+
+impl DeadLetterStore {
+    /// Store a letter; content and error are redacted again, whoever built it.
+    pub fn record(conn: &Connection, letter: &DeadLetter) -> AiResult<i64> {
+        conn.execute(
+            "INSERT INTO dead_letters (project_hash, agent_id, source_type, content, file_path, is_prompt,
+                session_id, enrich_thread_id, stage, error, attempts, enqueued_at, failed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                letter.project_hash,
+                letter.agent_id,
+                letter.source_type,
+                redact_secrets(&letter.content),
+                letter.file_path,
+                letter.is_prompt,
+                letter.session_id,
+                letter.enrich_thread_id,
+                letter.stage.as_str(),
+                redact_secrets(&letter.error),
+                letter.attempts,
+                letter.enqueued_at,
+                letter.failed_at,
+            ],
+        )
+        .map_err(|e| AiError::Storage(format!("Dead letter insert failed: {}", e)))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Move a journal job to the dead letters. `error` is used when the job
+    /// has no recorded error. Returns the letter id (None if the job is gone).
+    pub fn bury(conn: &Connection, journal_id: i64, stage: FailureStage, error: &str) -> AiResult<Option<i64>> {
+        let Some(entry) = CaptureJournal::get(conn, journal_id)? else { return Ok(None) };
+
+        let letter = DeadLetter::from_journal(&entry, stage, entry.last_error.as_deref().unwrap_or(error));
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let id = Self::record(&tx, &letter)?;
+        tx.execute("DELETE FROM capture_jobs WHERE id = ?1", params![journal_id])
+            .map_err(|e| AiError::Storage(format!("Journal ack failed: {}", e)))?;
+        tx.commit().map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(Some(id))
+    }
+
+    /// Move a journal row that cannot be decoded to the dead letters, columns
+    /// copied as stored (negative attempt counts clamped to 0) except the
+    /// content, which is redacted.
+    pub fn bury_undecodable(conn: &Connection, journal_id: i64, error: &str) -> AiResult<()> {
+        let content: Option<String> = conn
+            .query_row("SELECT CAST(content AS TEXT) FROM capture_jobs WHERE id = ?1", params![journal_id], |r| r.get(0))
+            .optional()
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .flatten();
+        conn.execute(
+            "INSERT INTO dead_letters (project_hash, agent_id, source_type, content, file_path, is_prompt,
+                session_id, enrich_thread_id, stage, error, attempts, enqueued_at, failed_at)
+             SELECT project_hash, agent_id, source_type, ?5, file_path, is_prompt,
+                session_id, enrich_thread_id, ?2, ?3, MAX(attempts, 0), enqueued_at, ?4
+             FROM capture_jobs WHERE id = ?1",
+            params![
+                journal_id,
+                FailureStage::Capture.as_str(),
+                redact_secrets(error),
+                time_utils::to_sqlite(&time_utils::now()),
+                content.as_deref().map(redact_secrets),
+            ],
+        )
+        .map_err(|e| AiError::Storage(format!("Dead letter insert failed: {}", e)))?;
+        conn.execute("DELETE FROM capture_jobs WHERE id = ?1", params![journal_id])
+            .map_err(|e| AiError::Storage(format!("Journal ack failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// Matching letters, most recent failure first.
+    pub fn list(conn: &Connection, filter: &DeadLetterFilter, limit: usize) -> AiResult<Vec<DeadLetter>> {
+        let (clause, mut values) = filter.where_clause();
+        // LIMIT -1 = no limit
+        values.push(Box::new(i64::try_from(limit).unwrap_or(-1)));
+        let sql = format!("SELECT * FROM dead_letters{} ORDER BY id DESC LIMIT ?{}", clause, values.len());
+        let mut stmt = conn.prepare(&sql).map_err(|e| AiError::Storage(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), Self::from_row)
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    pub fn get(conn: &Connection, id: i64) -> AiResult<Option<DeadLetter>> {
+        conn.query_row("SELECT * FROM dead_letters WHERE id = ?1", params![id], Self::from_row)
+            .optional()
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    pub fn count(conn: &Connection) -> AiResult<usize> {
+        conn.query_row("SELECT COUNT(*) FROM dead_letters", [], |r| r.get::<_, i64>(0))
+            .map(|n| n as usize)
+            .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    /// Delete matching letters. Returns the number deleted.
+    pub fn purge(conn: &Connection, filter: &DeadLetterFilter) -> AiResult<usize> {
+        let (clause, values) = filter.where_clause();
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let deleted = tx
+            .execute(&format!("DELETE FROM dead_letters{}", clause), rusqlite::params_from_iter(values.iter()))
+            .map_err(|e| AiError::Storage(format!("Dead letter delete failed: {}", e)))?;
+        tx.commit().map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(deleted)
+    }
+
+    /// Retention: delete letters that failed more than `retention_days` ago
+    /// (0 = keep), then the oldest beyond `max_letters` (0 = unlimited).
+    /// Returns the number deleted.
+    pub fn prune(conn: &Connection, retention_days: u32, max_letters: usize) -> AiResult<usize> {
+        let mut removed = 0;
+        if retention_days > 0 {
+            let cutoff = time_utils::now() - chrono::Duration::days(retention_days as i64);
+            removed += conn
+                .execute(
+                    "DELETE FROM dead_letters WHERE failed_at < ?1",
+                    params![time_utils::to_sqlite(&cutoff)],
+                )
+                .map_err(|e| AiError::Storage(format!("Dead letter prune failed: {}", e)))?;
+        }
+        if max_letters > 0 {
+            removed += conn
+                .execute(
+                    "DELETE FROM dead_letters WHERE id NOT IN (
+                        SELECT id FROM dead_letters ORDER BY id DESC LIMIT ?1
+                     )",
+                    params![max_letters as i64],
+                )
+                .map_err(|e| AiError::Storage(format!("Dead letter prune failed: {}", e)))?;
+        }
+        Ok(removed)
+    }
+
+    /// Move matching letters back into the capture journal as spilled jobs
+    /// (oldest failure first). Returns the number of jobs restored.
+    pub fn restore(conn: &Connection, filter: &DeadLetterFilter) -> AiResult<usize> {
+        let mut letters = Self::list(conn, filter, usize::MAX)?;
+        letters.reverse();
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        for letter in &letters {
+            CaptureJournal::append(&tx, &letter.to_journal(), true)?;
+            tx.execute("DELETE FROM dead_letters WHERE id = ?1", params![letter.id])
+                .map_err(|e| AiError::Storage(format!("Dead letter delete failed: {}", e)))?;
+        }
+        tx.commit().map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(letters.len())
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<DeadLetter> {
+        let stage: String = row.get("stage")?;
+        Ok(DeadLetter {
+            id: row.get("id")?,
+            project_hash: row.get("project_hash")?,
+            agent_id: row.get("agent_id")?,
+            source_type: row.get("source_type")?,
+            content: row.get("content")?,
+            file_path: row.get("file_path")?,
+            is_prompt: row.get("is_prompt")?,
+            session_id: row.get("session_id")?,
+            enrich_thread_id: row.get("enrich_thread_id")?,
+            stage: FailureStage::parse(&stage).unwrap_or(FailureStage::Capture),
+            error: row.get("error")?,
+            attempts: row.get("attempts")?,
+            enqueued_at: row.get("enqueued_at")?,
+            failed_at: row.get("failed_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::migrations;
+
+    #[test]
+    fn test_bury_list_and_restore() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::migrate_journal_db(&conn).unwrap();
+
+        let mut prompt = JournalEntry::new("ph", "dev", "prompt", "lost prompt");
+        prompt.is_prompt = true;
+        let id = CaptureJournal::append(&conn, &prompt, false).unwrap();
+        CaptureJournal::begin_attempt(&conn, id).unwrap();
+        CaptureJournal::record_error(&conn, id, "Provider error: out of VRAM").unwrap();
+        let letter_id = DeadLetterStore::bury(&conn, id, FailureStage::Prompt, "unknown").unwrap().unwrap();
+        assert!(CaptureJournal::get(&conn, id).unwrap().is_none(), "Buried job leaves the journal");
+        assert!(DeadLetterStore::bury(&conn, id, FailureStage::Prompt, "unknown").unwrap().is_none());
+
+        let letter = DeadLetterStore::get(&conn, letter_id).unwrap().unwrap();
+        assert_eq!(letter.error, "Provider error: out of VRAM", "Last journal error is kept");
+        assert_eq!((letter.stage, letter.attempts), (FailureStage::Prompt, 1));
+
+        let other = DeadLetter::from_journal(&JournalEntry::new("ph", "ops", "Read", "file"), FailureStage::Capture, "parse");
+        DeadLetterStore::record(&conn, &other).unwrap();
+        let filter = DeadLetterFilter { agent_id: Some("ops".into()), ..Default::default() };
+        assert_eq!(DeadLetterStore::list(&conn, &filter, 10).unwrap().len(), 1);
+        assert_eq!(DeadLetterStore::list(&conn, &DeadLetterFilter::default(), 10).unwrap()[0].agent_id, "ops");
+
+        let filter = DeadLetterFilter { ids: Some(vec![letter_id]), ..Default::default() };
+        assert_eq!(DeadLetterStore::restore(&conn, &filter).unwrap(), 1);
+        let replay = CaptureJournal::take_spilled(&conn, 10).unwrap();
+        assert_eq!(replay.len(), 1);
+        assert_eq!((replay[0].content.as_str(), replay[0].attempts), ("lost prompt", 0));
+        assert!(replay[0].is_prompt);
+
+        assert_eq!(DeadLetterStore::purge(&conn, &DeadLetterFilter::default()).unwrap(), 1);
+        assert_eq!(DeadLetterStore::count(&conn).unwrap(), 0);
+
+        let mut old = DeadLetter::from_journal(&JournalEntry::new("ph", "dev", "Read", "old"), FailureStage::Capture, "parse");
+        old.failed_at = "2020-01-01T00:00:00+00:00".to_string();
+        DeadLetterStore::record(&conn, &old).unwrap();
+        for i in 0..3 {
+            let letter = DeadLetter::from_journal(&JournalEntry::new("ph", "dev", "Read", &i.to_string()), FailureStage::Capture, "parse");
+            DeadLetterStore::record(&conn, &letter).unwrap();
+        }
+        let filter = DeadLetterFilter { failed_before: Some("2021-01-01T00:00:00+00:00".into()), ..Default::default() };
+        assert_eq!(DeadLetterStore::list(&conn, &filter, 10).unwrap()[0].content, "old");
+        assert_eq!(DeadLetterStore::list(&conn, &DeadLetterFilter::default(), 2).unwrap().len(), 2);
+        assert_eq!(DeadLetterStore::prune(&conn, 30, 2).unwrap(), 2, "Expired letter, then the oldest over the cap");
+        let kept: Vec<String> = DeadLetterStore::list(&conn, &DeadLetterFilter::default(), 10)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.content)
+            .collect();
+        assert_eq!(kept, vec!["2", "1"]);
+    }
+
+    #[test]
+    fn test_letters_are_stored_redacted() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::migrate_journal_db(&conn).unwrap();
+
+        let payload = r#"{"command": "deploy", "env": {"API_TOKEN": "tok-s3cr3t"}} Authorization: Bearer abc.def.ghi"#;
+        let id = CaptureJournal::append(&conn, &JournalEntry::new("ph", "dev", "Bash", payload), false).unwrap();
+        CaptureJournal::record_error(&conn, id, "rejected password=hunter2").unwrap();
+        let letter_id = DeadLetterStore::bury(&conn, id, FailureStage::Capture, "unknown").unwrap().unwrap();
+
+        let letter = DeadLetterStore::get(&conn, letter_id).unwrap().unwrap();
+        for secret in ["tok-s3cr3t", "abc.def.ghi", "hunter2"] {
+            assert!(!letter.content.contains(secret) && !letter.error.contains(secret), "{} stored", secret);
+        }
+        assert!(letter.content.contains(r#""command": "deploy""#), "Rest of the payload kept");
+        assert!(letter.content.contains(r#""API_TOKEN": "[redacted]""#));
+
+        let mut raw = DeadLetter::from_journal(&JournalEntry::new("ph", "dev", "Bash", "x"), FailureStage::Capture, "e");
+        raw.content = "export SECRET=plain".into();
+        let raw_id = DeadLetterStore::record(&conn, &raw).unwrap();
+        assert_eq!(DeadLetterStore::get(&conn, raw_id).unwrap().unwrap().content, "export SECRET=[redacted]");
+    }
+}
@@ -2,7 +2,7 @@ use crate::{AiError, AiResult};
 use rusqlite::Connection;
 
 /// Schema version actuelle
-pub const CURRENT_SCHEMA_VERSION: u32 = 11;
+pub const CURRENT_SCHEMA_VERSION: u32 = 12;
 
 /// Retourne la version de schema actuelle (0 si table absente)
 pub fn get_schema_version(conn: &Connection) -> AiResult<u32> {
@@ -237,6 +237,23 @@ pub fn migrate_agent_db(conn: &Connection) -> AiResult<()> {
         set_schema_version(conn, 11)?;
     }
 
+    // V12: per-agent topic alias table (existing topics/concepts are left as written,
+    // canonical keys are computed when reading)
+    if version < 12 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS topic_aliases (
+                alias TEXT PRIMARY KEY,
+                canonical TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'suggested',
+                score REAL NOT NULL DEFAULT 0.0,
+                source TEXT NOT NULL DEFAULT 'manual',
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_topic_aliases_status ON topic_aliases(status);"
+        ).map_err(|e| AiError::Storage(format!("Agent DB V12 migration failed: {}", e)))?;
+        set_schema_version(conn, 12)?;
+    }
+
     Ok(())
 }
 
@@ -458,6 +475,49 @@ CREATE TABLE IF NOT EXISTS federation_links (
 /// V2 migration for registry DB — add thread_mode column to agents
 const REGISTRY_DB_V2: &str = "ALTER TABLE agents ADD COLUMN thread_mode TEXT NOT NULL DEFAULT 'normal';";
 
+/// V9 migration for registry DB — audit log
+const REGISTRY_DB_V9: &str = "
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL,
+    project_hash TEXT NOT NULL,
+    agent_id TEXT NOT NULL,
+    tool TEXT NOT NULL,
+    params TEXT NOT NULL DEFAULT '{}',
+    outcome TEXT NOT NULL,
+    error TEXT,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    affected_ids TEXT NOT NULL DEFAULT '[]'
+);
+CREATE INDEX IF NOT EXISTS idx_audit_project_time ON audit_log(project_hash, timestamp);
+CREATE INDEX IF NOT EXISTS idx_audit_agent ON audit_log(agent_id);
+CREATE INDEX IF NOT EXISTS idx_audit_tool ON audit_log(tool);
+";
+
+/// V10 migration for registry DB — cross-project bridges (global gossip store).
+/// Endpoints are stored in canonical order: (source_*) < (target_*).
+const REGISTRY_DB_V10: &str = "
+CREATE TABLE IF NOT EXISTS cross_bridges (
+    id TEXT PRIMARY KEY,
+    source_project TEXT NOT NULL,
+    source_agent TEXT NOT NULL,
+    source_thread TEXT NOT NULL,
+    source_title TEXT NOT NULL DEFAULT '',
+    target_project TEXT NOT NULL,
+    target_agent TEXT NOT NULL,
+    target_thread TEXT NOT NULL,
+    target_title TEXT NOT NULL DEFAULT '',
+    weight REAL NOT NULL,
+    embedding_similarity REAL,
+    shared_concepts TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (source_project, source_agent, source_thread, target_project, target_agent, target_thread)
+);
+CREATE INDEX IF NOT EXISTS idx_cross_source ON cross_bridges(source_project, source_agent, source_thread);
+CREATE INDEX IF NOT EXISTS idx_cross_target ON cross_bridges(target_project, target_agent, target_thread);
+";
+
 /// Verifie et applique les migrations pour registry.db
 pub fn migrate_registry_db(conn: &Connection) -> AiResult<()> {
     let version = get_schema_version(conn)?;
@@ -526,6 +586,90 @@ pub fn migrate_registry_db(conn: &Connection) -> AiResult<()> {
         set_schema_version(conn, 8)?;
     }
 
+    // V9: audit log of tool calls and memory mutations
+    if version < 9 {
+        conn.execute_batch(REGISTRY_DB_V9)
+            .map_err(|e| AiError::Storage(format!("Registry DB V9 migration failed: {}", e)))?;
+        set_schema_version(conn, 9)?;
+    }
+
+    // V10: cross-project bridges discovered by cross-project gossip
+    if version < 10 {
+        conn.execute_batch(REGISTRY_DB_V10)
+            .map_err(|e| AiError::Storage(format!("Registry DB V10 migration failed: {}", e)))?;
+        set_schema_version(conn, 10)?;
+    }
+
+    Ok(())
+}
+
+// ── Capture journal DB (daemon-owned) ──
+
+const JOURNAL_DB_V1: &str = "
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY,
+    applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS capture_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_hash TEXT NOT NULL,
+    agent_id TEXT NOT NULL,
+    source_type TEXT NOT NULL,
+    content TEXT NOT NULL,
+    file_path TEXT,
+    is_prompt BOOLEAN NOT NULL DEFAULT 0,
+    session_id TEXT,
+    enrich_thread_id TEXT,
+    enrichment_retry INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    spilled BOOLEAN NOT NULL DEFAULT 0,
+    enqueued_at TEXT NOT NULL,
+    last_attempt_at TEXT,
+    last_error TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_capture_jobs_spilled ON capture_jobs(spilled, id);
+CREATE INDEX IF NOT EXISTS idx_capture_jobs_agent ON capture_jobs(project_hash, agent_id);
+";
+
+/// V2 migration for journal DB — dead letters (captures that failed for good)
+const JOURNAL_DB_V2: &str = "
+CREATE TABLE IF NOT EXISTS dead_letters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_hash TEXT NOT NULL,
+    agent_id TEXT NOT NULL,
+    source_type TEXT NOT NULL,
+    content TEXT NOT NULL,
+    file_path TEXT,
+    is_prompt BOOLEAN NOT NULL DEFAULT 0,
+    session_id TEXT,
+    enrich_thread_id TEXT,
+    stage TEXT NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    enqueued_at TEXT NOT NULL,
+    failed_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_dead_letters_agent ON dead_letters(project_hash, agent_id);
+CREATE INDEX IF NOT EXISTS idx_dead_letters_failed_at ON dead_letters(failed_at);
+";
+
+/// Verifie et applique les migrations pour capture_journal.db
+pub fn migrate_journal_db(conn: &Connection) -> AiResult<()> {
+    let version = get_schema_version(conn)?;
+
+    if version < 1 {
+        conn.execute_batch(JOURNAL_DB_V1)
+            .map_err(|e| AiError::Storage(format!("Journal DB V1 migration failed: {}", e)))?;
+        set_schema_version(conn, 1)?;
+    }
+
+    if version < 2 {
+        conn.execute_batch(JOURNAL_DB_V2)
+            .map_err(|e| AiError::Storage(format!("Journal DB V2 migration failed: {}", e)))?;
+        set_schema_version(conn, 2)?;
+    }
+
     Ok(())
 }
 
@@ -1,7 +1,11 @@
+pub mod audit;
 pub mod backup;
 pub mod beat;
 pub mod bridges;
+pub mod capture_journal;
 pub mod cognitive_inbox;
+pub mod cross_bridges;
+pub mod dead_letters;
 pub mod database;
 pub mod manager;
 pub mod mcp_messages;
@@ -10,6 +14,7 @@ pub mod path_utils;
 pub mod project_registry_impl;
 pub mod shared_storage;
 pub mod threads;
+pub mod topic_aliases;
 pub mod topic_index;
 pub mod transcript;
 pub mod concept_index;
@@ -38,16 +38,59 @@ pub fn agent_data_dir(project_hash: &str, agent_id: &str) -> PathBuf {
         .join(agent_id)
 }
 
+/// Retourne le chemin du marqueur de throttling des captures d'un agent:
+/// {data_dir}/projects/{hash}/agents/{agent_id}/capture_throttle
+/// Contient la date RFC 3339 jusqu'a laquelle le hook n'envoie plus de captures d'outils.
+pub fn capture_throttle_path(project_hash: &str, agent_id: &str) -> PathBuf {
+    agent_data_dir(project_hash, agent_id).join("capture_throttle")
+}
+
+/// Retourne le chemin du jeton IPC d'un agent (emis par le daemon):
+/// {data_dir}/projects/{hash}/agents/{agent_id}/ipc_token
+pub fn agent_ipc_token_path(project_hash: &str, agent_id: &str) -> PathBuf {
+    agent_data_dir(project_hash, agent_id).join("ipc_token")
+}
+
 /// Retourne le chemin de shared.db: {data_dir}/projects/{hash}/shared.db
 pub fn shared_db_path(project_hash: &str) -> PathBuf {
     project_dir(project_hash).join("shared.db")
 }
 
+/// Retourne le repertoire des prompts MCP globaux: {data_dir}/prompts/
+pub fn global_prompts_dir() -> PathBuf {
+    data_dir().join("prompts")
+}
+
+/// Retourne le repertoire des prompts MCP d'un projet: {data_dir}/projects/{hash}/prompts/
+pub fn project_prompts_dir(project_hash: &str) -> PathBuf {
+    project_dir(project_hash).join("prompts")
+}
+
 /// Retourne le chemin de registry.db: {data_dir}/registry.db
 pub fn registry_db_path() -> PathBuf {
     data_dir().join("registry.db")
 }
 
+/// Retourne le chemin du journal des captures du daemon: {data_dir}/capture_journal.db
+pub fn capture_journal_path() -> PathBuf {
+    data_dir().join("capture_journal.db")
+}
+
+/// Retourne le chemin du jeton de l'API HTTP locale du daemon: {data_dir}/api_token
+pub fn api_token_path() -> PathBuf {
+    data_dir().join("api_token")
+}
+
+/// Retourne le chemin du secret d'authentification IPC du daemon: {data_dir}/ipc_secret
+pub fn ipc_secret_path() -> PathBuf {
+    data_dir().join("ipc_secret")
+}
+
+/// Retourne le chemin de l'etat des taches periodiques du daemon: {data_dir}/task_state.json
+pub fn task_state_path() -> PathBuf {
+    data_dir().join("task_state.json")
+}
+
 /// Retourne le repertoire des wake signals: {data_dir}/wake_signals/
 pub fn wake_signals_dir() -> PathBuf {
     data_dir().join("wake_signals")
@@ -68,6 +68,7 @@ impl ProjectRegistryTrait for SqliteProjectRegistry {
         self.conn
             .execute("DELETE FROM projects WHERE hash = ?1", params![hash])
             .map_err(|e| AiError::Storage(format!("Remove project failed: {}", e)))?;
+        crate::storage::cross_bridges::CrossBridgeStorage::delete_for_project(&self.conn, hash)?;
         Ok(())
     }
 
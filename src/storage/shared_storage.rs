@@ -230,6 +230,28 @@ impl SharedStorage {
         Ok(c)
     }
 
+    /// Count shared entries for any of the given source threads.
+    pub fn count_by_thread_ids(conn: &Connection, thread_ids: &[String]) -> AiResult<usize> {
+        if thread_ids.is_empty() {
+            return Ok(0);
+        }
+        if thread_ids.len() > 500 {
+            return thread_ids.chunks(500).try_fold(0, |acc, chunk| {
+                Self::count_by_thread_ids(conn, chunk).map(|n| acc + n)
+            });
+        }
+
+        let placeholders: Vec<String> = (1..=thread_ids.len()).map(|i| format!("?{i}")).collect();
+        let sql = format!(
+            "SELECT COUNT(*) FROM shared_threads WHERE source_thread_id IN ({})",
+            placeholders.join(", ")
+        );
+        let c: usize = conn
+            .query_row(&sql, rusqlite::params_from_iter(thread_ids), |r| r.get(0))
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(c)
+    }
+
     /// Return the full `SharedThread` rows for all threads an agent is subscribed to.
     /// Used by heartbeat to pre-cache subscription data into beat.json (no DB hit in inject).
     pub fn list_subscribed_threads(conn: &Connection, agent_id: &str) -> AiResult<Vec<SharedThread>> {
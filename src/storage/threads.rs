@@ -1,13 +1,23 @@
+use crate::storage::database::NestedTx;
 use crate::time_utils;
 use crate::thread::{
     InjectionStats, OriginType, Thread, ThreadMessage, ThreadStatus, WorkContext,
 };
 use crate::processing::extractor::ExtractionMode;
 use crate::{AiError, AiResult};
+use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, Row};
 
 pub struct ThreadStorage;
 
+/// Id, title and status of a thread, without the heavy columns (previews of bulk actions).
+#[derive(Debug, Clone)]
+pub struct ThreadHeader {
+    pub id: String,
+    pub title: String,
+    pub status: ThreadStatus,
+}
+
 // ── Row mapping ──
 
 fn thread_from_row(row: &Row) -> rusqlite::Result<Thread> {
@@ -280,6 +290,55 @@ impl ThreadStorage {
         Ok(threads)
     }
 
+    /// IDs of the threads with the given status (no row decoding).
+    pub fn ids_by_status(conn: &Connection, status: &ThreadStatus) -> AiResult<Vec<String>> {
+        let mut stmt = conn
+            .prepare("SELECT id FROM threads WHERE status = ?1 ORDER BY weight DESC")
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+
+        let ids = stmt
+            .query_map(params![status.as_str()], |row| row.get::<_, String>(0))
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(ids)
+    }
+
+    /// Headers of the given threads that exist (order not preserved).
+    pub fn headers(conn: &Connection, ids: &[String]) -> AiResult<Vec<ThreadHeader>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if ids.len() > 500 {
+            return ids.chunks(500).try_fold(Vec::new(), |mut acc, chunk| {
+                acc.extend(Self::headers(conn, chunk)?);
+                Ok(acc)
+            });
+        }
+
+        let placeholders: Vec<String> = (1..=ids.len()).map(|i| format!("?{i}")).collect();
+        let sql = format!(
+            "SELECT id, title, status FROM threads WHERE id IN ({})",
+            placeholders.join(", ")
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let headers = stmt
+            .query_map(rusqlite::params_from_iter(ids), |row| {
+                let status: String = row.get(2)?;
+                Ok(ThreadHeader {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    status: status.parse().unwrap_or(ThreadStatus::Active),
+                })
+            })
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(headers)
+    }
+
     pub fn list_all(conn: &Connection) -> AiResult<Vec<Thread>> {
         let mut stmt = conn
             .prepare("SELECT * FROM threads ORDER BY weight DESC")
@@ -421,6 +480,37 @@ impl ThreadStorage {
         Ok(())
     }
 
+    /// Embedding of one thread (None if the thread is missing or has none).
+    pub fn get_embedding(conn: &Connection, id: &str) -> AiResult<Option<Vec<f32>>> {
+        let blob: Option<Vec<u8>> = conn
+            .query_row("SELECT embedding FROM threads WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .flatten();
+        Ok(blob.map(|blob| {
+            blob.chunks_exact(4)
+                .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect()
+        }))
+    }
+
+    /// (id, title, changed) of every non-command thread; `changed` = active
+    /// since `since` (RFC 3339), always true without `since`. Cross-project gossip.
+    pub fn list_titles_changed_since(conn: &Connection, since: Option<&str>) -> AiResult<Vec<(String, String, bool)>> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, title, ?1 IS NULL OR last_active >= ?1 FROM threads
+                 WHERE origin_type != 'command'",
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(rows)
+    }
+
     pub fn update_embedding(conn: &Connection, id: &str, embedding: &[f32]) -> AiResult<()> {
         let blob: Vec<u8> = embedding
             .iter()
@@ -614,8 +704,7 @@ impl ThreadStorage {
     }
 
     pub fn add_message(conn: &Connection, msg: &ThreadMessage) -> AiResult<()> {
-        let tx = conn.unchecked_transaction()
-            .map_err(|e| AiError::Storage(format!("Begin transaction failed: {}", e)))?;
+        let tx = NestedTx::begin(conn)?;
 
         tx.execute(
             "INSERT INTO thread_messages (id, thread_id, content, source, source_type, timestamp, metadata, is_truncated, continuity_from, continuity_to)
@@ -643,8 +732,7 @@ impl ThreadStorage {
         )
         .map_err(|e| AiError::Storage(format!("Update thread last_active failed: {}", e)))?;
 
-        tx.commit()
-            .map_err(|e| AiError::Storage(format!("Commit failed: {}", e)))?;
+        tx.commit()?;
 
         tracing::debug!(thread_id = %msg.thread_id, msg_id = %msg.msg_id, "Message added");
         Ok(())
@@ -666,6 +754,22 @@ impl ThreadStorage {
         Ok(messages)
     }
 
+    /// Message timestamps only (oldest first) — cheap access history for decay models.
+    pub fn message_timestamps(conn: &Connection, thread_id: &str) -> AiResult<Vec<DateTime<Utc>>> {
+        let mut stmt = conn
+            .prepare("SELECT timestamp FROM thread_messages WHERE thread_id = ?1 ORDER BY timestamp ASC")
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+
+        let timestamps = stmt
+            .query_map(params![thread_id], |row| row.get::<_, String>(0))
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .filter_map(|ts| time_utils::from_sqlite(&ts).ok())
+            .collect();
+
+        Ok(timestamps)
+    }
+
     pub fn delete_messages(conn: &Connection, thread_id: &str) -> AiResult<()> {
         conn.execute(
             "DELETE FROM thread_messages WHERE thread_id = ?1",
@@ -731,6 +835,49 @@ impl ThreadStorage {
         Ok(edges)
     }
 
+    /// IDs of threads whose continuity_parent_id points to the given thread.
+    pub fn continuity_child_ids(conn: &Connection, thread_id: &str) -> AiResult<Vec<String>> {
+        let mut stmt = conn
+            .prepare("SELECT id FROM threads WHERE continuity_parent_id = ?1")
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+
+        let ids = stmt
+            .query_map(params![thread_id], |row| row.get::<_, String>(0))
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(ids)
+    }
+
+    /// IDs of threads whose continuity_parent_id points to any of the given threads.
+    pub fn continuity_child_ids_batch(conn: &Connection, ids: &[String]) -> AiResult<Vec<String>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if ids.len() > 500 {
+            return ids.chunks(500).try_fold(Vec::new(), |mut acc, chunk| {
+                acc.extend(Self::continuity_child_ids_batch(conn, chunk)?);
+                Ok(acc)
+            });
+        }
+
+        let placeholders: Vec<String> = (1..=ids.len()).map(|i| format!("?{i}")).collect();
+        let sql = format!(
+            "SELECT id FROM threads WHERE continuity_parent_id IN ({})",
+            placeholders.join(", ")
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let children = stmt
+            .query_map(rusqlite::params_from_iter(ids), |row| row.get::<_, String>(0))
+            .map_err(|e| AiError::Storage(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(children)
+    }
+
     /// Set continuity_parent_id (and optional coherence) on a thread.
     pub fn set_continuity_parent(
         conn: &Connection,
@@ -881,8 +1028,7 @@ impl ThreadStorage {
     /// Bulk delete all threads with a given status. Also removes their messages.
     /// Returns the number of threads deleted.
     pub fn delete_by_status(conn: &Connection, status: &ThreadStatus) -> AiResult<usize> {
-        let tx = conn.unchecked_transaction()
-            .map_err(|e| AiError::Storage(format!("Begin transaction failed: {}", e)))?;
+        let tx = NestedTx::begin(conn)?;
 
         // Relink continuity chain: children of deleted threads inherit their grandparent
         tx.execute(
@@ -921,8 +1067,7 @@ impl ThreadStorage {
             )
             .map_err(|e| AiError::Storage(format!("Delete threads by status failed: {}", e)))?;
 
-        tx.commit()
-            .map_err(|e| AiError::Storage(format!("Commit failed: {}", e)))?;
+        tx.commit()?;
         Ok(deleted)
     }
 
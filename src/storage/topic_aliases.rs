@@ -0,0 +1,169 @@
+//! Topic aliases — per-agent alias table for topic/concept normalisation.
+//!
+//! Rows map an alias key to a canonical key (both already canonicalised).
+//! `suggested` rows come from the TopicAliasSuggester; only `confirmed`
+//! rows are applied by the TopicNormalizer. `rejected` rows are kept so the
+//! suggester does not propose the same pair again.
+
+use std::collections::HashMap;
+
+use crate::{time_utils, AiError, AiResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_CONFIRMED: &str = "confirmed";
+pub const STATUS_SUGGESTED: &str = "suggested";
+pub const STATUS_REJECTED: &str = "rejected";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicAlias {
+    pub alias: String,
+    pub canonical: String,
+    pub status: String,
+    pub score: f64,
+    /// "manual", "embedding", "cooccurrence" or "embedding+cooccurrence"
+    pub source: String,
+    pub created_at: String,
+}
+
+pub struct TopicAliasStorage;
+
+impl TopicAliasStorage {
+    /// Insert or replace an alias. A suggestion never overwrites a confirmed
+    /// row nor the rejection of the same pair.
+    pub fn upsert(conn: &Connection, alias: &TopicAlias) -> AiResult<()> {
+        if alias.status == STATUS_SUGGESTED {
+            if let Some(existing) = Self::get(conn, &alias.alias)? {
+                let rejected_pair = existing.status == STATUS_REJECTED && existing.canonical == alias.canonical;
+                if existing.status == STATUS_CONFIRMED || rejected_pair {
+                    return Ok(());
+                }
+            }
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO topic_aliases (alias, canonical, status, score, source, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![alias.alias, alias.canonical, alias.status, alias.score, alias.source, alias.created_at],
+        )
+        .map_err(|e| AiError::Storage(format!("Topic alias upsert failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// Record a confirmed alias (manual add).
+    pub fn add_confirmed(conn: &Connection, alias: &str, canonical: &str) -> AiResult<()> {
+        Self::upsert(conn, &TopicAlias {
+            alias: alias.to_string(),
+            canonical: canonical.to_string(),
+            status: STATUS_CONFIRMED.to_string(),
+            score: 1.0,
+            source: "manual".to_string(),
+            created_at: time_utils::now().to_rfc3339(),
+        })
+    }
+
+    pub fn get(conn: &Connection, alias: &str) -> AiResult<Option<TopicAlias>> {
+        conn.query_row(
+            "SELECT alias, canonical, status, score, source, created_at FROM topic_aliases WHERE alias = ?1",
+            params![alias],
+            Self::from_row,
+        )
+        .optional()
+        .map_err(|e| AiError::Storage(e.to_string()))
+    }
+
+    /// Promote a suggestion to confirmed. Returns false if the alias is unknown.
+    pub fn confirm(conn: &Connection, alias: &str) -> AiResult<bool> {
+        Self::set_status(conn, alias, STATUS_CONFIRMED)
+    }
+
+    /// Mark an alias rejected (no longer applied nor suggested again).
+    /// Returns false if the alias is unknown.
+    pub fn reject(conn: &Connection, alias: &str) -> AiResult<bool> {
+        Self::set_status(conn, alias, STATUS_REJECTED)
+    }
+
+    fn set_status(conn: &Connection, alias: &str, status: &str) -> AiResult<bool> {
+        let n = conn
+            .execute(
+                "UPDATE topic_aliases SET status = ?1 WHERE alias = ?2",
+                params![status, alias],
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(n > 0)
+    }
+
+    pub fn delete(conn: &Connection, alias: &str) -> AiResult<bool> {
+        let n = conn
+            .execute("DELETE FROM topic_aliases WHERE alias = ?1", params![alias])
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(n > 0)
+    }
+
+    /// List aliases, optionally filtered by status (highest score first).
+    pub fn list(conn: &Connection, status: Option<&str>) -> AiResult<Vec<TopicAlias>> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT alias, canonical, status, score, source, created_at FROM topic_aliases
+                 WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY score DESC, alias ASC",
+            )
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![status], Self::from_row)
+            .map_err(|e| AiError::Storage(e.to_string()))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Confirmed alias → canonical map (used by the TopicNormalizer).
+    pub fn confirmed_map(conn: &Connection) -> AiResult<HashMap<String, String>> {
+        Ok(Self::list(conn, Some(STATUS_CONFIRMED))?
+            .into_iter()
+            .map(|a| (a.alias, a.canonical))
+            .collect())
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<TopicAlias> {
+        Ok(TopicAlias {
+            alias: row.get(0)?,
+            canonical: row.get(1)?,
+            status: row.get(2)?,
+            score: row.get(3)?,
+            source: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::setup_agent_db;
+
+    fn suggestion(alias: &str, canonical: &str) -> TopicAlias {
+        TopicAlias {
+            alias: alias.into(),
+            canonical: canonical.into(),
+            status: STATUS_SUGGESTED.into(),
+            score: 0.9,
+            source: "embedding".into(),
+            created_at: time_utils::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_rejected_pair_is_not_suggested_again() {
+        let conn = setup_agent_db();
+        TopicAliasStorage::upsert(&conn, &suggestion("k8s", "kubernetes")).unwrap();
+        assert!(TopicAliasStorage::reject(&conn, "k8s").unwrap());
+
+        TopicAliasStorage::upsert(&conn, &suggestion("k8s", "kubernetes")).unwrap();
+        let row = TopicAliasStorage::get(&conn, "k8s").unwrap().unwrap();
+        assert_eq!(row.status, STATUS_REJECTED, "Same pair stays rejected");
+        assert!(TopicAliasStorage::confirmed_map(&conn).unwrap().is_empty());
+
+        TopicAliasStorage::upsert(&conn, &suggestion("k8s", "k8s-cluster")).unwrap();
+        let row = TopicAliasStorage::get(&conn, "k8s").unwrap().unwrap();
+        assert_eq!((row.status.as_str(), row.canonical.as_str()), (STATUS_SUGGESTED, "k8s-cluster"));
+        assert!(!TopicAliasStorage::reject(&conn, "unknown").unwrap());
+    }
+}
@@ -9,6 +9,9 @@
 //!   - Build: O(N × T) where N=threads, T=avg topics per thread
 //!   - Lookup: O(K) where K=query topics count
 //!   - Update: O(T) per thread insert/update
+//!
+//! Keys are whatever the caller's key function returns (the agent's topic
+//! normalizer); the index itself does no normalisation.
 
 use std::collections::{HashMap, HashSet};
 use crate::{AiError, AiResult};
@@ -17,16 +20,17 @@ use rusqlite::Connection;
 /// Inverted topic index for O(1) candidate lookup.
 #[derive(Debug, Default)]
 pub struct TopicIndex {
-    /// topic (lowercase) → set of thread_ids
+    /// topic key → set of thread_ids
     index: HashMap<String, HashSet<String>>,
     /// bigram "topicA+topicB" → set of thread_ids (for multi-topic precision)
     bigram_index: HashMap<String, HashSet<String>>,
 }
 
 impl TopicIndex {
-    /// Build the index from all active/suspended threads in the database.
+    /// Build the index from all active/suspended threads in the database,
+    /// keying stored topics with `key`.
     /// Graceful: returns empty index if the threads table doesn't exist yet.
-    pub fn build_from_db(conn: &Connection) -> AiResult<Self> {
+    pub fn build_from_db(conn: &Connection, key: &dyn Fn(&str) -> String) -> AiResult<Self> {
         let mut idx = Self::default();
 
         let mut stmt = match conn.prepare(
@@ -49,7 +53,8 @@ impl TopicIndex {
             if let Ok((id, topics_json)) = row {
                 let topics: Vec<String> = serde_json::from_str(&topics_json).unwrap_or_default();
                 if !topics.is_empty() {
-                    idx.insert(&id, &topics);
+                    let keys: Vec<String> = topics.iter().map(|t| key(t)).collect();
+                    idx.insert(&id, &keys);
                 }
             }
         }
@@ -59,13 +64,14 @@ impl TopicIndex {
 
     /// Extract topics from text that match existing indexed topics.
     /// Used by EngramRetriever Phase 1 to convert user message → query topics.
-    /// Only returns words/phrases that exist in the index (known topics).
-    pub fn extract_matching_topics(&self, text: &str) -> Vec<String> {
+    /// Only returns words/phrases that exist in the index (known topics);
+    /// text words are keyed with `key` before matching.
+    pub fn extract_matching_topics(&self, text: &str, key: &dyn Fn(&str) -> String) -> Vec<String> {
         let text_lower = text.to_lowercase();
         let words: HashSet<String> = text_lower
             .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
             .filter(|w| w.len() >= 3)
-            .map(String::from)
+            .map(key)
             .collect();
 
         let mut matched = Vec::new();
@@ -76,7 +82,7 @@ impl TopicIndex {
                 continue;
             }
             // Multi-word topic: check if all parts appear in text
-            let parts: Vec<&str> = topic.split(|c: char| c == ' ' || c == '_' || c == '-')
+            let parts: Vec<&str> = topic.split(' ')
                 .filter(|p| p.len() >= 2)
                 .collect();
             if parts.len() >= 2 && parts.iter().all(|p| text_lower.contains(p)) {
@@ -91,12 +97,11 @@ impl TopicIndex {
         matched
     }
 
-    /// Lookup candidate thread_ids that share at least one topic with the query.
+    /// Lookup candidate thread_ids that share at least one topic key with the query.
     pub fn lookup(&self, query_topics: &[String]) -> HashSet<String> {
         let mut candidates = HashSet::new();
         for topic in query_topics {
-            let key = topic.to_lowercase();
-            if let Some(thread_ids) = self.index.get(&key) {
+            if let Some(thread_ids) = self.index.get(topic) {
                 candidates.extend(thread_ids.iter().cloned());
             }
         }
@@ -114,11 +119,10 @@ impl TopicIndex {
         candidates
     }
 
-    /// Add a thread to the index.
+    /// Add a thread to the index (topics already keyed).
     pub fn insert(&mut self, thread_id: &str, topics: &[String]) {
         for topic in topics {
-            let key = topic.to_lowercase();
-            self.index.entry(key).or_default().insert(thread_id.to_string());
+            self.index.entry(topic.clone()).or_default().insert(thread_id.to_string());
         }
         // Build bigrams
         if topics.len() >= 2 {
@@ -166,12 +170,10 @@ impl TopicIndex {
     }
 
     fn make_bigram(a: &str, b: &str) -> String {
-        let a_lower = a.to_lowercase();
-        let b_lower = b.to_lowercase();
-        if a_lower <= b_lower {
-            format!("{}+{}", a_lower, b_lower)
+        if a <= b {
+            format!("{}+{}", a, b)
         } else {
-            format!("{}+{}", b_lower, a_lower)
+            format!("{}+{}", b, a)
         }
     }
 }